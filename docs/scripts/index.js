@@ -34,6 +34,9 @@ function showDownloadLink() {
     case 'Linux x64':
       document.getElementById('LinuxX64').style.display = 'block';
       break;
+    case 'Linux arm64':
+      document.getElementById('LinuxArm64').style.display = 'block';
+      break;
     default:
       document.getElementById('UnsupportedPlatform').style.display = 'block';
       break;
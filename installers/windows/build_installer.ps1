@@ -1,7 +1,12 @@
+param(
+    [ValidateSet("x64", "arm64")]
+    [string]$Arch = "x64"
+)
+
 $innoCompilerPath = "C:\Program Files (x86)\Inno Setup 6\ISCC.exe"
 
 if (Test-Path $innoCompilerPath) {
-    & $innoCompilerPath ".\win_installer_definition.iss"
+    & $innoCompilerPath "/DArch=$Arch" ".\win_installer_definition.iss"
 } else {
     Write-Error "Inno Setup Compiler not found at path: $innoCompilerPath"
 }
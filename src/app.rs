@@ -1,40 +1,303 @@
+use crate::events::{AppEvent, EventBus, EventPublisher};
+use crate::i18n::tr;
+use crate::pages::StepFailed;
+use crate::pages::driver_station_setup::DriverStationSetupPage;
+use crate::pages::driver_station_setup::session::{self, SavedSession};
+use crate::settings::{Settings, SoftwareRepo};
+use crate::utils::panics::{PanicReport, catch_panic, find_report};
+use crate::utils::sounds::Cue;
+use crate::utils::status_export::StatusExporter;
+use crate::widgets::accessibility::{
+    Accessibility, heading, mark_as_heading, set_accessible_name, show_focus_ring,
+};
+use crate::widgets::language_picker::show_language_picker;
+use crate::widgets::performance_panel::{PerformancePanel, QueueDepths};
 use eframe::{App, Frame};
+use egui_file_dialog::FileDialog;
+use std::sync::atomic::AtomicU32;
+use std::sync::{Arc, RwLock};
 
 pub struct GlobalAppState {
     pub tmp_dir: tempfile::TempDir,
+    pub settings: Settings,
+    /// Directory of a local release bundle to install from instead of GitHub.
+    pub offline_source: Option<std::path::PathBuf>,
+    pub events: EventPublisher,
+    /// Which releases the version pickers offer, shared so every page agrees.
+    pub release_channel: crate::utils::github::ReleaseChannel,
+    /// Lets worker threads ask the user a question and wait for the answer.
+    pub prompts: crate::prompts::Prompter,
+    /// Whether GitHub was reachable the last time the network watcher checked.
+    pub network_online: Option<bool>,
+    /// Shared with the release poller so interval changes apply without a restart.
+    pub release_poll_interval: Arc<AtomicU32>,
+    /// File this session's log is written to, if logging could be started.
+    pub log_file: Option<std::path::PathBuf>,
+    /// Cards set up since the wizard was opened.
+    pub session_report: crate::utils::session_report::SessionReport,
+    /// Lists, formats, and ejects drives. Replaced whenever demonstration mode is switched.
+    pub drives: std::sync::Arc<dyn crate::utils::drive_management::DriveManager>,
+    /// Started with `--dry-run`, so demonstration mode stays on for this run whatever the
+    /// settings say.
+    pub dry_run: bool,
+    /// Versions installed this session, checked against new selections on every page.
+    pub compatibility: crate::utils::compatibility::Compatibility,
+    /// Repositories each kind of software is installed from. Copied from the settings when
+    /// they are applied, so a half-typed repository name is never used.
+    pub sources: crate::settings::SoftwareSources,
+    /// Client and settings that requests are made with. Shared with the release poller so
+    /// changes apply without a restart.
+    pub network: Arc<RwLock<crate::utils::http::Network>>,
+}
+
+impl GlobalAppState {
+    /// Directory that release assets are downloaded into.
+    pub fn download_cache_dir(&self) -> std::path::PathBuf {
+        self.settings
+            .use_persistent_cache
+            .then(crate::utils::cache::persistent_cache_dir)
+            .flatten()
+            .unwrap_or_else(|| self.tmp_dir.path().join("github_downloads"))
+    }
+
+    /// A copy of the network for a task to make its requests with.
+    pub fn network(&self) -> crate::utils::http::Network {
+        self.network
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Changes the network for every request started from now on.
+    pub fn update_network(&self, update: impl FnOnce(&mut crate::utils::http::Network)) {
+        update(&mut self.network.write().unwrap_or_else(|e| e.into_inner()));
+    }
+
+    /// Creates a scratch directory for one operation inside this session's temporary directory.
+    pub fn work_dir(&self, operation: &str) -> anyhow::Result<crate::utils::work_dir::WorkDir> {
+        crate::utils::work_dir::WorkDir::new(self.tmp_dir.path(), operation)
+    }
 }
 
 pub struct MyApp {
     current_page: Option<Box<dyn crate::pages::Page>>,
     state: GlobalAppState,
+    event_bus: EventBus,
+    prompt_queue: crate::prompts::PromptQueue,
+    status_text: Option<String>,
     page_error: Option<anyhow::Error>,
+    offline_dialog: FileDialog,
+    log_dialog: FileDialog,
+    report_dialog: FileDialog,
+    /// Whether a starter code release exists, or `None` while checking.
+    starter_code_available: Option<bool>,
+    /// The offline source and repository the starter code check last ran against.
+    starter_code_checked_source: Option<(Option<std::path::PathBuf>, SoftwareRepo)>,
+    starter_code_receiver: Option<std::sync::mpsc::Receiver<anyhow::Result<bool>>>,
+    /// Unfinished driver station session from an earlier run, offered on the start page.
+    saved_session: Option<SavedSession>,
+    /// Whether the session file has been read since the start page was last left.
+    saved_session_checked: bool,
+    /// Newer release of the wizard itself, until the user dismisses the banner.
+    wizard_update: Option<crate::utils::github::GithubRelease>,
+    self_update_receiver: Option<std::sync::mpsc::Receiver<anyhow::Result<()>>>,
+    performance_panel: PerformancePanel,
+    accessibility: Accessibility,
+    status_exporter: StatusExporter,
 }
 
 impl MyApp {
-    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+    pub fn new(cc: &eframe::CreationContext<'_>, dry_run: bool) -> Self {
         egui_extras::install_image_loaders(&cc.egui_ctx);
+        let log_file = crate::logging::init()
+            .inspect_err(|e| eprintln!("Could not start logging: {e:#}"))
+            .ok();
+        let migration_errors = crate::migrations::run_startup_migrations();
         let tmp_dir = tempfile::Builder::new()
             .prefix("best-gizmo-setup-wizard")
             .tempdir()
             .expect("Failed to create temporary directory");
+        let mut settings = Settings::load();
+        let conflicts = crate::reconciliation::reconcile(&mut settings);
+        let mut network = crate::utils::http::Network::new(&settings);
+        let network_error = network
+            .apply_network_settings(&settings.network)
+            .inspect_err(|e| log::warn!("Could not apply the network settings: {e:#}"))
+            .err()
+            .map(|e| tr!("app-network-settings-failed", error = format!("{e:#}")));
+        let network = Arc::new(RwLock::new(network));
+        crate::utils::sounds::set_sound_settings(&settings.sounds);
+        crate::i18n::set_language(settings.language);
+        crate::theme::apply_theme(&cc.egui_ctx, settings.theme);
+        if dry_run {
+            log::info!("Started with --dry-run, so nothing will be written to real drives");
+        }
+        crate::utils::demo::set_demo_mode(settings.demo_mode || dry_run);
+        let accessibility = Accessibility::new(&cc.egui_ctx, &settings);
+        let event_bus = EventBus::new(&cc.egui_ctx);
+        event_bus.start_watchers();
+        let prompt_queue = crate::prompts::PromptQueue::new(&cc.egui_ctx);
+        let release_poll_interval =
+            Arc::new(AtomicU32::new(settings.release_poll_interval_minutes));
+        crate::utils::self_update::start_update_check(
+            event_bus.publisher(),
+            network.read().unwrap_or_else(|e| e.into_inner()).clone(),
+        );
+        crate::utils::release_poller::start_release_poller(
+            event_bus.publisher(),
+            network.clone(),
+            release_poll_interval.clone(),
+            settings.software_sources.clone(),
+        );
+        let status_text = migration_errors
+            .first()
+            .cloned()
+            .or(network_error)
+            .or_else(|| {
+                (!conflicts.is_empty()).then(|| tr!("app-conflicts-found", count = conflicts.len()))
+            });
+        let current_page = (!conflicts.is_empty()).then(|| {
+            Box::new(
+                crate::pages::configuration_conflicts::ConfigurationConflictsPage::new(conflicts),
+            ) as Box<dyn crate::pages::Page>
+        });
         Self {
-            current_page: None,
-            state: GlobalAppState { tmp_dir },
+            current_page,
+            state: GlobalAppState {
+                tmp_dir,
+                sources: settings.software_sources.clone(),
+                drives: crate::utils::drive_management::drive_manager(&settings),
+                settings,
+                offline_source: None,
+                events: event_bus.publisher(),
+                release_channel: crate::utils::github::ReleaseChannel::Stable,
+                prompts: prompt_queue.prompter(),
+                network_online: None,
+                release_poll_interval,
+                log_file,
+                session_report: crate::utils::session_report::SessionReport::new(),
+                dry_run,
+                compatibility: crate::utils::compatibility::Compatibility::new(),
+                network,
+            },
+            event_bus,
+            prompt_queue,
+            status_text,
             page_error: None,
+            offline_dialog: FileDialog::new().title(&tr!("dialog-offline-bundle")),
+            log_dialog: FileDialog::new()
+                .title(&tr!("dialog-save-log"))
+                .default_file_name("gizmo-wizard.log"),
+            report_dialog: FileDialog::new()
+                .title(&tr!("dialog-save-report"))
+                .default_file_name("session-report.json"),
+            starter_code_available: None,
+            starter_code_checked_source: None,
+            starter_code_receiver: None,
+            saved_session: None,
+            saved_session_checked: false,
+            wizard_update: None,
+            self_update_receiver: None,
+            performance_panel: PerformancePanel::new(),
+            accessibility,
+            status_exporter: StatusExporter::new(),
+        }
+    }
+
+    fn open_page(&mut self, page: Box<dyn crate::pages::Page>) {
+        log::info!("Opening page: {}", page.get_title());
+        self.prompt_queue.cancel_all();
+        self.current_page = Some(page);
+        // The page may save a session of its own, so read the file again on the way back.
+        self.saved_session_checked = false;
+    }
+
+    /// Checks for a compatible starter code release whenever the release source or repository
+    /// changes.
+    fn update_starter_code_check(&mut self, ctx: &egui::Context) {
+        let source = (
+            self.state.offline_source.clone(),
+            self.state.sources.starter_code.clone(),
+        );
+        if self.starter_code_checked_source.as_ref() != Some(&source) {
+            self.starter_code_checked_source = Some(source.clone());
+            let (offline_source, repo) = source;
+            let network = self.state.network();
+            self.starter_code_available = None;
+            let ctx = ctx.clone();
+            let (tx, rx) = std::sync::mpsc::channel();
+            self.starter_code_receiver = Some(rx);
+            std::thread::spawn(move || {
+                let result = crate::pages::student_starter_code::starter_code_release_available(
+                    &network,
+                    offline_source.as_deref(),
+                    &repo,
+                );
+                // The receiver is dropped if the source changed again, which is fine.
+                let _ = tx.send(result);
+                ctx.request_repaint();
+            });
+        }
+        if let Some(ref receiver) = self.starter_code_receiver
+            && let Ok(result) = receiver.try_recv()
+        {
+            self.starter_code_receiver = None;
+            self.starter_code_available = Some(result.unwrap_or_else(|e| {
+                // The page can still install from a local file, so don't hide it just because
+                // GitHub couldn't be reached.
+                log::warn!("Could not check for starter code releases: {e:#}");
+                true
+            }));
+        }
+    }
+
+    /// Offers to carry on with a driver station session the wizard was closed in the middle of.
+    fn add_resume_session_row(&mut self, ui: &mut egui::Ui) {
+        if !self.saved_session_checked {
+            self.saved_session = session::load();
+            self.saved_session_checked = true;
+        }
+        let Some(ref saved) = self.saved_session else {
+            return;
+        };
+        let saved_at = std::time::UNIX_EPOCH + std::time::Duration::from_secs(saved.saved_at);
+        let mut resume = false;
+        let mut discard = false;
+        ui.group(|ui| {
+            ui.label(tr!(
+                "start-session-unfinished",
+                saved = crate::logging::format_timestamp(saved_at),
+                done = saved.completed_teams.len(),
+                total = saved.completed_teams.len() + saved.remaining_teams.len()
+            ));
+            ui.horizontal(|ui| {
+                resume = ui.button(tr!("start-resume-session")).clicked();
+                discard = ui.button(tr!("start-discard-session")).clicked();
+            });
+        });
+        if discard {
+            log::info!("Discarding saved driver station session");
+            session::clear();
+            self.saved_session = None;
+        } else if resume && let Some(saved) = self.saved_session.take() {
+            self.open_page(Box::new(DriverStationSetupPage::resume(
+                saved,
+                &self.state.settings,
+            )));
         }
     }
 
     fn run_start_page(&mut self, ui: &mut egui::Ui) {
+        self.update_starter_code_check(ui.ctx());
         egui_alignments::column(ui, egui::Align::LEFT, |ui| {
             egui_alignments::row(ui, egui::Align::TOP, |ui| {
                 egui_alignments::stretch(ui);
-                ui.heading("BEST Gizmo Software Installer");
+                mark_as_heading(&ui.heading(tr!("app-title")), 1);
                 egui_alignments::stretch(ui);
             });
-            ui.label("This tool will help you install or update your Gizmo software.");
-            ui.label(
-                "Select which software you would like to install, then follow the instructions.",
-            );
+            ui.label(tr!("start-intro"));
+            ui.label(tr!("start-instructions"));
+            self.add_resume_session_row(ui);
 
             egui_alignments::stretch(ui);
 
@@ -43,47 +306,185 @@ impl MyApp {
 
                 egui_alignments::column(ui, egui::Align::Center, |ui| {
                     let button =
-                        egui::ImageButton::new(egui::include_image!("assets/driver_station.png"));
-                    if ui.add_sized([150.0, 150.0], button).clicked() {
-                        self.current_page = Some(Box::new(
+                        egui::Button::image(egui::include_image!("assets/driver_station.png"));
+                    let response = ui.add_sized([150.0, 150.0], button);
+                    set_accessible_name(&response, &tr!("start-driver-station"));
+                    if response.clicked() {
+                        self.open_page(Box::new(
                             crate::pages::driver_station_setup::DriverStationSetupPage::new(),
                         ));
                     }
-                    ui.label("Driver Station");
+                    ui.label(tr!("start-driver-station"));
                 });
 
                 egui_alignments::column(ui, egui::Align::Center, |ui| {
-                    let button = egui::ImageButton::new(egui::include_image!(
+                    let button = egui::Button::image(egui::include_image!(
                         "assets/gizmo_system_processor.png"
                     ));
-                    if ui.add_sized([150.0, 150.0], button).clicked() {
-                        self.current_page = Some(Box::new(
+                    let response = ui.add_sized([150.0, 150.0], button);
+                    set_accessible_name(&response, &tr!("start-system-firmware"));
+                    if response.clicked() {
+                        self.open_page(Box::new(
                             crate::pages::system_firmware::SystemFirmwarePage::new(),
                         ));
                     }
-                    ui.label("System Firmware");
+                    ui.label(tr!("start-system-firmware"));
                 });
 
                 egui_alignments::column(ui, egui::Align::Center, |ui| {
-                    let button = egui::ImageButton::new(egui::include_image!(
+                    let button =
+                        egui::Button::image(egui::include_image!("assets/gizmo_system_config.png"));
+                    let response = ui.add_sized([150.0, 150.0], button);
+                    set_accessible_name(&response, &tr!("start-system-settings"));
+                    if response.clicked() {
+                        self.open_page(Box::new(
+                            crate::pages::system_config::SystemConfigPage::new(),
+                        ));
+                    }
+                    ui.label(tr!("start-system-settings"));
+                });
+
+                egui_alignments::column(ui, egui::Align::Center, |ui| {
+                    let button = egui::Button::image(egui::include_image!(
                         "assets/gizmo_student_processor.png"
                     ));
-                    if ui.add_sized([150.0, 150.0], button).clicked() {
-                        self.current_page = Some(Box::new(
-                            crate::pages::student_starter_code::StudentStarterCodePage::new(),
-                        ));
+                    let response = ui
+                        .add_enabled_ui(self.starter_code_available == Some(true), |ui| {
+                            ui.add_sized([150.0, 150.0], button)
+                        })
+                        .inner
+                        .on_disabled_hover_text(match self.starter_code_available {
+                            None => tr!("start-checking-starter-code"),
+                            Some(_) => tr!("start-no-starter-code"),
+                        });
+                    set_accessible_name(&response, &tr!("start-student-program"));
+                    if response.clicked() {
+                        let page = if self.state.settings.simple_student_mode {
+                            crate::pages::student_starter_code::StudentStarterCodePage::simplified()
+                        } else {
+                            crate::pages::student_starter_code::StudentStarterCodePage::new()
+                        };
+                        self.open_page(Box::new(page));
                     }
-                    ui.label("Default Student Program");
+                    ui.label(tr!("start-student-program"));
                 });
 
                 egui_alignments::stretch(ui);
             });
+
+            self.add_offline_mode_row(ui);
+            egui_alignments::row(ui, egui::Align::Center, |ui| {
+                egui_alignments::stretch(ui);
+                ui.label(tr!("start-language"));
+                if show_language_picker(ui, &mut self.state.settings)
+                    && let Err(e) = self.state.settings.save()
+                {
+                    self.status_text = Some(format!("{e}"));
+                }
+                egui_alignments::stretch(ui);
+            });
         });
     }
 
+    fn add_offline_mode_row(&mut self, ui: &mut egui::Ui) {
+        self.offline_dialog.update(ui.ctx());
+        if let Some(bundle_dir) = self.offline_dialog.take_picked() {
+            self.state.offline_source = Some(bundle_dir);
+        }
+        self.report_dialog.update(ui.ctx());
+        if let Some(destination) = self.report_dialog.take_picked() {
+            self.status_text = Some(match self.export_session_report(&destination) {
+                Ok(()) => tr!(
+                    "start-report-saved",
+                    path = destination.display().to_string()
+                ),
+                Err(e) => format!("{e:#}"),
+            });
+        }
+
+        egui_alignments::row(ui, egui::Align::Center, |ui| {
+            egui_alignments::stretch(ui);
+            if let Some(bundle_dir) = &self.state.offline_source {
+                ui.label(tr!(
+                    "start-offline-source",
+                    path = bundle_dir.display().to_string()
+                ));
+                if ui.link(tr!("start-go-online")).clicked() {
+                    self.state.offline_source = None;
+                }
+            } else {
+                if ui.link(tr!("start-offline-install")).clicked() {
+                    self.offline_dialog.pick_directory();
+                }
+                if ui.link(tr!("start-prepare-bundle")).clicked() {
+                    self.open_page(Box::new(
+                        crate::pages::offline_bundle::OfflineBundlePage::new(),
+                    ));
+                }
+            }
+            if (!self.state.session_report.cards.is_empty()
+                || !self.state.session_report.boards.is_empty())
+                && ui.link(tr!("start-save-report")).clicked()
+            {
+                self.report_dialog.save_file();
+            }
+            if ui.link(tr!("start-full-kit")).clicked() {
+                self.open_page(Box::new(
+                    crate::pages::full_kit_setup::FullKitSetupPage::new(),
+                ));
+            }
+            if ui.link(tr!("start-network-config")).clicked() {
+                self.open_page(Box::new(
+                    crate::pages::network_config::NetworkConfigPage::new(),
+                ));
+            }
+            if ui.link(tr!("start-card-audit")).clicked() {
+                self.open_page(Box::new(crate::pages::card_audit::CardAuditPage::new()));
+            }
+            if ui.link(tr!("start-card-inspection")).clicked() {
+                self.open_page(Box::new(
+                    crate::pages::card_inspection::CardInspectionPage::new(),
+                ));
+            }
+            if ui.link(tr!("start-kit-check")).clicked() {
+                self.open_page(Box::new(crate::pages::kit_check::KitCheckPage::new()));
+            }
+            if crate::pages::flash_stress_test::maintainer_mode_enabled()
+                && ui.link(tr!("start-flash-stress-test")).clicked()
+            {
+                self.open_page(Box::new(
+                    crate::pages::flash_stress_test::FlashStressTestPage::new(),
+                ));
+            }
+            if ui.link(tr!("start-laptop-check")).clicked() {
+                self.open_page(Box::new(crate::pages::laptop_check::LaptopCheckPage::new()));
+            }
+            if ui.link(tr!("start-diagnostics")).clicked() {
+                self.open_page(Box::new(crate::pages::diagnostics::DiagnosticsPage::new()));
+            }
+            if ui.link(tr!("start-settings")).clicked() {
+                self.open_page(Box::new(crate::pages::settings::SettingsPage::new()));
+            }
+            egui_alignments::stretch(ui);
+        });
+    }
+
+    fn export_session_report(&mut self, destination: &std::path::Path) -> anyhow::Result<()> {
+        let key = if self.state.settings.sign_session_reports {
+            Some(crate::utils::signing::load_signing_key()?.ok_or(anyhow::anyhow!(
+                "Session report signing is turned on, but this hub has no signing key. Generate one in Settings."
+            ))?)
+        } else {
+            None
+        };
+        let report = &mut self.state.session_report;
+        report.hub_name = self.state.settings.branding.hub_name.clone();
+        crate::utils::session_report::export_report(report, key.as_ref(), destination)
+    }
+
     fn add_top_panel(&mut self, ctx: &egui::Context) {
         let top_panel_frame = egui::containers::Frame::new()
-            .fill(egui::Color32::from_hex("#001E62").expect("Failed to parse color from hex."))
+            .fill(crate::theme::BEST_NAVY)
             .inner_margin(10);
         egui::TopBottomPanel::top("top_panel")
             .frame(top_panel_frame)
@@ -99,11 +500,16 @@ impl MyApp {
                             let icon = egui::include_image!(
                                 "../src/assets/icons/ic_fluent_arrow_hook_up_left_28_filled.svg"
                             );
-                            let start_over_button =
-                                egui::Button::image_and_text(icon, "Start Over")
-                                    .wrap_mode(egui::TextWrapMode::Extend)
-                                    .fill(egui::Color32::WHITE);
-                            if ui.add(start_over_button).clicked() {
+                            let start_over_button = egui::Button::image_and_text(
+                                icon,
+                                egui::RichText::new(tr!("app-start-over"))
+                                    .color(crate::theme::TEXT_ON_BRAND),
+                            )
+                            .wrap_mode(egui::TextWrapMode::Extend)
+                            .fill(egui::Color32::WHITE);
+                            let response = ui.add(start_over_button);
+                            show_focus_ring(ui, &response, egui::Color32::WHITE);
+                            if response.clicked() {
                                 self.current_page = None;
                             }
                         });
@@ -115,27 +521,251 @@ impl MyApp {
                                         .heading(),
                                 )
                                 .wrap_mode(egui::TextWrapMode::Extend);
-                                ui.add(title);
+                                mark_as_heading(&ui.add(title), 1);
                             }
                         });
-                        strip.empty();
+                        strip.cell(|ui| self.add_hub_branding(ui));
                     });
             });
     }
 
+    /// Shows how far through its steps the current page is, e.g. "Step 3 of 6: Download".
+    fn add_step_progress(&self, ctx: &egui::Context) {
+        let Some(progress) = self
+            .current_page
+            .as_ref()
+            .and_then(|page| page.step_progress())
+        else {
+            return;
+        };
+        egui::TopBottomPanel::top("step_progress").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.strong(tr!(
+                    "step-progress",
+                    current = progress.number(),
+                    total = progress.total(),
+                    label = progress.current_label()
+                ))
+                .on_hover_text(progress.labels().join(" › "));
+                ui.add(
+                    egui::ProgressBar::new(progress.number() as f32 / progress.total() as f32)
+                        .desired_height(6.0),
+                );
+            });
+        });
+    }
+
+    fn add_hub_branding(&self, ui: &mut egui::Ui) {
+        let branding = &self.state.settings.branding;
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            if let Some(ref logo_path) = branding.logo_path {
+                ui.add(
+                    egui::Image::new(format!("file://{}", logo_path.display())).max_height(32.0),
+                );
+            }
+            if !branding.hub_name.is_empty() {
+                let hub_name = egui::Label::new(
+                    egui::RichText::new(&branding.hub_name).color(egui::Color32::WHITE),
+                )
+                .wrap_mode(egui::TextWrapMode::Truncate);
+                ui.add(hub_name);
+            }
+        });
+    }
+
+    fn add_status_bar(&mut self, ctx: &egui::Context) {
+        let offline_warning = (self.state.network_online == Some(false)
+            && self.state.offline_source.is_none())
+        .then(|| tr!("app-no-internet"));
+        let Some(status) = offline_warning.clone().or_else(|| self.status_text.clone()) else {
+            return;
+        };
+        egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(status);
+                egui_alignments::stretch(ui);
+                if offline_warning.is_none() && ui.small_button(tr!("app-dismiss")).clicked() {
+                    self.status_text = None;
+                }
+            });
+        });
+    }
+
+    /// Tells the user a newer wizard is available without getting in the way of what they are
+    /// doing.
+    fn add_update_banner(&mut self, ctx: &egui::Context) {
+        if let Some(ref receiver) = self.self_update_receiver
+            && let Ok(result) = receiver.try_recv()
+        {
+            self.self_update_receiver = None;
+            match result {
+                // The installer can't replace the wizard while it is running.
+                Ok(()) => ctx.send_viewport_cmd(egui::ViewportCommand::Close),
+                Err(e) => {
+                    self.status_text = Some(tr!("app-update-failed", error = format!("{e:#}")))
+                }
+            }
+        }
+        let Some(ref release) = self.wizard_update else {
+            return;
+        };
+        let mut dismissed = false;
+        egui::TopBottomPanel::top("update_banner").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(tr!(
+                    "app-update-available",
+                    version = release.display_name()
+                ));
+                ui.hyperlink_to(
+                    tr!("app-download-update"),
+                    crate::utils::self_update::release_page_url(release),
+                );
+                #[cfg(target_os = "windows")]
+                if self.self_update_receiver.is_some() {
+                    ui.spinner();
+                } else if ui.small_button(tr!("app-update-now")).clicked() {
+                    let release = release.clone();
+                    let download_dir = self.state.tmp_dir.path().join("wizard_update");
+                    let network = self.state.network();
+                    let ctx = ctx.clone();
+                    let (tx, rx) = std::sync::mpsc::channel();
+                    self.self_update_receiver = Some(rx);
+                    std::thread::spawn(move || {
+                        let _ = tx.send(crate::utils::self_update::start_installer(
+                            &network,
+                            &release,
+                            &download_dir,
+                        ));
+                        ctx.request_repaint();
+                    });
+                }
+                egui_alignments::stretch(ui);
+                dismissed = ui.small_button(tr!("app-dismiss")).clicked();
+            });
+        });
+        if dismissed {
+            self.wizard_update = None;
+        }
+    }
+
+    /// Handles the events published since the last frame, and returns how many there were.
+    fn dispatch_events(&mut self) -> usize {
+        let events = self.event_bus.drain();
+        let count = events.len();
+        for event in events {
+            match &event {
+                AppEvent::NetworkChanged { online } => self.state.network_online = Some(*online),
+                AppEvent::DownloadFinished(path) => {
+                    self.status_text = path.file_name().map(|name| {
+                        tr!("app-downloaded", name = name.to_string_lossy().to_string())
+                    });
+                }
+                AppEvent::LowDiskSpace(path) => {
+                    self.status_text =
+                        Some(tr!("app-low-disk-space", path = path.display().to_string()));
+                }
+                AppEvent::DriveAdded(drive) => {
+                    self.status_text = Some(tr!("app-drive-added", drive = drive.to_string()));
+                }
+                AppEvent::DriveRemoved(drive) => {
+                    self.status_text = Some(tr!("app-drive-removed", drive = drive.to_string()));
+                }
+                AppEvent::WizardUpdateAvailable(release) => {
+                    self.wizard_update = Some(release.clone());
+                }
+                AppEvent::NewReleaseAvailable {
+                    repo,
+                    display_name,
+                    release,
+                } => {
+                    if *repo == self.state.sources.starter_code {
+                        self.starter_code_checked_source = None;
+                    }
+                    self.status_text = Some(tr!(
+                        "app-new-release",
+                        name = display_name,
+                        version = release.display_name()
+                    ));
+                }
+            }
+            if let Some(page) = &mut self.current_page {
+                page.handle_event(&mut self.state, &event);
+            }
+        }
+        count
+    }
+
     fn show_error_modal(&mut self, ctx: &egui::Context) {
+        self.log_dialog.update(ctx);
+        if let Some(destination) = self.log_dialog.take_picked()
+            && let Some(ref log_file) = self.state.log_file
+        {
+            self.status_text = Some(match crate::logging::export_log(log_file, &destination) {
+                Ok(()) => tr!("error-log-saved", path = destination.display().to_string()),
+                Err(e) => format!("{e:#}"),
+            });
+        }
         egui::Modal::new(egui::Id::new("ErrorModal")).show(ctx, |ui| {
-            ui.heading("Error");
-            ui.label("Sorry, an error has occurred. The install process has been cancelled.");
+            let recoverable = self
+                .page_error
+                .as_ref()
+                .is_some_and(|err| err.is::<StepFailed>());
+            let page_title = self
+                .current_page
+                .as_ref()
+                .map(|page| page.get_title())
+                .unwrap_or_default();
+            heading(ui, tr!("error-heading"));
+            if recoverable {
+                ui.label(tr!("error-step-failed"));
+            } else {
+                ui.label(tr!("error-cancelled"));
+            }
             ui.separator();
             if let Some(err) = &self.page_error {
                 ui.label(format!("{}", err));
+                if let Some(report) = panic_report(err) {
+                    egui::CollapsingHeader::new(tr!("error-technical-details")).show(ui, |ui| {
+                        egui::ScrollArea::vertical()
+                            .max_height(200.0)
+                            .show(ui, |ui| ui.monospace(report.details()));
+                    });
+                }
             } else {
-                ui.label("No error information found.");
+                ui.label(tr!("error-no-information"));
+            }
+            let support_contact = &self.state.settings.branding.support_contact;
+            if !support_contact.is_empty() {
+                ui.separator();
+                ui.label(tr!("error-support-contact", contact = support_contact));
             }
             egui_alignments::row(ui, egui::Align::Center, |ui| {
                 egui_alignments::stretch(ui);
-                if ui.button("Ok").clicked() {
+                if self.state.log_file.is_some() && ui.button(tr!("error-save-log")).clicked() {
+                    self.log_dialog.save_file();
+                }
+                if let Some(err) = &self.page_error {
+                    if ui.button(tr!("error-copy-details")).clicked() {
+                        ui.ctx().copy_text(error_details(&page_title, err));
+                    }
+                    if ui.button(tr!("error-report-issue")).clicked() {
+                        ui.ctx()
+                            .open_url(egui::OpenUrl::new_tab(issue_url(&page_title, err)));
+                    }
+                }
+                if recoverable && ui.button(tr!("error-retry-step")).clicked() {
+                    self.page_error = None;
+                    if let Some(page) = &mut self.current_page {
+                        log::info!("Retrying step on page: {}", page.get_title());
+                        page.retry_step();
+                    }
+                }
+                let ok_text = if recoverable {
+                    tr!("app-start-over")
+                } else {
+                    tr!("common-ok")
+                };
+                if ui.button(ok_text).clicked() {
                     self.page_error = None;
                     self.current_page = None;
                 }
@@ -144,20 +774,154 @@ impl MyApp {
     }
 }
 
+/// Longest issue title, in characters. GitHub cuts titles off at 256.
+const ISSUE_TITLE_LENGTH: usize = 120;
+/// Longest error text put in an issue, so the URL stays under what browsers accept.
+const ISSUE_ERROR_LENGTH: usize = 2000;
+
+/// The panic behind a page error, looking inside the wrapper of a step that can be retried.
+fn panic_report(err: &anyhow::Error) -> Option<&PanicReport> {
+    find_report(
+        err.downcast_ref::<StepFailed>()
+            .map_or(err, StepFailed::error),
+    )
+}
+
+/// The error as text to paste into a message to support, with the backtrace if it was a panic.
+fn error_details(page_title: &str, err: &anyhow::Error) -> String {
+    let mut details = format!("{}\n{page_title}: {err:#}\n", build_description());
+    if let Some(report) = panic_report(err) {
+        details.push_str(&format!("\n{}", report.details()));
+    }
+    details
+}
+
+/// A new GitHub issue for the error. The backtrace is left out to keep the URL short enough.
+fn issue_url(page_title: &str, err: &anyhow::Error) -> String {
+    let title = truncate(&format!("{page_title}: {err}"), ISSUE_TITLE_LENGTH);
+    let body = format!(
+        "<!-- What were you doing when this happened? Attach the log file if you can. -->\n\n\
+         **Error**\n```\n{}\n```\n\n**Wizard:** {}\n",
+        truncate(&format!("{err:#}"), ISSUE_ERROR_LENGTH),
+        build_description()
+    );
+    crate::utils::self_update::new_issue_url(&title, &body)
+}
+
+fn build_description() -> String {
+    format!(
+        "BEST Gizmo Setup Wizard {} on {} ({} build)",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        crate::utils::platform::BUILD_ARCH
+    )
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
 impl App for MyApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut Frame) {
-        ctx.set_visuals(egui::Visuals::light());
+    fn update(&mut self, ctx: &egui::Context, frame: &mut Frame) {
+        if self.accessibility.update(ctx, &mut self.state.settings)
+            && let Err(e) = self.state.settings.save()
+        {
+            self.status_text = Some(format!("{e}"));
+        }
+        let events = self.dispatch_events();
+        let refreshed = crate::utils::cache::take_refreshed_assets();
+        if !refreshed.is_empty() {
+            self.status_text = Some(tr!("app-cache-refreshed", files = refreshed.join(", ")));
+        }
+        self.state.compatibility.update(
+            ctx,
+            &self.state.network.read().unwrap_or_else(|e| e.into_inner()),
+            &self.state.offline_source,
+        );
+        if let Some(e) = self.status_exporter.update(
+            ctx,
+            self.state.settings.status_export_dir.as_deref(),
+            &self.state.settings.branding.hub_name,
+            &self.state.session_report,
+        ) {
+            log::warn!("Could not export the station status: {e:#}");
+            self.status_text = Some(tr!("app-status-export-failed", error = format!("{e:#}")));
+        }
+        self.performance_panel.update(
+            ctx,
+            frame.info().cpu_usage,
+            QueueDepths {
+                events,
+                prompts: self.prompt_queue.waiting(),
+            },
+        );
+        self.add_update_banner(ctx);
+        self.add_status_bar(ctx);
         if self.current_page.is_some() {
             self.add_top_panel(ctx);
+            self.add_step_progress(ctx);
             egui::CentralPanel::default().show(ctx, |ui| {
-                if self.page_error.is_some() {
-                    self.show_error_modal(ctx);
-                } else if let Some(page) = &mut self.current_page {
-                    self.page_error = page.run(&mut self.state, ui).err();
-                }
+                add_page_scroll_area(ui, |ui| {
+                    if self.page_error.is_some() {
+                        self.show_error_modal(ctx);
+                    } else if let Some(page) = &mut self.current_page {
+                        // A panic in the page shows in the error dialog instead of closing the app.
+                        self.page_error = catch_panic(|| page.run(&mut self.state, ui)).err();
+                        if let Some(ref err) = self.page_error {
+                            log::error!("{}: {err:#}", page.get_title());
+                            crate::utils::sounds::play_cue(Cue::Failure);
+                        }
+                    }
+                });
             });
+            if self.page_error.is_none() {
+                self.prompt_queue.show(ctx);
+            }
         } else {
-            egui::CentralPanel::default().show(ctx, |ui| self.run_start_page(ui));
+            // Workers from a closed page may still be waiting on an answer.
+            self.prompt_queue.cancel_all();
+            egui::CentralPanel::default().show(ctx, |ui| {
+                add_page_scroll_area(ui, |ui| self.run_start_page(ui))
+            });
+        }
+        if crate::utils::demo::demo_mode_enabled() {
+            add_demo_watermark(ctx);
         }
+        self.performance_panel.show(ctx);
     }
 }
+
+/// Pages are laid out to fill the window. When it is made smaller or the interface is zoomed in,
+/// they scroll instead of being cut off.
+fn add_page_scroll_area(ui: &mut egui::Ui, add_contents: impl FnOnce(&mut egui::Ui)) {
+    egui::ScrollArea::both()
+        .auto_shrink(false)
+        .show(ui, add_contents);
+}
+
+/// Marks every screen in demonstration mode, so nobody mistakes a projected demo for a real
+/// install.
+fn add_demo_watermark(ctx: &egui::Context) {
+    let painter = ctx.layer_painter(egui::LayerId::new(
+        egui::Order::Foreground,
+        egui::Id::new("demo_watermark"),
+    ));
+    let screen = ctx.content_rect();
+    painter.text(
+        screen.center(),
+        egui::Align2::CENTER_CENTER,
+        tr!("app-demo-badge"),
+        egui::FontId::proportional(screen.height() * 0.4),
+        egui::Color32::from_rgba_unmultiplied(200, 0, 0, 40),
+    );
+    painter.text(
+        screen.center_bottom() - egui::vec2(0.0, 40.0),
+        egui::Align2::CENTER_BOTTOM,
+        tr!("app-demo-watermark"),
+        egui::FontId::proportional(14.0),
+        egui::Color32::from_rgba_unmultiplied(200, 0, 0, 160),
+    );
+}
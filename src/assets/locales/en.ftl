@@ -0,0 +1,761 @@
+# English text for the wizard. Every message used in the code must be here, since other
+# languages fall back to it.
+
+## Start page
+
+app-title = BEST Gizmo Software Installer
+start-intro = This tool will help you install or update your Gizmo software.
+start-instructions = Select which software you would like to install, then follow the instructions.
+start-session-unfinished = A driver station setup from { $saved } UTC was not finished. { $done } of { $total } teams are done.
+start-resume-session = Resume Previous Session
+start-discard-session = Discard
+start-driver-station = Driver Station
+start-system-firmware = System Firmware
+start-system-settings = System Settings
+start-student-program = Default Student Program
+start-checking-starter-code = Checking for a starter code release...
+start-no-starter-code = No starter code release is available yet. Check back later, or load an offline bundle that includes one.
+start-report-saved = Session report saved to { $path }
+start-offline-source = Offline install from { $path }
+start-go-online = Go back online
+start-offline-install = Offline install...
+start-prepare-bundle = Prepare offline bundle...
+start-save-report = Save session report...
+start-full-kit = Set up a full kit...
+start-network-config = Change card network settings...
+start-card-audit = Audit returned cards...
+start-card-inspection = Inspect or repair a card...
+start-kit-check = Check a finished kit...
+start-flash-stress-test = Flash stress test...
+start-laptop-check = Prepare new laptop...
+start-diagnostics = Diagnostics
+start-settings = Settings
+
+## Top bar, status bar, and banners
+
+app-start-over = Start Over
+app-no-internet = No internet connection
+app-dismiss = Dismiss
+app-update-failed = Could not start the update: { $error }
+app-update-available = A new version of the wizard is available: { $version }
+app-download-update = Download update
+app-update-now = Update now
+app-downloaded = Downloaded { $name }
+app-low-disk-space = Low disk space in { $path }. Installs will be slower until space is freed.
+app-drive-added = Drive connected: { $drive }
+app-drive-removed = Drive removed: { $drive }
+app-new-release = A new { $name } release is available: { $version }
+app-status-export-failed = Could not update the dashboard status file: { $error }
+app-network-settings-failed = Could not use the network settings: { $error }
+app-cache-refreshed = The cached copy of { $files } was damaged, so it was downloaded again.
+app-demo-watermark = Demonstration mode: nothing is written to real drives
+app-demo-badge = DEMO
+app-conflicts-found = Found { $count } configuration conflicts.
+
+## Error dialog
+
+error-heading = Error
+error-step-failed = Sorry, this step failed. You can try it again or start over.
+error-cancelled = Sorry, an error has occurred. The install process has been cancelled.
+error-no-information = No error information found.
+error-support-contact = For help, contact { $contact }.
+error-save-log = Save Log File...
+error-retry-step = Retry Step
+error-copy-details = Copy Error Details
+error-report-issue = Report This Issue
+error-technical-details = Technical details
+error-log-saved = Log file saved to { $path }
+
+## File dialogs
+
+dialog-offline-bundle = Select Offline Bundle Folder
+dialog-bundle-folder = Select Bundle Folder
+dialog-save-log = Save Log File
+dialog-save-report = Save Session Report
+dialog-hub-logo = Select Hub Logo
+dialog-session-report = Select Session Report
+dialog-dashboard-folder = Select Dashboard Folder
+dialog-sound = Select Sound
+dialog-ca-bundle = Select Certificate File
+file-saved-to = Saved to { $path }
+file-uf2-filter = UF2 Firmware
+file-images-filter = Images
+file-session-reports-filter = Session Reports
+file-wav-filter = WAV Files
+file-certificates-filter = Certificates
+
+## Language picker
+
+language-system = System language
+start-language = Language:
+
+## Shared buttons
+
+common-next = Next
+common-ok = Ok
+common-cancel = Cancel
+common-close = Close
+common-refresh = Refresh
+common-retry = Retry
+common-try-again = Try Again
+common-yes = Yes
+common-no = No
+
+## Drives and teams
+
+team-number-label = Team number:
+team-not-number = "{ $team }" is not a number.
+team-wrong-digits = { $team } should have { $min } to { $max } digits.
+team-out-of-range = { $team } is not between { $lowest } and { $highest }.
+drives-none = No removable drives found.
+drives-searching = Searching for removable drives...
+drives-choose-device = Choose Device
+drives-free-of = { $free } free of { $total }
+drives-check-failed = Could not check what kind of drive this is: { $error }
+drives-system-disk = This drive holds the computer's operating system.
+drives-unexpected-bus = This drive is connected over { $bus }, not USB or an SD card slot.
+drives-unknown-bus = an unknown bus
+drives-too-large = This drive holds { $size }, which is larger than a typical microSD card.
+
+## Installs
+
+install-complete = Installation Complete
+install-disconnect = You can now disconnect the device from the computer.
+install-another-device = Setup Another Device
+bootsel-not-gizmo = This Drive Is Not a Gizmo
+bootsel-not-bootloader = { $drive } does not look like a board in BOOTSEL mode. Copying firmware onto it will not install anything.
+bootsel-hold = Make sure you held the BOOTSEL button while plugging in the board, then choose the drive named "RPI-RP2".
+bootsel-install-anyway = Install Anyway
+bootsel-choose-another-drive = Choose Another Drive
+
+## Releases
+
+releases-fetching = Fetching available releases...
+releases-fetch-failed = Could not fetch releases: { $error }
+releases-use-local-file = Use local file instead
+releases-software-version = Software Version
+fetch-work-offline = Work offline
+fetch-work-offline-hint = Use the releases this computer has already downloaded.
+fetch-nothing-downloaded = No releases have been downloaded on this computer.
+
+## Network settings
+
+network-name = Network name:
+network-key = Network key:
+network-server = Field server address:
+network-channel = Wi-Fi channel:
+network-name-hint = Chosen from the team number
+network-name-too-long = The network name must be at most 32 bytes long.
+network-name-spaces = The network name can't start or end with a space.
+network-key-invalid = The network key must be 8 to 63 plain (ASCII) characters.
+network-server-invalid = The field server address must be an IPv4 address, like 10.0.0.2.
+network-channel-invalid = The Wi-Fi channel must be between 1 and 11.
+network-key-short = This network key is short. Keys of 12 or more characters are much harder to guess.
+network-key-simple = This network key only uses one kind of character. Mix letters, digits, and symbols to make it harder to guess.
+network-write-settings = Write Settings
+network-writing-settings = Writing settings...
+network-settings-written = Settings Written
+
+## Step progress
+
+step-progress = Step { $current } of { $total }: { $label }
+step-version = Version
+step-versions = Versions
+step-source-card = Source card
+step-teams = Teams
+step-network = Network
+step-download = Download
+step-card = Card
+step-assign = Teams
+step-cards = Cards
+step-device = Device
+step-install = Install
+step-copy-code = Copy code
+step-copy-libraries = Copy libraries
+step-verify = Verify
+step-remove-card = Remove card
+step-build = Build
+step-board-revision = Board revision
+step-settings = Settings
+step-write = Write
+step-location = Location
+step-connect = Connect
+step-check = Check
+step-result = Result
+step-audit = Audit
+step-inspect = Inspect
+step-report = Report
+step-done = Done
+
+## Release notes
+
+notes-none = No release notes.
+notes-whats-new = What's new in { $version }
+
+## Version picker
+
+version-pick = Pick a version
+version-select = Select Version
+version-search = Search versions
+version-no-matches = No matching versions.
+version-prereleases = Include prereleases
+version-prereleases-hint = Show test builds of upcoming releases. These may not work at events.
+version-development = Include development builds
+version-development-hint = Builds from GitHub Actions that have not been released yet, for testing. Needs a GitHub access token.
+version-no-development-builds = No development builds were found. They need a GitHub access token in Settings that can read the repository's Actions.
+
+## Compatibility warnings
+
+compat-driver-station = driver station
+compat-firmware = system firmware
+compat-starter-code = starter code
+compat-may-not-work = { $version } may not work with { $component } { $installed }, which was installed earlier.
+compat-does-not-work = { $version } does not work with { $component } { $installed }, which was installed earlier.
+
+## Card checks
+
+check-deep-title = Deep Capacity Check
+check-card-test-title = Test Card
+check-deep-intro = This fills all free space on the card with test data and reads it back to find counterfeit cards that report more space than they have. It can take over an hour for large cards.
+check-deep-warning = Warning: if the card is counterfeit, the test will overwrite files already on it. Back up anything you need from this card first.
+check-deep-acknowledge = I understand that data on this card may be lost
+check-start-check = Start Check
+check-card-test-intro = This writes test data to the card's free space and reads it back, to measure how fast the card writes and find bad sectors. It takes about a minute and leaves the files on the card alone.
+check-advertised-size = Size printed on the card:
+check-start-test = Start Test
+check-writing = Writing test data ({ $done } of { $total })
+check-verifying = Verifying test data ({ $done } of { $total })
+check-stop = Stop
+check-deep-passed = All { $tested } of test data was read back correctly. This card looks genuine.
+check-deep-failed = This card appears to be counterfeit. Data was lost after the first { $good }, and { $bad } of { $tested } did not read back correctly. Do not use this card.
+check-bad-sectors = { $bad } of the { $tested } of test data did not read back correctly. The card has bad sectors.
+check-slow = The card writes at { $speed }/s, slower than the { $minimum }/s a Class 10 card should manage. Installs will be slow, and the card may be counterfeit or worn out.
+check-too-small = The card holds { $actual }, much less than the { $advertised } on its label. It may be counterfeit.
+check-card-passed = The card writes at { $speed }/s and read back all test data correctly.
+check-use-different-card = Consider using a different card.
+check-full-hash = Read every file (much slower, but catches more damage)
+
+## Themes
+
+theme-light = Light
+theme-dark = Dark
+theme-system = Match system
+
+## System processor settings
+
+sysconfig-title = System Processor Settings
+sysconfig-intro = These settings are written to the system processor's configuration.
+sysconfig-step-power = 1. Power on the Gizmo.
+sysconfig-step-connect = 2. Connect the system processor to your computer with the USB cable.
+sysconfig-step-refresh = 3. Click the "Refresh" button to update the list below.
+sysconfig-step-select = 4. Select the drive from the list and click "Write Settings". The drive should be named "{ $label }".
+sysconfig-change-team = Change it to team { $team }
+sysconfig-keep-team = Keep team { $team }
+sysconfig-already-set-up = This system processor is already set up for team { $team }.
+sysconfig-different-team = Different Team
+sysconfig-done = The system processor is now set up for team { $team }. You can disconnect it from the computer.
+sysconfig-another-hint = To configure another system processor, click "Configure Another Device". If you are done, you can close the wizard or click "Start Over".
+sysconfig-another = Configure Another Device
+
+## System firmware
+
+fw-build-dir-dialog = Select Firmware Build Directory
+fw-export-log-dialog = Export Flashing Log
+fw-version-heading = Firmware Version
+fw-version-intro = Select the version of the firmware you want to install. Usually, this should be the latest version.
+fw-use-build-dir = Use a local build directory instead
+fw-use-build-dir-hint = For firmware developers: pick a cargo target or pico-sdk build directory, and choose from the UF2 files built there.
+fw-hardware-heading = Choose Hardware Version
+fw-hardware-intro = Select the hardware version of the Gizmo PCB you are using. This should be printed on the board and should look something like "v01.00" or "v00.r6b"
+fw-no-firmware-files = Could not recognize any firmware files in the selected release.
+fw-downloading = Downloading firmware file...
+fw-build-heading = Choose Firmware Build
+fw-build-files = UF2 files in { $directory }, newest first:
+fw-build-none = No UF2 files were found. Build the firmware, then click Rescan.
+fw-rescan = Rescan
+fw-revision-auto = This release only supports board revision { $revision }, so it was selected automatically.
+fw-step-bootsel = 1. Press and hold the BOOTSEL button on the system processor.
+fw-step-connect = 2. Connect the system processor to your computer with the USB cable.
+fw-step-release = 3. Release the BOOTSEL button.
+fw-step-install = 4. The firmware is installed automatically once the drive named "RPI-RP2" appears. If it does not, click the "Refresh" button, select the drive from the list, and click "Install Firmware".
+fw-batch-mode = Batch mode
+fw-batch-mode-hint = Flash and verify each board as soon as it is plugged in, then wait for the next one, keeping a log of every board.
+fw-install = Install Firmware
+fw-installing = Installing firmware...
+fw-verifying = Verifying Firmware
+fw-board-id = Board ID: { $board }
+fw-waiting-restart = Waiting for the system processor to restart...
+fw-wrong-version = The system processor is running firmware { $version }, but { $expected } was installed. Try installing again.
+fw-running-version = The system processor is running firmware { $version }.
+fw-check-later = The firmware may still have installed correctly. You can check it later with "Check a finished kit".
+fw-log-save-failed = Could not save the log: { $error }
+fw-batch-summary = { $flashed } board(s) flashed: { $verified } verified, { $failed } failed.
+fw-batch-board = Board { $number }{ $board }: { $result }
+fw-export-log = Export Log...
+fw-export-log-hint = Save a CSV with each board's serial number from { $file }, where the bootloader lists one, and whether it was verified.
+fw-another-hint = To install system firmware onto another device, click "Setup Another Device". If you are done installing system firmware, you can close the wizard or click "Start Over".
+fw-title = System Firmware Install
+fw-built-now = built just now
+fw-built-minutes = built { $minutes } minutes ago
+fw-built-hours = built { $hours } hours ago
+fw-built-days = built { $days } days ago
+fw-start-address = at { $address }
+fw-artifact-version = version { $version }
+fw-wrong-chip = This firmware is for { $family }, not the Gizmo's RP2040.
+fw-result-verified = Verified
+fw-result-wrong-version = Wrong version ({ $version })
+fw-result-not-verified = Not verified: { $error }
+
+## Driver station setup
+
+ds-archive-filter = Archive
+ds-disk-image-filter = Disk Image
+ds-roster-dialog = Import Team Roster
+ds-csv-filter = CSV File
+ds-progress-dialog = Export Team Progress
+ds-version-intro = Select the version of the software you want to install. Usually, this should be the latest version.
+ds-clone-instead = Clone an existing card instead
+ds-clone-instead-hint = Copy a card you have already set up to each new card, changing only the team number.
+ds-teams-heading = Team Numbers
+ds-teams-intro = Enter your team numbers, one per line.
+ds-team-repeated = { $team } is already on line { $line }.
+ds-team-line-problem = Line { $line }: { $problem }
+ds-import-csv = Import from CSV...
+ds-teams-count = { $count } team numbers.
+ds-single-team-warning = The selected software looks like a demo or single-team build. It is not intended for provisioning multiple teams at a competition.
+ds-single-team-acknowledge = I understand, continue anyway
+ds-field-heading = Field Settings
+ds-field-intro = These settings are written to every card along with its team number.
+ds-downloading = Downloading software archive...
+ds-downloading-progress = Downloading software archive ({ $done } of { $total })
+ds-clone-heading = Choose Card to Clone
+ds-clone-intro = Insert a card you have already set up and tested, then select it below. Its files are copied to this computer, so you can remove it once it has been read. Each new card gets the same files, with its own team number and volume label.
+ds-reading-card = Reading card...
+ds-read-card = Read Card
+ds-drives-heading = Choose Drives
+ds-drives-remaining = Setting up driver stations for { $remaining } remaining team(s), starting with team { $team }.
+ds-drives-step-insert = 1. Insert the microSD cards for the next teams into your computer. You can use several card readers at once.
+ds-drives-step-refresh = 2. Click the "Refresh" button to update the list below.
+ds-drives-step-select = 3. Select the microSD card drives from the list and click "Install Software".
+ds-drive-for-team = { $drive } -> team { $team }
+ds-test-card = Test card...
+ds-deep-check = Deep capacity check...
+ds-right-click-hint = Right-click a drive to test its speed and health, or to check whether it is a counterfeit card.
+ds-install = Install Software
+ds-assign-heading = Match Teams to Cards
+ds-assign-intro = Choose which team goes on each card, then click "Install Software".
+ds-assign-cards = Cards
+ds-assign-teams = Teams
+ds-assign-team-on-card = { $team } -> card { $card }
+ds-assign-drag-hint = Drag a team onto a card to assign it. Two cards swap teams if the team was already on another card.
+ds-assign-parallel = Install all cards at the same time
+ds-assign-choose-cards = Choose Different Cards
+ds-erase-heading = Erase These Drives?
+ds-erase-intro = Some of the selected drives might not be microSD cards. Everything on them will be erased.
+ds-erase-type = Type ERASE to continue.
+ds-erase-confirm = Erase and Install
+ds-installing-heading = Installing Software
+ds-job-team = Team { $team }
+ds-formatting = Formatting drive...
+ds-copying = Copying files ({ $done } of { $total })
+ds-writing-image = Writing image ({ $size })
+ds-verifying-image = Verifying image ({ $size })
+ds-flushing = Finishing writes...
+ds-ejecting = Ejecting card...
+ds-done = Done
+ds-waiting = Waiting
+ds-stopping = Stopping. Cards that are being formatted will finish formatting first.
+ds-cancel-hint = Cards that haven't been erased yet are left alone. Cards that have been erased are cleared so they can be set up again.
+ds-progress-save-failed = Could not save team progress: { $error }
+ds-teams-panel = Teams
+ds-teams-done = { $done } of { $total } done
+ds-export = Export...
+ds-job-failed = Team { $team } ({ $drive }): { $error }
+ds-next-batch = Once you have removed the cards, click Next to set up the remaining teams. Cards that failed will be tried again.
+ds-next-card-hint = You can also take out the finished cards and put in a new one. The wizard notices it and offers it to the next team.
+ds-new-card-heading = New Card Found
+ds-new-card-found = A card was inserted in { $drive }. Set it up for team { $team }? Everything on it will be erased.
+ds-new-card-has-version = This card already has driver station software { $version }.
+ds-new-card-set-up = Set Up Card
+ds-new-card-not-now = Not Now
+ds-all-done = All team numbers have been processed. You can now close the wizard or click 'Start Over'.
+ds-streak-paused = Paused because { $failures } cards in a row failed. When this many fail, the problem is usually the card reader or this computer, not the cards.
+ds-streak-check = Before continuing, check for these common causes:
+ds-show-diagnostics = Show Diagnostics
+ds-continue = Continue
+ds-continue-hint = Set up the remaining teams. The batch pauses again if { $limit } more cards fail in a row.
+ds-cause-reader = The card reader is loose, failing, or on a USB hub without enough power. Try another USB port or another reader.
+ds-cause-other-program = Another program is using the cards, such as a file manager window, antivirus scan, or backup tool. Close it and try again.
+ds-cause-permissions = The wizard isn't allowed to format drives. Run it as an administrator, or approve the password prompt when it appears.
+ds-cause-disk-space = This computer is low on disk space, so the software can't be unpacked. Free some space or turn off the persistent cache.
+ds-remove-ejected = { $drive } was ejected. Remove it and insert it into the driver station for team { $team }.
+ds-remove-not-ejected = { $drive } could not be ejected. Wait a few seconds for it to finish writing, then remove it and insert it into the driver station for team { $team }.
+ds-changes-since = What changed since { $version }
+ds-progress-pending = Pending
+ds-progress-formatting = Formatting
+ds-progress-installing = Installing
+ds-progress-card-errors = Done (card errors)
+ds-progress-failed = Failed
+ds-io-errors = Done, but the computer logged { $errors } error(s) writing to this card. It may be failing, so consider replacing it.
+ds-overwrite-title = Overwrite Card?
+ds-overwrite-message = { $drive } is set up for team { $existing_team } ({ $version }). Erase it and set it up for team { $team }?
+ds-worn-card = This card has been set up { $count } times. Consider retiring it before it fails at a competition.
+ds-title = Driver Station Software Install
+
+## Student starter code
+
+code-version-intro = Select the version of the starter code you want to install. Usually, this should be the latest version.
+code-install-runtime = Install CircuitPython { $version } first
+code-install-runtime-hint = Use this for brand new boards or boards that were used for something else.
+code-install-libraries = Copy the Gizmo libraries onto the CIRCUITPY drive
+code-download-failed = The program could not be downloaded. Ask your teacher for help.
+code-getting-latest = Getting the latest program...
+code-downloading = Downloading starter program file...
+code-step-bootsel = 1. Press and hold the BOOTSEL button on the student processor.
+code-step-connect = 2. Connect the student processor to your computer with the USB cable.
+code-step-release = 3. Release the BOOTSEL button.
+code-step-refresh = 4. Click the "Refresh" button to update the list below.
+code-step-select = 5. Select the drive from the list and click "Install Program". The drive should be named "RPI-RP2".
+code-install-program = Install Program
+code-plug-in-heading = Plug In Your Board
+code-simple-step-hold = 1. Hold down the BOOTSEL button on the student processor.
+code-simple-step-plug = 2. While holding the button, plug the board into this computer.
+code-simple-step-release = 3. Let go of the button.
+code-board-found = Board found!
+code-looking-for-board = Looking for your board...
+code-install = Install
+code-installing-runtime = Installing CircuitPython...
+code-installing-program = Installing starter program...
+code-copying-code = Waiting for the { $volume } drive and copying the starter code...
+code-copying-libraries = Copying the Gizmo libraries to { $volume }/lib...
+code-libraries-copied = Copied { $count } library files to { $volume }/lib.
+code-another-hint = To install the starter program onto another device, click "Setup Another Device". If you are done installing starter code onto Gizmos, you can close the wizard or click "Start Over".
+code-title = BEST Default Program Install
+
+## Configuration conflicts
+
+conflicts-title = Configuration Conflicts
+conflicts-intro = Some of the wizard's configuration sources disagree. Each conflict was settled as shown below, and nothing was saved. Change the losing source to make the conflict go away.
+conflicts-directive = Directive
+conflicts-conflict = Conflict
+conflicts-resolution = Resolution
+conflicts-outcome = { $resolution } ({ $winner } over { $loser })
+conflicts-precedence = When sources disagree, the first one in this list wins:
+conflicts-start-over = Click "Start Over" to continue.
+
+## Diagnostics
+
+diag-title = Diagnostics
+diag-version = Wizard version:
+diag-os = Operating system:
+diag-build = Wizard build:
+diag-computer = Computer:
+diag-log-file = Log file:
+diag-not-available = Not available
+diag-emulated = This { $build } build is running under emulation. Installing the { $arch } build of the wizard will be faster and more reliable.
+diag-commands = Required system commands:
+diag-command-found = { $command }: found
+diag-command-missing = { $command }: missing
+diag-performance = If the wizard feels slow, press { $shortcut } to show performance statistics.
+perf-title = Performance
+perf-frames = Frames recorded:
+perf-frame-interval = Time between frames:
+perf-update-time = Time spent in update:
+perf-events = Events per frame (max):
+perf-prompts = Prompts waiting:
+perf-repaint-causes = Repaint causes:
+perf-no-repaints = None yet.
+perf-reset = Reset
+perf-times = { $average } ms average, { $slowest } ms slowest
+
+## Full kit setup
+
+kit-team-number = Team Number
+kit-system-firmware = System Firmware
+kit-starter-code = Student Starter Code
+kit-driver-station = Driver Station Card
+kit-finished = Finished
+kit-intro = This sets up the system firmware, the default student program, and the driver station card for one team, one after another.
+kit-enter-team = Enter the number of the team this kit is for.
+kit-progress = Team { $team }: part { $part } of 3, { $label }
+kit-skip = Skip this part
+kit-skip-hint = For kits that already have this part set up.
+kit-part-done = { $label } Done
+kit-firmware-family = Installed firmware { $version }. The next parts will start out on the same release family.
+kit-continue-to = Continue to { $label }
+kit-complete = Kit for Team { $team } Complete
+kit-part-skipped = { $label }: skipped
+kit-part-finished = { $label }: done
+kit-remove-card = Remove the driver station card from the computer and put it in the kit.
+kit-check-hint = Check the finished kit with "Check a finished kit" on the start page, or click "Start Over".
+kit-another = Set Up Another Kit
+kit-title = Full Kit Setup
+
+## Kit check
+
+kitcheck-card-unreadable = Could not read the driver station card: { $error }
+kitcheck-firmware-unreadable = Could not read the system firmware version: { $error }
+kitcheck-incompatible = Driver station { $driver_station } and system firmware { $firmware } are not compatible. Reinstall one of them so both come from the same major release.
+kitcheck-connect = Connect the Kit
+kitcheck-step-card = 1. Insert the kit's driver station microSD card into your computer.
+kitcheck-step-gizmo = 2. Power on the Gizmo and connect the system processor to your computer with the USB cable.
+kitcheck-step-select = 3. Click the "Refresh" button, select the card and the Gizmo below, and click "Check Kit".
+kitcheck-card = Driver station card:
+kitcheck-gizmo = Gizmo:
+kitcheck-no-gizmo = No Gizmo found.
+kitcheck-searching = Searching for devices...
+kitcheck-button = Check Kit
+kitcheck-checking = Checking kit...
+kitcheck-ok = Kit OK
+kitcheck-not-ready = Kit Not Ready
+kitcheck-driver-station = Driver station: { $version } for team { $team }
+kitcheck-firmware = System firmware: { $version }
+kitcheck-another = Check Another Kit
+kitcheck-title = Kit Check
+
+## Network configuration
+
+netconfig-choose-card = Choose Card
+netconfig-choose-card-intro = Insert a driver station card that has already been set up, then select it below.
+netconfig-card-team = { $drive } (team { $team })
+netconfig-no-config = No driver station configuration ({ $file }) was found on this drive. Set it up with the Driver Station page first.
+netconfig-settings = Network Settings
+netconfig-reused = These are the settings written to the last card. The team number on this card stays the same.
+netconfig-current = These are the settings on the card now. Change them to match the field network. The team number stays the same.
+netconfig-done = The card now uses the new network settings. You can remove it from the computer.
+netconfig-done-team = The card for team { $team } now uses the new network settings. You can remove it from the computer.
+netconfig-another-hint = To change another card, click "Configure Another Card". The same settings will be filled in.
+netconfig-another = Configure Another Card
+netconfig-title = Network Configuration
+
+## Card audit
+
+audit-choose-cards = Choose Cards
+audit-intro = Insert the driver station cards you want to check and select them below. Each card is compared against the release it was set up with.
+audit-start = Audit Cards
+audit-title = Card Audit
+audit-card-ok = { $drive }: OK (team { $team }, { $version })
+audit-card-problems = { $drive }: { $count } problem(s) (team { $team }, { $version })
+audit-checking = Checking card { $current } of { $total }...
+audit-more = Audit More Cards
+audit-unknown-card = { $drive } doesn't say which release is installed on it. Enter the release tag to check it against, or cancel to skip this card.
+audit-unknown-card-title = Unknown Card
+
+## Flash stress test
+
+stress-title = Flash Stress Test
+stress-intro = Repeatedly flashes the connected board and reads its firmware version back. The board is rebooted into BOOTSEL mode over USB serial between cycles.
+stress-firmware = Firmware:
+stress-choose-file = Choose File
+stress-expected-version = Expected version:
+stress-cycles = Cycles:
+stress-cycles-hint = (0 to run until stopped)
+stress-summary = Cycles: { $cycles }    Failures: { $failures } ({ $rate }%)
+stress-flash-time = Flash time: mean { $mean }s, min { $min }s, max { $max }s
+stress-last-failure = Last failure: { $error }
+stress-running = Running...
+stress-stop = Stop After This Cycle
+stress-start = Start
+
+## Laptop check
+
+laptop-commands = System commands
+laptop-drive-tools = Drive tools
+laptop-card-reader = Card reader
+laptop-release-source = Release source
+laptop-disk-space = Disk space
+laptop-self-tests = Self-tests
+laptop-title = Prepare New Laptop
+laptop-intro = Insert a driver station card before running the checks so the card reader can be tested.
+laptop-ready = Ready
+laptop-ready-details = This laptop has everything it needs for event day.
+laptop-not-ready = Not Ready
+laptop-fix-problems = Fix the { $count } problem(s) above, then run the checks again.
+laptop-copy = Copy results
+laptop-checking = Checking...
+laptop-run-again = Run Again
+laptop-missing-commands = Missing { $commands }.
+laptop-all-found = All found.
+laptop-working = Working.
+laptop-no-card = No card found. Connect a card reader with a card in it.
+laptop-cards-found = Found { $cards }.
+laptop-bundle-empty = The offline bundle at { $path } has no driver station releases.
+laptop-bundle = Offline bundle at { $path }.
+laptop-github-reachable = GitHub is reachable.
+laptop-no-release-source = GitHub can't be reached and no offline bundle is selected. Connect to the internet or choose an offline bundle from the start page.
+laptop-low-space = Only { $free } free. At least { $needed } is needed for downloads.
+laptop-free-space = { $free } free.
+laptop-passed = Passed.
+
+## Offline bundle
+
+bundle-title = Prepare Offline Bundle
+bundle-contents = Bundle Contents
+bundle-contents-intro = Select the versions to include in the offline bundle. Software left unselected will not be included.
+bundle-driver-station = Driver Station
+bundle-system-firmware = System Firmware
+bundle-student-program = Default Student Program
+bundle-fetch-failed = Could not fetch { $repo } releases: { $error }
+bundle-location = Bundle Location
+bundle-location-intro = Choose the folder to save the bundle into. Copy this folder to the computer you will install from, then choose "Offline install" on the start page.
+bundle-choose-folder = Choose Folder
+bundle-no-folder = No folder selected.
+bundle-download = Download Bundle
+bundle-downloading = Downloading { $asset } ({ $number } of { $total })...
+bundle-preparing = Preparing bundle...
+bundle-ready = Bundle Ready
+bundle-saved-to = The offline bundle was saved to { $path }.
+bundle-copy-hint = Copy this folder to the computer you will install from. On that computer, click "Offline install" on the start page and select the folder.
+bundle-do-not-include = Do not include
+
+## Settings
+
+settings-title = Settings
+settings-turn-off-hint = (0 to turn off)
+settings-cache = Download Cache
+settings-no-cache-dir = No cache directory is available on this computer.
+settings-keep-downloads = Keep downloaded software between sessions
+settings-cache-size = { $size } used in { $path }
+settings-clear-cache = Clear Download Cache
+settings-cache-cleared = Download cache cleared.
+settings-pinned-kept = Pinned versions are kept.
+settings-unpin-to-delete = Unpin this version to delete it.
+settings-pinned = Pinned
+settings-delete-selected = Delete Selected
+settings-verify-checksums = Verify Checksums
+settings-verify-checksums-hint = Re-reads each cached download to check it hasn't been damaged.
+settings-files-ok-unrecorded = { $verified } file(s) OK, { $unrecorded } downloaded before checksums were kept
+settings-files-ok = { $verified } file(s) OK
+settings-notifications = Release Notifications
+settings-notifications-intro = While the wizard is open, it can check GitHub for new software releases and notify you in the status bar.
+settings-check-every = Check every
+settings-minutes-suffix = min
+settings-cards = Driver Station Cards
+settings-copy-docs = Copy getting-started documentation onto each card
+settings-docs-url = Documentation URL
+settings-docs-url-hint = Leave the URL empty to use the PDFs attached to the driver station release.
+settings-native-formatting = Format cards without the system's formatting tools
+settings-native-formatting-hint = Falls back to the system's tools if the card can't be opened directly.
+settings-write-warning = Warn when a card has been set up more than
+settings-times-suffix = times
+settings-team-numbers = Team Numbers
+settings-team-numbers-intro = Team numbers are checked against these rules so a typo doesn't set up a card for a team that doesn't exist. Match them to the numbers registered at your hub.
+settings-team-digits = Digits
+settings-team-range = Numbers
+settings-to = to
+settings-reset-team-rules = Reset to Defaults
+settings-sounds = Sounds
+settings-sounds-intro = A sound plays when a long step finishes, with a different sound when something goes wrong.
+settings-volume-off = Off
+settings-volume-quiet = Quiet
+settings-volume-loud = Loud
+settings-cue-success = Success
+settings-cue-failure = Failure
+settings-built-in-sound = Built-in
+settings-choose-wav = Choose WAV
+settings-use-built-in = Use Built-in
+settings-test-sound = Test
+settings-custom-sound-volume = Custom sounds play at their own volume.
+settings-student-program = Student Program
+settings-simple-mode = Simplified mode for students
+settings-simple-mode-hint = Installs the latest default program onto the first board plugged in, with no version or drive choices.
+settings-github = GitHub Access
+settings-github-intro = Without a token, GitHub allows 60 requests per hour from each network. If many computers at your event share one internet connection, add a personal access token to raise the limit. The token does not need any scopes.
+settings-access-token = Access token
+settings-token-from-env = Using the token from the { $variable } environment variable.
+settings-list-at-most = List at most
+settings-releases-suffix = releases
+settings-all-hint = (0 for all)
+settings-max-releases-hint = Each hundred releases is one more request to GitHub. The lists update the next time they are loaded.
+settings-network = Network
+settings-network-intro = School and district networks often only allow internet access through a proxy, and some inspect secure connections with their own certificate. Ask the network's administrator for these if downloads fail.
+settings-proxy = Proxy URL
+settings-proxy-hint = Use the system's proxy settings
+settings-ca-bundle = Extra certificates
+settings-choose-pem = Choose PEM File
+settings-download-connections = Connections per download
+settings-download-connections-hint = Large files are downloaded over several connections at once, which is faster on slow networks. Use 1 if a proxy or server has trouble with this.
+settings-download-limit = Download speed limit
+settings-download-limit-hint = Keeps the wizard from using the whole connection at events, where scoring systems and streams share the network. The limit covers all downloads together.
+settings-no-limit = No limit
+settings-mbps = { $mbps } Mbps
+settings-mirror = Download Mirror
+settings-mirror-intro = Some venues block GitHub. A mirror is a server with copies of the releases, such as one run by BEST or a file server on your network. Releases come from the mirror whenever GitHub can't be reached.
+settings-mirror-url = Mirror URL
+settings-mirror-url-hint = GitHub only
+settings-prefer-mirror = Use the mirror first, and GitHub only if the mirror fails
+settings-sources = Software Sources
+settings-sources-intro = The GitHub repositories each kind of software is installed from, as owner / repository. Only change these to test a fork or private builds.
+settings-repo-invalid = "{ $repo }" is not a GitHub repository. Use only letters, numbers, "-", "_", and ".".
+settings-apply-sources = Apply
+settings-reset-sources = Reset to Defaults
+settings-sources-restart = New release notifications use the new repositories after the wizard is restarted.
+settings-branding = Hub Branding
+settings-branding-intro = Show your hub's name and logo at the top of the wizard, and tell volunteers who to contact when something goes wrong.
+settings-hub-name = Hub name
+settings-support-contact = Support contact
+settings-logo = Logo
+settings-choose-image = Choose Image
+settings-remove = Remove
+settings-accessibility = Accessibility
+settings-zoom = Interface scale: { $percent }%
+settings-zoom-out = Zoom out
+settings-zoom-in = Zoom in
+settings-zoom-reset = Reset
+settings-zoom-hint = Increase it if text looks tiny on a high-resolution screen. You can also change it with Ctrl + and Ctrl -. The scale and window size are remembered.
+settings-keyboard-hint = Tab moves between controls, and Enter or Space presses the highlighted one. Enter also presses Next when nothing is highlighted.
+settings-theme = Theme:
+settings-large-buttons = Large buttons for touchscreens
+settings-large-buttons-hint = Buttons and drive lists are also enlarged automatically once the screen is touched.
+settings-reports = Session Reports
+settings-reports-intro = For official events, session reports can be signed with a key that belongs to this hub. Give the public key to BEST officials so they can check that reports were not altered.
+settings-sign-reports = Sign session reports
+settings-public-key = Public key: { $key }
+settings-copy = Copy
+settings-replace-key = Replace Key
+settings-no-signing-key = This hub does not have a signing key yet.
+settings-generate-key = Generate Key
+settings-verify-report = Verify a Report...
+settings-report-valid = The report is valid and was signed by { $key }.
+settings-dashboard = Event Dashboard
+settings-dashboard-intro = At large events, each station can keep a status file in a shared folder, listing the teams it has set up. Dashboards read the files from every station to show overall progress.
+settings-writing-status = Writing status to { $path }
+settings-stop = Stop
+settings-choose-shared-folder = Choose Shared Folder...
+settings-demo = Demonstration Mode
+settings-demo-intro = Walks through every screen with simulated drives, for showing the setup process to a class on a projector. Nothing is formatted or written to real cards, and every screen is marked as a demo. Steps that talk to a Gizmo over USB still need a real Gizmo.
+settings-demo-dry-run = The wizard was started with --dry-run, so demonstration mode is on until it is closed.
+settings-demo-mode = Demonstration mode
+
+## Card inspection
+
+inspect-choose-card = Choose Card
+inspect-intro = Insert the driver station card you want to inspect and select it below.
+inspect-inspecting = Inspecting card...
+inspect-report = Card Report
+inspect-drive = Drive:
+inspect-version = Driver station version:
+inspect-version-unknown = Unknown, { $file } is missing or unreadable
+inspect-set-up-by = Set up by:
+inspect-wizard-version = Wizard { $version }, { $count } time(s) in total
+inspect-not-configured = Not configured
+inspect-team-mismatch = The card was set up for team { $manifest_team }, but is configured for team { $team }.
+inspect-config-unreadable = { $file } is missing settings or can't be read by the driver station.
+inspect-config-contents = { $file } contents
+inspect-config-missing = { $file } is missing.
+inspect-files-ok = All { $count } driver station files match the release.
+inspect-files-damaged = { $damaged } of { $count } driver station files are missing or damaged:
+inspect-files-unchecked = The driver station files could not be checked: { $error }
+inspect-no-release = The card doesn't say which release is installed on it.
+inspect-repair-config = Repair Configuration...
+inspect-repair-config-hint = Write a new { $file } with the team number and network settings you choose.
+inspect-restore-files = Restore Damaged Files
+inspect-restore-files-hint = Extract the missing and damaged files again from the release the card was set up with.
+inspect-another = Inspect Another Card
+inspect-repair-config-title = Repair Configuration
+inspect-repair-config-intro = { $file } will be replaced with these settings. They are filled in from what could be read from the card.
+inspect-write-config = Write Configuration
+inspect-config-written = Wrote a new { $file } for team { $team }.
+inspect-files-restored = Restored { $count } file(s) from { $version }.
+inspect-repairing = Repairing card...
+inspect-title = Card Inspection
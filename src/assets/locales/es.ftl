@@ -0,0 +1,760 @@
+# Spanish text for the wizard. Messages missing here are shown in English.
+
+## Start page
+
+app-title = Instalador de software BEST Gizmo
+start-intro = Esta herramienta te ayudará a instalar o actualizar el software de tu Gizmo.
+start-instructions = Selecciona el software que quieres instalar y sigue las instrucciones.
+start-session-unfinished = Una configuración de estación de control del { $saved } UTC no se terminó. { $done } de { $total } equipos están listos.
+start-resume-session = Reanudar sesión anterior
+start-discard-session = Descartar
+start-driver-station = Estación de control
+start-system-firmware = Firmware del sistema
+start-system-settings = Configuración del sistema
+start-student-program = Programa de estudiante predeterminado
+start-checking-starter-code = Buscando una versión del código inicial...
+start-no-starter-code = Todavía no hay una versión del código inicial. Vuelve a intentarlo más tarde o carga un paquete sin conexión que la incluya.
+start-report-saved = Informe de la sesión guardado en { $path }
+start-offline-source = Instalación sin conexión desde { $path }
+start-go-online = Volver a conectarse
+start-offline-install = Instalación sin conexión...
+start-prepare-bundle = Preparar paquete sin conexión...
+start-save-report = Guardar informe de la sesión...
+start-full-kit = Configurar un kit completo...
+start-network-config = Cambiar la configuración de red de una tarjeta...
+start-card-audit = Revisar tarjetas devueltas...
+start-card-inspection = Inspeccionar o reparar una tarjeta...
+start-kit-check = Comprobar un kit terminado...
+start-flash-stress-test = Prueba de resistencia de memoria...
+start-laptop-check = Preparar una computadora nueva...
+start-diagnostics = Diagnóstico
+start-settings = Configuración
+
+## Top bar, status bar, and banners
+
+app-start-over = Empezar de nuevo
+app-no-internet = Sin conexión a internet
+app-dismiss = Descartar
+app-update-failed = No se pudo iniciar la actualización: { $error }
+app-update-available = Hay una nueva versión del asistente: { $version }
+app-download-update = Descargar actualización
+app-update-now = Actualizar ahora
+app-downloaded = Descargado { $name }
+app-low-disk-space = Queda poco espacio en { $path }. Las instalaciones serán más lentas hasta que se libere espacio.
+app-drive-added = Unidad conectada: { $drive }
+app-drive-removed = Unidad retirada: { $drive }
+app-new-release = Hay una nueva versión de { $name }: { $version }
+app-status-export-failed = No se pudo actualizar el archivo de estado del panel: { $error }
+app-network-settings-failed = No se pudieron usar los ajustes de red: { $error }
+app-cache-refreshed = La copia guardada de { $files } estaba dañada, así que se descargó de nuevo.
+app-demo-watermark = Modo de demostración: no se escribe nada en unidades reales
+app-demo-badge = DEMO
+app-conflicts-found = Se encontraron { $count } conflictos de configuración.
+
+## Error dialog
+
+error-heading = Error
+error-step-failed = Lo sentimos, este paso falló. Puedes intentarlo de nuevo o empezar de nuevo.
+error-cancelled = Lo sentimos, ocurrió un error. Se canceló la instalación.
+error-no-information = No hay información sobre el error.
+error-support-contact = Para obtener ayuda, comunícate con { $contact }.
+error-save-log = Guardar archivo de registro...
+error-retry-step = Reintentar paso
+error-copy-details = Copiar detalles del error
+error-report-issue = Reportar este problema
+error-technical-details = Detalles técnicos
+error-log-saved = Archivo de registro guardado en { $path }
+
+## File dialogs
+
+dialog-offline-bundle = Seleccionar carpeta del paquete sin conexión
+dialog-bundle-folder = Seleccionar carpeta del paquete
+dialog-save-log = Guardar archivo de registro
+dialog-save-report = Guardar informe de la sesión
+dialog-hub-logo = Seleccionar logotipo del centro
+dialog-session-report = Seleccionar informe de la sesión
+dialog-dashboard-folder = Seleccionar carpeta del panel
+dialog-sound = Seleccionar sonido
+dialog-ca-bundle = Seleccionar archivo de certificados
+file-saved-to = Guardado en { $path }
+file-uf2-filter = Firmware UF2
+file-images-filter = Imágenes
+file-session-reports-filter = Informes de sesión
+file-wav-filter = Archivos WAV
+file-certificates-filter = Certificados
+
+## Language picker
+
+language-system = Idioma del sistema
+start-language = Idioma:
+
+## Shared buttons
+
+common-next = Siguiente
+common-ok = Aceptar
+common-cancel = Cancelar
+common-close = Cerrar
+common-refresh = Actualizar
+common-retry = Reintentar
+common-try-again = Intentar de nuevo
+common-yes = Sí
+common-no = No
+
+## Drives and teams
+
+team-number-label = Número de equipo:
+team-not-number = "{ $team }" no es un número.
+team-wrong-digits = { $team } debe tener de { $min } a { $max } dígitos.
+team-out-of-range = { $team } no está entre { $lowest } y { $highest }.
+drives-none = No se encontraron unidades extraíbles.
+drives-searching = Buscando unidades extraíbles...
+drives-choose-device = Elegir dispositivo
+drives-free-of = { $free } libres de { $total }
+drives-check-failed = No se pudo comprobar qué tipo de unidad es: { $error }
+drives-system-disk = Esta unidad contiene el sistema operativo de la computadora.
+drives-unexpected-bus = Esta unidad está conectada por { $bus }, no por USB ni por una ranura para tarjetas SD.
+drives-unknown-bus = un bus desconocido
+drives-too-large = Esta unidad tiene { $size }, más que una tarjeta microSD normal.
+
+## Installs
+
+install-complete = Instalación completa
+install-disconnect = Ya puedes desconectar el dispositivo de la computadora.
+install-another-device = Configurar otro dispositivo
+bootsel-not-gizmo = Esta unidad no es un Gizmo
+bootsel-not-bootloader = { $drive } no parece una placa en modo BOOTSEL. Copiar el firmware en ella no instalará nada.
+bootsel-hold = Asegúrate de mantener presionado el botón BOOTSEL al conectar la placa, y luego elige la unidad llamada "RPI-RP2".
+bootsel-install-anyway = Instalar de todos modos
+bootsel-choose-another-drive = Elegir otra unidad
+
+## Releases
+
+releases-fetching = Obteniendo las versiones disponibles...
+releases-fetch-failed = No se pudieron obtener las versiones: { $error }
+releases-use-local-file = Usar un archivo local
+releases-software-version = Versión del software
+fetch-work-offline = Trabajar sin conexión
+fetch-work-offline-hint = Usar las versiones que esta computadora ya descargó.
+fetch-nothing-downloaded = No se ha descargado ninguna versión en esta computadora.
+
+## Network settings
+
+network-name = Nombre de la red:
+network-key = Clave de la red:
+network-server = Dirección del servidor de campo:
+network-channel = Canal Wi-Fi:
+network-name-hint = Se elige según el número de equipo
+network-name-too-long = El nombre de la red debe tener como máximo 32 bytes.
+network-name-spaces = El nombre de la red no puede empezar ni terminar con un espacio.
+network-key-invalid = La clave de la red debe tener entre 8 y 63 caracteres simples (ASCII).
+network-server-invalid = La dirección del servidor de campo debe ser una dirección IPv4, como 10.0.0.2.
+network-channel-invalid = El canal Wi-Fi debe estar entre 1 y 11.
+network-key-short = Esta clave de red es corta. Las claves de 12 o más caracteres son mucho más difíciles de adivinar.
+network-key-simple = Esta clave de red usa un solo tipo de carácter. Combina letras, números y símbolos para que sea más difícil de adivinar.
+network-write-settings = Escribir ajustes
+network-writing-settings = Escribiendo ajustes...
+network-settings-written = Ajustes escritos
+
+## Step progress
+
+step-progress = Paso { $current } de { $total }: { $label }
+step-version = Versión
+step-versions = Versiones
+step-source-card = Tarjeta de origen
+step-teams = Equipos
+step-network = Red
+step-download = Descarga
+step-card = Tarjeta
+step-assign = Equipos
+step-cards = Tarjetas
+step-device = Dispositivo
+step-install = Instalación
+step-copy-code = Copia del código
+step-copy-libraries = Copia de las bibliotecas
+step-verify = Verificación
+step-remove-card = Retirar la tarjeta
+step-build = Compilación
+step-board-revision = Revisión de la placa
+step-settings = Configuración
+step-write = Escritura
+step-location = Ubicación
+step-connect = Conexión
+step-check = Comprobación
+step-result = Resultado
+step-audit = Auditoría
+step-inspect = Inspección
+step-report = Informe
+step-done = Listo
+
+## Release notes
+
+notes-none = No hay notas de la versión.
+notes-whats-new = Novedades de { $version }
+
+## Version picker
+
+version-pick = Elige una versión
+version-select = Seleccionar versión
+version-search = Buscar versiones
+version-no-matches = Ninguna versión coincide.
+version-prereleases = Incluir versiones preliminares
+version-prereleases-hint = Mostrar versiones de prueba de próximos lanzamientos. Es posible que no funcionen en los eventos.
+version-development = Incluir compilaciones de desarrollo
+version-development-hint = Compilaciones de GitHub Actions que aún no se han publicado, para hacer pruebas. Necesita un token de acceso de GitHub.
+version-no-development-builds = No se encontraron compilaciones de desarrollo. Necesitan un token de acceso de GitHub en la configuración que pueda leer las Actions del repositorio.
+
+## Compatibility warnings
+
+compat-driver-station = estación de control
+compat-firmware = firmware del sistema
+compat-starter-code = código inicial
+compat-may-not-work = Es posible que { $version } no funcione con { $component } { $installed }, que se instaló antes.
+compat-does-not-work = { $version } no funciona con { $component } { $installed }, que se instaló antes.
+
+## Card checks
+
+check-deep-title = Comprobación profunda de capacidad
+check-card-test-title = Probar tarjeta
+check-deep-intro = Esto llena todo el espacio libre de la tarjeta con datos de prueba y los vuelve a leer para detectar tarjetas falsificadas que dicen tener más espacio del real. Puede tardar más de una hora en tarjetas grandes.
+check-deep-warning = Atención: si la tarjeta es falsificada, la prueba sobrescribirá los archivos que ya tiene. Haz primero una copia de lo que necesites de esta tarjeta.
+check-deep-acknowledge = Entiendo que se pueden perder los datos de esta tarjeta
+check-start-check = Iniciar comprobación
+check-card-test-intro = Esto escribe datos de prueba en el espacio libre de la tarjeta y los vuelve a leer, para medir la velocidad de escritura y encontrar sectores dañados. Tarda alrededor de un minuto y no toca los archivos de la tarjeta.
+check-advertised-size = Tamaño impreso en la tarjeta:
+check-start-test = Iniciar prueba
+check-writing = Escribiendo datos de prueba ({ $done } de { $total })
+check-verifying = Verificando datos de prueba ({ $done } de { $total })
+check-stop = Detener
+check-deep-passed = Los { $tested } de datos de prueba se leyeron correctamente. Esta tarjeta parece auténtica.
+check-deep-failed = Esta tarjeta parece falsificada. Se perdieron datos después de los primeros { $good }, y { $bad } de { $tested } no se leyeron correctamente. No uses esta tarjeta.
+check-bad-sectors = { $bad } de los { $tested } de datos de prueba no se leyeron correctamente. La tarjeta tiene sectores dañados.
+check-slow = La tarjeta escribe a { $speed }/s, más lento que los { $minimum }/s que debería alcanzar una tarjeta Clase 10. Las instalaciones serán lentas, y la tarjeta puede ser falsificada o estar desgastada.
+check-too-small = La tarjeta tiene { $actual }, mucho menos que los { $advertised } de su etiqueta. Puede ser falsificada.
+check-card-passed = La tarjeta escribe a { $speed }/s y leyó correctamente todos los datos de prueba.
+check-use-different-card = Considera usar otra tarjeta.
+check-full-hash = Leer todos los archivos (mucho más lento, pero detecta más daños)
+
+## Themes
+
+theme-light = Claro
+theme-dark = Oscuro
+theme-system = Igual que el sistema
+
+## System processor settings
+
+sysconfig-title = Configuración del procesador del sistema
+sysconfig-intro = Estos ajustes se escriben en la configuración del procesador del sistema.
+sysconfig-step-power = 1. Enciende el Gizmo.
+sysconfig-step-connect = 2. Conecta el procesador del sistema a tu computadora con el cable USB.
+sysconfig-step-refresh = 3. Haz clic en el botón "Actualizar" para actualizar la lista de abajo.
+sysconfig-step-select = 4. Selecciona la unidad de la lista y haz clic en "Escribir ajustes". La unidad debería llamarse "{ $label }".
+sysconfig-change-team = Cambiarlo al equipo { $team }
+sysconfig-keep-team = Mantener el equipo { $team }
+sysconfig-already-set-up = Este procesador del sistema ya está configurado para el equipo { $team }.
+sysconfig-different-team = Equipo diferente
+sysconfig-done = El procesador del sistema ya está configurado para el equipo { $team }. Puedes desconectarlo de la computadora.
+sysconfig-another-hint = Para configurar otro procesador del sistema, haz clic en "Configurar otro dispositivo". Si terminaste, puedes cerrar el asistente o hacer clic en "Empezar de nuevo".
+sysconfig-another = Configurar otro dispositivo
+
+## System firmware
+
+fw-build-dir-dialog = Seleccionar el directorio de compilación del firmware
+fw-export-log-dialog = Exportar el registro de instalación
+fw-version-heading = Versión del firmware
+fw-version-intro = Selecciona la versión del firmware que quieres instalar. Normalmente debería ser la más reciente.
+fw-use-build-dir = Usar un directorio de compilación local
+fw-use-build-dir-hint = Para desarrolladores de firmware: elige un directorio de compilación de cargo o pico-sdk y selecciona uno de los archivos UF2 compilados allí.
+fw-hardware-heading = Elegir la versión del hardware
+fw-hardware-intro = Selecciona la versión del hardware de la placa Gizmo que estás usando. Debería estar impresa en la placa y parecerse a "v01.00" o "v00.r6b"
+fw-no-firmware-files = No se reconoció ningún archivo de firmware en la versión seleccionada.
+fw-downloading = Descargando el archivo de firmware...
+fw-build-heading = Elegir la compilación del firmware
+fw-build-files = Archivos UF2 en { $directory }, los más recientes primero:
+fw-build-none = No se encontraron archivos UF2. Compila el firmware y luego haz clic en Volver a buscar.
+fw-rescan = Volver a buscar
+fw-revision-auto = Esta versión solo es compatible con la revisión de placa { $revision }, así que se seleccionó automáticamente.
+fw-step-bootsel = 1. Mantén presionado el botón BOOTSEL del procesador del sistema.
+fw-step-connect = 2. Conecta el procesador del sistema a tu computadora con el cable USB.
+fw-step-release = 3. Suelta el botón BOOTSEL.
+fw-step-install = 4. El firmware se instala automáticamente cuando aparece la unidad llamada "RPI-RP2". Si no, haz clic en el botón "Actualizar", selecciona la unidad de la lista y haz clic en "Instalar firmware".
+fw-batch-mode = Modo por lotes
+fw-batch-mode-hint = Instala y verifica cada placa en cuanto se conecta, y luego espera la siguiente, guardando un registro de todas las placas.
+fw-install = Instalar firmware
+fw-installing = Instalando el firmware...
+fw-verifying = Verificando el firmware
+fw-board-id = ID de la placa: { $board }
+fw-waiting-restart = Esperando a que el procesador del sistema se reinicie...
+fw-wrong-version = El procesador del sistema ejecuta el firmware { $version }, pero se instaló { $expected }. Intenta instalarlo de nuevo.
+fw-running-version = El procesador del sistema ejecuta el firmware { $version }.
+fw-check-later = Es posible que el firmware se haya instalado correctamente. Puedes comprobarlo más tarde con "Comprobar un kit terminado".
+fw-log-save-failed = No se pudo guardar el registro: { $error }
+fw-batch-summary = { $flashed } placa(s) instaladas: { $verified } verificadas, { $failed } con fallos.
+fw-batch-board = Placa { $number }{ $board }: { $result }
+fw-export-log = Exportar registro...
+fw-export-log-hint = Guarda un CSV con el número de serie de cada placa según { $file }, si el cargador de arranque lo indica, y si se verificó.
+fw-another-hint = Para instalar el firmware del sistema en otro dispositivo, haz clic en "Configurar otro dispositivo". Si terminaste, puedes cerrar el asistente o hacer clic en "Empezar de nuevo".
+fw-title = Instalación del firmware del sistema
+fw-built-now = compilado hace un momento
+fw-built-minutes = compilado hace { $minutes } minutos
+fw-built-hours = compilado hace { $hours } horas
+fw-built-days = compilado hace { $days } días
+fw-start-address = en { $address }
+fw-artifact-version = versión { $version }
+fw-wrong-chip = Este firmware es para { $family }, no para el RP2040 del Gizmo.
+fw-result-verified = Verificada
+fw-result-wrong-version = Versión incorrecta ({ $version })
+fw-result-not-verified = No verificada: { $error }
+
+## Driver station setup
+
+ds-archive-filter = Archivo comprimido
+ds-disk-image-filter = Imagen de disco
+ds-roster-dialog = Importar lista de equipos
+ds-csv-filter = Archivo CSV
+ds-progress-dialog = Exportar el progreso de los equipos
+ds-version-intro = Selecciona la versión del software que quieres instalar. Normalmente debería ser la más reciente.
+ds-clone-instead = Clonar una tarjeta existente
+ds-clone-instead-hint = Copiar una tarjeta que ya configuraste a cada tarjeta nueva, cambiando solo el número de equipo.
+ds-teams-heading = Números de equipo
+ds-teams-intro = Escribe los números de tus equipos, uno por línea.
+ds-team-repeated = { $team } ya está en la línea { $line }.
+ds-team-line-problem = Línea { $line }: { $problem }
+ds-import-csv = Importar desde CSV...
+ds-teams-count = { $count } números de equipo.
+ds-single-team-warning = El software seleccionado parece una versión de demostración o para un solo equipo. No está pensado para preparar varios equipos en una competencia.
+ds-single-team-acknowledge = Entiendo, continuar de todos modos
+ds-field-heading = Ajustes del campo
+ds-field-intro = Estos ajustes se escriben en cada tarjeta junto con su número de equipo.
+ds-downloading = Descargando el archivo del software...
+ds-downloading-progress = Descargando el archivo de software ({ $done } de { $total })
+ds-clone-heading = Elegir la tarjeta que se clonará
+ds-clone-intro = Inserta una tarjeta que ya configuraste y probaste, y luego selecciónala abajo. Sus archivos se copian a esta computadora, así que puedes retirarla una vez leída. Cada tarjeta nueva recibe los mismos archivos, con su propio número de equipo y nombre de volumen.
+ds-reading-card = Leyendo la tarjeta...
+ds-read-card = Leer tarjeta
+ds-drives-heading = Elegir unidades
+ds-drives-remaining = Preparando estaciones de control para { $remaining } equipo(s) restantes, empezando por el equipo { $team }.
+ds-drives-step-insert = 1. Inserta en tu computadora las tarjetas microSD de los próximos equipos. Puedes usar varios lectores de tarjetas a la vez.
+ds-drives-step-refresh = 2. Haz clic en el botón "Actualizar" para actualizar la lista de abajo.
+ds-drives-step-select = 3. Selecciona las unidades de las tarjetas microSD en la lista y haz clic en "Instalar software".
+ds-drive-for-team = { $drive } -> equipo { $team }
+ds-test-card = Probar tarjeta...
+ds-deep-check = Comprobación profunda de capacidad...
+ds-right-click-hint = Haz clic derecho en una unidad para probar su velocidad y estado, o para comprobar si es una tarjeta falsificada.
+ds-install = Instalar software
+ds-assign-heading = Asigna equipos a las tarjetas
+ds-assign-intro = Elige qué equipo va en cada tarjeta y haz clic en "Instalar software".
+ds-assign-cards = Tarjetas
+ds-assign-teams = Equipos
+ds-assign-team-on-card = { $team } -> tarjeta { $card }
+ds-assign-drag-hint = Arrastra un equipo a una tarjeta para asignarlo. Si el equipo ya estaba en otra tarjeta, las dos tarjetas intercambian equipos.
+ds-assign-parallel = Instalar todas las tarjetas al mismo tiempo
+ds-assign-choose-cards = Elegir otras tarjetas
+ds-erase-heading = ¿Borrar estas unidades?
+ds-erase-intro = Es posible que algunas de las unidades seleccionadas no sean tarjetas microSD. Se borrará todo su contenido.
+ds-erase-type = Escribe ERASE para continuar.
+ds-erase-confirm = Borrar e instalar
+ds-installing-heading = Instalando el software
+ds-job-team = Equipo { $team }
+ds-formatting = Formateando la unidad...
+ds-copying = Copiando archivos ({ $done } de { $total })
+ds-writing-image = Escribiendo la imagen ({ $size })
+ds-verifying-image = Verificando la imagen ({ $size })
+ds-flushing = Terminando de escribir...
+ds-ejecting = Expulsando la tarjeta...
+ds-done = Listo
+ds-waiting = En espera
+ds-stopping = Deteniendo. Las tarjetas que se están formateando terminarán de formatearse primero.
+ds-cancel-hint = Las tarjetas que aún no se borraron no se tocan. Las que ya se borraron se vacían para poder configurarlas de nuevo.
+ds-progress-save-failed = No se pudo guardar el progreso de los equipos: { $error }
+ds-teams-panel = Equipos
+ds-teams-done = { $done } de { $total } listos
+ds-export = Exportar...
+ds-job-failed = Equipo { $team } ({ $drive }): { $error }
+ds-next-batch = Cuando hayas retirado las tarjetas, haz clic en Siguiente para preparar los equipos restantes. Las tarjetas que fallaron se intentarán de nuevo.
+ds-next-card-hint = También puedes sacar las tarjetas terminadas y poner una nueva. El asistente la detecta y la ofrece al siguiente equipo.
+ds-new-card-heading = Nueva tarjeta detectada
+ds-new-card-found = Se insertó una tarjeta en { $drive }. ¿Quieres configurarla para el equipo { $team }? Se borrará todo su contenido.
+ds-new-card-has-version = Esta tarjeta ya tiene el software de estación de control { $version }.
+ds-new-card-set-up = Configurar tarjeta
+ds-new-card-not-now = Ahora no
+ds-all-done = Se procesaron todos los números de equipo. Ya puedes cerrar el asistente o hacer clic en 'Empezar de nuevo'.
+ds-streak-paused = En pausa porque fallaron { $failures } tarjetas seguidas. Cuando fallan tantas, el problema suele ser el lector de tarjetas o esta computadora, no las tarjetas.
+ds-streak-check = Antes de continuar, revisa estas causas comunes:
+ds-show-diagnostics = Mostrar diagnóstico
+ds-continue = Continuar
+ds-continue-hint = Preparar los equipos restantes. El lote se pausa de nuevo si fallan { $limit } tarjetas más seguidas.
+ds-cause-reader = El lector de tarjetas está suelto, falla o está en un hub USB sin suficiente energía. Prueba otro puerto USB u otro lector.
+ds-cause-other-program = Otro programa está usando las tarjetas, como una ventana del explorador de archivos, un análisis antivirus o una herramienta de copias de seguridad. Ciérralo e inténtalo de nuevo.
+ds-cause-permissions = El asistente no tiene permiso para formatear unidades. Ejecútalo como administrador o acepta la solicitud de contraseña cuando aparezca.
+ds-cause-disk-space = A esta computadora le queda poco espacio en disco, así que no se puede descomprimir el software. Libera espacio o desactiva la caché persistente.
+ds-remove-ejected = { $drive } se expulsó. Retírala e insértala en la estación de control del equipo { $team }.
+ds-remove-not-ejected = { $drive } no se pudo expulsar. Espera unos segundos a que termine de escribir, luego retírala e insértala en la estación de control del equipo { $team }.
+ds-changes-since = Qué cambió desde { $version }
+ds-progress-pending = Pendiente
+ds-progress-formatting = Formateando
+ds-progress-installing = Instalando
+ds-progress-card-errors = Listo (errores en la tarjeta)
+ds-progress-failed = Falló
+ds-io-errors = Listo, pero la computadora registró { $errors } error(es) al escribir en esta tarjeta. Puede estar fallando, así que considera reemplazarla.
+ds-overwrite-title = ¿Sobrescribir la tarjeta?
+ds-overwrite-message = { $drive } está configurada para el equipo { $existing_team } ({ $version }). ¿Borrarla y configurarla para el equipo { $team }?
+ds-worn-card = Esta tarjeta se ha configurado { $count } veces. Considera retirarla antes de que falle en una competencia.
+ds-title = Instalación del software de la estación de control
+
+## Student starter code
+
+code-version-intro = Selecciona la versión del código inicial que quieres instalar. Normalmente debería ser la más reciente.
+code-install-runtime = Instalar CircuitPython { $version } primero
+code-install-runtime-hint = Usa esto en placas nuevas o en placas que se usaron para otra cosa.
+code-install-libraries = Copiar las bibliotecas de Gizmo a la unidad CIRCUITPY
+code-download-failed = No se pudo descargar el programa. Pide ayuda a tu profesor.
+code-getting-latest = Obteniendo el programa más reciente...
+code-downloading = Descargando el archivo del programa inicial...
+code-step-bootsel = 1. Mantén presionado el botón BOOTSEL del procesador de los estudiantes.
+code-step-connect = 2. Conecta el procesador de los estudiantes a tu computadora con el cable USB.
+code-step-release = 3. Suelta el botón BOOTSEL.
+code-step-refresh = 4. Haz clic en el botón "Actualizar" para actualizar la lista de abajo.
+code-step-select = 5. Selecciona la unidad de la lista y haz clic en "Instalar programa". La unidad debería llamarse "RPI-RP2".
+code-install-program = Instalar programa
+code-plug-in-heading = Conecta tu placa
+code-simple-step-hold = 1. Mantén presionado el botón BOOTSEL del procesador de los estudiantes.
+code-simple-step-plug = 2. Sin soltar el botón, conecta la placa a esta computadora.
+code-simple-step-release = 3. Suelta el botón.
+code-board-found = ¡Placa encontrada!
+code-looking-for-board = Buscando tu placa...
+code-install = Instalar
+code-installing-runtime = Instalando CircuitPython...
+code-installing-program = Instalando el programa inicial...
+code-copying-code = Esperando la unidad { $volume } y copiando el código inicial...
+code-copying-libraries = Copiando las bibliotecas de Gizmo a { $volume }/lib...
+code-libraries-copied = Se copiaron { $count } archivos de bibliotecas a { $volume }/lib.
+code-another-hint = Para instalar el programa inicial en otro dispositivo, haz clic en "Configurar otro dispositivo". Si terminaste de instalar el código inicial en los Gizmos, puedes cerrar el asistente o hacer clic en "Empezar de nuevo".
+code-title = Instalación del programa predeterminado de BEST
+
+## Configuration conflicts
+
+conflicts-title = Conflictos de configuración
+conflicts-intro = Algunas fuentes de configuración del asistente no coinciden. Cada conflicto se resolvió como se muestra abajo y no se guardó nada. Cambia la fuente que perdió para que el conflicto desaparezca.
+conflicts-directive = Directiva
+conflicts-conflict = Conflicto
+conflicts-resolution = Resolución
+conflicts-outcome = { $resolution } ({ $winner } sobre { $loser })
+conflicts-precedence = Cuando las fuentes no coinciden, gana la primera de esta lista:
+conflicts-start-over = Haz clic en "Empezar de nuevo" para continuar.
+
+## Diagnostics
+
+diag-title = Diagnóstico
+diag-version = Versión del asistente:
+diag-os = Sistema operativo:
+diag-build = Compilación del asistente:
+diag-computer = Computadora:
+diag-log-file = Archivo de registro:
+diag-not-available = No disponible
+diag-emulated = Esta compilación para { $build } se ejecuta con emulación. Instalar la compilación del asistente para { $arch } será más rápido y fiable.
+diag-commands = Comandos del sistema necesarios:
+diag-command-found = { $command }: encontrado
+diag-command-missing = { $command }: falta
+diag-performance = Si el asistente va lento, presiona { $shortcut } para ver estadísticas de rendimiento.
+perf-title = Rendimiento
+perf-frames = Fotogramas registrados:
+perf-frame-interval = Tiempo entre fotogramas:
+perf-update-time = Tiempo dedicado a actualizar:
+perf-events = Eventos por fotograma (máx.):
+perf-prompts = Preguntas en espera:
+perf-repaint-causes = Causas de redibujado:
+perf-no-repaints = Ninguna todavía.
+perf-reset = Reiniciar
+perf-times = { $average } ms de media, { $slowest } ms el más lento
+
+## Full kit setup
+
+kit-team-number = Número de equipo
+kit-system-firmware = Firmware del sistema
+kit-starter-code = Código inicial de los estudiantes
+kit-driver-station = Tarjeta de la estación de control
+kit-finished = Terminado
+kit-intro = Esto configura el firmware del sistema, el programa predeterminado de los estudiantes y la tarjeta de la estación de control de un equipo, uno tras otro.
+kit-enter-team = Escribe el número del equipo al que pertenece este kit.
+kit-progress = Equipo { $team }: parte { $part } de 3, { $label }
+kit-skip = Omitir esta parte
+kit-skip-hint = Para kits que ya tienen esta parte configurada.
+kit-part-done = { $label }: listo
+kit-firmware-family = Se instaló el firmware { $version }. Las siguientes partes empezarán con la misma familia de versiones.
+kit-continue-to = Continuar con { $label }
+kit-complete = Kit del equipo { $team } completo
+kit-part-skipped = { $label }: omitido
+kit-part-finished = { $label }: listo
+kit-remove-card = Retira la tarjeta de la estación de control de la computadora y ponla en el kit.
+kit-check-hint = Revisa el kit terminado con "Comprobar un kit terminado" en la página de inicio, o haz clic en "Empezar de nuevo".
+kit-another = Configurar otro kit
+kit-title = Configuración del kit completo
+
+## Kit check
+
+kitcheck-card-unreadable = No se pudo leer la tarjeta de la estación de control: { $error }
+kitcheck-firmware-unreadable = No se pudo leer la versión del firmware del sistema: { $error }
+kitcheck-incompatible = La estación de control { $driver_station } y el firmware del sistema { $firmware } no son compatibles. Vuelve a instalar uno de ellos para que ambos sean de la misma versión principal.
+kitcheck-connect = Conectar el kit
+kitcheck-step-card = 1. Inserta en tu computadora la tarjeta microSD de la estación de control del kit.
+kitcheck-step-gizmo = 2. Enciende el Gizmo y conecta el procesador del sistema a tu computadora con el cable USB.
+kitcheck-step-select = 3. Haz clic en el botón "Actualizar", selecciona la tarjeta y el Gizmo abajo, y haz clic en "Comprobar kit".
+kitcheck-card = Tarjeta de la estación de control:
+kitcheck-gizmo = Gizmo:
+kitcheck-no-gizmo = No se encontró ningún Gizmo.
+kitcheck-searching = Buscando dispositivos...
+kitcheck-button = Comprobar kit
+kitcheck-checking = Comprobando el kit...
+kitcheck-ok = Kit correcto
+kitcheck-not-ready = El kit no está listo
+kitcheck-driver-station = Estación de control: { $version } para el equipo { $team }
+kitcheck-firmware = Firmware del sistema: { $version }
+kitcheck-another = Comprobar otro kit
+kitcheck-title = Comprobación del kit
+
+## Network configuration
+
+netconfig-choose-card = Elegir tarjeta
+netconfig-choose-card-intro = Inserta una tarjeta de estación de control que ya esté configurada y luego selecciónala abajo.
+netconfig-card-team = { $drive } (equipo { $team })
+netconfig-no-config = No se encontró ninguna configuración de estación de control ({ $file }) en esta unidad. Configúrala primero con la página de la estación de control.
+netconfig-settings = Ajustes de red
+netconfig-reused = Estos son los ajustes que se escribieron en la última tarjeta. El número de equipo de esta tarjeta no cambia.
+netconfig-current = Estos son los ajustes actuales de la tarjeta. Cámbialos para que coincidan con la red del campo. El número de equipo no cambia.
+netconfig-done = La tarjeta ya usa los nuevos ajustes de red. Puedes retirarla de la computadora.
+netconfig-done-team = La tarjeta del equipo { $team } ya usa los nuevos ajustes de red. Puedes retirarla de la computadora.
+netconfig-another-hint = Para cambiar otra tarjeta, haz clic en "Configurar otra tarjeta". Se rellenarán los mismos ajustes.
+netconfig-another = Configurar otra tarjeta
+netconfig-title = Configuración de red
+
+## Card audit
+
+audit-choose-cards = Elegir tarjetas
+audit-intro = Inserta las tarjetas de estación de control que quieres revisar y selecciónalas abajo. Cada tarjeta se compara con la versión con la que se configuró.
+audit-start = Revisar tarjetas
+audit-title = Revisión de tarjetas
+audit-card-ok = { $drive }: correcta (equipo { $team }, { $version })
+audit-card-problems = { $drive }: { $count } problema(s) (equipo { $team }, { $version })
+audit-checking = Revisando la tarjeta { $current } de { $total }...
+audit-more = Revisar más tarjetas
+audit-unknown-card = { $drive } no indica qué versión tiene instalada. Escribe la etiqueta de la versión con la que se debe comparar, o cancela para omitir esta tarjeta.
+audit-unknown-card-title = Tarjeta desconocida
+
+## Flash stress test
+
+stress-title = Prueba de resistencia de memoria
+stress-intro = Instala el firmware una y otra vez en la placa conectada y vuelve a leer su versión. Entre ciclos, la placa se reinicia en modo BOOTSEL por el puerto serie USB.
+stress-firmware = Firmware:
+stress-choose-file = Elegir archivo
+stress-expected-version = Versión esperada:
+stress-cycles = Ciclos:
+stress-cycles-hint = (0 para seguir hasta que se detenga)
+stress-summary = Ciclos: { $cycles }    Fallos: { $failures } ({ $rate } %)
+stress-flash-time = Tiempo de instalación: media { $mean } s, mín. { $min } s, máx. { $max } s
+stress-last-failure = Último fallo: { $error }
+stress-running = En curso...
+stress-stop = Detener después de este ciclo
+stress-start = Iniciar
+
+## Laptop check
+
+laptop-commands = Comandos del sistema
+laptop-drive-tools = Herramientas de unidades
+laptop-card-reader = Lector de tarjetas
+laptop-release-source = Origen de las versiones
+laptop-disk-space = Espacio en disco
+laptop-self-tests = Autopruebas
+laptop-title = Preparar una computadora nueva
+laptop-intro = Inserta una tarjeta de estación de control antes de hacer las comprobaciones para poder probar el lector de tarjetas.
+laptop-ready = Lista
+laptop-ready-details = Esta computadora tiene todo lo que necesita para el día del evento.
+laptop-not-ready = No está lista
+laptop-fix-problems = Corrige los { $count } problema(s) de arriba y vuelve a hacer las comprobaciones.
+laptop-copy = Copiar resultados
+laptop-checking = Comprobando...
+laptop-run-again = Volver a comprobar
+laptop-missing-commands = Faltan { $commands }.
+laptop-all-found = Se encontraron todos.
+laptop-working = Funcionan.
+laptop-no-card = No se encontró ninguna tarjeta. Conecta un lector con una tarjeta dentro.
+laptop-cards-found = Se encontró { $cards }.
+laptop-bundle-empty = El paquete sin conexión en { $path } no tiene versiones de la estación de control.
+laptop-bundle = Paquete sin conexión en { $path }.
+laptop-github-reachable = Se puede acceder a GitHub.
+laptop-no-release-source = No se puede acceder a GitHub y no hay ningún paquete sin conexión seleccionado. Conéctate a internet o elige un paquete sin conexión en la página de inicio.
+laptop-low-space = Solo quedan { $free } libres. Se necesitan al menos { $needed } para las descargas.
+laptop-free-space = { $free } libres.
+laptop-passed = Superadas.
+
+## Offline bundle
+
+bundle-title = Preparar paquete sin conexión
+bundle-contents = Contenido del paquete
+bundle-contents-intro = Selecciona las versiones que quieres incluir en el paquete sin conexión. El software que no selecciones no se incluirá.
+bundle-driver-station = Estación de control
+bundle-system-firmware = Firmware del sistema
+bundle-student-program = Programa de estudiante predeterminado
+bundle-fetch-failed = No se pudieron obtener las versiones de { $repo }: { $error }
+bundle-location = Ubicación del paquete
+bundle-location-intro = Elige la carpeta donde guardar el paquete. Copia esta carpeta a la computadora desde la que vas a instalar y elige "Instalación sin conexión" en la página de inicio.
+bundle-choose-folder = Elegir carpeta
+bundle-no-folder = No se ha seleccionado ninguna carpeta.
+bundle-download = Descargar paquete
+bundle-downloading = Descargando { $asset } ({ $number } de { $total })...
+bundle-preparing = Preparando el paquete...
+bundle-ready = Paquete listo
+bundle-saved-to = El paquete sin conexión se guardó en { $path }.
+bundle-copy-hint = Copia esta carpeta a la computadora desde la que vas a instalar. En esa computadora, haz clic en "Instalación sin conexión" en la página de inicio y selecciona la carpeta.
+bundle-do-not-include = No incluir
+
+## Settings
+
+settings-title = Configuración
+settings-turn-off-hint = (0 para desactivar)
+settings-cache = Caché de descargas
+settings-no-cache-dir = No hay ninguna carpeta de caché disponible en esta computadora.
+settings-keep-downloads = Conservar el software descargado entre sesiones
+settings-cache-size = { $size } usados en { $path }
+settings-clear-cache = Vaciar la caché de descargas
+settings-cache-cleared = Se vació la caché de descargas.
+settings-pinned-kept = Las versiones fijadas se conservan.
+settings-unpin-to-delete = Deja de fijar esta versión para eliminarla.
+settings-pinned = Fijada
+settings-delete-selected = Eliminar seleccionadas
+settings-verify-checksums = Verificar sumas de comprobación
+settings-verify-checksums-hint = Vuelve a leer cada descarga guardada en caché para comprobar que no esté dañada.
+settings-files-ok-unrecorded = { $verified } archivo(s) correcto(s), { $unrecorded } descargado(s) antes de que se guardaran las sumas de comprobación
+settings-files-ok = { $verified } archivo(s) correcto(s)
+settings-notifications = Avisos de nuevas versiones
+settings-notifications-intro = Mientras el asistente está abierto, puede buscar nuevas versiones del software en GitHub y avisarte en la barra de estado.
+settings-check-every = Comprobar cada
+settings-minutes-suffix = min
+settings-cards = Tarjetas de la estación de control
+settings-copy-docs = Copiar la documentación de introducción en cada tarjeta
+settings-docs-url = URL de la documentación
+settings-docs-url-hint = Deja la URL vacía para usar los PDF adjuntos a la versión de la estación de control.
+settings-native-formatting = Formatear las tarjetas sin las herramientas de formato del sistema
+settings-native-formatting-hint = Usa las herramientas del sistema si la tarjeta no se puede abrir directamente.
+settings-write-warning = Avisar cuando una tarjeta se haya configurado más de
+settings-times-suffix = veces
+settings-team-numbers = Números de equipo
+settings-team-numbers-intro = Los números de equipo se comparan con estas reglas para que un error de escritura no prepare una tarjeta para un equipo que no existe. Ajústalas a los números registrados en tu centro.
+settings-team-digits = Dígitos
+settings-team-range = Números
+settings-to = a
+settings-reset-team-rules = Restablecer valores predeterminados
+settings-sounds = Sonidos
+settings-sounds-intro = Suena un sonido cuando termina un paso largo, y otro distinto cuando algo sale mal.
+settings-volume-off = Apagado
+settings-volume-quiet = Bajo
+settings-volume-loud = Alto
+settings-cue-success = Éxito
+settings-cue-failure = Error
+settings-built-in-sound = Integrado
+settings-choose-wav = Elegir WAV
+settings-use-built-in = Usar el integrado
+settings-test-sound = Probar
+settings-custom-sound-volume = Los sonidos personalizados suenan a su propio volumen.
+settings-student-program = Programa de estudiante
+settings-simple-mode = Modo simplificado para estudiantes
+settings-simple-mode-hint = Instala el programa predeterminado más reciente en la primera placa que se conecte, sin elegir versión ni unidad.
+settings-github = Acceso a GitHub
+settings-github-intro = Sin un token, GitHub permite 60 solicitudes por hora desde cada red. Si muchas computadoras de tu evento comparten una conexión a internet, añade un token de acceso personal para aumentar el límite. El token no necesita ningún permiso.
+settings-access-token = Token de acceso
+settings-token-from-env = Usando el token de la variable de entorno { $variable }.
+settings-list-at-most = Mostrar como máximo
+settings-releases-suffix = versiones
+settings-all-hint = (0 para todas)
+settings-max-releases-hint = Cada cien versiones es una solicitud más a GitHub. Las listas se actualizan la próxima vez que se cargan.
+settings-network = Red
+settings-network-intro = Las redes de escuelas y distritos a menudo solo permiten el acceso a internet a través de un proxy, y algunas inspeccionan las conexiones seguras con su propio certificado. Pide estos datos al administrador de la red si las descargas fallan.
+settings-proxy = URL del proxy
+settings-proxy-hint = Usar la configuración de proxy del sistema
+settings-ca-bundle = Certificados adicionales
+settings-choose-pem = Elegir archivo PEM
+settings-download-connections = Conexiones por descarga
+settings-download-connections-hint = Los archivos grandes se descargan por varias conexiones a la vez, lo que es más rápido en redes lentas. Usa 1 si un proxy o servidor tiene problemas con esto.
+settings-download-limit = Límite de velocidad de descarga
+settings-download-limit-hint = Evita que el asistente use toda la conexión en los eventos, donde los sistemas de puntuación y las transmisiones comparten la red. El límite abarca todas las descargas juntas.
+settings-no-limit = Sin límite
+settings-mbps = { $mbps } Mbps
+settings-mirror = Servidor espejo
+settings-mirror-intro = Algunos lugares bloquean GitHub. Un servidor espejo tiene copias de las versiones, como uno de BEST o un servidor de archivos en tu red. Las versiones se descargan del espejo cuando no se puede acceder a GitHub.
+settings-mirror-url = URL del espejo
+settings-mirror-url-hint = Solo GitHub
+settings-prefer-mirror = Usar primero el espejo, y GitHub solo si el espejo falla
+settings-sources = Origen del software
+settings-sources-intro = Los repositorios de GitHub de los que se instala cada tipo de software, como propietario / repositorio. Cámbialos solo para probar una bifurcación o compilaciones privadas.
+settings-repo-invalid = "{ $repo }" no es un repositorio de GitHub. Usa solo letras, números, "-", "_" y ".".
+settings-apply-sources = Aplicar
+settings-reset-sources = Restablecer valores predeterminados
+settings-sources-restart = Los avisos de nuevas versiones usan los nuevos repositorios después de reiniciar el asistente.
+settings-branding = Imagen del centro
+settings-branding-intro = Muestra el nombre y el logotipo de tu centro en la parte superior del asistente, e indica a los voluntarios a quién contactar cuando algo sale mal.
+settings-hub-name = Nombre del centro
+settings-support-contact = Contacto de soporte
+settings-logo = Logotipo
+settings-choose-image = Elegir imagen
+settings-remove = Quitar
+settings-accessibility = Accesibilidad
+settings-zoom = Escala de la interfaz: { $percent } %
+settings-zoom-out = Alejar
+settings-zoom-in = Acercar
+settings-zoom-reset = Restablecer
+settings-zoom-hint = Auméntala si el texto se ve muy pequeño en una pantalla de alta resolución. También puedes cambiarla con Ctrl + y Ctrl -. La escala y el tamaño de la ventana se recuerdan.
+settings-keyboard-hint = Tab pasa de un control a otro, y Enter o Espacio pulsan el que está resaltado. Enter también pulsa Siguiente cuando no hay nada resaltado.
+settings-theme = Tema:
+settings-large-buttons = Botones grandes para pantallas táctiles
+settings-large-buttons-hint = Los botones y las listas de unidades también se agrandan automáticamente en cuanto se toca la pantalla.
+settings-reports = Informes de sesión
+settings-reports-intro = En los eventos oficiales, los informes de sesión se pueden firmar con una clave que pertenece a este centro. Entrega la clave pública a los responsables de BEST para que puedan comprobar que los informes no se modificaron.
+settings-sign-reports = Firmar los informes de sesión
+settings-public-key = Clave pública: { $key }
+settings-copy = Copiar
+settings-replace-key = Reemplazar clave
+settings-no-signing-key = Este centro todavía no tiene una clave de firma.
+settings-generate-key = Generar clave
+settings-verify-report = Verificar un informe...
+settings-report-valid = El informe es válido y lo firmó { $key }.
+settings-dashboard = Panel del evento
+settings-dashboard-intro = En los eventos grandes, cada puesto puede guardar un archivo de estado en una carpeta compartida con los equipos que ha configurado. Los paneles leen los archivos de todos los puestos para mostrar el progreso general.
+settings-writing-status = Escribiendo el estado en { $path }
+settings-stop = Detener
+settings-choose-shared-folder = Elegir carpeta compartida...
+settings-demo = Modo de demostración
+settings-demo-intro = Recorre todas las pantallas con unidades simuladas, para mostrar el proceso de configuración a una clase con un proyector. No se formatea ni se escribe nada en tarjetas reales, y cada pantalla se marca como demostración. Los pasos que se comunican con un Gizmo por USB siguen necesitando un Gizmo real.
+settings-demo-dry-run = El asistente se inició con --dry-run, así que el modo de demostración está activado hasta que se cierre.
+settings-demo-mode = Modo de demostración
+
+## Card inspection
+
+inspect-choose-card = Elegir tarjeta
+inspect-intro = Inserta la tarjeta de la estación de control que quieres inspeccionar y selecciónala abajo.
+inspect-inspecting = Inspeccionando la tarjeta...
+inspect-report = Informe de la tarjeta
+inspect-drive = Unidad:
+inspect-version = Versión de la estación de control:
+inspect-version-unknown = Desconocida, { $file } falta o no se puede leer
+inspect-set-up-by = Configurada por:
+inspect-wizard-version = Asistente { $version }, { $count } vez/veces en total
+inspect-not-configured = Sin configurar
+inspect-team-mismatch = La tarjeta se configuró para el equipo { $manifest_team }, pero está configurada para el equipo { $team }.
+inspect-config-unreadable = A { $file } le faltan ajustes o la estación de control no lo puede leer.
+inspect-config-contents = Contenido de { $file }
+inspect-config-missing = Falta { $file }.
+inspect-files-ok = Los { $count } archivos de la estación de control coinciden con la versión.
+inspect-files-damaged = Faltan o están dañados { $damaged } de { $count } archivos de la estación de control:
+inspect-files-unchecked = No se pudieron comprobar los archivos de la estación de control: { $error }
+inspect-no-release = La tarjeta no indica qué versión tiene instalada.
+inspect-repair-config = Reparar configuración...
+inspect-repair-config-hint = Escribe un nuevo { $file } con el número de equipo y la configuración de red que elijas.
+inspect-restore-files = Restaurar archivos dañados
+inspect-restore-files-hint = Vuelve a extraer los archivos que faltan o están dañados de la versión con la que se configuró la tarjeta.
+inspect-another = Inspeccionar otra tarjeta
+inspect-repair-config-title = Reparar configuración
+inspect-repair-config-intro = { $file } se reemplazará con esta configuración. Se rellenó con lo que se pudo leer de la tarjeta.
+inspect-write-config = Escribir configuración
+inspect-config-written = Se escribió un nuevo { $file } para el equipo { $team }.
+inspect-files-restored = Se restauraron { $count } archivo(s) de { $version }.
+inspect-repairing = Reparando la tarjeta...
+inspect-title = Inspección de tarjetas
@@ -0,0 +1,122 @@
+use crate::settings::SoftwareRepo;
+use crate::utils::drive_management::{DriveInfo, list_drives};
+use crate::utils::github::GithubRelease;
+use std::sync::mpsc::{Receiver, Sender};
+use std::time::Duration;
+
+const DRIVE_POLL_INTERVAL: Duration = Duration::from_secs(3);
+const NETWORK_POLL_INTERVAL: Duration = Duration::from_secs(15);
+const NETWORK_PROBE_HOST: &str = "api.github.com:443";
+
+/// Application-wide notifications published by background watchers and worker threads.
+#[derive(Debug, Clone)]
+pub enum AppEvent {
+    DriveAdded(DriveInfo),
+    DriveRemoved(DriveInfo),
+    DownloadFinished(std::path::PathBuf),
+    /// A disk filled up while writing temporary files under this path.
+    LowDiskSpace(std::path::PathBuf),
+    NetworkChanged {
+        online: bool,
+    },
+    NewReleaseAvailable {
+        repo: SoftwareRepo,
+        display_name: String,
+        release: GithubRelease,
+    },
+    /// A newer version of the wizard itself has been released.
+    WizardUpdateAvailable(GithubRelease),
+}
+
+/// Receives events on the UI thread. Events are handed to the current page once per frame.
+pub struct EventBus {
+    publisher: EventPublisher,
+    receiver: Receiver<AppEvent>,
+}
+
+/// Cloneable handle for publishing events from any thread.
+#[derive(Clone)]
+pub struct EventPublisher {
+    sender: Sender<AppEvent>,
+    ctx: egui::Context,
+}
+
+impl EventPublisher {
+    pub fn publish(&self, event: AppEvent) {
+        // The bus only goes away when the app is closing, so a failed send can be ignored.
+        let _ = self.sender.send(event);
+        self.ctx.request_repaint();
+    }
+}
+
+impl EventBus {
+    pub fn new(ctx: &egui::Context) -> Self {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        Self {
+            publisher: EventPublisher {
+                sender,
+                ctx: ctx.clone(),
+            },
+            receiver,
+        }
+    }
+
+    pub fn publisher(&self) -> EventPublisher {
+        self.publisher.clone()
+    }
+
+    pub fn drain(&self) -> Vec<AppEvent> {
+        self.receiver.try_iter().collect()
+    }
+
+    /// Starts the background threads that watch for removable drive and network changes.
+    pub fn start_watchers(&self) {
+        let publisher = self.publisher();
+        std::thread::spawn(move || watch_drives(publisher));
+        let publisher = self.publisher();
+        std::thread::spawn(move || watch_network(publisher));
+    }
+}
+
+fn watch_drives(publisher: EventPublisher) {
+    let mut known_drives: Option<Vec<DriveInfo>> = None;
+    loop {
+        // Looked up each time, so switching demonstration mode takes effect right away.
+        if let Ok(drives) = list_drives() {
+            if let Some(previous) = &known_drives {
+                for drive in drives.iter().filter(|d| !previous.contains(d)) {
+                    publisher.publish(AppEvent::DriveAdded(drive.clone()));
+                }
+                for drive in previous.iter().filter(|d| !drives.contains(d)) {
+                    publisher.publish(AppEvent::DriveRemoved(drive.clone()));
+                }
+            }
+            known_drives = Some(drives);
+        }
+        std::thread::sleep(DRIVE_POLL_INTERVAL);
+    }
+}
+
+fn watch_network(publisher: EventPublisher) {
+    let mut was_online = None;
+    loop {
+        let online = is_network_available();
+        if was_online != Some(online) {
+            publisher.publish(AppEvent::NetworkChanged { online });
+            was_online = Some(online);
+        }
+        std::thread::sleep(NETWORK_POLL_INTERVAL);
+    }
+}
+
+/// Returns true if GitHub can be reached.
+pub fn is_network_available() -> bool {
+    use std::net::ToSocketAddrs;
+    NETWORK_PROBE_HOST
+        .to_socket_addrs()
+        .ok()
+        .and_then(|mut addrs| addrs.next())
+        .is_some_and(|addr| {
+            std::net::TcpStream::connect_timeout(&addr, Duration::from_secs(5)).is_ok()
+        })
+}
@@ -0,0 +1,164 @@
+//! Translations of the text shown in the wizard.
+//!
+//! Text is looked up by message ID with [`tr!`] from the Fluent files in `assets/locales`.
+//! Messages missing from a translation fall back to English, so pages can be translated a bit
+//! at a time.
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue};
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use unic_langid::LanguageIdentifier;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Language {
+    #[default]
+    English,
+    Spanish,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::Spanish];
+
+    /// BCP 47 language tag, as used in the Fluent files.
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Spanish => "es",
+        }
+    }
+
+    /// The language's name in itself, so a speaker can find it whatever the current language.
+    pub fn native_name(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Spanish => "Español",
+        }
+    }
+
+    fn messages(self) -> &'static str {
+        match self {
+            Language::English => include_str!("assets/locales/en.ftl"),
+            Language::Spanish => include_str!("assets/locales/es.ftl"),
+        }
+    }
+
+    /// The language the operating system is set to, if the wizard has a translation for it.
+    pub fn from_system() -> Option<Self> {
+        let locale = sys_locale::get_locale()?;
+        let language = locale.split(['-', '_']).next()?;
+        Self::ALL
+            .into_iter()
+            .find(|candidate| candidate.code().eq_ignore_ascii_case(language))
+    }
+}
+
+static LANGUAGE: AtomicUsize = AtomicUsize::new(0);
+static BUNDLES: OnceLock<Vec<FluentBundle<FluentResource>>> = OnceLock::new();
+
+/// Sets the language text is shown in. `None` follows the operating system.
+pub fn set_language(language: Option<Language>) {
+    let language = language.or_else(Language::from_system).unwrap_or_default();
+    log::info!("Showing text in {language:?}");
+    let index = Language::ALL
+        .iter()
+        .position(|&candidate| candidate == language)
+        .unwrap_or_default();
+    LANGUAGE.store(index, Ordering::Relaxed);
+}
+
+fn bundles() -> &'static [FluentBundle<FluentResource>] {
+    BUNDLES.get_or_init(|| {
+        Language::ALL
+            .into_iter()
+            .map(|language| {
+                let id: LanguageIdentifier = language
+                    .code()
+                    .parse()
+                    .expect("Language codes are valid identifiers.");
+                let mut bundle = FluentBundle::new_concurrent(vec![id]);
+                // egui doesn't handle the Unicode isolation marks Fluent puts around arguments.
+                bundle.set_use_isolating(false);
+                let resource = FluentResource::try_new(language.messages().to_string())
+                    .unwrap_or_else(|(resource, errors)| {
+                        log::error!("Errors in the {language:?} translation: {errors:?}");
+                        resource
+                    });
+                if let Err(errors) = bundle.add_resource(resource) {
+                    log::error!("Errors in the {language:?} translation: {errors:?}");
+                }
+                bundle
+            })
+            .collect()
+    })
+}
+
+/// Looks up a message in the current language. Use [`tr!`] instead of calling this directly.
+pub fn translate(id: &str, args: Option<&FluentArgs>) -> String {
+    let bundles = bundles();
+    let current = LANGUAGE.load(Ordering::Relaxed);
+    for bundle in [&bundles[current], &bundles[0]] {
+        if let Some(pattern) = bundle.get_message(id).and_then(|message| message.value()) {
+            let mut errors = vec![];
+            let text = bundle.format_pattern(pattern, args, &mut errors);
+            if !errors.is_empty() {
+                log::warn!("Errors formatting message {id}: {errors:?}");
+            }
+            return text.into_owned();
+        }
+    }
+    log::warn!("No message with ID {id}");
+    id.to_string()
+}
+
+/// Values that can be passed to a message with [`tr!`]. Numbers stay numbers, so messages can
+/// pick plural forms from them.
+pub trait MessageArg {
+    fn into_value(self) -> FluentValue<'static>;
+}
+
+impl MessageArg for String {
+    fn into_value(self) -> FluentValue<'static> {
+        FluentValue::from(self)
+    }
+}
+
+impl MessageArg for &String {
+    fn into_value(self) -> FluentValue<'static> {
+        FluentValue::from(self.clone())
+    }
+}
+
+impl MessageArg for &str {
+    fn into_value(self) -> FluentValue<'static> {
+        FluentValue::from(self.to_string())
+    }
+}
+
+macro_rules! impl_number_message_arg {
+    ($($number:ty),+) => {
+        $(impl MessageArg for $number {
+            fn into_value(self) -> FluentValue<'static> {
+                FluentValue::from(self)
+            }
+        })+
+    };
+}
+
+impl_number_message_arg!(u8, u16, u32, u64, usize, i32, i64);
+
+/// Returns a message in the current language, such as `tr!("app-title")`. Arguments are
+/// given by name: `tr!("fw-running-version", version = &version)`.
+macro_rules! tr {
+    ($id:literal) => {
+        $crate::i18n::translate($id, None)
+    };
+    ($id:literal, $($name:ident = $value:expr),+ $(,)?) => {{
+        let mut args = fluent_bundle::FluentArgs::new();
+        $(args.set(stringify!($name), $crate::i18n::MessageArg::into_value($value));)+
+        $crate::i18n::translate($id, Some(&args))
+    }};
+}
+
+pub(crate) use tr;
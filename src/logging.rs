@@ -0,0 +1,123 @@
+use crate::settings::APP_DIR_NAME;
+use anyhow::{Context, Result};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Number of log files kept from previous runs.
+const MAX_LOG_FILES: usize = 10;
+
+/// Writes log records to a file, one per line.
+struct FileLogger {
+    file: Mutex<std::fs::File>,
+}
+
+impl log::Log for FileLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        // Dependencies such as reqwest are chatty at debug level, so only keep their warnings.
+        if metadata.target().starts_with(env!("CARGO_CRATE_NAME")) {
+            metadata.level() <= log::Level::Debug
+        } else {
+            metadata.level() <= log::Level::Warn
+        }
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        // There is nowhere to report a failure to write the log, so it is ignored.
+        let _ = writeln!(
+            file,
+            "{} {:<5} {}: {}",
+            format_timestamp(std::time::SystemTime::now()),
+            record.level(),
+            record.target(),
+            record.args()
+        );
+    }
+
+    fn flush(&self) {
+        let _ = self.file.lock().unwrap_or_else(|e| e.into_inner()).flush();
+    }
+}
+
+pub fn log_dir() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|dir| dir.join(APP_DIR_NAME).join("logs"))
+}
+
+/// Starts writing the log to a new file in the log directory and returns its path.
+pub fn init() -> Result<PathBuf> {
+    let dir = log_dir().ok_or(anyhow::anyhow!("Could not find a directory for log files."))?;
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {:?}", dir))?;
+    remove_old_logs(&dir);
+    let timestamp = format_timestamp(std::time::SystemTime::now()).replace([' ', ':'], "-");
+    let path = dir.join(format!("wizard-{timestamp}.log"));
+    let file =
+        std::fs::File::create(&path).with_context(|| format!("Failed to create {:?}", path))?;
+    log::set_boxed_logger(Box::new(FileLogger {
+        file: Mutex::new(file),
+    }))?;
+    log::set_max_level(log::LevelFilter::Debug);
+    log::info!(
+        "BEST Gizmo Setup Wizard {} on {} ({} build)",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        crate::utils::platform::BUILD_ARCH
+    );
+    Ok(path)
+}
+
+/// Copies the log file somewhere the user chose, such as a flash drive to send to support.
+pub fn export_log(log_path: &Path, destination: &Path) -> Result<()> {
+    log::logger().flush();
+    std::fs::copy(log_path, destination)
+        .with_context(|| format!("Failed to save log file to {:?}", destination))?;
+    Ok(())
+}
+
+fn remove_old_logs(dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut logs: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "log"))
+        .collect();
+    // File names start with a sortable timestamp, so the oldest come first.
+    logs.sort();
+    let excess = (logs.len() + 1).saturating_sub(MAX_LOG_FILES);
+    for path in logs.into_iter().take(excess) {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+/// Formats a time as "YYYY-MM-DD HH:MM:SS" in UTC.
+pub fn format_timestamp(time: std::time::SystemTime) -> String {
+    let seconds = time
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let days = (seconds / 86400) as i64;
+    let time_of_day = seconds % 86400;
+    // Converts days since the epoch to a civil date. See
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02}",
+        time_of_day / 3600,
+        time_of_day % 3600 / 60,
+        time_of_day % 60
+    )
+}
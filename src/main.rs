@@ -1,16 +1,47 @@
 mod app;
+mod events;
+mod i18n;
+mod logging;
+mod migrations;
 mod pages;
+mod prompts;
+mod reconciliation;
+mod settings;
+mod theme;
 mod utils;
+mod widgets;
 
-fn main() {
-    let mut options = eframe::NativeOptions::default();
-    options.centered = true;
-    options.viewport = options.viewport.with_inner_size([500.0, 300.0]);
+/// Starts in demonstration mode for this run only, whatever the settings say.
+const DRY_RUN_FLAG: &str = "--dry-run";
+/// Size the window opens at until it is resized, in points.
+const DEFAULT_WINDOW_SIZE: [f32; 2] = [500.0, 300.0];
+/// Smallest the window can be made while still showing a line of text and a step's Next button.
+const MIN_WINDOW_SIZE: [f32; 2] = [400.0, 240.0];
 
+fn main() {
+    crate::utils::panics::install_hook();
+    let dry_run = std::env::args().any(|arg| arg == DRY_RUN_FLAG);
+    let window_size = crate::settings::Settings::load()
+        .window_size
+        .map(|[width, height]| {
+            [
+                width.max(MIN_WINDOW_SIZE[0]),
+                height.max(MIN_WINDOW_SIZE[1]),
+            ]
+        })
+        .unwrap_or(DEFAULT_WINDOW_SIZE);
+    let options = eframe::NativeOptions {
+        centered: true,
+        viewport: egui::ViewportBuilder::default()
+            .with_inner_size(window_size)
+            .with_min_inner_size(MIN_WINDOW_SIZE)
+            .with_resizable(true),
+        ..Default::default()
+    };
     eframe::run_native(
         "BEST Gizmo Software Installer",
         options,
-        Box::new(|cc| Ok(Box::new(crate::app::MyApp::new(cc)))),
+        Box::new(move |cc| Ok(Box::new(crate::app::MyApp::new(cc, dry_run)))),
     )
     .expect("Unhandled error encountered.");
 }
@@ -0,0 +1,89 @@
+use anyhow::{Context, Result, bail};
+use serde_json::Value;
+
+/// Key holding the format version in every versioned JSON file. Files without it are version 1.
+pub const FORMAT_VERSION_KEY: &str = "format_version";
+
+/// Upgrades the contents of a file from one format version to the next.
+pub type Migration = fn(Value) -> Result<Value>;
+
+/// A JSON file persisted by the wizard whose format may change between releases.
+pub struct VersionedFile {
+    pub name: &'static str,
+    pub path: fn() -> Option<std::path::PathBuf>,
+    /// `migrations[0]` upgrades version 1 to 2, `migrations[1]` upgrades 2 to 3, and so on.
+    pub migrations: &'static [Migration],
+}
+
+impl VersionedFile {
+    pub const fn current_version(&self) -> u32 {
+        self.migrations.len() as u32 + 1
+    }
+}
+
+const VERSIONED_FILES: &[VersionedFile] = &[
+    crate::settings::SETTINGS_FILE,
+    crate::pages::driver_station_setup::session::SESSION_FILE,
+];
+
+/// Upgrades all persisted files to their current format. Called once at startup, before anything
+/// is loaded. Returns a description of each file that could not be migrated.
+pub fn run_startup_migrations() -> Vec<String> {
+    VERSIONED_FILES
+        .iter()
+        .filter_map(|file| {
+            migrate_file(file)
+                .err()
+                .map(|e| format!("Could not upgrade {}: {:#}", file.name, e))
+        })
+        .collect()
+}
+
+fn migrate_file(file: &VersionedFile) -> Result<()> {
+    let Some(path) = (file.path)() else {
+        return Ok(());
+    };
+    if !path.is_file() {
+        return Ok(());
+    }
+    let contents =
+        std::fs::read_to_string(&path).with_context(|| format!("Failed to read {:?}", path))?;
+    let mut value: Value =
+        serde_json::from_str(&contents).with_context(|| format!("Failed to parse {:?}", path))?;
+    let original_version = format_version(&value);
+    let current_version = file.current_version();
+    if original_version == current_version {
+        return Ok(());
+    }
+    if original_version > current_version {
+        bail!(
+            "{:?} was written by a newer version of the wizard (format {}, expected {}).",
+            path,
+            original_version,
+            current_version
+        );
+    }
+
+    for migration in &file.migrations[(original_version - 1) as usize..] {
+        value = migration(value)?;
+    }
+    if let Value::Object(ref mut fields) = value {
+        fields.insert(FORMAT_VERSION_KEY.to_string(), current_version.into());
+    }
+
+    // Keep the old file so users can recover if the upgrade loses something they needed.
+    let mut backup_name = path.file_name().unwrap_or_default().to_os_string();
+    backup_name.push(format!(".v{original_version}.bak"));
+    std::fs::copy(&path, path.with_file_name(backup_name))
+        .with_context(|| format!("Failed to back up {:?}", path))?;
+    std::fs::write(&path, serde_json::to_string_pretty(&value)?)
+        .with_context(|| format!("Failed to write {:?}", path))?;
+    Ok(())
+}
+
+fn format_version(value: &Value) -> u32 {
+    value
+        .get(FORMAT_VERSION_KEY)
+        .and_then(Value::as_u64)
+        .map_or(1, |v| v.max(1) as u32)
+}
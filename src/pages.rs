@@ -1,35 +1,181 @@
 use crate::app::GlobalAppState;
+use crate::events::AppEvent;
+use crate::i18n::tr;
+use crate::theme;
+use crate::widgets::accessibility::show_focus_ring;
 use anyhow::Result;
 
+pub mod card_audit;
+pub mod card_inspection;
+pub mod configuration_conflicts;
+pub mod diagnostics;
 pub mod driver_station_setup;
+pub mod flash_stress_test;
+pub mod full_kit_setup;
+pub mod kit_check;
+pub mod laptop_check;
+pub mod network_config;
+pub mod offline_bundle;
+pub mod settings;
 pub mod student_starter_code;
+pub mod system_config;
 pub mod system_firmware;
 
 pub trait Page {
     fn run(&mut self, app_state: &mut GlobalAppState, ui: &mut egui::Ui) -> Result<()>;
 
     fn get_title(&self) -> String;
+
+    /// Called on the UI thread for each application event, before the page is run.
+    fn handle_event(&mut self, _app_state: &mut GlobalAppState, _event: &AppEvent) {}
+
+    /// Called when the user retries after a [`StepFailed`] error. Pages should clear whatever
+    /// the failed step left behind so it starts over on the next run.
+    fn retry_step(&mut self) {}
+
+    /// Where the page is in its steps, shown in a header under the top panel. Pages without
+    /// distinct steps return `None`.
+    fn step_progress(&self) -> Option<StepProgress> {
+        None
+    }
+}
+
+/// The steps a page goes through, and which of them is being shown.
+pub struct StepProgress {
+    current: usize,
+    labels: Vec<String>,
+}
+
+impl StepProgress {
+    /// Lists the steps from `first` by following `next` until the page finishes or loops back
+    /// to a step already listed, as it does to set up another device. Steps the flow skips, such
+    /// as downloads when installing a local file, are left out. Returns `None` if `current`
+    /// isn't among the steps.
+    fn from_flow<S: Copy + PartialEq>(
+        first: S,
+        current: S,
+        next: impl Fn(S) -> Option<S>,
+        label: impl Fn(S) -> String,
+    ) -> Option<Self> {
+        let mut steps = vec![first];
+        let mut step = first;
+        while let Some(next_step) = next(step)
+            && !steps.contains(&next_step)
+        {
+            steps.push(next_step);
+            step = next_step;
+        }
+        let current = steps.iter().position(|&s| s == current)?;
+        Some(Self {
+            current,
+            labels: steps.into_iter().map(label).collect(),
+        })
+    }
+
+    /// For pages whose steps always run in the same order.
+    fn from_steps<S: Copy + PartialEq>(
+        steps: &[S],
+        current: S,
+        label: impl Fn(S) -> String,
+    ) -> Option<Self> {
+        Self::from_flow(
+            steps[0],
+            current,
+            |step| {
+                let index = steps.iter().position(|&s| s == step)?;
+                steps.get(index + 1).copied()
+            },
+            label,
+        )
+    }
+
+    /// The current step, counting from 1.
+    pub fn number(&self) -> usize {
+        self.current + 1
+    }
+
+    pub fn total(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn current_label(&self) -> &str {
+        &self.labels[self.current]
+    }
+
+    pub fn labels(&self) -> &[String] {
+        &self.labels
+    }
+}
+
+/// Wraps an error that only affects the current step, such as a failed download. Instead of
+/// cancelling the whole install, the app offers to run the step again.
+#[derive(Debug)]
+pub struct StepFailed(anyhow::Error);
+
+impl std::fmt::Display for StepFailed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:#}", self.0)
+    }
+}
+
+impl std::error::Error for StepFailed {}
+
+impl StepFailed {
+    pub fn error(&self) -> &anyhow::Error {
+        &self.0
+    }
+}
+
+fn recoverable(error: anyhow::Error) -> anyhow::Error {
+    anyhow::Error::new(StepFailed(error))
+}
+
+/// The button that moves a page on to its next step.
+struct NextButton {
+    response: egui::Response,
+    /// Enter was pressed while no other widget had keyboard focus.
+    enter_pressed: bool,
+}
+
+impl NextButton {
+    fn clicked(&self) -> bool {
+        self.response.clicked() || self.enter_pressed
+    }
 }
 
-fn add_next_button(ui: &mut egui::Ui, enabled: bool) -> egui::Response {
-    add_custom_next_button(ui, "Next", enabled)
+fn add_next_button(ui: &mut egui::Ui, enabled: bool) -> NextButton {
+    add_custom_next_button(ui, tr!("common-next"), enabled)
 }
 
 fn add_custom_next_button(
     ui: &mut egui::Ui,
     text: impl Into<egui::WidgetText>,
     enabled: bool,
-) -> egui::Response {
-    egui_alignments::Row::new(egui::Align::BOTTOM)
+) -> NextButton {
+    let response = egui_alignments::Row::new(egui::Align::BOTTOM)
         .show(ui, |ui| -> egui::Response {
             egui_alignments::stretch(ui);
             ui.style_mut().text_styles.insert(
                 egui::TextStyle::Button,
                 egui::FontId::new(18.0, egui::FontFamily::Proportional),
             );
-            let button_color = egui::Color32::from_hex(if enabled { "#71CC98" } else { "#A0A0A0" })
-                .expect("Failed to parse color from hex.");
-            ui.add_enabled(enabled, egui::Button::new(text).fill(button_color))
+            let button_color = if enabled {
+                theme::NEXT_BUTTON
+            } else {
+                theme::NEXT_BUTTON_DISABLED
+            };
+            let text = text.into().color(theme::TEXT_ON_BRAND);
+            let response = ui.add_enabled(enabled, egui::Button::new(text).fill(button_color));
+            show_focus_ring(ui, &response, ui.visuals().selection.stroke.color);
+            response
         })
-        .inner
+        .inner;
+    // Enter moves on from anywhere on the page, unless a focused widget or dialog wants it.
+    let enter_pressed = enabled
+        && ui.memory(|m| m.focused().is_none() && m.top_modal_layer().is_none())
+        && ui.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Enter));
+    NextButton {
+        response,
+        enter_pressed,
+    }
 }
@@ -0,0 +1,296 @@
+use crate::app::GlobalAppState;
+use crate::events::AppEvent;
+use crate::i18n::tr;
+use crate::pages::{Page, StepProgress, add_custom_next_button, recoverable};
+use crate::prompts::Prompter;
+use crate::theme::palette;
+use crate::utils::card_index::{FileProblem, IndexSource, verify_card};
+use crate::utils::card_manifest::read_card_manifest;
+use crate::utils::drive_management::DriveInfo;
+use crate::utils::sounds::{Cue, play_cue};
+use crate::utils::tasks::Task;
+use crate::widgets::accessibility::heading;
+use anyhow::{Context, Result, bail};
+use egui_alignments::{column, stretch};
+use std::path::Path;
+use std::sync::mpsc::Receiver;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    ChooseCards,
+    Audit,
+}
+
+struct CardAudit {
+    team_number: String,
+    software_version: String,
+    /// Empty if the card matches what was installed.
+    problems: Vec<FileProblem>,
+}
+
+/// Checks returned driver station cards against the files their release put on them, so cards
+/// can be reused at the next event without reinstalling.
+pub struct CardAuditPage {
+    current_step: Step,
+    available_drives: Option<Vec<DriveInfo>>,
+    selected_drives: Vec<DriveInfo>,
+    full_hash: bool,
+    results: Vec<(DriveInfo, Result<CardAudit, String>)>,
+
+    drive_list_task: Option<Task<Vec<DriveInfo>>>,
+    audit_task: Option<Task<()>>,
+    /// Receives each card's result as soon as it has been audited.
+    audit_receiver: Option<Receiver<(DriveInfo, Result<CardAudit, String>)>>,
+}
+
+impl CardAuditPage {
+    pub fn new() -> Self {
+        Self {
+            current_step: Step::ChooseCards,
+            available_drives: None,
+            selected_drives: vec![],
+            full_hash: false,
+            results: vec![],
+
+            drive_list_task: None,
+            audit_task: None,
+            audit_receiver: None,
+        }
+    }
+
+    fn set_step(&mut self, step: Step) {
+        log::info!("Card audit: {:?} -> {:?}", self.current_step, step);
+        self.current_step = step;
+    }
+
+    fn run_choose_cards(
+        &mut self,
+        app_state: &mut GlobalAppState,
+        ui: &mut egui::Ui,
+    ) -> anyhow::Result<()> {
+        if self.available_drives.is_none() && self.drive_list_task.is_none() {
+            let drives = app_state.drives.clone();
+            self.drive_list_task = Some(Task::spawn(move || {
+                drives
+                    .list()
+                    .context("Failed to get list of available drives.")
+            }));
+        }
+
+        if let Some(drives) = Task::poll(&mut self.drive_list_task)? {
+            self.available_drives = Some(drives);
+        }
+
+        column(ui, egui::Align::LEFT, |ui| {
+            heading(ui, tr!("audit-choose-cards"));
+            ui.label(tr!("audit-intro"));
+            if let Some(ref drives) = self.available_drives {
+                if drives.is_empty() {
+                    ui.label(tr!("drives-none"));
+                }
+                for drive in drives {
+                    let position = self.selected_drives.iter().position(|d| d == drive);
+                    let mut selected = position.is_some();
+                    if ui.checkbox(&mut selected, drive.picker_label()).changed() {
+                        match position {
+                            Some(i) => {
+                                self.selected_drives.remove(i);
+                            }
+                            None => self.selected_drives.push(drive.clone()),
+                        }
+                    }
+                }
+                if ui.button(tr!("common-refresh")).clicked() {
+                    self.available_drives = None;
+                    self.selected_drives.clear();
+                }
+            } else {
+                ui.spinner();
+                ui.label(tr!("drives-searching"));
+            }
+            ui.checkbox(&mut self.full_hash, tr!("check-full-hash"));
+            stretch(ui);
+            if add_custom_next_button(ui, tr!("audit-start"), !self.selected_drives.is_empty())
+                .clicked()
+            {
+                self.results.clear();
+                self.set_step(Step::Audit);
+            }
+        });
+        Ok(())
+    }
+
+    fn run_audit(&mut self, app_state: &mut GlobalAppState, ui: &mut egui::Ui) -> Result<()> {
+        if self.audit_task.is_none() && self.results.is_empty() {
+            let drives = self.selected_drives.clone();
+            let full_hash = self.full_hash;
+            let network = app_state.network();
+            let cache_dir = app_state.download_cache_dir();
+            let offline_source = app_state.offline_source.clone();
+            let repo = app_state.sources.driver_station.clone();
+            let prompts = app_state.prompts.clone();
+            let ctx = ui.ctx().clone();
+            let (tx, rx) = std::sync::mpsc::channel();
+            self.audit_receiver = Some(rx);
+            self.audit_task = Some(Task::spawn(move || {
+                let mut indexes = IndexSource::new(network, cache_dir, offline_source, repo);
+                for drive in drives {
+                    let result = audit_card(&drive, &mut indexes, full_hash, &prompts)
+                        .map_err(|e| format!("{e:#}"));
+                    // The receiver is dropped if the user leaves the page, which stops the audit.
+                    if tx.send((drive, result)).is_err() {
+                        break;
+                    }
+                    ctx.request_repaint();
+                }
+                Ok(())
+            }));
+        }
+
+        if let Some(ref receiver) = self.audit_receiver {
+            self.results.extend(receiver.try_iter());
+        }
+        if Task::poll(&mut self.audit_task)?.is_some() {
+            if let Some(receiver) = self.audit_receiver.take() {
+                self.results.extend(receiver.try_iter());
+            }
+            let all_ok = self
+                .results
+                .iter()
+                .all(|(_, result)| result.as_ref().is_ok_and(|a| a.problems.is_empty()));
+            play_cue(if all_ok { Cue::Success } else { Cue::Failure });
+        }
+        let finished = self.audit_task.is_none();
+
+        let mut audit_more = false;
+        column(ui, egui::Align::LEFT, |ui| {
+            heading(ui, tr!("audit-title"));
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for (drive, result) in &self.results {
+                    match result {
+                        Ok(audit) if audit.problems.is_empty() => {
+                            ui.colored_label(
+                                palette(ui).success,
+                                tr!(
+                                    "audit-card-ok",
+                                    drive = drive.to_string(),
+                                    team = &audit.team_number,
+                                    version = &audit.software_version
+                                ),
+                            );
+                        }
+                        Ok(audit) => {
+                            ui.colored_label(
+                                palette(ui).error,
+                                tr!(
+                                    "audit-card-problems",
+                                    drive = drive.to_string(),
+                                    count = audit.problems.len(),
+                                    team = &audit.team_number,
+                                    version = &audit.software_version
+                                ),
+                            );
+                            for problem in &audit.problems {
+                                ui.label(format!("    {problem}"));
+                            }
+                        }
+                        Err(message) => {
+                            ui.colored_label(palette(ui).error, format!("{drive}: {message}"));
+                        }
+                    }
+                }
+                if !finished {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(tr!(
+                            "audit-checking",
+                            current = self.results.len() + 1,
+                            total = self.selected_drives.len()
+                        ));
+                    });
+                }
+            });
+            stretch(ui);
+            audit_more = add_custom_next_button(ui, tr!("audit-more"), finished).clicked();
+        });
+        if audit_more {
+            self.results.clear();
+            self.selected_drives.clear();
+            self.available_drives = None;
+            self.set_step(Step::ChooseCards);
+        }
+        Ok(())
+    }
+}
+
+fn audit_card(
+    drive: &DriveInfo,
+    indexes: &mut IndexSource,
+    full_hash: bool,
+    prompts: &Prompter,
+) -> Result<CardAudit> {
+    let (team_number, software_version) = match read_card_manifest(&drive.drive_path) {
+        Ok(manifest) => (manifest.team_number, manifest.software_version),
+        // Cards set up before manifests were written can still be checked if the user knows
+        // which release is on them.
+        Err(e) => {
+            log::info!("No manifest on {drive}: {e:#}");
+            let message = tr!("audit-unknown-card", drive = drive.to_string());
+            match prompts.input(&tr!("audit-unknown-card-title"), &message, "")? {
+                Some(tag) if !tag.trim().is_empty() => {
+                    ("unknown".to_string(), tag.trim().to_string())
+                }
+                _ => bail!("Skipped because the card has no manifest."),
+            }
+        }
+    };
+    let index = indexes.get(&software_version)?;
+    Ok(CardAudit {
+        problems: verify_card(Path::new(&drive.drive_path), index, full_hash),
+        team_number,
+        software_version,
+    })
+}
+
+impl Page for CardAuditPage {
+    fn run(&mut self, app_state: &mut GlobalAppState, ui: &mut egui::Ui) -> anyhow::Result<()> {
+        match self.current_step {
+            Step::ChooseCards => self.run_choose_cards(app_state, ui).map_err(recoverable),
+            Step::Audit => self.run_audit(app_state, ui).map_err(recoverable),
+        }
+    }
+
+    fn get_title(&self) -> String {
+        tr!("audit-title")
+    }
+
+    fn step_progress(&self) -> Option<StepProgress> {
+        StepProgress::from_steps(
+            &[Step::ChooseCards, Step::Audit],
+            self.current_step,
+            |step| match step {
+                Step::ChooseCards => tr!("step-cards"),
+                Step::Audit => tr!("step-audit"),
+            },
+        )
+    }
+
+    fn retry_step(&mut self) {
+        self.drive_list_task = None;
+        self.audit_task = None;
+        self.audit_receiver = None;
+        self.available_drives = None;
+        self.results.clear();
+    }
+
+    fn handle_event(&mut self, _app_state: &mut GlobalAppState, event: &AppEvent) {
+        if let AppEvent::DriveAdded(_) | AppEvent::DriveRemoved(_) = event {
+            if let AppEvent::DriveRemoved(drive) = event {
+                self.selected_drives.retain(|d| d != drive);
+            }
+            if self.current_step == Step::ChooseCards && self.drive_list_task.is_none() {
+                self.available_drives = None;
+            }
+        }
+    }
+}
@@ -0,0 +1,586 @@
+use crate::app::GlobalAppState;
+use crate::events::AppEvent;
+use crate::i18n::tr;
+use crate::pages::{Page, StepProgress, add_custom_next_button, add_next_button, recoverable};
+use crate::theme::palette;
+use crate::utils::archive::extract_files;
+use crate::utils::card_index::{FileProblem, IndexSource, verify_card};
+use crate::utils::card_manifest::{CARD_MANIFEST_FILE, CardManifest, read_card_manifest};
+use crate::utils::drive_management::DriveInfo;
+use crate::utils::gizmo_config::{
+    FieldSettings, GIZMO_CONFIG_FILE, read_configured_team, read_field_settings, write_gizmo_config,
+};
+use crate::utils::sounds::{Cue, play_cue};
+use crate::utils::tasks::Task;
+use crate::widgets::accessibility::heading;
+use crate::widgets::field_settings_editor::{add_field_settings_rows, show_field_settings_problem};
+use anyhow::{Context, anyhow};
+use egui_alignments::{column, stretch};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    ChooseCard,
+    Inspect,
+    Report,
+    EditConfig,
+    Repair,
+}
+
+/// What was found on a card.
+struct Inspection {
+    manifest: Option<CardManifest>,
+    /// The configuration file as it is on the card, with the network key hidden.
+    config_contents: Option<String>,
+    team_number: Option<u32>,
+    field_settings: Option<FieldSettings>,
+    files_checked: usize,
+    /// Why the files couldn't be checked, if they couldn't.
+    file_problems: Result<Vec<FileProblem>, String>,
+}
+
+#[derive(Clone)]
+enum Repair {
+    Config {
+        team_number: String,
+        field_settings: FieldSettings,
+    },
+    Files {
+        software_version: String,
+        paths: Vec<PathBuf>,
+    },
+}
+
+/// Shows what is on one driver station card and fixes what it can, for cards that come back
+/// from a team not working.
+pub struct CardInspectionPage {
+    current_step: Step,
+    available_drives: Option<Vec<DriveInfo>>,
+    selected_drive: Option<DriveInfo>,
+    full_hash: bool,
+    inspection: Option<Inspection>,
+    team_number: String,
+    field_settings: FieldSettings,
+    /// The repair chosen on the report or configuration step, kept until it succeeds so it can
+    /// be retried.
+    repair: Option<Repair>,
+    /// What the last repair did, shown above the report of the card after it.
+    repair_result: Option<String>,
+
+    drive_list_task: Option<Task<Vec<DriveInfo>>>,
+    inspection_task: Option<Task<Inspection>>,
+    repair_task: Option<Task<String>>,
+}
+
+impl CardInspectionPage {
+    pub fn new() -> Self {
+        Self {
+            current_step: Step::ChooseCard,
+            available_drives: None,
+            selected_drive: None,
+            full_hash: false,
+            inspection: None,
+            team_number: String::new(),
+            field_settings: FieldSettings::default(),
+            repair: None,
+            repair_result: None,
+
+            drive_list_task: None,
+            inspection_task: None,
+            repair_task: None,
+        }
+    }
+
+    fn set_step(&mut self, step: Step) {
+        log::info!("Card inspection: {:?} -> {:?}", self.current_step, step);
+        self.current_step = step;
+    }
+
+    fn run_choose_card(
+        &mut self,
+        app_state: &mut GlobalAppState,
+        ui: &mut egui::Ui,
+    ) -> anyhow::Result<()> {
+        if self.available_drives.is_none() && self.drive_list_task.is_none() {
+            let drives = app_state.drives.clone();
+            self.drive_list_task = Some(Task::spawn(move || {
+                drives
+                    .list()
+                    .context("Failed to get list of available drives.")
+            }));
+        }
+
+        if let Some(drives) = Task::poll(&mut self.drive_list_task)? {
+            self.available_drives = Some(drives);
+        }
+
+        column(ui, egui::Align::LEFT, |ui| {
+            heading(ui, tr!("inspect-choose-card"));
+            ui.label(tr!("inspect-intro"));
+            if let Some(ref drives) = self.available_drives {
+                if drives.is_empty() {
+                    ui.label(tr!("drives-none"));
+                }
+                for drive in drives {
+                    let is_selected = self.selected_drive.as_ref() == Some(drive);
+                    if ui
+                        .add(egui::Button::selectable(is_selected, drive.picker_label()))
+                        .clicked()
+                    {
+                        self.selected_drive = Some(drive.clone());
+                    }
+                }
+                if ui.button(tr!("common-refresh")).clicked() {
+                    self.available_drives = None;
+                    self.selected_drive = None;
+                }
+            } else {
+                ui.spinner();
+                ui.label(tr!("drives-searching"));
+            }
+            ui.checkbox(&mut self.full_hash, tr!("check-full-hash"));
+            stretch(ui);
+            if add_next_button(ui, self.selected_drive.is_some()).clicked() {
+                self.repair_result = None;
+                self.set_step(Step::Inspect);
+            }
+        });
+        Ok(())
+    }
+
+    fn run_inspect(
+        &mut self,
+        app_state: &mut GlobalAppState,
+        ui: &mut egui::Ui,
+    ) -> anyhow::Result<()> {
+        if self.inspection_task.is_none() {
+            let drive = self
+                .selected_drive
+                .clone()
+                .ok_or(anyhow!("Expected selected_drive to not be None."))?;
+            let full_hash = self.full_hash;
+            let mut indexes = IndexSource::new(
+                app_state.network(),
+                app_state.download_cache_dir(),
+                app_state.offline_source.clone(),
+                app_state.sources.driver_station.clone(),
+            );
+            self.inspection_task = Some(Task::spawn(move || {
+                Ok(inspect_card(
+                    Path::new(&drive.drive_path),
+                    &mut indexes,
+                    full_hash,
+                ))
+            }));
+        }
+
+        if let Some(inspection) = Task::poll(&mut self.inspection_task)? {
+            let healthy = inspection.config_contents.is_some()
+                && inspection
+                    .file_problems
+                    .as_ref()
+                    .is_ok_and(|problems| problems.is_empty());
+            play_cue(if healthy { Cue::Success } else { Cue::Failure });
+            self.inspection = Some(inspection);
+            self.set_step(Step::Report);
+        }
+
+        column(ui, egui::Align::Center, |ui| {
+            stretch(ui);
+            ui.spinner();
+            ui.label(tr!("inspect-inspecting"));
+            stretch(ui);
+        });
+        Ok(())
+    }
+
+    fn run_report(
+        &mut self,
+        _app_state: &mut GlobalAppState,
+        ui: &mut egui::Ui,
+    ) -> anyhow::Result<()> {
+        let inspection = self
+            .inspection
+            .as_ref()
+            .ok_or(anyhow!("Expected inspection to not be None."))?;
+        let drive = self
+            .selected_drive
+            .as_ref()
+            .ok_or(anyhow!("Expected selected_drive to not be None."))?;
+
+        let mut repair_config = false;
+        let mut restore_files = None;
+        let mut inspect_another = false;
+        column(ui, egui::Align::LEFT, |ui| {
+            heading(ui, tr!("inspect-report"));
+            if let Some(ref message) = self.repair_result {
+                ui.colored_label(palette(ui).success, message);
+            }
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                egui::Grid::new("card_inspection_grid")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label(tr!("inspect-drive"));
+                        ui.label(drive.to_string());
+                        ui.end_row();
+                        ui.label(tr!("inspect-version"));
+                        match inspection.manifest {
+                            Some(ref manifest) => ui.label(&manifest.software_version),
+                            None => ui.colored_label(
+                                palette(ui).error,
+                                tr!("inspect-version-unknown", file = CARD_MANIFEST_FILE),
+                            ),
+                        };
+                        ui.end_row();
+                        if let Some(ref manifest) = inspection.manifest {
+                            ui.label(tr!("inspect-set-up-by"));
+                            ui.label(tr!(
+                                "inspect-wizard-version",
+                                version = &manifest.wizard_version,
+                                count = manifest.write_count
+                            ));
+                            ui.end_row();
+                        }
+                        ui.label(tr!("team-number-label"));
+                        match inspection.team_number {
+                            Some(team_number) => ui.label(team_number.to_string()),
+                            None => {
+                                ui.colored_label(palette(ui).error, tr!("inspect-not-configured"))
+                            }
+                        };
+                        ui.end_row();
+                    });
+                if let Some(ref manifest) = inspection.manifest
+                    && let Some(team_number) = inspection.team_number
+                    && manifest.team_number != team_number.to_string()
+                {
+                    ui.colored_label(
+                        palette(ui).warning,
+                        tr!(
+                            "inspect-team-mismatch",
+                            manifest_team = &manifest.team_number,
+                            team = team_number
+                        ),
+                    );
+                }
+
+                ui.separator();
+                match inspection.config_contents {
+                    Some(ref contents) => {
+                        if inspection.field_settings.is_none() {
+                            ui.colored_label(
+                                palette(ui).error,
+                                tr!("inspect-config-unreadable", file = GIZMO_CONFIG_FILE),
+                            );
+                        }
+                        egui::CollapsingHeader::new(tr!(
+                            "inspect-config-contents",
+                            file = GIZMO_CONFIG_FILE
+                        ))
+                        .show(ui, |ui| {
+                            ui.monospace(contents);
+                        });
+                    }
+                    None => {
+                        ui.colored_label(
+                            palette(ui).error,
+                            tr!("inspect-config-missing", file = GIZMO_CONFIG_FILE),
+                        );
+                    }
+                }
+
+                ui.separator();
+                match inspection.file_problems {
+                    Ok(ref problems) if problems.is_empty() => {
+                        ui.colored_label(
+                            palette(ui).success,
+                            tr!("inspect-files-ok", count = inspection.files_checked),
+                        );
+                    }
+                    Ok(ref problems) => {
+                        ui.colored_label(
+                            palette(ui).error,
+                            tr!(
+                                "inspect-files-damaged",
+                                damaged = problems.len(),
+                                count = inspection.files_checked
+                            ),
+                        );
+                        for problem in problems {
+                            ui.label(format!("    {problem}"));
+                        }
+                    }
+                    Err(ref message) => {
+                        ui.colored_label(
+                            palette(ui).error,
+                            tr!("inspect-files-unchecked", error = message),
+                        );
+                    }
+                }
+            });
+
+            ui.horizontal(|ui| {
+                repair_config = ui
+                    .button(tr!("inspect-repair-config"))
+                    .on_hover_text(tr!("inspect-repair-config-hint", file = GIZMO_CONFIG_FILE))
+                    .clicked();
+                let damaged_files = match (&inspection.manifest, &inspection.file_problems) {
+                    (Some(manifest), Ok(problems)) if !problems.is_empty() => Some(Repair::Files {
+                        software_version: manifest.software_version.clone(),
+                        paths: problems
+                            .iter()
+                            .map(|problem| PathBuf::from(&problem.path))
+                            .collect(),
+                    }),
+                    _ => None,
+                };
+                let clicked = ui
+                    .add_enabled(
+                        damaged_files.is_some(),
+                        egui::Button::new(tr!("inspect-restore-files")),
+                    )
+                    .on_hover_text(tr!("inspect-restore-files-hint"))
+                    .clicked();
+                if clicked {
+                    restore_files = damaged_files;
+                }
+            });
+            stretch(ui);
+            inspect_another = add_custom_next_button(ui, tr!("inspect-another"), true).clicked();
+        });
+
+        if repair_config {
+            self.team_number = inspection
+                .team_number
+                .map(|team_number| team_number.to_string())
+                .or_else(|| {
+                    inspection
+                        .manifest
+                        .as_ref()
+                        .map(|manifest| manifest.team_number.clone())
+                })
+                .unwrap_or_default();
+            self.field_settings = inspection.field_settings.clone().unwrap_or_default();
+            self.set_step(Step::EditConfig);
+        } else if let Some(repair) = restore_files {
+            self.repair = Some(repair);
+            self.set_step(Step::Repair);
+        } else if inspect_another {
+            self.inspection = None;
+            self.selected_drive = None;
+            self.available_drives = None;
+            self.repair_result = None;
+            self.set_step(Step::ChooseCard);
+        }
+        Ok(())
+    }
+
+    fn run_edit_config(
+        &mut self,
+        app_state: &mut GlobalAppState,
+        ui: &mut egui::Ui,
+    ) -> anyhow::Result<()> {
+        column(ui, egui::Align::LEFT, |ui| {
+            heading(ui, tr!("inspect-repair-config-title"));
+            ui.label(tr!("inspect-repair-config-intro", file = GIZMO_CONFIG_FILE));
+            egui::Grid::new("card_inspection_config_grid")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    ui.label(tr!("team-number-label"));
+                    ui.text_edit_singleline(&mut self.team_number);
+                    ui.end_row();
+                    add_field_settings_rows(ui, &mut self.field_settings);
+                });
+            let problem = app_state
+                .settings
+                .team_number_rules
+                .problem(self.team_number.trim())
+                .or_else(|| self.field_settings.problem());
+            show_field_settings_problem(ui, &self.field_settings, problem.as_deref());
+            ui.horizontal(|ui| {
+                if ui.button(tr!("common-cancel")).clicked() {
+                    self.set_step(Step::Report);
+                }
+            });
+            stretch(ui);
+            if add_custom_next_button(ui, tr!("inspect-write-config"), problem.is_none()).clicked()
+            {
+                self.repair = Some(Repair::Config {
+                    team_number: self.team_number.trim().to_string(),
+                    field_settings: self.field_settings.clone(),
+                });
+                self.set_step(Step::Repair);
+            }
+        });
+        Ok(())
+    }
+
+    fn run_repair(
+        &mut self,
+        app_state: &mut GlobalAppState,
+        ui: &mut egui::Ui,
+    ) -> anyhow::Result<()> {
+        if self.repair_task.is_none() {
+            let drive = self
+                .selected_drive
+                .clone()
+                .ok_or(anyhow!("Expected selected_drive to not be None."))?;
+            let repair = self
+                .repair
+                .clone()
+                .ok_or(anyhow!("Expected repair to not be None."))?;
+            let mut indexes = IndexSource::new(
+                app_state.network(),
+                app_state.download_cache_dir(),
+                app_state.offline_source.clone(),
+                app_state.sources.driver_station.clone(),
+            );
+            let drives = app_state.drives.clone();
+            self.repair_task = Some(Task::spawn(move || {
+                let card_root = Path::new(&drive.drive_path);
+                let message = match repair {
+                    Repair::Config {
+                        team_number,
+                        field_settings,
+                    } => {
+                        write_gizmo_config(card_root, &team_number, &field_settings)
+                            .context("Failed to write the configuration to the card.")?;
+                        tr!(
+                            "inspect-config-written",
+                            file = GIZMO_CONFIG_FILE,
+                            team = team_number
+                        )
+                    }
+                    Repair::Files {
+                        software_version,
+                        paths,
+                    } => {
+                        let archive = indexes
+                            .archive(&software_version)
+                            .context("Failed to get the release archive.")?;
+                        let restored = extract_files(&archive, card_root, true, &paths)
+                            .context("Failed to restore files to the card.")?;
+                        tr!(
+                            "inspect-files-restored",
+                            count = restored,
+                            version = software_version
+                        )
+                    }
+                };
+                drives
+                    .flush(&drive)
+                    .context("Failed to flush the repair to the card.")?;
+                Ok(message)
+            }));
+        }
+
+        if let Some(message) = Task::poll(&mut self.repair_task)? {
+            self.repair_result = Some(message);
+            self.repair = None;
+            // Inspect again so the report shows the card as it is now.
+            self.inspection = None;
+            self.set_step(Step::Inspect);
+        }
+
+        column(ui, egui::Align::Center, |ui| {
+            stretch(ui);
+            ui.spinner();
+            ui.label(tr!("inspect-repairing"));
+            stretch(ui);
+        });
+        Ok(())
+    }
+}
+
+fn inspect_card(card_root: &Path, indexes: &mut IndexSource, full_hash: bool) -> Inspection {
+    let manifest = read_card_manifest(card_root)
+        .inspect_err(|e| log::info!("No manifest on {:?}: {e:#}", card_root))
+        .ok();
+    let (files_checked, file_problems) = match manifest {
+        Some(ref manifest) => match indexes.get(&manifest.software_version) {
+            Ok(index) => (
+                index.files.len(),
+                Ok(verify_card(card_root, index, full_hash)),
+            ),
+            Err(e) => (0, Err(format!("{e:#}"))),
+        },
+        None => (0, Err(tr!("inspect-no-release"))),
+    };
+    Inspection {
+        manifest,
+        config_contents: std::fs::read_to_string(card_root.join(GIZMO_CONFIG_FILE))
+            .ok()
+            .map(|contents| hide_network_key(&contents)),
+        team_number: read_configured_team(card_root),
+        field_settings: read_field_settings(card_root),
+        files_checked,
+        file_problems,
+    }
+}
+
+/// Replaces the network key in a configuration file, so it isn't shown to everyone at the help
+/// desk. Files that aren't valid JSON are returned as they are.
+fn hide_network_key(contents: &str) -> String {
+    let Ok(mut config) = serde_json::from_str::<serde_json::Value>(contents) else {
+        return contents.to_string();
+    };
+    if let Some(psk) = config.get_mut("NetPSK") {
+        *psk = serde_json::Value::String("********".to_string());
+    }
+    serde_json::to_string_pretty(&config).unwrap_or_else(|_| contents.to_string())
+}
+
+impl Page for CardInspectionPage {
+    fn run(&mut self, app_state: &mut GlobalAppState, ui: &mut egui::Ui) -> anyhow::Result<()> {
+        match self.current_step {
+            Step::ChooseCard => self.run_choose_card(app_state, ui).map_err(recoverable),
+            Step::Inspect => self.run_inspect(app_state, ui).map_err(recoverable),
+            Step::Report => self.run_report(app_state, ui),
+            Step::EditConfig => self.run_edit_config(app_state, ui),
+            Step::Repair => self.run_repair(app_state, ui).map_err(recoverable),
+        }
+    }
+
+    fn get_title(&self) -> String {
+        tr!("inspect-title")
+    }
+
+    fn step_progress(&self) -> Option<StepProgress> {
+        StepProgress::from_steps(
+            &[Step::ChooseCard, Step::Inspect, Step::Report],
+            match self.current_step {
+                // Editing and repairing are done from the report, and return to it.
+                Step::EditConfig | Step::Repair => Step::Report,
+                step => step,
+            },
+            |step| match step {
+                Step::ChooseCard => tr!("step-card"),
+                Step::Inspect => tr!("step-inspect"),
+                Step::Report | Step::EditConfig | Step::Repair => tr!("step-report"),
+            },
+        )
+    }
+
+    fn retry_step(&mut self) {
+        self.drive_list_task = None;
+        self.inspection_task = None;
+        self.repair_task = None;
+        if self.current_step == Step::ChooseCard {
+            self.available_drives = None;
+        }
+    }
+
+    fn handle_event(&mut self, _app_state: &mut GlobalAppState, event: &AppEvent) {
+        if let AppEvent::DriveAdded(_) | AppEvent::DriveRemoved(_) = event {
+            if let AppEvent::DriveRemoved(drive) = event
+                && self.selected_drive.as_ref() == Some(drive)
+                && self.current_step == Step::ChooseCard
+            {
+                self.selected_drive = None;
+            }
+            if self.current_step == Step::ChooseCard && self.drive_list_task.is_none() {
+                self.available_drives = None;
+            }
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use crate::app::GlobalAppState;
+use crate::i18n::tr;
+use crate::pages::Page;
+use crate::reconciliation::{Conflict, Source};
+use crate::widgets::accessibility::heading;
+use egui_alignments::column;
+
+/// Lists the configuration conflicts found at startup, so a volunteer knows why the wizard
+/// isn't doing what one of their configuration sources asked for.
+pub struct ConfigurationConflictsPage {
+    conflicts: Vec<Conflict>,
+}
+
+impl ConfigurationConflictsPage {
+    pub fn new(conflicts: Vec<Conflict>) -> Self {
+        Self { conflicts }
+    }
+}
+
+impl Page for ConfigurationConflictsPage {
+    fn run(&mut self, _app_state: &mut GlobalAppState, ui: &mut egui::Ui) -> anyhow::Result<()> {
+        column(ui, egui::Align::LEFT, |ui| {
+            heading(ui, tr!("conflicts-title"));
+            ui.label(tr!("conflicts-intro"));
+            ui.separator();
+            egui::Grid::new("configuration_conflicts_grid")
+                .num_columns(3)
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.strong(tr!("conflicts-directive"));
+                    ui.strong(tr!("conflicts-conflict"));
+                    ui.strong(tr!("conflicts-resolution"));
+                    ui.end_row();
+                    for conflict in &self.conflicts {
+                        ui.label(&conflict.directive);
+                        ui.label(&conflict.details);
+                        ui.label(tr!(
+                            "conflicts-outcome",
+                            resolution = &conflict.resolution,
+                            winner = conflict.winner.to_string(),
+                            loser = conflict.loser.to_string()
+                        ));
+                        ui.end_row();
+                    }
+                });
+            ui.separator();
+            ui.label(tr!("conflicts-precedence"));
+            for (i, source) in [
+                Source::BuiltIn,
+                Source::Settings,
+                Source::Environment,
+                Source::Disk,
+            ]
+            .iter()
+            .enumerate()
+            {
+                ui.label(format!("{}. {source}", i + 1));
+            }
+            ui.label(tr!("conflicts-start-over"));
+        });
+        Ok(())
+    }
+
+    fn get_title(&self) -> String {
+        tr!("conflicts-title")
+    }
+}
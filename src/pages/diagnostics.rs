@@ -0,0 +1,130 @@
+use crate::app::GlobalAppState;
+use crate::i18n::tr;
+use crate::pages::Page;
+use crate::theme::palette;
+use crate::utils::platform::{BUILD_ARCH, check_required_commands, host_arch};
+use crate::utils::tasks::Task;
+use crate::widgets::accessibility::heading;
+use crate::widgets::performance_panel::TOGGLE_SHORTCUT;
+use egui_alignments::{column, stretch};
+
+struct Diagnostics {
+    host_arch: Result<String, String>,
+    commands: Result<Vec<(&'static str, bool)>, String>,
+}
+
+/// Shows details about this computer that support needs when the wizard misbehaves.
+pub struct DiagnosticsPage {
+    diagnostics: Option<Diagnostics>,
+
+    diagnostics_task: Option<Task<Diagnostics>>,
+}
+
+impl DiagnosticsPage {
+    pub fn new() -> Self {
+        Self {
+            diagnostics: None,
+
+            diagnostics_task: None,
+        }
+    }
+}
+
+impl Page for DiagnosticsPage {
+    fn run(&mut self, app_state: &mut GlobalAppState, ui: &mut egui::Ui) -> anyhow::Result<()> {
+        if self.diagnostics.is_none() && self.diagnostics_task.is_none() {
+            self.diagnostics_task = Some(Task::spawn(move || {
+                Ok(Diagnostics {
+                    host_arch: host_arch().map_err(|e| format!("{e:#}")),
+                    commands: check_required_commands().map_err(|e| format!("{e:#}")),
+                })
+            }));
+        }
+
+        if let Some(diagnostics) = Task::poll(&mut self.diagnostics_task)? {
+            self.diagnostics = Some(diagnostics);
+        }
+
+        let mut refresh = false;
+        column(ui, egui::Align::LEFT, |ui| {
+            heading(ui, tr!("diag-title"));
+            egui::Grid::new("diagnostics_grid")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    ui.label(tr!("diag-version"));
+                    ui.label(env!("CARGO_PKG_VERSION"));
+                    ui.end_row();
+                    ui.label(tr!("diag-os"));
+                    ui.label(std::env::consts::OS);
+                    ui.end_row();
+                    ui.label(tr!("diag-build"));
+                    ui.label(BUILD_ARCH);
+                    ui.end_row();
+                    ui.label(tr!("diag-computer"));
+                    match self.diagnostics.as_ref().map(|d| &d.host_arch) {
+                        Some(Ok(arch)) => ui.label(arch),
+                        Some(Err(e)) => ui.colored_label(palette(ui).error, e),
+                        None => ui.spinner(),
+                    };
+                    ui.end_row();
+                    ui.label(tr!("diag-log-file"));
+                    match app_state.log_file {
+                        Some(ref path) => ui.label(format!("{}", path.display())),
+                        None => ui.label(tr!("diag-not-available")),
+                    };
+                    ui.end_row();
+                });
+            if let Some(Ok(arch)) = self.diagnostics.as_ref().map(|d| &d.host_arch)
+                && arch != BUILD_ARCH
+            {
+                ui.colored_label(
+                    palette(ui).error,
+                    tr!("diag-emulated", build = BUILD_ARCH, arch = arch),
+                );
+            }
+
+            ui.separator();
+            ui.label(tr!("diag-commands"));
+            match self.diagnostics.as_ref().map(|d| &d.commands) {
+                Some(Ok(commands)) => {
+                    for (command, available) in commands {
+                        if *available {
+                            ui.label(tr!("diag-command-found", command = *command));
+                        } else {
+                            ui.colored_label(
+                                palette(ui).error,
+                                tr!("diag-command-missing", command = *command),
+                            );
+                        }
+                    }
+                }
+                Some(Err(e)) => {
+                    ui.colored_label(palette(ui).error, e);
+                }
+                None => {
+                    ui.spinner();
+                }
+            }
+            ui.separator();
+            ui.label(tr!(
+                "diag-performance",
+                shortcut = ui.ctx().format_shortcut(&TOGGLE_SHORTCUT)
+            ));
+            stretch(ui);
+            refresh = ui
+                .add_enabled(
+                    self.diagnostics.is_some(),
+                    egui::Button::new(tr!("common-refresh")),
+                )
+                .clicked();
+        });
+        if refresh {
+            self.diagnostics = None;
+        }
+        Ok(())
+    }
+
+    fn get_title(&self) -> String {
+        tr!("diag-title")
+    }
+}
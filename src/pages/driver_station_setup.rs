@@ -1,42 +1,120 @@
 use crate::app::GlobalAppState;
-use crate::pages::{Page, add_custom_next_button, add_next_button};
-use crate::utils::drive_management::{DriveInfo, list_drives};
-use crate::utils::github::GithubRelease;
-use crate::utils::threads::join_thread;
-use anyhow::anyhow;
+use crate::events::AppEvent;
+use crate::i18n::tr;
+use crate::pages::diagnostics::DiagnosticsPage;
+use crate::pages::{Page, StepProgress, add_custom_next_button, add_next_button, recoverable};
+use crate::settings::Settings;
+use crate::theme::palette;
+use crate::utils::cache::format_size;
+use crate::utils::card_manifest::read_card_manifest;
+use crate::utils::compatibility::{Component, preferred_release};
+use crate::utils::csv::csv_field;
+use crate::utils::drive_management::{DriveInfo, DriveManager, DriveSafety, check_drive_safety};
+use crate::utils::file_download::DownloadProgress;
+use crate::utils::github::{GithubRelease, download_versioned_asset_with_progress};
+use crate::utils::github_artifacts::add_development_builds;
+use crate::utils::gizmo_config::FieldSettings;
+use crate::utils::imaging::{ImagePhase, is_disk_image};
+use crate::utils::release_manifest::{ReleaseManifest, load_release_manifest};
+use crate::utils::session_report::{CardRecord, unix_now};
+use crate::utils::sounds::{Cue, play_cue};
+use crate::utils::tasks::Task;
+use crate::utils::work_dir::WorkDir;
+use crate::widgets::accessibility::heading;
+use crate::widgets::capacity_check_dialog::CapacityCheckDialog;
+use crate::widgets::compatibility_warnings::show_compatibility_warnings;
+use crate::widgets::fetch_error_card::{FetchErrorAction, show_fetch_error_card};
+use crate::widgets::field_settings_editor::{add_field_settings_rows, show_field_settings_problem};
+use crate::widgets::release_notes::show_release_notes;
+use crate::widgets::version_picker::show_version_picker;
+use anyhow::{Context, anyhow};
 use egui_alignments::{column, stretch};
 use egui_file_dialog::FileDialog;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 use std::sync::mpsc::Receiver;
-use std::time::Duration;
-
-enum Step {
-    ChooseVersion,
-    EnterTeamNumbers,
-    DownloadArchive,
-    ChooseDrive,
-    InstallSoftware,
-    RemoveCard,
-}
+
+mod card_job;
+mod card_sources;
+mod flow;
+pub mod session;
+mod team_progress;
+
+use card_job::{CardInstall, CardJob, CardStatus, worn_card_warning};
+use card_sources::{CardDownloads, ClonedCard, DocsSource, read_source_card, stage_archive};
+use flow::{FAILURE_STREAK_LIMIT, FlowState, Step};
+use session::{SESSION_FILE, SavedSession};
+use team_progress::{TeamProgress, io_error_warning};
 
 pub struct DriverStationSetupPage {
     current_step: Step,
     available_releases: Option<Vec<GithubRelease>>,
+    releases_error: Option<String>,
     software_version: Option<GithubRelease>,
+    /// Major version to preselect, so a kit gets a driver station from the same release family.
+    release_family: Option<u32>,
     archive_path: Option<std::path::PathBuf>,
+    /// The archive extracted once to local disk, so each card is a plain copy instead of a
+    /// decompression. `None` if staging was skipped or ran out of space.
+    staged_dir: Option<WorkDir>,
     use_local_archive: bool,
+    /// Copy an existing card to each new card instead of installing a release.
+    clone_card: bool,
+    clone_source: Option<DriveInfo>,
+    /// Software version recorded on the card being cloned.
+    cloned_version: Option<String>,
+    docs_source: Option<DocsSource>,
+    docs_paths: Vec<std::path::PathBuf>,
+    release_manifest: ReleaseManifest,
     team_numbers_text: String,
     team_numbers: Vec<String>,
+    /// Team names from an imported roster, keyed by team number.
+    team_names: HashMap<String, String>,
+    roster_error: Option<String>,
+    /// How each team's most recent install went. Teams in the current batch are tracked by
+    /// their card jobs until the batch finishes.
+    team_progress: HashMap<String, TeamProgress>,
+    team_progress_status: Option<String>,
     team_number_index: usize,
-    available_drives: Option<Vec<DriveInfo>>,
-    selected_drive: Option<DriveInfo>,
+    single_team_warning_acknowledged: bool,
+    field_settings: FieldSettings,
+    available_drives: Option<Vec<DriveCandidate>>,
+    selected_drives: Vec<DriveInfo>,
+    /// The team to install on each selected drive, in the same order.
+    drive_teams: Vec<String>,
+    /// Install every card in the batch at once instead of one after another.
+    parallel_install: bool,
+    erase_confirmation_text: Option<String>,
+    erase_confirmed: bool,
+    card_jobs: Vec<CardJob>,
+    /// Cards in the batch waiting for the one before them to finish.
+    queued_cards: Vec<(DriveInfo, String, CardInstall)>,
+    finished_card_jobs: Vec<CardJob>,
+    /// Cards from the finished batch that are still plugged in. Once they are all gone, the next
+    /// card inserted is offered for the next team.
+    cards_to_remove: Vec<DriveInfo>,
+    /// A card inserted after the batch was removed, waiting for the user to confirm it.
+    new_card: Option<DriveCandidate>,
+    capacity_check: Option<CapacityCheckDialog>,
+    /// Cards that failed in a row, across batches. Cards the user cancelled don't count.
+    failure_streak: usize,
+    /// Diagnostics shown over the page while the batch is paused for repeated failures.
+    diagnostics: Option<DiagnosticsPage>,
+    /// Save the queue so it can be resumed after a restart. Off for single cards set up as
+    /// part of a kit.
+    remember_session: bool,
 
-    available_releases_receiver: Option<Receiver<Vec<GithubRelease>>>,
-    download_finished_receiver: Option<Receiver<std::path::PathBuf>>,
-    drive_list_receiver: Option<Receiver<Vec<DriveInfo>>>,
-    install_finished_receiver: Option<Receiver<()>>,
+    releases_task: Option<Task<anyhow::Result<Vec<GithubRelease>>>>,
+    download_task: Option<Task<CardDownloads>>,
+    download_progress: Option<DownloadProgress>,
+    download_progress_receiver: Option<Receiver<DownloadProgress>>,
+    drive_list_task: Option<Task<Vec<DriveCandidate>>>,
+    clone_task: Option<Task<ClonedCard>>,
+    new_card_task: Option<Task<DriveCandidate>>,
 
-    background_thread: Option<std::thread::JoinHandle<()>>,
     file_dialog: FileDialog,
+    roster_dialog: FileDialog,
+    team_progress_dialog: FileDialog,
 }
 
 impl DriverStationSetupPage {
@@ -44,60 +122,239 @@ impl DriverStationSetupPage {
         Self {
             current_step: Step::ChooseVersion,
             available_releases: None,
+            releases_error: None,
             software_version: None,
+            release_family: None,
             archive_path: None,
+            staged_dir: None,
             use_local_archive: false,
+            clone_card: false,
+            clone_source: None,
+            cloned_version: None,
+            docs_source: None,
+            docs_paths: vec![],
+            release_manifest: ReleaseManifest::default(),
             team_numbers_text: String::new(),
             team_numbers: vec![],
+            team_names: HashMap::new(),
+            roster_error: None,
+            team_progress: HashMap::new(),
+            team_progress_status: None,
             team_number_index: 0,
+            single_team_warning_acknowledged: false,
+            field_settings: FieldSettings::default(),
             available_drives: None,
-            selected_drive: None,
+            selected_drives: vec![],
+            drive_teams: vec![],
+            parallel_install: false,
+            erase_confirmation_text: None,
+            erase_confirmed: false,
+            card_jobs: vec![],
+            queued_cards: vec![],
+            finished_card_jobs: vec![],
+            cards_to_remove: vec![],
+            new_card: None,
+            capacity_check: None,
+            failure_streak: 0,
+            diagnostics: None,
+            remember_session: true,
 
-            available_releases_receiver: None,
-            download_finished_receiver: None,
-            drive_list_receiver: None,
-            install_finished_receiver: None,
+            releases_task: None,
+            download_task: None,
+            download_progress: None,
+            download_progress_receiver: None,
+            drive_list_task: None,
+            clone_task: None,
+            new_card_task: None,
 
-            background_thread: None,
             file_dialog: FileDialog::new()
-                .add_file_filter_extensions("ZIP Archive", vec!["zip"])
-                .default_file_filter("ZIP Archive"),
+                .add_file_filter_extensions(
+                    &tr!("ds-archive-filter"),
+                    vec!["zip", "tar", "gz", "tgz", "xz", "zst", "tzst"],
+                )
+                .add_file_filter_extensions(
+                    &tr!("ds-disk-image-filter"),
+                    vec!["img", "gz", "xz", "zst"],
+                )
+                .default_file_filter(&tr!("ds-archive-filter")),
+            roster_dialog: FileDialog::new()
+                .title(&tr!("ds-roster-dialog"))
+                .add_file_filter_extensions(&tr!("ds-csv-filter"), vec!["csv"])
+                .default_file_filter(&tr!("ds-csv-filter")),
+            team_progress_dialog: FileDialog::new()
+                .title(&tr!("ds-progress-dialog"))
+                .default_file_name("team-progress.csv"),
+        }
+    }
+
+    fn flow_state(&self) -> FlowState {
+        FlowState {
+            use_local_archive: self.use_local_archive,
+            clone_card: self.clone_card,
+            fetch_docs: self.docs_source.is_some(),
+            teams_remaining: self.team_numbers.len() - self.team_number_index,
+            cards_selected: self.selected_drives.len(),
+        }
+    }
+
+    /// Sets up the card for one team, preselecting the newest release from the same family as
+    /// the rest of the kit.
+    pub fn for_team(team_number: &str, release_family: Option<u32>) -> Self {
+        Self {
+            release_family,
+            team_numbers_text: team_number.to_string(),
+            team_numbers: vec![team_number.to_string()],
+            remember_session: false,
+            ..Self::new()
+        }
+    }
+
+    /// Picks up a session saved by an earlier run, at the network settings so they can be
+    /// checked before the next card.
+    pub fn resume(session: SavedSession, settings: &Settings) -> Self {
+        let mut page = Self::new();
+        page.team_number_index = session.completed_teams.len();
+        page.team_progress = session
+            .completed_teams
+            .iter()
+            .map(|team| (team.clone(), TeamProgress::Done(None)))
+            .collect();
+        page.team_numbers = session.completed_teams;
+        page.team_numbers.extend(session.remaining_teams);
+        page.team_numbers_text = page.team_numbers.join("\n");
+        page.team_names = session.team_names;
+        page.field_settings = session.field_settings;
+        match session.local_archive {
+            Some(path) if !path.is_file() => {
+                // The archive was moved or deleted, so the version has to be chosen again.
+                log::warn!("Archive {:?} from the saved session is missing", path);
+            }
+            Some(path) => {
+                page.archive_path = Some(path);
+                page.use_local_archive = true;
+                page.current_step = Step::ConfigureField;
+            }
+            None => {
+                page.software_version = session.software_version;
+                page.current_step = Step::ConfigureField;
+            }
+        }
+        page.docs_source = DocsSource::from_settings(
+            settings,
+            page.software_version
+                .as_ref()
+                .filter(|_| !page.use_local_archive),
+        );
+        log::info!(
+            "Resuming driver station session with {} of {} teams done",
+            page.team_number_index,
+            page.team_numbers.len()
+        );
+        page
+    }
+
+    /// Whether every team's card has been set up, for flows that continue to another page.
+    pub fn is_finished(&self) -> bool {
+        self.current_step == Step::RemoveCard
+            && self.current_step.next(&self.flow_state()).is_none()
+    }
+
+    fn advance(&mut self) {
+        if let Some(next) = self.current_step.next(&self.flow_state()) {
+            log::info!(
+                "Driver station setup: {:?} -> {:?}",
+                self.current_step,
+                next
+            );
+            self.current_step = next;
+            if next == Step::ChooseDrive {
+                self.save_session();
+            }
+        }
+    }
+
+    /// Writes the queue to the session file, or removes the file once every team is done.
+    /// Cloned cards aren't saved, since the source card has to be chosen again anyway.
+    fn save_session(&self) {
+        if !self.remember_session || self.clone_card {
+            return;
+        }
+        let remaining_teams = self.team_numbers[self.team_number_index..].to_vec();
+        if remaining_teams.is_empty() {
+            session::clear();
+            return;
+        }
+        let session = SavedSession {
+            format_version: SESSION_FILE.current_version(),
+            saved_at: unix_now(),
+            software_version: self
+                .software_version
+                .clone()
+                .filter(|_| !self.use_local_archive),
+            local_archive: self.archive_path.clone().filter(|_| self.use_local_archive),
+            completed_teams: self.team_numbers[..self.team_number_index].to_vec(),
+            remaining_teams,
+            team_names: self.team_names.clone(),
+            field_settings: self.field_settings.clone(),
+        };
+        if let Err(e) = session::save(&session) {
+            log::warn!("Could not save the driver station session: {e:#}");
         }
     }
 
     fn run_choose_version(
         &mut self,
-        _app_state: &mut GlobalAppState,
+        app_state: &mut GlobalAppState,
         ui: &mut egui::Ui,
     ) -> anyhow::Result<()> {
-        if self.available_releases.is_none() && self.background_thread.is_none() {
-            let (tx, rx) = std::sync::mpsc::channel();
-            self.available_releases_receiver = Some(rx);
-            self.background_thread = Some(std::thread::spawn(move || {
-                let releases = crate::utils::github::get_releases("gizmo-platform", "gizmo")
-                    .unwrap_or(Vec::new());
-                tx.send(releases)
-                    .expect("Failed to send release details to main thread.");
+        if self.available_releases.is_none() && self.releases_task.is_none() {
+            let offline_source = app_state.offline_source.clone();
+            let repo = app_state.sources.driver_station.clone();
+            let channel = app_state.release_channel;
+            let network = app_state.network();
+            self.releases_task = Some(Task::spawn(move || {
+                // Failing to fetch releases is shown on the page rather than as an error.
+                Ok(crate::utils::offline::get_releases_for_source(
+                    &network,
+                    offline_source.as_deref(),
+                    &repo.owner,
+                    &repo.name,
+                )
+                .map(|mut releases| {
+                    add_development_builds(
+                        &network,
+                        &mut releases,
+                        channel,
+                        offline_source.as_deref(),
+                        &repo,
+                    );
+                    releases
+                }))
             }));
         }
-        if let Some(thread) = self.background_thread.take_if(|t| t.is_finished()) {
-            join_thread(thread)?;
-            let receiver = self.available_releases_receiver.take().ok_or(anyhow!(
-                "Expected available_releases_receiver to not be None."
-            ))?;
-            self.available_releases = Some(receiver.recv_timeout(Duration::from_secs(1))?);
-        }
-        if let Some(releases) = self.available_releases.as_ref() {
-            if !releases.is_empty() && self.software_version.is_none() {
-                self.software_version = Some(
-                    releases
-                        .iter()
-                        .find(|r| r.latest)
-                        .ok_or(anyhow!("Latest release not found"))?
-                        .clone(),
-                );
+        if let Some(releases) = Task::poll(&mut self.releases_task)? {
+            match releases {
+                Ok(releases) => {
+                    self.releases_error = None;
+                    self.available_releases = Some(releases);
+                }
+                Err(e) => {
+                    self.releases_error =
+                        Some(tr!("releases-fetch-failed", error = format!("{e:#}")));
+                    self.available_releases = Some(vec![]);
+                }
             }
         }
+        if let Some(releases) = self.available_releases.as_ref()
+            && !releases.is_empty()
+            && self.software_version.is_none()
+        {
+            self.software_version = Some(
+                preferred_release(releases, self.release_family)
+                    .ok_or(anyhow!("Latest release not found"))?
+                    .clone(),
+            );
+        }
         let next_button_enabled = self.software_version.is_some();
 
         self.file_dialog.update(ui.ctx());
@@ -105,301 +362,1423 @@ impl DriverStationSetupPage {
         if let Some(selected_file) = self.file_dialog.take_picked() {
             self.archive_path = Some(selected_file);
             self.use_local_archive = true;
-            self.current_step = Step::EnterTeamNumbers;
+            self.advance();
         }
 
+        let can_work_offline =
+            app_state.offline_source.is_none() && app_state.download_cache_dir().is_dir();
+        let mut fetch_error_action = None;
+        let mut compatible = true;
+        let mut channel_changed = false;
         column(ui, egui::Align::LEFT, |ui| {
-            ui.heading("Software Version");
-            ui.label("Select the version of the software you want to install. Usually, this should be the latest version.");
+            heading(ui, tr!("releases-software-version"));
+            ui.label(tr!("ds-version-intro"));
             if let Some(ref releases) = self.available_releases {
-                egui::ComboBox::from_label("Pick a version")
-                    .selected_text(match self.software_version {
-                        Some(ref version) => version.display_name(),
-                        None => "Select Version".to_string(),
-                    })
-                    .show_ui(ui, |ui| {
-                        for release in releases {
-                            ui.selectable_value(
-                                &mut self.software_version,
-                                Some(release.clone()),
-                                release.display_name(),
-                            );
-                        }
-                    });
+                channel_changed = show_version_picker(
+                    ui,
+                    releases,
+                    &mut self.software_version,
+                    &mut app_state.release_channel,
+                );
+                compatible = show_compatibility_warnings(
+                    ui,
+                    &app_state.compatibility,
+                    Component::DriverStation,
+                    self.software_version.as_ref(),
+                );
             } else {
                 ui.spinner();
-                ui.label("Fetching available releases...");
+                ui.label(tr!("releases-fetching"));
+            }
+            if let Some(ref error) = self.releases_error {
+                fetch_error_action = show_fetch_error_card(ui, error, can_work_offline);
             }
-            if ui.link("Use local file instead").clicked() {
+            if ui.link(tr!("releases-use-local-file")).clicked() {
                 self.file_dialog.pick_file();
             }
+            if ui
+                .link(tr!("ds-clone-instead"))
+                .on_hover_text(tr!("ds-clone-instead-hint"))
+                .clicked()
+            {
+                self.clone_card = true;
+                self.advance();
+            }
             stretch(ui);
-            if add_next_button(ui, next_button_enabled).clicked() {
-                self.current_step = Step::EnterTeamNumbers;
+            if add_next_button(ui, next_button_enabled && compatible).clicked() {
+                self.advance();
             }
         });
+        if channel_changed {
+            self.available_releases = None;
+        }
+        match fetch_error_action {
+            Some(FetchErrorAction::Retry) => self.available_releases = None,
+            Some(FetchErrorAction::WorkOffline) => {
+                let cache_dir = app_state.download_cache_dir();
+                log::info!("Working offline from {:?}", cache_dir);
+                app_state.offline_source = Some(cache_dir);
+                self.available_releases = None;
+            }
+            None => {}
+        }
         Ok(())
     }
 
     fn run_enter_team_numbers(
+        &mut self,
+        app_state: &mut GlobalAppState,
+        ui: &mut egui::Ui,
+    ) -> anyhow::Result<()> {
+        let release = self
+            .software_version
+            .as_ref()
+            .filter(|_| !self.use_local_archive);
+        // Cloned cards bring their documentation with them.
+        self.docs_source = if self.clone_card {
+            None
+        } else {
+            DocsSource::from_settings(&app_state.settings, release)
+        };
+
+        self.roster_dialog.update(ui.ctx());
+        if let Some(roster_path) = self.roster_dialog.take_picked() {
+            match import_roster(&roster_path) {
+                Ok(roster) => {
+                    log::info!("Imported {} teams from {:?}", roster.len(), roster_path);
+                    self.team_numbers = roster.iter().map(|e| e.team_number.clone()).collect();
+                    self.team_numbers_text = self.team_numbers.join("\n");
+                    self.team_names = roster
+                        .into_iter()
+                        .filter_map(|e| Some((e.team_number, e.team_name?)))
+                        .collect();
+                    self.roster_error = None;
+                }
+                Err(e) => self.roster_error = Some(format!("{e:#}")),
+            }
+        }
+
+        column(ui, egui::Align::LEFT, |ui| {
+            heading(ui, tr!("ds-teams-heading"));
+            ui.label(tr!("ds-teams-intro"));
+            if ui.button(tr!("ds-import-csv")).clicked() {
+                self.roster_dialog.pick_file();
+            }
+            if let Some(ref error) = self.roster_error {
+                ui.colored_label(palette(ui).error, error);
+            }
+
+            let rules = &app_state.settings.team_number_rules;
+            let problem_lines: HashSet<usize> =
+                match flow::parse_team_numbers(&self.team_numbers_text, rules) {
+                    Ok(_) => HashSet::new(),
+                    Err(problems) => problems.iter().map(|problem| problem.line).collect(),
+                };
+            let error_color = palette(ui).error;
+            let mut layouter = |ui: &egui::Ui, text: &dyn egui::TextBuffer, wrap_width: f32| {
+                let font_id = egui::TextStyle::Body.resolve(ui.style());
+                let mut job = egui::text::LayoutJob::default();
+                for (line, line_text) in text.as_str().split_inclusive('\n').enumerate() {
+                    let color = if problem_lines.contains(&line) {
+                        error_color
+                    } else {
+                        ui.visuals().text_color()
+                    };
+                    job.append(
+                        line_text,
+                        0.0,
+                        egui::TextFormat::simple(font_id.clone(), color),
+                    );
+                }
+                job.wrap.max_width = wrap_width;
+                ui.fonts_mut(|fonts| fonts.layout_job(job))
+            };
+            let text_edit_response = ui.add(
+                egui::TextEdit::multiline(&mut self.team_numbers_text).layouter(&mut layouter),
+            );
+            match flow::parse_team_numbers(&self.team_numbers_text, rules) {
+                Ok(team_numbers) => {
+                    if text_edit_response.changed() {
+                        self.team_numbers = team_numbers;
+                    }
+                }
+                Err(problems) => {
+                    egui::ScrollArea::vertical()
+                        .id_salt("team_number_problems")
+                        .max_height(80.0)
+                        .show(ui, |ui| {
+                            for problem in problems {
+                                ui.colored_label(
+                                    error_color,
+                                    tr!(
+                                        "ds-team-line-problem",
+                                        line = problem.line + 1,
+                                        problem = problem.message
+                                    ),
+                                );
+                            }
+                        });
+                    self.team_numbers.clear();
+                }
+            }
+            ui.label(tr!("ds-teams-count", count = self.team_numbers.len()));
+            if !self.team_names.is_empty() {
+                egui::ScrollArea::vertical()
+                    .max_height(150.0)
+                    .show(ui, |ui| {
+                        for team_number in &self.team_numbers {
+                            ui.label(team_label(&self.team_names, team_number));
+                        }
+                    });
+            }
+
+            let show_single_team_warning = flow::needs_single_team_acknowledgement(
+                self.team_numbers.len(),
+                self.is_single_team_variant(),
+            );
+            if show_single_team_warning {
+                ui.colored_label(palette(ui).error, tr!("ds-single-team-warning"));
+                ui.checkbox(
+                    &mut self.single_team_warning_acknowledged,
+                    tr!("ds-single-team-acknowledge"),
+                );
+            }
+
+            stretch(ui);
+
+            let next_enabled = flow::team_numbers_next_enabled(
+                self.team_numbers.len(),
+                show_single_team_warning,
+                self.single_team_warning_acknowledged,
+            );
+            if add_next_button(ui, next_enabled).clicked() {
+                self.advance();
+            }
+        });
+        Ok(())
+    }
+
+    fn run_configure_field(
         &mut self,
         _app_state: &mut GlobalAppState,
         ui: &mut egui::Ui,
     ) -> anyhow::Result<()> {
         column(ui, egui::Align::LEFT, |ui| {
-            ui.heading("Team Numbers");
-            ui.label("Enter your team numbers, one per line.");
-
-            let text_edit_response = ui.text_edit_multiline(&mut self.team_numbers_text);
-            let text_valid = self
-                .team_numbers_text
-                .chars()
-                .all(|c| c.is_ascii_digit() || c == '\n');
-            if !text_valid {
-                ui.colored_label(egui::Color32::DARK_RED, "Invalid team numbers.");
-                self.team_numbers.clear();
-            }
-            if text_valid && text_edit_response.changed() {
-                self.team_numbers = self
-                    .team_numbers_text
-                    .lines()
-                    .filter(|l| !l.is_empty())
-                    .map(|s| s.to_string())
-                    .collect();
-            }
-            ui.label(format!("{} team numbers.", self.team_numbers.len()));
+            heading(ui, tr!("ds-field-heading"));
+            ui.label(tr!("ds-field-intro"));
+            egui::Grid::new("field_settings_grid")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    add_field_settings_rows(ui, &mut self.field_settings)
+                });
+            let problem = self.field_settings.problem();
+            show_field_settings_problem(ui, &self.field_settings, problem.as_deref());
 
             stretch(ui);
 
-            if add_next_button(ui, !self.team_numbers.is_empty()).clicked() {
-                self.current_step = Step::DownloadArchive;
+            if add_next_button(ui, problem.is_none()).clicked() {
+                self.advance();
             }
         });
         Ok(())
     }
 
+    fn is_single_team_variant(&self) -> bool {
+        if self.clone_card {
+            false
+        } else if self.use_local_archive {
+            self.archive_path
+                .as_ref()
+                .and_then(|p| p.file_name())
+                .and_then(|n| n.to_str())
+                .is_some_and(crate::utils::github::is_single_team_name)
+        } else {
+            self.software_version
+                .as_ref()
+                .is_some_and(|r| r.is_single_team_variant())
+        }
+    }
+
     fn run_download_archive(
         &mut self,
         app_state: &mut GlobalAppState,
         ui: &mut egui::Ui,
     ) -> anyhow::Result<()> {
-        if self.use_local_archive {
-            self.current_step = Step::ChooseDrive;
-            return Ok(());
-        }
-        if self.archive_path.is_none() && self.background_thread.is_none() {
-            let thread_release = self
-                .software_version
-                .clone()
-                .ok_or(anyhow!("Expected software_version to not be None."))?;
-            let cache_path = app_state.tmp_dir.path().join("github_downloads");
-            let (tx, rx) = std::sync::mpsc::channel();
-            self.download_finished_receiver = Some(rx);
-            self.background_thread = Some(std::thread::spawn(move || {
-                let asset = thread_release
-                    .assets
-                    .iter()
-                    .find(|a| a.name == "ds-ramdisk.zip")
-                    .expect("Could not find ds-ramdisk.zip in release assets.");
-                let archive_path = crate::utils::github::download_versioned_asset(
-                    asset,
-                    "gizmo-platform",
-                    "gizmo",
-                    &thread_release,
-                    &cache_path,
-                )
-                .expect("Failed to download ramdisk archive.");
-                tx.send(archive_path)
-                    .expect("Failed to send download path to main thread.");
+        if self.download_task.is_none() {
+            let thread_release = self.software_version.clone();
+            let local_archive = self.archive_path.clone().filter(|_| self.use_local_archive);
+            let use_local_archive = self.use_local_archive;
+            let docs_source = self.docs_source.clone();
+            let repo = app_state.sources.driver_station.clone();
+            let cache_path = app_state.download_cache_dir();
+            let staging_dir = app_state.work_dir("ds-staging")?;
+            let events = app_state.events.clone();
+            let network = app_state.network();
+            let (progress_sender, progress_receiver) = std::sync::mpsc::channel();
+            self.download_progress = None;
+            self.download_progress_receiver = Some(progress_receiver);
+            self.download_task = Some(Task::spawn(move || {
+                let archive_path = match local_archive {
+                    Some(archive_path) => archive_path,
+                    None => {
+                        let release = thread_release
+                            .as_ref()
+                            .context("Expected software_version to not be None.")?;
+                        let asset = release.driver_station_asset().context(
+                            "Could not find a ds-ramdisk archive or a disk image in release assets.",
+                        )?;
+                        let archive_path = download_versioned_asset_with_progress(
+                            &network,
+                            asset,
+                            &repo.owner,
+                            &repo.name,
+                            release,
+                            &cache_path,
+                            |progress| {
+                                let _ = progress_sender.send(progress.clone());
+                            },
+                        )
+                        .context("Failed to download ramdisk archive.")?;
+                        events.publish(AppEvent::DownloadFinished(archive_path.clone()));
+                        archive_path
+                    }
+                };
+                let docs = match docs_source {
+                    Some(source) => source
+                        .download(&network, thread_release.as_ref(), &repo, &cache_path)
+                        .context("Failed to download documentation.")?,
+                    None => vec![],
+                };
+                // Local archives have no release to read a manifest from.
+                let release_manifest = match thread_release.as_ref().filter(|_| !use_local_archive)
+                {
+                    Some(release) => load_release_manifest(&network, release, &repo, &cache_path)
+                        .context("Failed to read release manifest.")?,
+                    None => ReleaseManifest::default(),
+                };
+                // Disk images are written to each card as they are, so there is nothing to stage.
+                let staged_dir = if is_disk_image(&archive_path) {
+                    None
+                } else {
+                    stage_archive(&archive_path, staging_dir, &events)
+                        .context("Failed to extract software archive.")?
+                };
+                Ok(CardDownloads {
+                    archive_path,
+                    staged_dir,
+                    docs,
+                    release_manifest,
+                })
             }));
         }
 
-        if let Some(thread) = self.background_thread.take_if(|t| t.is_finished()) {
-            join_thread(thread)?;
-            let receiver = self.download_finished_receiver.take().ok_or(anyhow!(
-                "Expected download_finished_receiver to not be None."
-            ))?;
-            self.archive_path = Some(receiver.recv_timeout(Duration::from_secs(1))?);
-            self.current_step = Step::ChooseDrive;
+        if let Some(receiver) = &self.download_progress_receiver {
+            while let Ok(progress) = receiver.try_recv() {
+                self.download_progress = Some(progress);
+            }
+        }
+        if let Some(downloads) = Task::poll(&mut self.download_task)? {
+            self.download_progress_receiver = None;
+            self.archive_path = Some(downloads.archive_path);
+            self.staged_dir = downloads.staged_dir;
+            self.docs_paths = downloads.docs;
+            self.release_manifest = downloads.release_manifest;
+            self.advance();
         }
 
         column(ui, egui::Align::Center, |ui| {
             stretch(ui);
-            ui.spinner();
-            ui.label("Downloading software archive...");
+            match self.download_progress {
+                Some(ref progress) if progress.bytes_done < progress.bytes_total => {
+                    ui.add(egui::ProgressBar::new(progress.fraction()).text(tr!(
+                        "ds-downloading-progress",
+                        done = format_size(progress.bytes_done),
+                        total = format_size(progress.bytes_total)
+                    )));
+                }
+                _ => {
+                    ui.spinner();
+                    ui.label(tr!("ds-downloading"));
+                }
+            }
             stretch(ui);
         });
         Ok(())
     }
 
-    fn run_choose_drive(
+    fn start_drive_listing(&mut self, drives: Arc<dyn DriveManager>) {
+        self.drive_list_task = Some(Task::spawn(move || {
+            let drives = drives
+                .list()
+                .context("Failed to get list of available drives.")?;
+            Ok(drives.into_iter().map(DriveCandidate::inspect).collect())
+        }));
+    }
+
+    fn run_choose_source_card(
         &mut self,
-        _app_state: &mut GlobalAppState,
+        app_state: &mut GlobalAppState,
         ui: &mut egui::Ui,
     ) -> anyhow::Result<()> {
-        if self.available_drives.is_none() && self.background_thread.is_none() {
-            let (tx, rx) = std::sync::mpsc::channel();
-            self.drive_list_receiver = Some(rx);
-            self.background_thread = Some(std::thread::spawn(move || {
-                let drives = list_drives().expect("Falied to get list of available drives.");
-                tx.send(drives)
-                    .expect("Failed to send drive list to main thread.");
-            }));
+        if self.available_drives.is_none() && self.drive_list_task.is_none() {
+            self.start_drive_listing(app_state.drives.clone());
         }
 
-        if let Some(thread) = self.background_thread.take_if(|t| t.is_finished()) {
-            join_thread(thread)?;
-            let receiver = self
-                .drive_list_receiver
+        if let Some(cloned) = Task::poll(&mut self.clone_task)? {
+            let source = self
+                .clone_source
                 .take()
-                .ok_or(anyhow!("Expected drive_list_receiver to not be None."))?;
-            self.available_drives = Some(receiver.recv_timeout(Duration::from_secs(1))?);
+                .ok_or(anyhow!("Expected clone_source to not be None."))?;
+            log::info!("Read {} for cloning", source);
+            // Cards are copied from the staged clone. The archive path only records where
+            // the software came from.
+            self.archive_path = Some(source.drive_path);
+            self.staged_dir = Some(cloned.staged_dir);
+            self.cloned_version = Some(cloned.software_version);
+            if let Some(field_settings) = cloned.field_settings {
+                self.field_settings = field_settings;
+            }
+            self.available_drives = None;
+            self.advance();
+            return Ok(());
+        }
+        if let Some(drives) = Task::poll(&mut self.drive_list_task)? {
+            self.available_drives = Some(drives);
         }
 
+        let reading = self.clone_task.is_some();
+        let mut read_card = false;
         column(ui, egui::Align::LEFT, |ui| {
-            ui.heading("Choose Drive");
-
-            let team_number = self.team_numbers[self.team_number_index].clone();
-            ui.label(format!(
-                r#"Setting up driver station for team {team_number}.
-            
-1. Insert the microSD card for this team into your computer.
-2. Click the "Refresh" button to update the list below.
-3. Select the microSD card drive from the list and click "Install Software".
-"#
-            ));
+            heading(ui, tr!("ds-clone-heading"));
+            ui.label(tr!("ds-clone-intro"));
+            if reading {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(tr!("ds-reading-card"));
+                });
+            } else if let Some(ref drives) = self.available_drives {
+                if drives.is_empty() {
+                    ui.label(tr!("drives-none"));
+                }
+                for candidate in drives {
+                    let label = match candidate.installed_version {
+                        Some(ref version) => {
+                            format!("{} - {version}", candidate.drive.picker_label())
+                        }
+                        None => candidate.drive.picker_label(),
+                    };
+                    ui.radio_value(&mut self.clone_source, Some(candidate.drive.clone()), label);
+                }
+                if ui.button(tr!("common-refresh")).clicked() {
+                    self.available_drives = None;
+                    self.clone_source = None;
+                }
+            } else {
+                ui.spinner();
+                ui.label(tr!("drives-searching"));
+            }
+            stretch(ui);
+            read_card = add_custom_next_button(
+                ui,
+                tr!("ds-read-card"),
+                !reading && self.clone_source.is_some(),
+            )
+            .clicked();
+        });
+
+        if read_card
+            && self.clone_task.is_none()
+            && let Some(ref source) = self.clone_source
+        {
+            let source = source.clone();
+            let staging_dir = app_state.work_dir("ds-clone")?;
+            self.clone_task = Some(Task::spawn(move || read_source_card(&source, staging_dir)));
+        }
+        Ok(())
+    }
+
+    fn run_choose_drive(
+        &mut self,
+        app_state: &mut GlobalAppState,
+        ui: &mut egui::Ui,
+    ) -> anyhow::Result<()> {
+        if self.available_drives.is_none() && self.drive_list_task.is_none() {
+            self.start_drive_listing(app_state.drives.clone());
+        }
+
+        if let Some(drives) = Task::poll(&mut self.drive_list_task)? {
+            self.available_drives = Some(drives);
+        }
+
+        column(ui, egui::Align::LEFT, |ui| {
+            heading(ui, tr!("ds-drives-heading"));
+
+            let pending_teams = &self.team_numbers[self.team_number_index..];
+            ui.label(
+                [
+                    tr!(
+                        "ds-drives-remaining",
+                        remaining = pending_teams.len(),
+                        team = &pending_teams[0]
+                    ),
+                    String::new(),
+                    tr!("ds-drives-step-insert"),
+                    tr!("ds-drives-step-refresh"),
+                    tr!("ds-drives-step-select"),
+                ]
+                .join("\n"),
+            );
 
             if let Some(ref drives) = self.available_drives {
                 if drives.is_empty() {
-                    ui.label("No removable drives found.");
+                    ui.label(tr!("drives-none"));
                 } else {
-                    for drive in drives {
-                        ui.selectable_value(
-                            &mut self.selected_drive,
-                            Some(drive.clone()),
-                            format!("{drive}"),
+                    let target_tag = self
+                        .software_version
+                        .as_ref()
+                        .filter(|_| !self.use_local_archive && !self.clone_card)
+                        .map(|release| release.tag_name.as_str());
+                    for DriveCandidate {
+                        drive,
+                        safety,
+                        installed_version,
+                    } in drives
+                    {
+                        let position = self.selected_drives.iter().position(|d| d == drive);
+                        let can_select = flow::can_select_drive(
+                            position.is_some(),
+                            self.selected_drives.len(),
+                            pending_teams.len(),
                         );
+                        let label = match position {
+                            Some(i) => tr!(
+                                "ds-drive-for-team",
+                                drive = drive.picker_label(),
+                                team = team_label(&self.team_names, &pending_teams[i])
+                            ),
+                            None => drive.picker_label(),
+                        };
+                        let mut selected = position.is_some();
+                        let response =
+                            ui.add_enabled(can_select, egui::Checkbox::new(&mut selected, label));
+                        if response.changed() {
+                            match position {
+                                Some(i) => {
+                                    self.selected_drives.remove(i);
+                                }
+                                None => self.selected_drives.push(drive.clone()),
+                            }
+                        }
+                        response.context_menu(|ui| {
+                            if ui.button(tr!("ds-test-card")).clicked() {
+                                self.capacity_check = Some(CapacityCheckDialog::card_test(
+                                    app_state.drives.clone(),
+                                    drive.clone(),
+                                ));
+                                ui.close();
+                            }
+                            if ui.button(tr!("ds-deep-check")).clicked() {
+                                self.capacity_check = Some(CapacityCheckDialog::new(
+                                    app_state.drives.clone(),
+                                    drive.clone(),
+                                ));
+                                ui.close();
+                            }
+                        });
+                        for warning in &safety.warnings {
+                            ui.colored_label(palette(ui).error, warning);
+                        }
+                        if let (Some(installed), Some(target), Some(releases)) = (
+                            installed_version,
+                            target_tag,
+                            self.available_releases.as_deref(),
+                        ) && let Some(changes) =
+                            flow::releases_between(releases, installed, target)
+                        {
+                            add_release_notes(ui, drive, installed, &changes);
+                        }
                     }
+                    ui.weak(tr!("ds-right-click-hint"));
                 }
 
-                if ui.button("Refresh").clicked() {
+                if ui.button(tr!("common-refresh")).clicked() {
                     self.available_drives = None;
-                    self.selected_drive = None;
+                    self.selected_drives.clear();
                 }
             } else {
                 ui.spinner();
-                ui.label("Searching for removable drives...");
+                ui.label(tr!("drives-searching"));
             }
 
             stretch(ui);
 
-            if add_custom_next_button(ui, "Install Software", self.selected_drive.is_some())
+            if add_custom_next_button(ui, tr!("ds-install"), !self.selected_drives.is_empty())
                 .clicked()
             {
-                self.current_step = Step::InstallSoftware;
+                if self.suspicious_selected_drives().is_empty() {
+                    self.advance();
+                } else {
+                    self.erase_confirmation_text = Some(String::new());
+                }
             }
         });
+
+        self.show_erase_confirmation(ui.ctx());
+        if let Some(dialog) = &mut self.capacity_check
+            && !dialog.show(ui.ctx())
+        {
+            self.capacity_check = None;
+        }
         Ok(())
     }
 
-    fn run_install_software(
+    fn suspicious_selected_drives(&self) -> Vec<(&DriveInfo, &DriveSafety)> {
+        self.available_drives
+            .iter()
+            .flatten()
+            .filter(|c| c.safety.is_suspicious() && self.selected_drives.contains(&c.drive))
+            .map(|c| (&c.drive, &c.safety))
+            .collect()
+    }
+
+    /// Offers a card inserted after the last batch was removed to the next team in the queue.
+    fn show_new_card_prompt(&mut self, ctx: &egui::Context) {
+        let Some(ref candidate) = self.new_card else {
+            return;
+        };
+        let team = &self.team_numbers[self.team_number_index];
+        let mut set_up = false;
+        let mut dismissed = false;
+        egui::Modal::new(egui::Id::new("new_card_prompt")).show(ctx, |ui| {
+            ui.set_max_width(400.0);
+            heading(ui, tr!("ds-new-card-heading"));
+            ui.label(tr!(
+                "ds-new-card-found",
+                drive = candidate.drive.picker_label(),
+                team = team_label(&self.team_names, team)
+            ));
+            if let Some(ref version) = candidate.installed_version {
+                ui.colored_label(
+                    palette(ui).warning,
+                    tr!("ds-new-card-has-version", version = version),
+                );
+            }
+            ui.horizontal(|ui| {
+                set_up = ui.button(tr!("ds-new-card-set-up")).clicked();
+                dismissed = ui.button(tr!("ds-new-card-not-now")).clicked();
+            });
+        });
+        if set_up {
+            log::info!(
+                "Setting up {} for team {} after it was inserted",
+                candidate.drive,
+                team
+            );
+            self.selected_drives = vec![candidate.drive.clone()];
+            self.new_card = None;
+            self.finished_card_jobs.clear();
+            // The queue is saved as it would be on the way through the drive picker.
+            self.save_session();
+            let next = Step::after_new_card(&self.flow_state());
+            log::info!(
+                "Driver station setup: {:?} -> {:?}",
+                self.current_step,
+                next
+            );
+            self.current_step = next;
+        } else if dismissed {
+            self.new_card = None;
+        }
+    }
+
+    /// Asks the user to type "ERASE" before formatting drives that might not be SD cards.
+    fn show_erase_confirmation(&mut self, ctx: &egui::Context) {
+        let Some(mut text) = self.erase_confirmation_text.take() else {
+            return;
+        };
+        let mut open = true;
+        let mut confirmed = false;
+        egui::Modal::new(egui::Id::new("erase_confirmation")).show(ctx, |ui| {
+            ui.set_max_width(400.0);
+            heading(ui, tr!("ds-erase-heading"));
+            ui.label(tr!("ds-erase-intro"));
+            for (drive, safety) in self.suspicious_selected_drives() {
+                ui.label(format!("{drive}"));
+                for warning in &safety.warnings {
+                    ui.colored_label(palette(ui).error, warning);
+                }
+            }
+            ui.label(tr!("ds-erase-type"));
+            ui.text_edit_singleline(&mut text);
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(text == "ERASE", egui::Button::new(tr!("ds-erase-confirm")))
+                    .clicked()
+                {
+                    confirmed = true;
+                }
+                if ui.button(tr!("common-cancel")).clicked() {
+                    open = false;
+                }
+            });
+        });
+        if confirmed {
+            self.erase_confirmed = true;
+            self.advance();
+        } else if open {
+            self.erase_confirmation_text = Some(text);
+        }
+    }
+
+    fn start_card_jobs(
+        &mut self,
+        app_state: &GlobalAppState,
+        ctx: &egui::Context,
+    ) -> anyhow::Result<()> {
+        let archive_path = self
+            .archive_path
+            .clone()
+            .ok_or(anyhow!("Expected archive_path to not be None."))?;
+        let software_version = match (&self.cloned_version, &self.software_version) {
+            (Some(version), _) => version.clone(),
+            (None, Some(release)) if !self.use_local_archive => release.tag_name.clone(),
+            _ => archive_path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+        };
+        let install = CardInstall {
+            archive_path,
+            staged_dir: self.staged_dir.clone(),
+            docs_paths: self.docs_paths.clone(),
+            software_version,
+            cluster_size: self.release_manifest.cluster_size,
+            field_settings: self.field_settings.clone(),
+            allow_suspicious_drive: self.erase_confirmed,
+            prompts: app_state.prompts.clone(),
+            drives: app_state.drives.clone(),
+        };
+        let pending_teams = &self.team_numbers[self.team_number_index..];
+        self.queued_cards = self
+            .selected_drives
+            .iter()
+            .zip(pending_teams)
+            .map(|(drive, team_number)| (drive.clone(), team_number.clone(), install.clone()))
+            .collect();
+        if self.parallel_install {
+            self.card_jobs = self
+                .queued_cards
+                .drain(..)
+                .map(|(drive, team_number, install)| {
+                    CardJob::start(drive, team_number, install, ctx)
+                })
+                .collect();
+        } else {
+            self.start_next_queued_card(ctx);
+        }
+        Ok(())
+    }
+
+    fn start_next_queued_card(&mut self, ctx: &egui::Context) {
+        if self.queued_cards.is_empty() {
+            return;
+        }
+        let (drive, team_number, install) = self.queued_cards.remove(0);
+        log::info!("Starting card for team {} on {}", team_number, drive);
+        self.card_jobs
+            .push(CardJob::start(drive, team_number, install, ctx));
+    }
+
+    fn run_assign_teams(
         &mut self,
         _app_state: &mut GlobalAppState,
         ui: &mut egui::Ui,
     ) -> anyhow::Result<()> {
-        if self.install_finished_receiver.is_none() {
-            let (tx, rx) = std::sync::mpsc::channel();
-            self.install_finished_receiver = Some(rx);
-            let archive_path = self
-                .archive_path
-                .as_ref()
-                .ok_or(anyhow!("Expected archive_path to not be None."))?;
-            #[allow(unused_mut)] // drive needs to be mutable on Linux, but not on Windows
-            let mut drive = self
-                .selected_drive
-                .clone()
-                .ok_or(anyhow!("Expected selected_drive to not be None."))?;
-            let ramdisk_archive = std::fs::File::open(archive_path)?;
-            let team_number = self.team_numbers[self.team_number_index].clone();
-            self.background_thread = Some(std::thread::spawn(move || {
-                crate::utils::drive_management::format_drive(&drive, &team_number)
-                    .expect("Failed to format drive.");
-                #[cfg(target_os = "linux")]
-                {
-                    // On linux, the drive path includes the volume label, so we need to update the
-                    // path after we change the name during formatting.
-                    drive.drive_path = drive
-                        .drive_path
-                        .parent()
-                        .expect("Failed to get parent path of drive path")
-                        .join(format!("GIZMO{team_number}"));
-                };
-                zip_extract::extract(ramdisk_archive, &drive.drive_path, true)
-                    .expect("Failed to extract ramdisk archive.");
-                crate::utils::drive_management::write_filesystem_cache(&drive)
-                    .expect("Failed to flush filesystem cache.");
-                tx.send(())
-                    .expect("Failed to signal intall finish to main thread.");
-            }));
+        let pending_teams = self.team_numbers[self.team_number_index..].to_vec();
+        if self.drive_teams.len() != self.selected_drives.len() {
+            self.drive_teams = pending_teams
+                .iter()
+                .take(self.selected_drives.len())
+                .cloned()
+                .collect();
         }
 
-        if let Some(thread) = self.background_thread.take_if(|t| t.is_finished()) {
-            join_thread(thread)?;
-            self.install_finished_receiver.take().ok_or(anyhow!(
-                "Expected install_finished_receiver to not be None."
-            ))?;
-            self.current_step = Step::RemoveCard;
-        }
+        let mut assignment: Option<(usize, String)> = None;
+        let mut confirmed = false;
+        let mut choose_again = false;
+        column(ui, egui::Align::LEFT, |ui| {
+            heading(ui, tr!("ds-assign-heading"));
+            ui.label(tr!("ds-assign-intro"));
+            ui.columns(2, |columns| {
+                columns[0].strong(tr!("ds-assign-cards"));
+                for (card, drive) in self.selected_drives.iter().enumerate() {
+                    let frame = egui::Frame::group(columns[0].style());
+                    let (_, dropped) = columns[0].dnd_drop_zone::<String, ()>(frame, |ui| {
+                        ui.label(format!("{drive}"));
+                        egui::ComboBox::from_id_salt(("card_team", card))
+                            .selected_text(team_label(&self.team_names, &self.drive_teams[card]))
+                            .show_ui(ui, |ui| {
+                                for team in &pending_teams {
+                                    let selected = *team == self.drive_teams[card];
+                                    if ui
+                                        .selectable_label(
+                                            selected,
+                                            team_label(&self.team_names, team),
+                                        )
+                                        .clicked()
+                                    {
+                                        assignment = Some((card, team.clone()));
+                                    }
+                                }
+                            });
+                    });
+                    if let Some(team) = dropped {
+                        assignment = Some((card, team.as_ref().clone()));
+                    }
+                }
 
-        column(ui, egui::Align::Center, |ui| {
+                columns[1].strong(tr!("ds-assign-teams"));
+                egui::ScrollArea::vertical()
+                    .id_salt("assign_teams")
+                    .show(&mut columns[1], |ui| {
+                        for team in &pending_teams {
+                            let label = match self.drive_teams.iter().position(|t| t == team) {
+                                Some(card) => tr!(
+                                    "ds-assign-team-on-card",
+                                    team = team_label(&self.team_names, team),
+                                    card = card + 1
+                                ),
+                                None => team_label(&self.team_names, team),
+                            };
+                            ui.dnd_drag_source(
+                                egui::Id::new(("assign_team", team)),
+                                team.clone(),
+                                |ui| ui.label(label),
+                            );
+                        }
+                    });
+            });
+            ui.weak(tr!("ds-assign-drag-hint"));
+            ui.checkbox(&mut self.parallel_install, tr!("ds-assign-parallel"));
             stretch(ui);
-            ui.spinner();
-            ui.label("Installing software...");
+            ui.horizontal(|ui| {
+                choose_again = ui.button(tr!("ds-assign-choose-cards")).clicked();
+                confirmed =
+                    add_custom_next_button(ui, tr!("ds-install"), !self.selected_drives.is_empty())
+                        .clicked();
+            });
+        });
+
+        if let Some((card, team)) = assignment {
+            flow::assign_team(&mut self.drive_teams, card, &team);
+        }
+        if choose_again {
+            log::info!(
+                "Driver station setup: {:?} -> {:?}",
+                self.current_step,
+                Step::ChooseDrive
+            );
+            self.current_step = Step::ChooseDrive;
+        } else if confirmed {
+            // Cards are installed with the teams at the front of the queue, in drive order.
+            flow::move_to_front(
+                &mut self.team_numbers,
+                self.team_number_index,
+                &self.drive_teams,
+            );
+            self.advance();
+        }
+        Ok(())
+    }
+
+    fn run_install_software(
+        &mut self,
+        app_state: &mut GlobalAppState,
+        ui: &mut egui::Ui,
+    ) -> anyhow::Result<()> {
+        if self.card_jobs.is_empty() {
+            self.start_card_jobs(app_state, ui.ctx())?;
+        }
+
+        for job in &mut self.card_jobs {
+            job.update();
+        }
+
+        if self.card_jobs.iter().all(|job| job.is_finished()) && !self.queued_cards.is_empty() {
+            self.start_next_queued_card(ui.ctx());
+        } else if self.card_jobs.iter().all(|job| job.is_finished()) {
+            let any_failed = self
+                .card_jobs
+                .iter()
+                .any(|job| matches!(job.status, CardStatus::Failed(_)));
+            play_cue(if any_failed {
+                Cue::Failure
+            } else {
+                Cue::Success
+            });
+            self.finish_card_jobs(app_state);
+            self.advance();
+        }
+
+        column(ui, egui::Align::LEFT, |ui| {
+            heading(ui, tr!("ds-installing-heading"));
+            egui::Grid::new("card_jobs")
+                .num_columns(3)
+                .striped(true)
+                .show(ui, |ui| {
+                    for job in &self.card_jobs {
+                        ui.label(tr!(
+                            "ds-job-team",
+                            team = team_label(&self.team_names, &job.team_number)
+                        ));
+                        ui.label(format!("{}", job.drive));
+                        match job.status {
+                            CardStatus::Formatting => {
+                                ui.horizontal(|ui| {
+                                    ui.spinner();
+                                    ui.label(tr!("ds-formatting"));
+                                });
+                            }
+                            CardStatus::Copying(ref progress) => {
+                                ui.add(egui::ProgressBar::new(progress.fraction()).text(tr!(
+                                    "ds-copying",
+                                    done = progress.files_done,
+                                    total = progress.files_total
+                                )));
+                            }
+                            CardStatus::Imaging(ref progress) => {
+                                let size = format_size(progress.card_bytes);
+                                let text = match progress.phase {
+                                    ImagePhase::Writing => tr!("ds-writing-image", size = size),
+                                    ImagePhase::Verifying => tr!("ds-verifying-image", size = size),
+                                };
+                                ui.add(egui::ProgressBar::new(progress.fraction()).text(text));
+                            }
+                            CardStatus::Flushing => {
+                                ui.horizontal(|ui| {
+                                    ui.spinner();
+                                    ui.label(tr!("ds-flushing"));
+                                });
+                            }
+                            CardStatus::Ejecting => {
+                                ui.horizontal(|ui| {
+                                    ui.spinner();
+                                    ui.label(tr!("ds-ejecting"));
+                                });
+                            }
+                            CardStatus::Done {
+                                io_errors: Some(io_errors),
+                                ..
+                            } if io_errors > 0 => {
+                                ui.colored_label(palette(ui).warning, io_error_warning(io_errors));
+                            }
+                            CardStatus::Done { .. } => {
+                                ui.colored_label(palette(ui).success, tr!("ds-done"));
+                            }
+                            CardStatus::Failed(ref message) => {
+                                ui.colored_label(palette(ui).error, message);
+                            }
+                        }
+                        ui.end_row();
+                    }
+                    for (drive, team_number, _) in &self.queued_cards {
+                        ui.label(tr!(
+                            "ds-job-team",
+                            team = team_label(&self.team_names, team_number)
+                        ));
+                        ui.label(format!("{drive}"));
+                        ui.label(tr!("ds-waiting"));
+                        ui.end_row();
+                    }
+                });
             stretch(ui);
+            let cancelling = self.card_jobs.iter().any(|job| job.is_cancelled());
+            if cancelling {
+                ui.label(tr!("ds-stopping"));
+            }
+            if ui
+                .add_enabled(!cancelling, egui::Button::new(tr!("common-cancel")))
+                .on_hover_text(tr!("ds-cancel-hint"))
+                .clicked()
+            {
+                for job in &self.card_jobs {
+                    job.cancel();
+                }
+                // Cards that haven't started stay in the queue for the next batch.
+                self.queued_cards.clear();
+            }
         });
         Ok(())
     }
 
+    /// Moves the teams from the finished batch out of the queue. Teams whose cards failed are
+    /// kept at the front of the queue so they are retried next.
+    fn finish_card_jobs(&mut self, app_state: &mut GlobalAppState) {
+        for job in &self.card_jobs {
+            self.team_progress
+                .insert(job.team_number.clone(), TeamProgress::from(&job.status));
+        }
+        let finished_at = unix_now();
+        app_state
+            .session_report
+            .cards
+            .extend(self.card_jobs.iter().map(|job| CardRecord {
+                team_number: job.team_number.clone(),
+                software_version: job.software_version.clone(),
+                drive: format!("{}", job.drive),
+                finished_at,
+                error: match job.status {
+                    CardStatus::Failed(ref message) => Some(message.clone()),
+                    _ => None,
+                },
+                io_errors: match job.status {
+                    CardStatus::Done { io_errors, .. } => io_errors,
+                    _ => None,
+                },
+            }));
+        // Local archives are only known by file name, which says nothing about the version.
+        if self.cloned_version.is_some() || !self.use_local_archive {
+            for job in &self.card_jobs {
+                if matches!(job.status, CardStatus::Done { .. }) {
+                    app_state
+                        .compatibility
+                        .record_install(Component::DriverStation, &job.software_version);
+                }
+            }
+        }
+        let results: Vec<(String, bool)> = self
+            .card_jobs
+            .iter()
+            .map(|job| {
+                (
+                    job.team_number.clone(),
+                    matches!(job.status, CardStatus::Done { .. }),
+                )
+            })
+            .collect();
+        self.team_number_index +=
+            flow::requeue_failed_teams(&mut self.team_numbers, self.team_number_index, &results);
+        self.save_session();
+        self.failure_streak = flow::update_failure_streak(
+            self.failure_streak,
+            self.card_jobs
+                .iter()
+                .filter(|job| !job.is_cancelled())
+                .map(|job| !matches!(job.status, CardStatus::Failed(_))),
+        );
+        if self.failure_streak >= FAILURE_STREAK_LIMIT {
+            log::warn!(
+                "Pausing after {} cards failed in a row",
+                self.failure_streak
+            );
+        }
+        self.finished_card_jobs = std::mem::take(&mut self.card_jobs);
+        self.cards_to_remove = self
+            .finished_card_jobs
+            .iter()
+            .map(|job| job.drive.clone())
+            .collect();
+        self.queued_cards.clear();
+        self.selected_drives.clear();
+        self.drive_teams.clear();
+        self.erase_confirmed = false;
+        self.available_drives = None;
+    }
+
+    fn current_team_progress(&self, team_number: &str) -> TeamProgress {
+        match self
+            .card_jobs
+            .iter()
+            .find(|job| job.team_number == team_number)
+        {
+            Some(job) => TeamProgress::from(&job.status),
+            None => self
+                .team_progress
+                .get(team_number)
+                .cloned()
+                .unwrap_or(TeamProgress::Pending),
+        }
+    }
+
+    /// Shows every team in the queue with how far along it is, so it's clear which teams are
+    /// left when working through a large event.
+    fn add_team_progress_panel(&mut self, ui: &mut egui::Ui) {
+        self.team_progress_dialog.update(ui.ctx());
+        if let Some(destination) = self.team_progress_dialog.take_picked() {
+            self.team_progress_status = Some(match self.export_team_progress(&destination) {
+                Ok(()) => tr!("file-saved-to", path = destination.display().to_string()),
+                Err(e) => tr!("ds-progress-save-failed", error = format!("{e:#}")),
+            });
+        }
+
+        egui::SidePanel::right("team_progress")
+            .resizable(false)
+            .show_inside(ui, |ui| {
+                heading(ui, tr!("ds-teams-panel"));
+                let done = self
+                    .team_numbers
+                    .iter()
+                    .filter(|team| self.current_team_progress(team).is_done())
+                    .count();
+                ui.label(tr!(
+                    "ds-teams-done",
+                    done = done,
+                    total = self.team_numbers.len()
+                ));
+                egui::ScrollArea::vertical()
+                    .max_height(ui.available_height() - 60.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("team_progress_grid")
+                            .num_columns(2)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for team_number in &self.team_numbers {
+                                    let progress = self.current_team_progress(team_number);
+                                    ui.label(team_label(&self.team_names, team_number));
+                                    let status = ui.colored_label(
+                                        progress.color(palette(ui)),
+                                        progress.label(),
+                                    );
+                                    if let Some(details) = progress.details() {
+                                        status.on_hover_text(details);
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                if ui.button(tr!("ds-export")).clicked() {
+                    self.team_progress_dialog.save_file();
+                }
+                if let Some(ref status) = self.team_progress_status {
+                    ui.label(status);
+                }
+            });
+    }
+
+    fn export_team_progress(&self, destination: &std::path::Path) -> anyhow::Result<()> {
+        let mut lines = vec!["Team Number,Team Name,Status,Details".to_string()];
+        for team_number in &self.team_numbers {
+            let progress = self.current_team_progress(team_number);
+            let fields = [
+                team_number.as_str(),
+                self.team_names
+                    .get(team_number)
+                    .map(String::as_str)
+                    .unwrap_or_default(),
+                &progress.label(),
+                &progress.details().unwrap_or_default(),
+            ];
+            lines.push(fields.map(csv_field).join(","));
+        }
+        std::fs::write(destination, lines.join("\n") + "\n")
+            .with_context(|| format!("Failed to write {:?}", destination))
+    }
+
     fn run_remove_card(
         &mut self,
-        _app_state: &mut GlobalAppState,
+        app_state: &mut GlobalAppState,
         ui: &mut egui::Ui,
     ) -> anyhow::Result<()> {
+        if let Some(candidate) = Task::poll(&mut self.new_card_task)? {
+            if candidate.safety.is_suspicious() {
+                // Drives that might not be SD cards are only erased from the drive picker.
+                log::info!("Not offering {} for the next team", candidate.drive);
+            } else {
+                self.new_card = Some(candidate);
+            }
+        }
+
         column(ui, egui::Align::LEFT, |ui| {
-            ui.heading("Installation Complete");
-            let team_number = self.team_numbers[self.team_number_index].clone();
-            ui.label(format!("Please remove the card from the drive and insert it into the driver station for team {team_number}."));
+            heading(ui, tr!("install-complete"));
+            for job in &self.finished_card_jobs {
+                match job.status {
+                    CardStatus::Failed(ref message) => ui.colored_label(
+                        palette(ui).error,
+                        tr!(
+                            "ds-job-failed",
+                            team = &job.team_number,
+                            drive = job.drive.to_string(),
+                            error = message
+                        ),
+                    ),
+                    CardStatus::Done {
+                        io_errors: Some(io_errors),
+                        ejected,
+                        ..
+                    } if io_errors > 0 => ui.colored_label(
+                        palette(ui).warning,
+                        format!(
+                            "{} {}",
+                            removal_instructions(&job.drive, &job.team_number, ejected),
+                            io_error_warning(io_errors)
+                        ),
+                    ),
+                    CardStatus::Done { ejected: true, .. } => ui.label(removal_instructions(
+                        &job.drive,
+                        &team_label(&self.team_names, &job.team_number),
+                        true,
+                    )),
+                    _ => ui.colored_label(
+                        palette(ui).warning,
+                        removal_instructions(
+                            &job.drive,
+                            &team_label(&self.team_names, &job.team_number),
+                            false,
+                        ),
+                    ),
+                };
+                if let CardStatus::Done { write_count, .. } = job.status
+                    && let Some(warning) = worn_card_warning(
+                        write_count,
+                        app_state.settings.card_write_warning_threshold,
+                    )
+                {
+                    ui.colored_label(palette(ui).warning, warning);
+                }
+            }
 
-            if self.team_number_index < self.team_numbers.len() - 1 {
-                ui.label("Once you have done this, click Next.");
+            if self.failure_streak >= FAILURE_STREAK_LIMIT {
+                self.add_failure_streak_pause(ui);
+            } else if self.current_step.next(&self.flow_state()).is_some() {
+                ui.label(tr!("ds-next-batch"));
+                ui.weak(tr!("ds-next-card-hint"));
                 stretch(ui);
                 if add_next_button(ui, true).clicked() {
-                    self.team_number_index += 1;
-                    self.selected_drive = None;
-                    self.available_drives = None;
-                    self.current_step = Step::ChooseDrive;
+                    self.finished_card_jobs.clear();
+                    self.advance();
                 }
             } else {
-                ui.label("All team numbers have been processed. You can now close the wizard or click 'Start Over'.");
+                ui.label(tr!("ds-all-done"));
             }
         });
+
+        if let Some(ref mut diagnostics) = self.diagnostics {
+            let mut open = true;
+            let mut result = Ok(());
+            egui::Window::new(tr!("diag-title"))
+                .open(&mut open)
+                .collapsible(false)
+                .fixed_size([450.0, 350.0])
+                .show(ui.ctx(), |ui| result = diagnostics.run(app_state, ui));
+            result?;
+            if !open {
+                self.diagnostics = None;
+            }
+        }
+        self.show_new_card_prompt(ui.ctx());
         Ok(())
     }
+
+    /// Stops the batch after several cards in a row failed, since the next cards would most
+    /// likely fail the same way.
+    fn add_failure_streak_pause(&mut self, ui: &mut egui::Ui) {
+        ui.separator();
+        ui.colored_label(
+            palette(ui).error,
+            tr!("ds-streak-paused", failures = self.failure_streak),
+        );
+        ui.label(tr!("ds-streak-check"));
+        for cause in likely_streak_causes() {
+            ui.label(format!("• {cause}"));
+        }
+        ui.horizontal(|ui| {
+            if ui.button(tr!("ds-show-diagnostics")).clicked() {
+                self.diagnostics = Some(DiagnosticsPage::new());
+            }
+            if ui
+                .button(tr!("ds-continue"))
+                .on_hover_text(tr!("ds-continue-hint", limit = FAILURE_STREAK_LIMIT))
+                .clicked()
+            {
+                log::info!("Continuing after {} failures in a row", self.failure_streak);
+                self.failure_streak = 0;
+            }
+        });
+    }
+}
+
+/// Problems that make every card fail, shown when a batch is paused for repeated failures.
+fn likely_streak_causes() -> [String; 4] {
+    [
+        tr!("ds-cause-reader"),
+        tr!("ds-cause-other-program"),
+        tr!("ds-cause-permissions"),
+        tr!("ds-cause-disk-space"),
+    ]
+}
+
+/// Tells the user to move a finished card to its driver station. Cards that could not be
+/// ejected may still be writing, so the user is asked to wait before pulling them.
+fn removal_instructions(drive: &DriveInfo, team: &str, ejected: bool) -> String {
+    if ejected {
+        tr!("ds-remove-ejected", drive = drive.to_string(), team = team)
+    } else {
+        tr!(
+            "ds-remove-not-ejected",
+            drive = drive.to_string(),
+            team = team
+        )
+    }
+}
+
+/// Reads a team roster CSV. See [`flow::parse_roster`] for the format.
+fn import_roster(path: &std::path::Path) -> anyhow::Result<Vec<flow::RosterEntry>> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read roster file {:?}", path))?;
+    flow::parse_roster(&text).with_context(|| format!("Could not import {:?}", path))
+}
+
+/// Shows the team's name from the imported roster next to its number, if there is one.
+fn team_label(team_names: &HashMap<String, String>, team_number: &str) -> String {
+    match team_names.get(team_number) {
+        Some(name) => format!("{team_number} ({name})"),
+        None => team_number.to_string(),
+    }
+}
+
+/// A drive offered in the drive picker, along with what was learned about it while listing.
+struct DriveCandidate {
+    drive: DriveInfo,
+    safety: DriveSafety,
+    /// Software version from the card's manifest, if it was set up by this wizard before.
+    installed_version: Option<String>,
+}
+
+impl DriveCandidate {
+    /// Looks at the drive. This reads from it, so run it in the background.
+    fn inspect(drive: DriveInfo) -> Self {
+        Self {
+            safety: check_drive_safety(&drive),
+            installed_version: read_card_manifest(&drive.drive_path)
+                .ok()
+                .map(|manifest| manifest.software_version),
+            drive,
+        }
+    }
+}
+
+/// Shows the notes for every release between what is on a card and what is about to replace it,
+/// so coaches know what behavior changes to expect at the field.
+fn add_release_notes(
+    ui: &mut egui::Ui,
+    drive: &DriveInfo,
+    installed_version: &str,
+    changes: &[&GithubRelease],
+) {
+    egui::CollapsingHeader::new(tr!("ds-changes-since", version = installed_version))
+        .id_salt(&drive.drive_path)
+        .show(ui, |ui| {
+            egui::ScrollArea::vertical()
+                .max_height(150.0)
+                .show(ui, |ui| {
+                    for release in changes {
+                        heading(ui, release.display_name());
+                        show_release_notes(ui, release);
+                    }
+                });
+        });
 }
 
 impl Page for DriverStationSetupPage {
     fn run(&mut self, app_state: &mut GlobalAppState, ui: &mut egui::Ui) -> anyhow::Result<()> {
+        if self.team_numbers.len() > 1
+            && matches!(
+                self.current_step,
+                Step::ChooseDrive | Step::AssignTeams | Step::InstallSoftware | Step::RemoveCard
+            )
+        {
+            self.add_team_progress_panel(ui);
+        }
         match self.current_step {
-            Step::ChooseVersion => self.run_choose_version(app_state, ui),
+            Step::ChooseVersion => self.run_choose_version(app_state, ui).map_err(recoverable),
+            Step::ChooseSourceCard => self
+                .run_choose_source_card(app_state, ui)
+                .map_err(recoverable),
             Step::EnterTeamNumbers => self.run_enter_team_numbers(app_state, ui),
-            Step::DownloadArchive => self.run_download_archive(app_state, ui),
-            Step::ChooseDrive => self.run_choose_drive(app_state, ui),
-            Step::InstallSoftware => self.run_install_software(app_state, ui),
+            Step::ConfigureField => self.run_configure_field(app_state, ui),
+            Step::DownloadArchive => self
+                .run_download_archive(app_state, ui)
+                .map_err(recoverable),
+            Step::ChooseDrive => self.run_choose_drive(app_state, ui).map_err(recoverable),
+            Step::AssignTeams => self.run_assign_teams(app_state, ui),
+            Step::InstallSoftware => self
+                .run_install_software(app_state, ui)
+                .map_err(recoverable),
             Step::RemoveCard => self.run_remove_card(app_state, ui),
         }
     }
 
     fn get_title(&self) -> String {
-        "Driver Station Software Install".to_string()
+        tr!("ds-title")
+    }
+
+    fn step_progress(&self) -> Option<StepProgress> {
+        let state = self.flow_state();
+        StepProgress::from_flow(
+            Step::ChooseVersion,
+            self.current_step,
+            |step| step.next(&state),
+            Step::label,
+        )
+    }
+
+    fn retry_step(&mut self) {
+        self.releases_task = None;
+        self.download_task = None;
+        self.download_progress_receiver = None;
+        self.drive_list_task = None;
+        self.clone_task = None;
+        self.new_card_task = None;
+        match self.current_step {
+            Step::ChooseVersion => self.available_releases = None,
+            Step::ChooseSourceCard => self.available_drives = None,
+            Step::ChooseDrive => self.available_drives = None,
+            Step::InstallSoftware => {
+                self.card_jobs.clear();
+                self.queued_cards.clear();
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_event(&mut self, app_state: &mut GlobalAppState, event: &AppEvent) {
+        match event {
+            AppEvent::DriveAdded(_) | AppEvent::DriveRemoved(_) => {
+                if let AppEvent::DriveRemoved(drive) = event {
+                    self.selected_drives.retain(|d| d != drive);
+                    self.cards_to_remove.retain(|d| d != drive);
+                    self.new_card.take_if(|card| &card.drive == drive);
+                }
+                if let AppEvent::DriveAdded(drive) = event
+                    && self.current_step == Step::RemoveCard
+                    && self.current_step.next(&self.flow_state()).is_some()
+                    && self.failure_streak < FAILURE_STREAK_LIMIT
+                    && self.cards_to_remove.is_empty()
+                    && self.new_card.is_none()
+                    && self.new_card_task.is_none()
+                {
+                    let drive = drive.clone();
+                    self.new_card_task =
+                        Some(Task::spawn(move || Ok(DriveCandidate::inspect(drive))));
+                }
+                // Re-list drives so the picker reflects what is plugged in right now.
+                if matches!(
+                    self.current_step,
+                    Step::ChooseDrive | Step::ChooseSourceCard
+                ) && self.drive_list_task.is_none()
+                {
+                    self.available_drives = None;
+                }
+            }
+            // Fetch the release list again so the new version shows up in the picker.
+            AppEvent::NewReleaseAvailable { repo, .. }
+                if *repo == app_state.sources.driver_station
+                    && self.current_step == Step::ChooseVersion
+                    && self.releases_task.is_none() =>
+            {
+                self.available_releases = None;
+            }
+            _ => {}
+        }
     }
 }
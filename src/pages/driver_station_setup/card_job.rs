@@ -0,0 +1,461 @@
+//! Installing the driver station software onto one card, on a worker thread per card.
+
+use crate::i18n::tr;
+use crate::prompts::Prompter;
+use crate::utils::archive::ExtractProgress;
+use crate::utils::card_manifest::{CardManifest, read_card_manifest, write_card_manifest};
+use crate::utils::drive_management::{DriveInfo, DriveManager};
+use crate::utils::gizmo_config::{FieldSettings, write_gizmo_config};
+use crate::utils::imaging::{ImageProgress, is_disk_image, write_image_to_drive};
+use crate::utils::session_report::unix_now;
+use crate::utils::tasks::Task;
+use crate::utils::threads::CancellationToken;
+use crate::utils::work_dir::WorkDir;
+use anyhow::{Context, anyhow, bail};
+use std::sync::Arc;
+use std::sync::mpsc::Receiver;
+
+pub enum CardStatus {
+    Formatting,
+    Copying(ExtractProgress),
+    Imaging(ImageProgress),
+    Flushing,
+    Ejecting,
+    Done {
+        /// How many I/O errors the OS logged for the card during the install, or `None` if
+        /// that could not be checked.
+        io_errors: Option<u32>,
+        /// Whether the card was ejected and is safe to pull out.
+        ejected: bool,
+        /// How many times the wizard has set up this card, counting this time.
+        write_count: u32,
+    },
+    Failed(String),
+}
+
+/// Copies documentation into a docs folder on the card and returns the names of the copied files.
+fn copy_docs_to_card(
+    docs_paths: &[std::path::PathBuf],
+    card_root: &std::path::Path,
+) -> anyhow::Result<Vec<String>> {
+    if docs_paths.is_empty() {
+        return Ok(vec![]);
+    }
+    let docs_dir = card_root.join("docs");
+    std::fs::create_dir_all(&docs_dir)?;
+    let mut names = vec![];
+    for path in docs_paths {
+        let name = path
+            .file_name()
+            .ok_or(anyhow!("Could not find filename in {:?}", path))?;
+        std::fs::copy(path, docs_dir.join(name))?;
+        names.push(name.to_string_lossy().to_string());
+    }
+    Ok(names)
+}
+
+/// What to put on each card in a batch.
+#[derive(Clone)]
+pub struct CardInstall {
+    pub archive_path: std::path::PathBuf,
+    pub staged_dir: Option<WorkDir>,
+    pub docs_paths: Vec<std::path::PathBuf>,
+    pub software_version: String,
+    /// FAT32 cluster size requested by the release manifest.
+    pub cluster_size: Option<u32>,
+    pub field_settings: FieldSettings,
+    pub allow_suspicious_drive: bool,
+    pub prompts: Prompter,
+    pub drives: Arc<dyn DriveManager>,
+}
+
+/// Installs the driver station software onto one card on its own worker thread.
+pub struct CardJob {
+    pub drive: DriveInfo,
+    pub team_number: String,
+    pub software_version: String,
+    pub status: CardStatus,
+    status_receiver: Receiver<CardStatus>,
+    task: Option<Task<()>>,
+    cancel: CancellationToken,
+}
+
+impl Drop for CardJob {
+    /// Stops the worker if the page is closed while it is still installing.
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+impl CardJob {
+    pub fn start(
+        drive: DriveInfo,
+        team_number: String,
+        install: CardInstall,
+        ctx: &egui::Context,
+    ) -> Self {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let software_version = install.software_version.clone();
+        let thread_drive = drive.clone();
+        let thread_team_number = team_number.clone();
+        let ctx = ctx.clone();
+        let (task, cancel) = Task::spawn_cancellable(move |cancel| {
+            let report = |status: CardStatus| {
+                // The receiver is dropped if the user leaves the page, which is fine.
+                let _ = tx.send(status);
+                ctx.request_repaint();
+            };
+            let result = install_to_card(
+                thread_drive,
+                &thread_team_number,
+                &install,
+                &cancel,
+                &report,
+            );
+            report(result.unwrap_or_else(|e| CardStatus::Failed(format!("{e:#}"))));
+            Ok(())
+        });
+        Self {
+            drive,
+            team_number,
+            software_version,
+            status: CardStatus::Formatting,
+            status_receiver: rx,
+            task: Some(task),
+            cancel,
+        }
+    }
+
+    /// Asks the worker to stop at its next safe point.
+    pub fn cancel(&self) {
+        if !self.is_finished() && !self.cancel.is_cancelled() {
+            log::info!(
+                "Cancelling install for team {} on {}",
+                self.team_number,
+                self.drive
+            );
+            self.cancel.cancel();
+        }
+    }
+
+    pub fn update(&mut self) {
+        // Check for the end first, so every status the worker sent before finishing is read.
+        let finished = Task::poll(&mut self.task);
+        if let Some(status) = self.status_receiver.try_iter().last() {
+            self.status = status;
+        }
+        if let Err(e) = finished {
+            self.status = CardStatus::Failed(format!("{e:#}"));
+        }
+    }
+
+    /// Returns true once the user has asked to stop this card.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.is_cancelled()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.task.is_none()
+    }
+}
+
+pub fn install_to_card(
+    mut drive: DriveInfo,
+    team_number: &str,
+    install: &CardInstall,
+    cancel: &CancellationToken,
+    report: &impl Fn(CardStatus),
+) -> anyhow::Result<CardStatus> {
+    // Cards handed back from another team are reused, but a mix-up here would leave a team
+    // without their driver station, so ask before erasing one.
+    let existing = read_card_manifest(&drive.drive_path).ok();
+    if let Some(ref existing) = existing
+        && existing.team_number != team_number
+        && !install.prompts.confirm(
+            &tr!("ds-overwrite-title"),
+            &tr!(
+                "ds-overwrite-message",
+                drive = drive.to_string(),
+                existing_team = &existing.team_number,
+                version = &existing.software_version,
+                team = team_number
+            ),
+        )?
+    {
+        bail!(
+            "Skipped because the card is set up for team {}.",
+            existing.team_number
+        );
+    }
+    cancel
+        .check()
+        .context("Stopped before the card was erased")?;
+    let started_at = unix_now();
+    if is_disk_image(&install.archive_path) {
+        // The image replaces the card's partitions, so the team's files go on the volume the
+        // image brings with it.
+        drive = write_image_to_drive(
+            &drive,
+            &install.archive_path,
+            install.allow_suspicious_drive,
+            cancel,
+            |progress| report(CardStatus::Imaging(progress.clone())),
+        )?;
+    } else {
+        format_and_copy(&mut drive, team_number, install, cancel, report)?;
+    }
+    write_gizmo_config(&drive.drive_path, team_number, &install.field_settings)?;
+    let mut manifest = CardManifest::new(&install.software_version, team_number);
+    manifest.docs = copy_docs_to_card(&install.docs_paths, &drive.drive_path)?;
+    manifest.count_write_after(existing.as_ref());
+    log::info!("{drive} has been set up {} time(s)", manifest.write_count);
+    write_card_manifest(&drive.drive_path, &manifest)?;
+    report(CardStatus::Flushing);
+    install.drives.flush(&drive)?;
+    // Errors the card recovered from don't fail the install, but they are an early sign of
+    // failing media.
+    let io_errors = match install.drives.count_io_errors(&drive, started_at) {
+        Ok(io_errors) => {
+            if io_errors > 0 {
+                log::warn!("{io_errors} I/O error(s) logged for {drive} during install");
+            }
+            Some(io_errors)
+        }
+        Err(e) => {
+            log::warn!("Could not check for I/O errors on {drive}: {e:#}");
+            None
+        }
+    };
+    // Volunteers pull cards out as soon as they see the install is done, so eject them first.
+    // The card is already flushed, so an eject failure doesn't fail the install.
+    report(CardStatus::Ejecting);
+    let ejected = match install.drives.eject(&drive) {
+        Ok(()) => true,
+        Err(e) => {
+            log::warn!("Could not eject {drive}: {e:#}");
+            false
+        }
+    };
+    Ok(CardStatus::Done {
+        io_errors,
+        ejected,
+        write_count: manifest.write_count,
+    })
+}
+
+/// Returns a nudge to retire the card if it has been set up more times than the hub allows.
+pub fn worn_card_warning(write_count: u32, threshold: u32) -> Option<String> {
+    (threshold > 0 && write_count > threshold).then(|| tr!("ds-worn-card", count = write_count))
+}
+
+/// Erases the card and copies the driver station files onto it.
+fn format_and_copy(
+    drive: &mut DriveInfo,
+    team_number: &str,
+    install: &CardInstall,
+    cancel: &CancellationToken,
+    report: &impl Fn(CardStatus),
+) -> anyhow::Result<()> {
+    let requested_cluster_size = install.drives.format(
+        drive,
+        team_number,
+        install.allow_suspicious_drive,
+        install.cluster_size,
+    )?;
+    // Once the card has been erased, a cancelled install removes what it copied so a
+    // half-finished card isn't mistaken for a working one.
+    let stop_if_cancelled = || -> anyhow::Result<()> {
+        if cancel.is_cancelled() {
+            clear_card(&drive.drive_path)?;
+            bail!(
+                "Stopped after the card was erased. The card is empty and needs to be set up again."
+            );
+        }
+        Ok(())
+    };
+    stop_if_cancelled()?;
+    if let Some(requested) = requested_cluster_size {
+        let actual = crate::utils::drive_management::cluster_size(drive)?;
+        if actual != requested {
+            bail!(
+                "{drive} was formatted with {actual} byte clusters instead of the {requested} bytes this release needs."
+            );
+        }
+    }
+    let on_progress = |progress: &ExtractProgress| report(CardStatus::Copying(progress.clone()));
+    match install.staged_dir {
+        Some(ref staged_dir) => {
+            crate::utils::archive::copy_tree(staged_dir.path(), &drive.drive_path, on_progress)?
+        }
+        None => crate::utils::archive::extract_archive(
+            &install.archive_path,
+            &drive.drive_path,
+            true,
+            on_progress,
+        )?,
+    }
+    stop_if_cancelled()
+}
+
+/// Removes everything from a card's root folder.
+fn clear_card(card_root: &std::path::Path) -> anyhow::Result<()> {
+    for entry in
+        std::fs::read_dir(card_root).with_context(|| format!("Failed to read {:?}", card_root))?
+    {
+        let path = entry?.path();
+        if path.is_dir() {
+            std::fs::remove_dir_all(&path)
+        } else {
+            std::fs::remove_file(&path)
+        }
+        .with_context(|| format!("Failed to remove {:?}", path))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prompts::PromptQueue;
+    use crate::utils::drive_management::mock::{DriveCall, DriveOperation, MockDriveManager};
+    use crate::utils::gizmo_config::read_configured_team;
+
+    const TEAM: &str = "1234";
+
+    /// A card folder and a staged ramdisk to install onto it.
+    struct Fixture {
+        _root: tempfile::TempDir,
+        _prompts: PromptQueue,
+        drive: DriveInfo,
+        install: CardInstall,
+        drives: Arc<MockDriveManager>,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            let root = tempfile::tempdir().unwrap();
+            let card = root.path().join("card");
+            std::fs::create_dir(&card).unwrap();
+            let staged_dir = WorkDir::new(root.path(), "staged").unwrap();
+            std::fs::write(staged_dir.path().join("ramdisk.img"), "ramdisk").unwrap();
+            let prompts = PromptQueue::new(&egui::Context::default());
+            let drives = Arc::new(MockDriveManager::default());
+            let install = CardInstall {
+                archive_path: root.path().join("ds-ramdisk.zip"),
+                staged_dir: Some(staged_dir),
+                docs_paths: vec![],
+                software_version: "v1.0".to_string(),
+                cluster_size: None,
+                field_settings: FieldSettings::default(),
+                allow_suspicious_drive: false,
+                prompts: prompts.prompter(),
+                drives: drives.clone(),
+            };
+            Self {
+                drive: DriveInfo {
+                    drive_path: card,
+                    file_system_label: "CARD".to_string(),
+                    total_bytes: 0,
+                    free_bytes: 0,
+                    file_system: String::new(),
+                },
+                _root: root,
+                _prompts: prompts,
+                install,
+                drives,
+            }
+        }
+
+        fn install(&self) -> anyhow::Result<CardStatus> {
+            install_to_card(
+                self.drive.clone(),
+                TEAM,
+                &self.install,
+                &CancellationToken::default(),
+                &|_| {},
+            )
+        }
+
+        fn operations(&self) -> Vec<DriveOperation> {
+            self.drives
+                .calls()
+                .iter()
+                .map(DriveCall::operation)
+                .collect()
+        }
+    }
+
+    #[test]
+    fn install_formats_then_flushes_before_ejecting() {
+        let fixture = Fixture::new();
+        let status = fixture.install().unwrap();
+        assert!(matches!(
+            status,
+            CardStatus::Done {
+                io_errors: Some(0),
+                ejected: true,
+                write_count: 1,
+            }
+        ));
+        let card = fixture.drive.drive_path.clone();
+        assert_eq!(
+            fixture.drives.calls(),
+            [
+                DriveCall::Format {
+                    drive: card.clone(),
+                    team_number: TEAM.to_string(),
+                },
+                DriveCall::Flush(card.clone()),
+                DriveCall::CountIoErrors(card.clone()),
+                DriveCall::Eject(card.clone()),
+            ]
+        );
+        assert!(card.join("ramdisk.img").is_file());
+        assert_eq!(read_configured_team(&card), Some(1234));
+    }
+
+    #[test]
+    fn failed_format_stops_before_copying() {
+        let fixture = Fixture::new();
+        fixture.drives.fail(DriveOperation::Format);
+        assert!(fixture.install().is_err());
+        assert_eq!(fixture.operations(), [DriveOperation::Format]);
+        assert!(!fixture.drive.drive_path.join("ramdisk.img").exists());
+    }
+
+    #[test]
+    fn failed_flush_fails_without_ejecting() {
+        let fixture = Fixture::new();
+        fixture.drives.fail(DriveOperation::Flush);
+        assert!(fixture.install().is_err());
+        assert_eq!(
+            fixture.operations(),
+            [DriveOperation::Format, DriveOperation::Flush]
+        );
+    }
+
+    #[test]
+    fn failed_eject_still_finishes_the_card() {
+        let fixture = Fixture::new();
+        fixture.drives.fail(DriveOperation::Eject);
+        fixture.drives.fail(DriveOperation::CountIoErrors);
+        let status = fixture.install().unwrap();
+        assert!(matches!(
+            status,
+            CardStatus::Done {
+                io_errors: None,
+                ejected: false,
+                ..
+            }
+        ));
+        assert_eq!(
+            fixture.operations(),
+            [
+                DriveOperation::Format,
+                DriveOperation::Flush,
+                DriveOperation::CountIoErrors,
+                DriveOperation::Eject,
+            ]
+        );
+    }
+}
@@ -0,0 +1,158 @@
+//! Getting what goes on the cards ready before any card is erased: the downloaded release and
+//! documentation, or the contents of a card being cloned.
+
+use crate::events::{AppEvent, EventPublisher};
+use crate::settings::{Settings, SoftwareRepo};
+use crate::utils::card_manifest::{CARD_MANIFEST_FILE, read_card_manifest};
+use crate::utils::drive_management::DriveInfo;
+use crate::utils::github::{GithubRelease, GithubReleaseAsset};
+use crate::utils::gizmo_config::{FieldSettings, GIZMO_CONFIG_FILE, read_field_settings};
+use crate::utils::http::Network;
+use crate::utils::release_manifest::ReleaseManifest;
+use crate::utils::work_dir::WorkDir;
+use anyhow::{Context, anyhow};
+
+pub struct CardDownloads {
+    pub archive_path: std::path::PathBuf,
+    pub staged_dir: Option<WorkDir>,
+    pub docs: Vec<std::path::PathBuf>,
+    pub release_manifest: ReleaseManifest,
+}
+
+/// Where the documentation copied onto each card comes from.
+#[derive(Clone)]
+pub enum DocsSource {
+    ReleaseAssets(Vec<GithubReleaseAsset>),
+    Url(String),
+}
+
+impl DocsSource {
+    pub fn from_settings(settings: &Settings, release: Option<&GithubRelease>) -> Option<Self> {
+        if !settings.copy_docs_to_cards {
+            return None;
+        }
+        let url = settings.docs_url.trim();
+        if !url.is_empty() {
+            return Some(DocsSource::Url(url.to_string()));
+        }
+        let assets: Vec<GithubReleaseAsset> = release?
+            .assets
+            .iter()
+            .filter(|a| a.name.to_lowercase().ends_with(".pdf"))
+            .cloned()
+            .collect();
+        (!assets.is_empty()).then_some(DocsSource::ReleaseAssets(assets))
+    }
+
+    pub fn download(
+        &self,
+        network: &Network,
+        release: Option<&GithubRelease>,
+        repo: &SoftwareRepo,
+        cache_dir: &std::path::Path,
+    ) -> anyhow::Result<Vec<std::path::PathBuf>> {
+        match self {
+            DocsSource::ReleaseAssets(assets) => {
+                let release = release.ok_or(anyhow!("Expected release to not be None."))?;
+                assets
+                    .iter()
+                    .map(|asset| {
+                        crate::utils::github::download_versioned_asset(
+                            network,
+                            asset,
+                            &repo.owner,
+                            &repo.name,
+                            release,
+                            cache_dir,
+                        )
+                    })
+                    .collect()
+            }
+            DocsSource::Url(url) => {
+                let file_name = url
+                    .split(['?', '#'])
+                    .next()
+                    .and_then(|path| path.rsplit('/').next())
+                    .filter(|name| !name.is_empty())
+                    .unwrap_or("documentation.pdf");
+                let dest_path = cache_dir.join("docs").join(file_name);
+                // The file behind a configured URL can change, so it is fetched every time.
+                crate::utils::file_download::download_file(network, url, &dest_path)?;
+                Ok(vec![dest_path])
+            }
+        }
+    }
+}
+
+/// Extracts the archive into `staging_dir` so it only has to be decompressed once per batch.
+/// Extracting reads the whole archive, so a damaged one fails here, before any card is erased.
+///
+/// Running out of space while staging is not fatal, since each card can still be extracted
+/// directly from the archive. The partial copy is removed and a warning is published instead,
+/// and the archive is checked on its own.
+pub fn stage_archive(
+    archive_path: &std::path::Path,
+    staging_dir: WorkDir,
+    events: &EventPublisher,
+) -> anyhow::Result<Option<WorkDir>> {
+    match crate::utils::archive::extract_archive(archive_path, staging_dir.path(), true, |_| {}) {
+        Ok(()) => Ok(Some(staging_dir)),
+        Err(e) if crate::utils::archive::is_out_of_space(&e) => {
+            log::warn!("Ran out of space staging {:?}: {e:#}", archive_path);
+            // Dropping the working directory deletes the partial copy.
+            events.publish(AppEvent::LowDiskSpace(staging_dir.path().to_path_buf()));
+            crate::utils::archive::verify_archive(archive_path)?;
+            Ok(None)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Files on a card that are rewritten for each team, or that belong to the OS rather than the
+/// driver station software, and so aren't copied when cloning it.
+const SKIPPED_CLONE_FILES: [&str; 3] = [
+    CARD_MANIFEST_FILE,
+    GIZMO_CONFIG_FILE,
+    "System Volume Information",
+];
+
+/// The contents of a card read for cloning.
+pub struct ClonedCard {
+    pub staged_dir: WorkDir,
+    pub software_version: String,
+    /// Field settings from the card's configuration, if it has one.
+    pub field_settings: Option<FieldSettings>,
+}
+
+/// Copies a set-up card to local disk so it can be written to other cards.
+pub fn read_source_card(source: &DriveInfo, staging_dir: WorkDir) -> anyhow::Result<ClonedCard> {
+    let source_root = &source.drive_path;
+    for entry in std::fs::read_dir(source_root)
+        .with_context(|| format!("Failed to read {:?}", source_root))?
+    {
+        let entry = entry?;
+        let name = entry.file_name();
+        let name_str = name.to_string_lossy();
+        // Hidden files are left by the OS, like macOS's .Trashes and .Spotlight-V100.
+        if SKIPPED_CLONE_FILES.contains(&name_str.as_ref()) || name_str.starts_with('.') {
+            continue;
+        }
+        let out_path = staging_dir.path().join(&name);
+        if entry.file_type()?.is_dir() {
+            std::fs::create_dir_all(&out_path)?;
+            crate::utils::archive::copy_tree(&entry.path(), &out_path, |_| {})?;
+        } else {
+            std::fs::copy(entry.path(), &out_path)
+                .with_context(|| format!("Failed to copy {:?}", entry.path()))?;
+        }
+    }
+    let software_version = match read_card_manifest(source_root) {
+        Ok(manifest) => manifest.software_version,
+        Err(_) => format!("Clone of {}", source.file_system_label),
+    };
+    Ok(ClonedCard {
+        staged_dir: staging_dir,
+        software_version,
+        field_settings: read_field_settings(source_root),
+    })
+}
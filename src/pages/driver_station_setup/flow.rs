@@ -0,0 +1,482 @@
+//! Step transitions for the driver station setup page. Nothing in here touches egui, so the
+//! page's render functions only have to draw the current step and report what the user did.
+
+use crate::i18n::tr;
+use crate::settings::TeamNumberRules;
+use crate::utils::github::GithubRelease;
+use anyhow::{Result, bail};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    ChooseVersion,
+    ChooseSourceCard,
+    EnterTeamNumbers,
+    ConfigureField,
+    DownloadArchive,
+    ChooseDrive,
+    AssignTeams,
+    InstallSoftware,
+    RemoveCard,
+}
+
+/// The parts of the page state that decide which step comes next.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FlowState {
+    pub use_local_archive: bool,
+    /// Cards are copied from an existing card instead of installed from a release.
+    pub clone_card: bool,
+    /// Documentation has to be downloaded to copy onto the cards.
+    pub fetch_docs: bool,
+    pub teams_remaining: usize,
+    /// Cards picked for the next batch.
+    pub cards_selected: usize,
+}
+
+impl Step {
+    /// Returns the step after this one, or `None` if the wizard is finished.
+    pub fn next(self, state: &FlowState) -> Option<Step> {
+        match self {
+            Step::ChooseVersion if state.clone_card => Some(Step::ChooseSourceCard),
+            Step::ChooseVersion => Some(Step::EnterTeamNumbers),
+            Step::ChooseSourceCard => Some(Step::EnterTeamNumbers),
+            Step::EnterTeamNumbers => Some(Step::ConfigureField),
+            Step::ConfigureField
+                if (state.use_local_archive || state.clone_card) && !state.fetch_docs =>
+            {
+                Some(Step::ChooseDrive)
+            }
+            Step::ConfigureField => Some(Step::DownloadArchive),
+            Step::DownloadArchive => Some(Step::ChooseDrive),
+            Step::ChooseDrive if state.cards_selected > 1 => Some(Step::AssignTeams),
+            Step::ChooseDrive => Some(Step::InstallSoftware),
+            Step::AssignTeams => Some(Step::InstallSoftware),
+            Step::InstallSoftware => Some(Step::RemoveCard),
+            Step::RemoveCard if state.teams_remaining > 0 => Some(Step::ChooseDrive),
+            Step::RemoveCard => None,
+        }
+    }
+
+    /// Returns the step after the user accepts a card inserted on [`Step::RemoveCard`]. The
+    /// card is already picked, so choosing a drive is skipped.
+    pub fn after_new_card(state: &FlowState) -> Step {
+        if state.cards_selected > 1 {
+            Step::AssignTeams
+        } else {
+            Step::InstallSoftware
+        }
+    }
+
+    pub fn label(self) -> String {
+        match self {
+            Step::ChooseVersion => tr!("step-version"),
+            Step::ChooseSourceCard => tr!("step-source-card"),
+            Step::EnterTeamNumbers => tr!("step-teams"),
+            Step::ConfigureField => tr!("step-network"),
+            Step::DownloadArchive => tr!("step-download"),
+            Step::ChooseDrive => tr!("step-card"),
+            Step::AssignTeams => tr!("step-assign"),
+            Step::InstallSoftware => tr!("step-install"),
+            Step::RemoveCard => tr!("step-remove-card"),
+        }
+    }
+}
+
+/// A line of the team number text box that can't be used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TeamNumberProblem {
+    /// Counted from zero.
+    pub line: usize,
+    pub message: String,
+}
+
+/// Parses the team number text box, one team per line. Blank lines are ignored. Returns the
+/// problems instead if any line breaks `rules` or repeats a team from an earlier line.
+pub fn parse_team_numbers(
+    text: &str,
+    rules: &TeamNumberRules,
+) -> Result<Vec<String>, Vec<TeamNumberProblem>> {
+    let mut team_numbers: Vec<(usize, &str)> = vec![];
+    let mut problems = vec![];
+    for (line, team_number) in text.split('\n').enumerate() {
+        if team_number.is_empty() {
+            continue;
+        }
+        let problem = rules.problem(team_number).or_else(|| {
+            team_numbers
+                .iter()
+                .find(|(_, earlier)| *earlier == team_number)
+                .map(|(first, _)| tr!("ds-team-repeated", team = team_number, line = first + 1))
+        });
+        match problem {
+            Some(message) => problems.push(TeamNumberProblem { line, message }),
+            None => team_numbers.push((line, team_number)),
+        }
+    }
+    if problems.is_empty() {
+        Ok(team_numbers
+            .into_iter()
+            .map(|(_, team_number)| team_number.to_string())
+            .collect())
+    } else {
+        Err(problems)
+    }
+}
+
+/// One row of an imported team roster.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RosterEntry {
+    pub team_number: String,
+    pub team_name: Option<String>,
+}
+
+/// Parses a roster CSV with the team number in the first column and an optional team name in
+/// the second. A header row is skipped, as are blank rows and repeated team numbers.
+pub fn parse_roster(text: &str) -> Result<Vec<RosterEntry>> {
+    let mut entries: Vec<RosterEntry> = vec![];
+    for (i, line) in text.trim_start_matches('\u{feff}').lines().enumerate() {
+        let fields = split_csv_line(line);
+        let team_number = fields.first().map(String::as_str).unwrap_or_default();
+        if team_number.is_empty() {
+            continue;
+        }
+        if !team_number.chars().all(|c| c.is_ascii_digit()) {
+            if i == 0 {
+                continue;
+            }
+            bail!("Line {}: \"{team_number}\" is not a team number.", i + 1);
+        }
+        if entries.iter().any(|e| e.team_number == team_number) {
+            continue;
+        }
+        entries.push(RosterEntry {
+            team_number: team_number.to_string(),
+            team_name: fields.get(1).filter(|name| !name.is_empty()).cloned(),
+        });
+    }
+    if entries.is_empty() {
+        bail!("The file has no team numbers in its first column.");
+    }
+    Ok(entries)
+}
+
+/// Splits one CSV row into trimmed fields, handling quoted fields that contain commas.
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = vec![];
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field).trim().to_string()),
+            c => field.push(c),
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
+
+/// Single-team builds are only a problem when they are about to be installed for several teams.
+pub fn needs_single_team_acknowledgement(team_count: usize, is_single_team_variant: bool) -> bool {
+    team_count > 1 && is_single_team_variant
+}
+
+pub fn team_numbers_next_enabled(
+    team_count: usize,
+    needs_acknowledgement: bool,
+    acknowledged: bool,
+) -> bool {
+    team_count > 0 && (!needs_acknowledgement || acknowledged)
+}
+
+/// The number of drives that can be picked for the next batch, one per remaining team.
+pub fn can_select_drive(
+    already_selected: bool,
+    selected_count: usize,
+    teams_remaining: usize,
+) -> bool {
+    already_selected || selected_count < teams_remaining
+}
+
+/// Returns the releases a card goes through when updated from `installed_tag` to `target_tag`,
+/// oldest first. `releases` is newest first, as GitHub lists them. Returns `None` if either tag
+/// is missing from the list or the target is not newer than what is installed.
+pub fn releases_between<'a>(
+    releases: &'a [GithubRelease],
+    installed_tag: &str,
+    target_tag: &str,
+) -> Option<Vec<&'a GithubRelease>> {
+    let target = releases.iter().position(|r| r.tag_name == target_tag)?;
+    let installed = releases.iter().position(|r| r.tag_name == installed_tag)?;
+    (target < installed).then(|| releases[target..installed].iter().rev().collect())
+}
+
+/// Gives `team` to the card at `card`. If another card had that team, the two cards swap teams,
+/// so no team is installed twice.
+pub fn assign_team(card_teams: &mut [String], card: usize, team: &str) {
+    match card_teams.iter().position(|t| t == team) {
+        Some(previous) => card_teams.swap(previous, card),
+        None => card_teams[card] = team.to_string(),
+    }
+}
+
+/// Moves the teams in `batch` to the front of the queue at `start`, in the order given, so they
+/// are the next teams installed. The other remaining teams keep their order.
+pub fn move_to_front(team_numbers: &mut Vec<String>, start: usize, batch: &[String]) {
+    let rest: Vec<String> = team_numbers
+        .drain(start..)
+        .filter(|team| !batch.contains(team))
+        .collect();
+    team_numbers.extend(batch.iter().cloned());
+    team_numbers.extend(rest);
+}
+
+/// Cards that fail in a row before the batch is paused. Several failures in a row point at the
+/// card reader or the computer rather than the cards.
+pub const FAILURE_STREAK_LIMIT: usize = 3;
+
+/// Extends the count of cards that failed in a row with the results of a finished batch, in
+/// the order the cards were started. Any success ends the streak.
+pub fn update_failure_streak(streak: usize, succeeded: impl IntoIterator<Item = bool>) -> usize {
+    succeeded
+        .into_iter()
+        .fold(streak, |streak, ok| if ok { 0 } else { streak + 1 })
+}
+
+/// Reorders the teams in a finished batch so the ones that succeeded come first, followed by
+/// the ones that failed. Returns how far to advance the queue, which leaves failed teams at the
+/// front so they are retried next.
+pub fn requeue_failed_teams(
+    team_numbers: &mut Vec<String>,
+    batch_start: usize,
+    results: &[(String, bool)],
+) -> usize {
+    let batch_end = batch_start + results.len();
+    let succeeded: Vec<&String> = results
+        .iter()
+        .filter(|(_, ok)| *ok)
+        .map(|(t, _)| t)
+        .collect();
+    let failed = results.iter().filter(|(_, ok)| !ok).map(|(t, _)| t);
+    let succeeded_count = succeeded.len();
+    let reordered: Vec<String> = succeeded.into_iter().chain(failed).cloned().collect();
+    team_numbers.splice(batch_start..batch_end, reordered);
+    succeeded_count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn next(step: Step, state: FlowState) -> Option<Step> {
+        step.next(&state)
+    }
+
+    #[test]
+    fn choose_version_goes_to_source_card_only_when_cloning() {
+        assert_eq!(
+            next(Step::ChooseVersion, FlowState::default()),
+            Some(Step::EnterTeamNumbers)
+        );
+        let cloning = FlowState {
+            clone_card: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            next(Step::ChooseVersion, cloning),
+            Some(Step::ChooseSourceCard)
+        );
+        assert_eq!(
+            next(Step::ChooseSourceCard, cloning),
+            Some(Step::EnterTeamNumbers)
+        );
+    }
+
+    #[test]
+    fn team_numbers_go_to_field_settings() {
+        for clone_card in [false, true] {
+            let state = FlowState {
+                clone_card,
+                ..Default::default()
+            };
+            assert_eq!(
+                next(Step::EnterTeamNumbers, state),
+                Some(Step::ConfigureField)
+            );
+        }
+    }
+
+    #[test]
+    fn field_settings_skip_the_download_only_when_nothing_is_needed() {
+        for use_local_archive in [false, true] {
+            for clone_card in [false, true] {
+                for fetch_docs in [false, true] {
+                    let state = FlowState {
+                        use_local_archive,
+                        clone_card,
+                        fetch_docs,
+                        ..Default::default()
+                    };
+                    let expected = if (use_local_archive || clone_card) && !fetch_docs {
+                        Step::ChooseDrive
+                    } else {
+                        Step::DownloadArchive
+                    };
+                    assert_eq!(
+                        next(Step::ConfigureField, state),
+                        Some(expected),
+                        "{state:?}"
+                    );
+                }
+            }
+        }
+        assert_eq!(
+            next(Step::DownloadArchive, FlowState::default()),
+            Some(Step::ChooseDrive)
+        );
+    }
+
+    #[test]
+    fn several_cards_are_matched_to_teams_before_installing() {
+        for (cards_selected, expected) in [
+            (0, Step::InstallSoftware),
+            (1, Step::InstallSoftware),
+            (2, Step::AssignTeams),
+            (5, Step::AssignTeams),
+        ] {
+            let state = FlowState {
+                cards_selected,
+                ..Default::default()
+            };
+            assert_eq!(next(Step::ChooseDrive, state), Some(expected));
+        }
+        assert_eq!(
+            next(Step::AssignTeams, FlowState::default()),
+            Some(Step::InstallSoftware)
+        );
+        assert_eq!(
+            next(Step::InstallSoftware, FlowState::default()),
+            Some(Step::RemoveCard)
+        );
+    }
+
+    #[test]
+    fn removing_a_card_loops_until_no_teams_remain() {
+        let more_teams = FlowState {
+            teams_remaining: 2,
+            ..Default::default()
+        };
+        assert_eq!(next(Step::RemoveCard, more_teams), Some(Step::ChooseDrive));
+        assert_eq!(next(Step::RemoveCard, FlowState::default()), None);
+    }
+
+    #[test]
+    fn a_new_card_goes_straight_to_installing() {
+        let new_card = FlowState {
+            teams_remaining: 2,
+            cards_selected: 1,
+            ..Default::default()
+        };
+        assert_eq!(Step::after_new_card(&new_card), Step::InstallSoftware);
+        assert_eq!(
+            Step::after_new_card(&new_card),
+            next(Step::ChooseDrive, new_card).unwrap()
+        );
+    }
+
+    fn problem_lines(result: Result<Vec<String>, Vec<TeamNumberProblem>>) -> Vec<usize> {
+        result
+            .expect_err("Expected problems")
+            .iter()
+            .map(|problem| problem.line)
+            .collect()
+    }
+
+    #[test]
+    fn team_numbers_skip_blank_lines() {
+        let rules = TeamNumberRules::default();
+        assert_eq!(
+            parse_team_numbers("12\n\n345\n", &rules),
+            Ok(vec!["12".to_string(), "345".to_string()])
+        );
+        assert_eq!(parse_team_numbers("", &rules), Ok(vec![]));
+        assert_eq!(parse_team_numbers("\n\n", &rules), Ok(vec![]));
+    }
+
+    #[test]
+    fn team_numbers_report_the_lines_that_break_the_rules() {
+        let rules = TeamNumberRules::default();
+        assert_eq!(
+            problem_lines(parse_team_numbers("12\nabc\n\n12345\n0\n 7", &rules)),
+            vec![1, 3, 4, 5]
+        );
+        // A line ending from a Windows clipboard is not part of the number.
+        assert_eq!(
+            problem_lines(parse_team_numbers("12\r\n34", &rules)),
+            vec![0]
+        );
+    }
+
+    #[test]
+    fn team_numbers_report_repeats_after_the_first() {
+        let rules = TeamNumberRules::default();
+        assert_eq!(
+            problem_lines(parse_team_numbers("12\n34\n12\n12", &rules)),
+            vec![2, 3]
+        );
+    }
+
+    #[test]
+    fn team_numbers_follow_custom_rules() {
+        let rules = TeamNumberRules {
+            min_digits: 3,
+            max_digits: 3,
+            lowest: 100,
+            highest: 500,
+        };
+        assert_eq!(
+            parse_team_numbers("100\n500", &rules),
+            Ok(vec!["100".to_string(), "500".to_string()])
+        );
+        assert_eq!(
+            problem_lines(parse_team_numbers("99\n501\n0100", &rules)),
+            vec![0, 1, 2]
+        );
+    }
+
+    fn teams(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn failed_teams_are_requeued_after_the_ones_that_succeeded() {
+        let mut team_numbers = teams(&["1", "2", "3", "4", "5"]);
+        let results = [
+            ("2".to_string(), false),
+            ("3".to_string(), true),
+            ("4".to_string(), false),
+        ];
+        assert_eq!(requeue_failed_teams(&mut team_numbers, 1, &results), 1);
+        assert_eq!(team_numbers, teams(&["1", "3", "2", "4", "5"]));
+    }
+
+    #[test]
+    fn assigning_a_taken_team_swaps_cards() {
+        let mut card_teams = teams(&["1", "2", "3"]);
+        assign_team(&mut card_teams, 0, "3");
+        assert_eq!(card_teams, teams(&["3", "2", "1"]));
+        assign_team(&mut card_teams, 1, "9");
+        assert_eq!(card_teams, teams(&["3", "9", "1"]));
+    }
+
+    #[test]
+    fn moved_teams_keep_the_rest_in_order() {
+        let mut team_numbers = teams(&["1", "2", "3", "4", "5"]);
+        move_to_front(&mut team_numbers, 1, &teams(&["4", "2"]));
+        assert_eq!(team_numbers, teams(&["1", "4", "2", "3", "5"]));
+    }
+}
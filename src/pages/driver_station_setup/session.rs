@@ -0,0 +1,66 @@
+//! The driver station session saved between runs, so an event can pick up where it left off if
+//! the wizard is closed or crashes partway through a long queue of teams.
+
+use crate::migrations::VersionedFile;
+use crate::settings::config_dir;
+use crate::utils::github::GithubRelease;
+use crate::utils::gizmo_config::FieldSettings;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Describes the session file to the startup migrations.
+pub const SESSION_FILE: VersionedFile = VersionedFile {
+    name: "driver station session",
+    path: session_path,
+    migrations: &[],
+};
+
+/// Everything needed to carry on setting up cards for the remaining teams.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SavedSession {
+    pub format_version: u32,
+    /// Seconds since the Unix epoch.
+    pub saved_at: u64,
+    /// The release being installed. `None` if a local archive was chosen instead.
+    pub software_version: Option<GithubRelease>,
+    pub local_archive: Option<PathBuf>,
+    pub completed_teams: Vec<String>,
+    pub remaining_teams: Vec<String>,
+    /// Team names from an imported roster, keyed by team number.
+    pub team_names: HashMap<String, String>,
+    pub field_settings: FieldSettings,
+}
+
+fn session_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("ds-session.json"))
+}
+
+/// The session left behind by an earlier run, if there is one with teams still to set up.
+pub fn load() -> Option<SavedSession> {
+    let contents = std::fs::read_to_string(session_path()?).ok()?;
+    let session: SavedSession = serde_json::from_str(&contents)
+        .inspect_err(|e| log::warn!("Ignoring unreadable driver station session: {e}"))
+        .ok()?;
+    (!session.remaining_teams.is_empty()).then_some(session)
+}
+
+pub fn save(session: &SavedSession) -> Result<()> {
+    let path = session_path().ok_or(anyhow::anyhow!("Could not find config directory."))?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, serde_json::to_string_pretty(session)?)
+        .with_context(|| format!("Failed to save driver station session to {:?}", path))
+}
+
+/// Forgets the saved session, once its teams are all done or the user discards it.
+pub fn clear() {
+    if let Some(path) = session_path()
+        && path.is_file()
+        && let Err(e) = std::fs::remove_file(&path)
+    {
+        log::warn!("Could not remove {:?}: {e}", path);
+    }
+}
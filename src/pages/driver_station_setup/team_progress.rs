@@ -0,0 +1,70 @@
+//! How far along each team in the queue is, for the team progress panel.
+
+use super::card_job::CardStatus;
+use crate::i18n::tr;
+use crate::theme::Palette;
+
+/// Where a team is in the install queue, as shown in the team progress panel.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TeamProgress {
+    Pending,
+    Formatting,
+    Installing,
+    Done(Option<u32>),
+    Failed(String),
+}
+
+impl From<&CardStatus> for TeamProgress {
+    fn from(status: &CardStatus) -> Self {
+        match status {
+            CardStatus::Formatting => TeamProgress::Formatting,
+            CardStatus::Copying(_)
+            | CardStatus::Imaging(_)
+            | CardStatus::Flushing
+            | CardStatus::Ejecting => TeamProgress::Installing,
+            CardStatus::Done { io_errors, .. } => TeamProgress::Done(*io_errors),
+            CardStatus::Failed(message) => TeamProgress::Failed(message.clone()),
+        }
+    }
+}
+
+impl TeamProgress {
+    pub fn is_done(&self) -> bool {
+        matches!(self, TeamProgress::Done(_))
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            TeamProgress::Pending => tr!("ds-progress-pending"),
+            TeamProgress::Formatting => tr!("ds-progress-formatting"),
+            TeamProgress::Installing => tr!("ds-progress-installing"),
+            TeamProgress::Done(Some(io_errors)) if *io_errors > 0 => tr!("ds-progress-card-errors"),
+            TeamProgress::Done(_) => tr!("ds-done"),
+            TeamProgress::Failed(_) => tr!("ds-progress-failed"),
+        }
+    }
+
+    pub fn color(&self, palette: &Palette) -> egui::Color32 {
+        match self {
+            TeamProgress::Pending => palette.pending,
+            TeamProgress::Formatting | TeamProgress::Installing => palette.active,
+            TeamProgress::Done(Some(io_errors)) if *io_errors > 0 => palette.warning,
+            TeamProgress::Done(_) => palette.success,
+            TeamProgress::Failed(_) => palette.error,
+        }
+    }
+
+    pub fn details(&self) -> Option<String> {
+        match self {
+            TeamProgress::Done(Some(io_errors)) if *io_errors > 0 => {
+                Some(io_error_warning(*io_errors))
+            }
+            TeamProgress::Failed(message) => Some(message.clone()),
+            _ => None,
+        }
+    }
+}
+
+pub fn io_error_warning(io_errors: u32) -> String {
+    tr!("ds-io-errors", errors = io_errors)
+}
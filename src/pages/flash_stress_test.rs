@@ -0,0 +1,315 @@
+use crate::app::GlobalAppState;
+use crate::i18n::tr;
+use crate::pages::Page;
+use crate::theme::palette;
+use crate::utils::drive_management::{DriveInfo, DriveManager, is_rp2040_bootloader};
+use crate::utils::gizmo_serial::{
+    list_serial_ports, query_firmware_version, reset_to_bootloader, wait_for_new_serial_port,
+};
+use crate::utils::tasks::Task;
+use crate::widgets::accessibility::heading;
+use anyhow::{Result, anyhow, bail};
+use egui_alignments::{column, stretch};
+use egui_file_dialog::FileDialog;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{Receiver, Sender};
+use std::time::{Duration, Instant};
+
+/// Set this environment variable to show the stress test on the start page.
+pub const MAINTAINER_MODE_ENV_VAR: &str = "GIZMO_WIZARD_MAINTAINER";
+
+const REBOOT_TIMEOUT: Duration = Duration::from_secs(30);
+const DRIVE_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+pub fn maintainer_mode_enabled() -> bool {
+    std::env::var_os(MAINTAINER_MODE_ENV_VAR).is_some()
+}
+
+/// The result of one flash and verify cycle: the version read back and how long it took from
+/// starting the copy, or why the cycle failed.
+type CycleResult = Result<(String, Duration), String>;
+
+#[derive(Default)]
+struct FlashStats {
+    cycles: u32,
+    failures: u32,
+    flash_times: Vec<Duration>,
+    last_error: Option<String>,
+}
+
+impl FlashStats {
+    fn record(&mut self, result: CycleResult) {
+        self.cycles += 1;
+        match result {
+            Ok((_, flash_time)) => self.flash_times.push(flash_time),
+            Err(e) => {
+                self.failures += 1;
+                self.last_error = Some(e);
+            }
+        }
+    }
+
+    fn failure_rate(&self) -> f32 {
+        if self.cycles == 0 {
+            0.0
+        } else {
+            self.failures as f32 / self.cycles as f32
+        }
+    }
+
+    fn mean_flash_time(&self) -> Option<Duration> {
+        let count = u32::try_from(self.flash_times.len())
+            .ok()
+            .filter(|c| *c > 0)?;
+        Some(self.flash_times.iter().sum::<Duration>() / count)
+    }
+}
+
+/// Maintainer tool that flashes and verifies a connected board over and over, to check wizard
+/// changes against real RP2040 hardware before a release.
+pub struct FlashStressTestPage {
+    firmware_path: Option<PathBuf>,
+    expected_version: String,
+    /// Zero means run until stopped.
+    max_cycles: u32,
+    stats: FlashStats,
+    cancel: Arc<AtomicBool>,
+
+    cycles_task: Option<Task<()>>,
+    cycle_receiver: Option<Receiver<CycleResult>>,
+
+    file_dialog: FileDialog,
+}
+
+impl FlashStressTestPage {
+    pub fn new() -> Self {
+        Self {
+            firmware_path: None,
+            expected_version: String::new(),
+            max_cycles: 0,
+            stats: FlashStats::default(),
+            cancel: Arc::new(AtomicBool::new(false)),
+
+            cycles_task: None,
+            cycle_receiver: None,
+
+            file_dialog: FileDialog::new()
+                .add_file_filter_extensions(&tr!("file-uf2-filter"), vec!["uf2"])
+                .default_file_filter(&tr!("file-uf2-filter")),
+        }
+    }
+
+    fn start(&mut self, drives: Arc<dyn DriveManager>, ctx: &egui::Context) -> Result<()> {
+        let firmware_path = self
+            .firmware_path
+            .clone()
+            .ok_or(anyhow!("Expected firmware_path to not be None."))?;
+        let expected_version =
+            Some(self.expected_version.trim().to_string()).filter(|v| !v.is_empty());
+        let max_cycles = self.max_cycles;
+        let ctx = ctx.clone();
+        self.cancel = Arc::new(AtomicBool::new(false));
+        let cancel = self.cancel.clone();
+        self.stats = FlashStats::default();
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.cycle_receiver = Some(rx);
+        log::info!("Starting flash stress test with {:?}", firmware_path);
+        self.cycles_task = Some(Task::spawn(move || {
+            run_cycles(
+                drives.as_ref(),
+                &firmware_path,
+                expected_version.as_deref(),
+                max_cycles,
+                &cancel,
+                &tx,
+                &ctx,
+            );
+            Ok(())
+        }));
+        Ok(())
+    }
+}
+
+impl Page for FlashStressTestPage {
+    fn run(&mut self, app_state: &mut GlobalAppState, ui: &mut egui::Ui) -> Result<()> {
+        if let Some(ref receiver) = self.cycle_receiver {
+            for result in receiver.try_iter() {
+                log::info!("Stress test cycle {}: {result:?}", self.stats.cycles + 1);
+                self.stats.record(result);
+            }
+        }
+        if Task::poll(&mut self.cycles_task)?.is_some() {
+            self.cycle_receiver = None;
+        }
+
+        self.file_dialog.update(ui.ctx());
+        if let Some(path) = self.file_dialog.take_picked() {
+            self.firmware_path = Some(path);
+        }
+
+        let running = self.cycles_task.is_some();
+        let mut start = false;
+        column(ui, egui::Align::LEFT, |ui| {
+            heading(ui, tr!("stress-title"));
+            ui.label(tr!("stress-intro"));
+            ui.add_enabled_ui(!running, |ui| {
+                egui::Grid::new("stress_test_settings")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label(tr!("stress-firmware"));
+                        ui.horizontal(|ui| {
+                            if ui.button(tr!("stress-choose-file")).clicked() {
+                                self.file_dialog.pick_file();
+                            }
+                            if let Some(ref path) = self.firmware_path {
+                                ui.label(format!("{}", path.display()));
+                            }
+                        });
+                        ui.end_row();
+                        ui.label(tr!("stress-expected-version"));
+                        ui.text_edit_singleline(&mut self.expected_version);
+                        ui.end_row();
+                        ui.label(tr!("stress-cycles"));
+                        ui.horizontal(|ui| {
+                            ui.add(egui::DragValue::new(&mut self.max_cycles));
+                            ui.label(tr!("stress-cycles-hint"));
+                        });
+                        ui.end_row();
+                    });
+            });
+
+            ui.separator();
+            let stats = &self.stats;
+            ui.label(tr!(
+                "stress-summary",
+                cycles = stats.cycles,
+                failures = stats.failures,
+                rate = format!("{:.1}", stats.failure_rate() * 100.0)
+            ));
+            if let (Some(mean), Some(min), Some(max)) = (
+                stats.mean_flash_time(),
+                stats.flash_times.iter().min(),
+                stats.flash_times.iter().max(),
+            ) {
+                ui.label(tr!(
+                    "stress-flash-time",
+                    mean = format!("{:.1}", mean.as_secs_f32()),
+                    min = format!("{:.1}", min.as_secs_f32()),
+                    max = format!("{:.1}", max.as_secs_f32())
+                ));
+            }
+            if let Some(ref error) = stats.last_error {
+                ui.colored_label(palette(ui).error, tr!("stress-last-failure", error = error));
+            }
+            if running {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(tr!("stress-running"));
+                });
+            }
+
+            stretch(ui);
+            egui_alignments::row(ui, egui::Align::Center, |ui| {
+                stretch(ui);
+                if running {
+                    let stopping = self.cancel.load(Ordering::Relaxed);
+                    if ui
+                        .add_enabled(!stopping, egui::Button::new(tr!("stress-stop")))
+                        .clicked()
+                    {
+                        self.cancel.store(true, Ordering::Relaxed);
+                    }
+                } else {
+                    start = ui
+                        .add_enabled(
+                            self.firmware_path.is_some(),
+                            egui::Button::new(tr!("stress-start")),
+                        )
+                        .clicked();
+                }
+            });
+        });
+        if start {
+            self.start(app_state.drives.clone(), ui.ctx())?;
+        }
+        Ok(())
+    }
+
+    fn get_title(&self) -> String {
+        tr!("stress-title")
+    }
+}
+
+fn run_cycles(
+    drives: &dyn DriveManager,
+    firmware_path: &Path,
+    expected_version: Option<&str>,
+    max_cycles: u32,
+    cancel: &AtomicBool,
+    tx: &Sender<CycleResult>,
+    ctx: &egui::Context,
+) {
+    let mut cycle = 0;
+    while !cancel.load(Ordering::Relaxed) && (max_cycles == 0 || cycle < max_cycles) {
+        let result =
+            flash_and_verify(drives, firmware_path, expected_version).map_err(|e| format!("{e:#}"));
+        // The receiver is dropped if the user leaves the page, which ends the test.
+        if tx.send(result).is_err() {
+            return;
+        }
+        ctx.request_repaint();
+        cycle += 1;
+    }
+}
+
+fn flash_and_verify(
+    drives: &dyn DriveManager,
+    firmware_path: &Path,
+    expected_version: Option<&str>,
+) -> Result<(String, Duration)> {
+    let drive = enter_bootloader(drives)?;
+    let known_ports = list_serial_ports().unwrap_or_default();
+    let file_name = firmware_path
+        .file_name()
+        .ok_or(anyhow!("Could not find filename in {:?}", firmware_path))?;
+    let start = Instant::now();
+    std::fs::copy(firmware_path, drive.drive_path.join(file_name))?;
+    let port = wait_for_new_serial_port(&known_ports, REBOOT_TIMEOUT)?;
+    let version = query_firmware_version(&port)?;
+    let flash_time = start.elapsed();
+    if let Some(expected) = expected_version
+        && expected != version
+    {
+        bail!("Expected firmware {expected}, but the board reported {version}.");
+    }
+    Ok((version, flash_time))
+}
+
+/// Returns the board's bootloader drive, rebooting it into BOOTSEL mode first if it is running.
+fn enter_bootloader(drives: &dyn DriveManager) -> Result<DriveInfo> {
+    if let Some(drive) = find_bootloader(drives)? {
+        return Ok(drive);
+    }
+    let ports = list_serial_ports()?;
+    let port = ports.first().ok_or(anyhow!(
+        "No board found. Connect it in BOOTSEL mode or running firmware."
+    ))?;
+    reset_to_bootloader(port)?;
+    let start = Instant::now();
+    while start.elapsed() < REBOOT_TIMEOUT {
+        if let Some(drive) = find_bootloader(drives)? {
+            return Ok(drive);
+        }
+        std::thread::sleep(DRIVE_POLL_INTERVAL);
+    }
+    bail!(
+        "The board did not enter BOOTSEL mode within {} seconds.",
+        REBOOT_TIMEOUT.as_secs()
+    )
+}
+
+fn find_bootloader(drives: &dyn DriveManager) -> Result<Option<DriveInfo>> {
+    Ok(drives.list()?.into_iter().find(is_rp2040_bootloader))
+}
@@ -0,0 +1,229 @@
+use crate::app::GlobalAppState;
+use crate::events::AppEvent;
+use crate::i18n::tr;
+use crate::pages::driver_station_setup::DriverStationSetupPage;
+use crate::pages::student_starter_code::StudentStarterCodePage;
+use crate::pages::system_firmware::SystemFirmwarePage;
+use crate::pages::{Page, StepProgress, add_custom_next_button, add_next_button};
+use crate::theme::palette;
+use crate::utils::compatibility::major_version;
+use crate::widgets::accessibility::heading;
+use egui_alignments::{column, stretch};
+
+mod flow;
+
+use flow::Step;
+
+/// Sets up everything one team needs in a single pass: the system firmware, then the student
+/// starter code, then the driver station card. The team number is entered once, and the starter
+/// code and driver station start out on the release family of the firmware.
+pub struct FullKitSetupPage {
+    current_step: Step,
+    team_number: String,
+    /// Major version of the firmware that was installed, or `None` if it came from a local file.
+    release_family: Option<u32>,
+    /// Parts the user chose to skip, so the summary doesn't claim they were set up.
+    skipped: Vec<Step>,
+    firmware: SystemFirmwarePage,
+    starter_code: StudentStarterCodePage,
+    driver_station: Option<DriverStationSetupPage>,
+}
+
+impl FullKitSetupPage {
+    pub fn new() -> Self {
+        Self {
+            current_step: Step::EnterTeamNumber,
+            team_number: String::new(),
+            release_family: None,
+            skipped: vec![],
+            firmware: SystemFirmwarePage::new(),
+            starter_code: StudentStarterCodePage::new(),
+            driver_station: None,
+        }
+    }
+
+    fn advance(&mut self) {
+        let next = self.current_step.next();
+        log::info!("Full kit setup: {:?} -> {:?}", self.current_step, next);
+        match next {
+            Step::StarterCode => {
+                self.release_family = self
+                    .firmware
+                    .chosen_release()
+                    .and_then(|release| major_version(&release.tag_name));
+                self.starter_code =
+                    StudentStarterCodePage::with_release_family(self.release_family);
+            }
+            Step::DriverStation => {
+                self.driver_station = Some(DriverStationSetupPage::for_team(
+                    &self.team_number,
+                    self.release_family,
+                ));
+            }
+            _ => {}
+        }
+        self.current_step = next;
+    }
+
+    /// The page running the current part of the kit, if any.
+    fn current_page(&mut self) -> Option<&mut dyn Page> {
+        match self.current_step {
+            Step::SystemFirmware => Some(&mut self.firmware),
+            Step::StarterCode => Some(&mut self.starter_code),
+            Step::DriverStation => self.driver_station.as_mut().map(|p| p as &mut dyn Page),
+            Step::EnterTeamNumber | Step::Finished => None,
+        }
+    }
+
+    fn current_part_finished(&self) -> bool {
+        match self.current_step {
+            Step::SystemFirmware => self.firmware.is_finished(),
+            Step::StarterCode => self.starter_code.is_finished(),
+            Step::DriverStation => self
+                .driver_station
+                .as_ref()
+                .is_some_and(DriverStationSetupPage::is_finished),
+            Step::EnterTeamNumber | Step::Finished => false,
+        }
+    }
+
+    fn run_enter_team_number(&mut self, app_state: &GlobalAppState, ui: &mut egui::Ui) {
+        column(ui, egui::Align::LEFT, |ui| {
+            heading(ui, tr!("kit-team-number"));
+            ui.label(tr!("kit-intro"));
+            ui.label(tr!("kit-enter-team"));
+            ui.text_edit_singleline(&mut self.team_number);
+            let problem = app_state
+                .settings
+                .team_number_rules
+                .problem(self.team_number.trim());
+            let valid = problem.is_none();
+            if let Some(problem) = problem.filter(|_| !self.team_number.is_empty()) {
+                ui.colored_label(palette(ui).error, problem);
+            }
+            stretch(ui);
+            if add_next_button(ui, valid).clicked() {
+                self.team_number = self.team_number.trim().to_string();
+                self.advance();
+            }
+        });
+    }
+
+    /// Shows which part of the kit is being set up, with a way to skip parts the kit already
+    /// has.
+    fn add_progress_header(&mut self, ui: &mut egui::Ui) {
+        let Some(part_number) = self.current_step.part_number() else {
+            return;
+        };
+        ui.horizontal(|ui| {
+            ui.strong(tr!(
+                "kit-progress",
+                team = &self.team_number,
+                part = part_number,
+                label = self.current_step.label()
+            ));
+            stretch(ui);
+            if ui
+                .link(tr!("kit-skip"))
+                .on_hover_text(tr!("kit-skip-hint"))
+                .clicked()
+            {
+                log::info!(
+                    "Skipping {:?} for team {}",
+                    self.current_step,
+                    self.team_number
+                );
+                self.skipped.push(self.current_step);
+                self.advance();
+            }
+        });
+        ui.separator();
+    }
+
+    fn run_part_finished(&mut self, ui: &mut egui::Ui) {
+        let next = self.current_step.next();
+        column(ui, egui::Align::LEFT, |ui| {
+            heading(ui, tr!("kit-part-done", label = self.current_step.label()));
+            if self.current_step == Step::SystemFirmware
+                && let Some(release) = self.firmware.chosen_release()
+            {
+                ui.label(tr!("kit-firmware-family", version = &release.tag_name));
+            }
+            ui.label(tr!("install-disconnect"));
+            stretch(ui);
+            if add_custom_next_button(ui, tr!("kit-continue-to", label = next.label()), true)
+                .clicked()
+            {
+                self.advance();
+            }
+        });
+    }
+
+    fn run_finished(&mut self, ui: &mut egui::Ui) {
+        column(ui, egui::Align::LEFT, |ui| {
+            heading(ui, tr!("kit-complete", team = &self.team_number));
+            for part in [Step::SystemFirmware, Step::StarterCode, Step::DriverStation] {
+                if self.skipped.contains(&part) {
+                    ui.label(tr!("kit-part-skipped", label = part.label()));
+                } else {
+                    ui.colored_label(
+                        palette(ui).success,
+                        tr!("kit-part-finished", label = part.label()),
+                    );
+                }
+            }
+            ui.label(tr!("kit-remove-card"));
+            ui.label(tr!("kit-check-hint"));
+            stretch(ui);
+            if add_custom_next_button(ui, tr!("kit-another"), true).clicked() {
+                *self = Self::new();
+            }
+        });
+    }
+}
+
+impl Page for FullKitSetupPage {
+    fn run(&mut self, app_state: &mut GlobalAppState, ui: &mut egui::Ui) -> anyhow::Result<()> {
+        match self.current_step {
+            Step::EnterTeamNumber => self.run_enter_team_number(app_state, ui),
+            Step::Finished => self.run_finished(ui),
+            // The card was ejected when the install finished, so there is nothing left to say
+            // about it before the summary.
+            Step::DriverStation if self.current_part_finished() => self.advance(),
+            _ if self.current_part_finished() => self.run_part_finished(ui),
+            _ => {
+                self.add_progress_header(ui);
+                if let Some(page) = self.current_page() {
+                    page.run(app_state, ui)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn get_title(&self) -> String {
+        tr!("kit-title")
+    }
+
+    /// Shows the progress of the part being set up, since each part is a page of its own.
+    fn step_progress(&self) -> Option<StepProgress> {
+        match self.current_step {
+            Step::SystemFirmware => self.firmware.step_progress(),
+            Step::StarterCode => self.starter_code.step_progress(),
+            Step::DriverStation => self.driver_station.as_ref()?.step_progress(),
+            Step::EnterTeamNumber | Step::Finished => None,
+        }
+    }
+
+    fn handle_event(&mut self, app_state: &mut GlobalAppState, event: &AppEvent) {
+        if let Some(page) = self.current_page() {
+            page.handle_event(app_state, event);
+        }
+    }
+
+    fn retry_step(&mut self) {
+        if let Some(page) = self.current_page() {
+            page.retry_step();
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use crate::i18n::tr;
+
+/// The parts of a kit, in the order they are set up. The system firmware comes first so the
+/// other parts can be picked from the same release family.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    EnterTeamNumber,
+    SystemFirmware,
+    StarterCode,
+    DriverStation,
+    Finished,
+}
+
+impl Step {
+    pub fn next(self) -> Step {
+        match self {
+            Step::EnterTeamNumber => Step::SystemFirmware,
+            Step::SystemFirmware => Step::StarterCode,
+            Step::StarterCode => Step::DriverStation,
+            Step::DriverStation => Step::Finished,
+            Step::Finished => Step::Finished,
+        }
+    }
+
+    /// Where this part falls among the three installs, for the progress header.
+    pub fn part_number(self) -> Option<usize> {
+        match self {
+            Step::SystemFirmware => Some(1),
+            Step::StarterCode => Some(2),
+            Step::DriverStation => Some(3),
+            Step::EnterTeamNumber | Step::Finished => None,
+        }
+    }
+
+    pub fn label(self) -> String {
+        match self {
+            Step::EnterTeamNumber => tr!("kit-team-number"),
+            Step::SystemFirmware => tr!("kit-system-firmware"),
+            Step::StarterCode => tr!("kit-starter-code"),
+            Step::DriverStation => tr!("kit-driver-station"),
+            Step::Finished => tr!("kit-finished"),
+        }
+    }
+}
@@ -0,0 +1,292 @@
+use crate::app::GlobalAppState;
+use crate::events::AppEvent;
+use crate::i18n::tr;
+use crate::pages::{Page, StepProgress, add_custom_next_button, recoverable};
+use crate::theme::palette;
+use crate::utils::card_manifest::{CardManifest, read_card_manifest};
+use crate::utils::compatibility::versions_compatible;
+use crate::utils::drive_management::DriveInfo;
+use crate::utils::gizmo_serial::{list_serial_ports, query_firmware_version};
+use crate::utils::sounds::{Cue, play_cue};
+use crate::utils::tasks::Task;
+use crate::widgets::accessibility::heading;
+use anyhow::{Context, anyhow};
+use egui_alignments::{column, stretch};
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    Connect,
+    CheckKit,
+    Verdict,
+}
+
+struct KitDevices {
+    drives: Vec<DriveInfo>,
+    serial_ports: Vec<PathBuf>,
+}
+
+struct KitReport {
+    card: Result<CardManifest, String>,
+    firmware_version: Result<String, String>,
+}
+
+impl KitReport {
+    /// Returns why the kit fails the check, or `None` if it passes.
+    fn problem(&self) -> Option<String> {
+        match (&self.card, &self.firmware_version) {
+            (Err(e), _) => Some(tr!("kitcheck-card-unreadable", error = e)),
+            (_, Err(e)) => Some(tr!("kitcheck-firmware-unreadable", error = e)),
+            (Ok(card), Ok(firmware_version)) => {
+                (!versions_compatible(&card.software_version, firmware_version)).then(|| {
+                    tr!(
+                        "kitcheck-incompatible",
+                        driver_station = &card.software_version,
+                        firmware = firmware_version
+                    )
+                })
+            }
+        }
+    }
+}
+
+/// Final QA step for a finished kit: checks that the driver station card and the running system
+/// processor have software versions that work together.
+pub struct KitCheckPage {
+    current_step: Step,
+    devices: Option<KitDevices>,
+    selected_drive: Option<DriveInfo>,
+    selected_port: Option<PathBuf>,
+    report: Option<KitReport>,
+
+    devices_task: Option<Task<KitDevices>>,
+    report_task: Option<Task<KitReport>>,
+}
+
+impl KitCheckPage {
+    pub fn new() -> Self {
+        Self {
+            current_step: Step::Connect,
+            devices: None,
+            selected_drive: None,
+            selected_port: None,
+            report: None,
+
+            devices_task: None,
+            report_task: None,
+        }
+    }
+
+    fn set_step(&mut self, step: Step) {
+        log::info!("Kit check: {:?} -> {:?}", self.current_step, step);
+        self.current_step = step;
+    }
+
+    fn run_connect(
+        &mut self,
+        app_state: &mut GlobalAppState,
+        ui: &mut egui::Ui,
+    ) -> anyhow::Result<()> {
+        if self.devices.is_none() && self.devices_task.is_none() {
+            let drives = app_state.drives.clone();
+            self.devices_task = Some(Task::spawn(move || {
+                Ok(KitDevices {
+                    drives: drives
+                        .list()
+                        .context("Failed to get list of available drives.")?,
+                    serial_ports: list_serial_ports().unwrap_or_default(),
+                })
+            }));
+        }
+
+        if let Some(devices) = Task::poll(&mut self.devices_task)? {
+            self.devices = Some(devices);
+        }
+
+        column(ui, egui::Align::LEFT, |ui| {
+            heading(ui, tr!("kitcheck-connect"));
+            ui.label(
+                [
+                    tr!("kitcheck-step-card"),
+                    tr!("kitcheck-step-gizmo"),
+                    tr!("kitcheck-step-select"),
+                ]
+                .join("\n"),
+            );
+            if let Some(ref devices) = self.devices {
+                ui.label(tr!("kitcheck-card"));
+                if devices.drives.is_empty() {
+                    ui.label(tr!("drives-none"));
+                }
+                for drive in &devices.drives {
+                    ui.selectable_value(
+                        &mut self.selected_drive,
+                        Some(drive.clone()),
+                        drive.picker_label(),
+                    );
+                }
+                ui.label(tr!("kitcheck-gizmo"));
+                if devices.serial_ports.is_empty() {
+                    ui.label(tr!("kitcheck-no-gizmo"));
+                }
+                for port in &devices.serial_ports {
+                    ui.selectable_value(
+                        &mut self.selected_port,
+                        Some(port.clone()),
+                        format!("{}", port.display()),
+                    );
+                }
+                if ui.button(tr!("common-refresh")).clicked() {
+                    self.devices = None;
+                    self.selected_drive = None;
+                    self.selected_port = None;
+                }
+            } else {
+                ui.spinner();
+                ui.label(tr!("kitcheck-searching"));
+            }
+            stretch(ui);
+            let next_enabled = self.selected_drive.is_some() && self.selected_port.is_some();
+            if add_custom_next_button(ui, tr!("kitcheck-button"), next_enabled).clicked() {
+                self.set_step(Step::CheckKit);
+            }
+        });
+        Ok(())
+    }
+
+    fn run_check_kit(
+        &mut self,
+        _app_state: &mut GlobalAppState,
+        ui: &mut egui::Ui,
+    ) -> anyhow::Result<()> {
+        if self.report_task.is_none() {
+            let drive = self
+                .selected_drive
+                .clone()
+                .ok_or(anyhow!("Expected selected_drive to not be None."))?;
+            let port = self
+                .selected_port
+                .clone()
+                .ok_or(anyhow!("Expected selected_port to not be None."))?;
+            self.report_task = Some(Task::spawn(move || {
+                Ok(KitReport {
+                    card: read_card_manifest(&drive.drive_path).map_err(|e| format!("{e:#}")),
+                    firmware_version: query_firmware_version(&port).map_err(|e| format!("{e:#}")),
+                })
+            }));
+        }
+
+        if let Some(report) = Task::poll(&mut self.report_task)? {
+            play_cue(match report.problem() {
+                None => Cue::Success,
+                Some(_) => Cue::Failure,
+            });
+            self.report = Some(report);
+            self.set_step(Step::Verdict);
+        }
+
+        column(ui, egui::Align::Center, |ui| {
+            stretch(ui);
+            ui.spinner();
+            ui.label(tr!("kitcheck-checking"));
+            stretch(ui);
+        });
+        Ok(())
+    }
+
+    fn run_verdict(
+        &mut self,
+        _app_state: &mut GlobalAppState,
+        ui: &mut egui::Ui,
+    ) -> anyhow::Result<()> {
+        let report = self
+            .report
+            .as_ref()
+            .ok_or(anyhow!("Expected report to not be None."))?;
+        let mut check_another = false;
+        column(ui, egui::Align::LEFT, |ui| {
+            match report.problem() {
+                None => {
+                    heading(
+                        ui,
+                        egui::RichText::new(tr!("kitcheck-ok")).color(palette(ui).success),
+                    );
+                }
+                Some(problem) => {
+                    heading(
+                        ui,
+                        egui::RichText::new(tr!("kitcheck-not-ready")).color(palette(ui).error),
+                    );
+                    ui.colored_label(palette(ui).error, problem);
+                }
+            }
+            if let Ok(ref card) = report.card {
+                ui.label(tr!(
+                    "kitcheck-driver-station",
+                    version = &card.software_version,
+                    team = &card.team_number
+                ));
+            }
+            if let Ok(ref firmware_version) = report.firmware_version {
+                ui.label(tr!("kitcheck-firmware", version = firmware_version));
+            }
+            stretch(ui);
+            check_another = add_custom_next_button(ui, tr!("kitcheck-another"), true).clicked();
+        });
+        if check_another {
+            self.report = None;
+            self.devices = None;
+            self.selected_drive = None;
+            self.selected_port = None;
+            self.set_step(Step::Connect);
+        }
+        Ok(())
+    }
+}
+
+impl Page for KitCheckPage {
+    fn run(&mut self, app_state: &mut GlobalAppState, ui: &mut egui::Ui) -> anyhow::Result<()> {
+        match self.current_step {
+            Step::Connect => self.run_connect(app_state, ui).map_err(recoverable),
+            Step::CheckKit => self.run_check_kit(app_state, ui).map_err(recoverable),
+            Step::Verdict => self.run_verdict(app_state, ui),
+        }
+    }
+
+    fn get_title(&self) -> String {
+        tr!("kitcheck-title")
+    }
+
+    fn step_progress(&self) -> Option<StepProgress> {
+        StepProgress::from_steps(
+            &[Step::Connect, Step::CheckKit, Step::Verdict],
+            self.current_step,
+            |step| match step {
+                Step::Connect => tr!("step-connect"),
+                Step::CheckKit => tr!("step-check"),
+                Step::Verdict => tr!("step-result"),
+            },
+        )
+    }
+
+    fn retry_step(&mut self) {
+        self.devices_task = None;
+        self.report_task = None;
+        if self.current_step == Step::Connect {
+            self.devices = None;
+        }
+    }
+
+    fn handle_event(&mut self, _app_state: &mut GlobalAppState, event: &AppEvent) {
+        if let AppEvent::DriveAdded(_) | AppEvent::DriveRemoved(_) = event {
+            if let AppEvent::DriveRemoved(drive) = event
+                && self.selected_drive.as_ref() == Some(drive)
+            {
+                self.selected_drive = None;
+            }
+            if self.current_step == Step::Connect && self.devices_task.is_none() {
+                self.devices = None;
+            }
+        }
+    }
+}
@@ -0,0 +1,277 @@
+use crate::app::GlobalAppState;
+use crate::i18n::tr;
+use crate::pages::{Page, recoverable};
+use crate::settings::SoftwareRepo;
+use crate::theme::palette;
+use crate::utils::cache::format_size;
+use crate::utils::drive_management::{DriveManager, check_drive_safety, free_space_at};
+use crate::utils::platform::{check_drive_tools, check_required_commands};
+use crate::utils::tasks::Task;
+use crate::utils::work_dir::WorkDir;
+use crate::widgets::accessibility::heading;
+use anyhow::{Result, bail};
+use egui_alignments::{column, stretch};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::mpsc::Receiver;
+
+/// Space needed for a release download, its staged copy, and the firmware and starter code.
+const MIN_FREE_SPACE: u64 = 4 * 1000 * 1000 * 1000;
+
+/// One item on the checklist, with what was found or why it failed.
+struct CheckResult {
+    name: String,
+    outcome: Result<String, String>,
+}
+
+/// Runs one check, returning what was found or why the laptop isn't ready.
+type Check = fn(&CheckInputs) -> Result<String>;
+
+/// Everything the checks need from the app, gathered on the UI thread.
+struct CheckInputs {
+    offline_source: Option<PathBuf>,
+    driver_station_repo: SoftwareRepo,
+    download_cache_dir: PathBuf,
+    work_dir: WorkDir,
+    drives: Arc<dyn DriveManager>,
+}
+
+/// Walks through everything a fresh volunteer laptop needs before event day and gives a
+/// ready or not-ready verdict, so hubs find missing pieces while there is still time to fix them.
+pub struct LaptopCheckPage {
+    results: Vec<CheckResult>,
+
+    checks_task: Option<Task<()>>,
+    /// Receives each check's result as soon as it has run.
+    results_receiver: Option<Receiver<CheckResult>>,
+}
+
+impl LaptopCheckPage {
+    pub fn new() -> Self {
+        Self {
+            results: vec![],
+
+            checks_task: None,
+            results_receiver: None,
+        }
+    }
+
+    fn start_checks(&mut self, app_state: &GlobalAppState, ctx: &egui::Context) -> Result<()> {
+        let inputs = CheckInputs {
+            offline_source: app_state.offline_source.clone(),
+            driver_station_repo: app_state.sources.driver_station.clone(),
+            download_cache_dir: app_state.download_cache_dir(),
+            work_dir: app_state.work_dir("laptop-check")?,
+            drives: app_state.drives.clone(),
+        };
+        let ctx = ctx.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.results_receiver = Some(rx);
+        self.checks_task = Some(Task::spawn(move || {
+            let checks: [(String, Check); 6] = [
+                (tr!("laptop-commands"), check_commands),
+                (tr!("laptop-drive-tools"), check_drive_access),
+                (tr!("laptop-card-reader"), check_card_reader),
+                (tr!("laptop-release-source"), check_release_source),
+                (tr!("laptop-disk-space"), check_disk_space),
+                (tr!("laptop-self-tests"), check_self_tests),
+            ];
+            for (name, check) in checks {
+                let outcome = check(&inputs).map_err(|e| format!("{e:#}"));
+                log::info!("Laptop check {name}: {outcome:?}");
+                // The receiver is dropped if the user leaves the page, which stops the checks.
+                if tx.send(CheckResult { name, outcome }).is_err() {
+                    break;
+                }
+                ctx.request_repaint();
+            }
+            Ok(())
+        }));
+        Ok(())
+    }
+
+    fn run_checklist(&mut self, app_state: &mut GlobalAppState, ui: &mut egui::Ui) -> Result<()> {
+        if self.checks_task.is_none() && self.results.is_empty() {
+            self.start_checks(app_state, ui.ctx())?;
+        }
+
+        if let Some(ref receiver) = self.results_receiver {
+            self.results.extend(receiver.try_iter());
+        }
+        if Task::poll(&mut self.checks_task)?.is_some()
+            && let Some(receiver) = self.results_receiver.take()
+        {
+            self.results.extend(receiver.try_iter());
+        }
+        let finished = self.checks_task.is_none();
+
+        let mut run_again = false;
+        column(ui, egui::Align::LEFT, |ui| {
+            heading(ui, tr!("laptop-title"));
+            ui.label(tr!("laptop-intro"));
+            egui::Grid::new("laptop_check_grid")
+                .num_columns(2)
+                .striped(true)
+                .show(ui, |ui| {
+                    for result in &self.results {
+                        ui.label(&result.name);
+                        match result.outcome {
+                            Ok(ref details) => {
+                                ui.colored_label(palette(ui).success, details);
+                            }
+                            Err(ref problem) => {
+                                ui.colored_label(palette(ui).error, problem);
+                            }
+                        }
+                        ui.end_row();
+                    }
+                });
+            if finished {
+                let failures = self.results.iter().filter(|r| r.outcome.is_err()).count();
+                ui.separator();
+                if failures == 0 {
+                    heading(
+                        ui,
+                        egui::RichText::new(tr!("laptop-ready")).color(palette(ui).success),
+                    );
+                    ui.label(tr!("laptop-ready-details"));
+                } else {
+                    heading(
+                        ui,
+                        egui::RichText::new(tr!("laptop-not-ready")).color(palette(ui).error),
+                    );
+                    ui.label(tr!("laptop-fix-problems", count = failures));
+                }
+                if ui.button(tr!("laptop-copy")).clicked() {
+                    ui.ctx().copy_text(self.summary());
+                }
+            } else {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(tr!("laptop-checking"));
+                });
+            }
+            stretch(ui);
+            run_again = ui
+                .add_enabled(finished, egui::Button::new(tr!("laptop-run-again")))
+                .clicked();
+        });
+        if run_again {
+            self.results.clear();
+        }
+        Ok(())
+    }
+
+    /// Plain text version of the results for pasting into a hub's checklist or a support request.
+    fn summary(&self) -> String {
+        let ready = self.results.iter().all(|r| r.outcome.is_ok());
+        let mut lines = vec![format!(
+            "Laptop check ({}, wizard {}): {}",
+            std::env::consts::OS,
+            env!("CARGO_PKG_VERSION"),
+            if ready { "READY" } else { "NOT READY" }
+        )];
+        for result in &self.results {
+            lines.push(match result.outcome {
+                Ok(ref details) => format!("[ok] {}: {details}", result.name),
+                Err(ref problem) => format!("[FAIL] {}: {problem}", result.name),
+            });
+        }
+        lines.join("\n")
+    }
+}
+
+fn check_commands(_inputs: &CheckInputs) -> Result<String> {
+    let missing: Vec<&str> = check_required_commands()?
+        .into_iter()
+        .filter(|(_, available)| !available)
+        .map(|(command, _)| command)
+        .collect();
+    if !missing.is_empty() {
+        bail!(tr!(
+            "laptop-missing-commands",
+            commands = missing.join(", ")
+        ));
+    }
+    Ok(tr!("laptop-all-found"))
+}
+
+fn check_drive_access(_inputs: &CheckInputs) -> Result<String> {
+    check_drive_tools()?;
+    Ok(tr!("laptop-working"))
+}
+
+fn check_card_reader(inputs: &CheckInputs) -> Result<String> {
+    let cards: Vec<String> = inputs
+        .drives
+        .list()?
+        .into_iter()
+        .filter(|drive| !check_drive_safety(drive).is_suspicious())
+        .map(|drive| format!("{drive}"))
+        .collect();
+    if cards.is_empty() {
+        bail!(tr!("laptop-no-card"));
+    }
+    Ok(tr!("laptop-cards-found", cards = cards.join(", ")))
+}
+
+fn check_release_source(inputs: &CheckInputs) -> Result<String> {
+    if let Some(ref bundle_dir) = inputs.offline_source {
+        let repo = &inputs.driver_station_repo;
+        let releases =
+            crate::utils::offline::get_local_releases(bundle_dir, &repo.owner, &repo.name)?;
+        if releases.is_empty() {
+            bail!(tr!(
+                "laptop-bundle-empty",
+                path = bundle_dir.display().to_string()
+            ));
+        }
+        return Ok(tr!(
+            "laptop-bundle",
+            path = bundle_dir.display().to_string()
+        ));
+    }
+    if crate::events::is_network_available() {
+        return Ok(tr!("laptop-github-reachable"));
+    }
+    bail!(tr!("laptop-no-release-source"))
+}
+
+fn check_disk_space(inputs: &CheckInputs) -> Result<String> {
+    // The cache directory may not exist until the first download.
+    let existing_dir = inputs
+        .download_cache_dir
+        .ancestors()
+        .find(|dir| dir.is_dir())
+        .unwrap_or(Path::new("."));
+    let free_space = free_space_at(existing_dir)?;
+    if free_space < MIN_FREE_SPACE {
+        bail!(tr!(
+            "laptop-low-space",
+            free = format_size(free_space),
+            needed = format_size(MIN_FREE_SPACE)
+        ));
+    }
+    Ok(tr!("laptop-free-space", free = format_size(free_space)))
+}
+
+fn check_self_tests(inputs: &CheckInputs) -> Result<String> {
+    crate::utils::self_test::run_self_tests(inputs.work_dir.path())?;
+    Ok(tr!("laptop-passed"))
+}
+
+impl Page for LaptopCheckPage {
+    fn run(&mut self, app_state: &mut GlobalAppState, ui: &mut egui::Ui) -> anyhow::Result<()> {
+        self.run_checklist(app_state, ui).map_err(recoverable)
+    }
+
+    fn get_title(&self) -> String {
+        tr!("laptop-title")
+    }
+
+    fn retry_step(&mut self) {
+        self.checks_task = None;
+        self.results_receiver = None;
+        self.results.clear();
+    }
+}
@@ -0,0 +1,299 @@
+use crate::app::GlobalAppState;
+use crate::events::AppEvent;
+use crate::i18n::tr;
+use crate::pages::{Page, StepProgress, add_custom_next_button, add_next_button, recoverable};
+use crate::utils::drive_management::DriveInfo;
+use crate::utils::gizmo_config::{
+    FieldSettings, GIZMO_CONFIG_FILE, read_configured_team, read_field_settings,
+    update_field_settings,
+};
+use crate::utils::sounds::{Cue, play_cue};
+use crate::utils::tasks::Task;
+use crate::widgets::accessibility::heading;
+use crate::widgets::field_settings_editor::{add_field_settings_rows, show_field_settings_problem};
+use anyhow::{Context, anyhow};
+use egui_alignments::{column, stretch};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    ChooseCard,
+    EditSettings,
+    WriteConfig,
+    Finished,
+}
+
+/// A drive, with the driver station configuration found on it.
+#[derive(Debug, Clone)]
+struct CardCandidate {
+    drive: DriveInfo,
+    team_number: Option<u32>,
+    field_settings: Option<FieldSettings>,
+}
+
+/// Changes the network settings on a driver station card that was already set up, for when the
+/// field network changes after cards were handed out.
+pub struct NetworkConfigPage {
+    current_step: Step,
+    available_cards: Option<Vec<CardCandidate>>,
+    selected_card: Option<CardCandidate>,
+    field_settings: FieldSettings,
+    /// Set after the first card, so the same settings are written to the rest instead of each
+    /// card's own.
+    reuse_settings: bool,
+
+    card_list_task: Option<Task<Vec<CardCandidate>>>,
+    write_task: Option<Task<()>>,
+}
+
+impl NetworkConfigPage {
+    pub fn new() -> Self {
+        Self {
+            current_step: Step::ChooseCard,
+            available_cards: None,
+            selected_card: None,
+            field_settings: FieldSettings::default(),
+            reuse_settings: false,
+
+            card_list_task: None,
+            write_task: None,
+        }
+    }
+
+    fn set_step(&mut self, step: Step) {
+        log::info!("Network config: {:?} -> {:?}", self.current_step, step);
+        self.current_step = step;
+    }
+
+    fn run_choose_card(
+        &mut self,
+        app_state: &mut GlobalAppState,
+        ui: &mut egui::Ui,
+    ) -> anyhow::Result<()> {
+        if self.available_cards.is_none() && self.card_list_task.is_none() {
+            let drives = app_state.drives.clone();
+            self.card_list_task = Some(Task::spawn(move || {
+                let cards = drives
+                    .list()
+                    .context("Failed to get list of available drives.")?
+                    .into_iter()
+                    .map(|drive| CardCandidate {
+                        team_number: read_configured_team(&drive.drive_path),
+                        field_settings: read_field_settings(&drive.drive_path),
+                        drive,
+                    })
+                    .collect();
+                Ok(cards)
+            }));
+        }
+
+        if let Some(cards) = Task::poll(&mut self.card_list_task)? {
+            self.available_cards = Some(cards);
+        }
+
+        column(ui, egui::Align::LEFT, |ui| {
+            heading(ui, tr!("netconfig-choose-card"));
+            ui.label(tr!("netconfig-choose-card-intro"));
+            if let Some(ref cards) = self.available_cards {
+                if cards.is_empty() {
+                    ui.label(tr!("drives-none"));
+                }
+                for card in cards {
+                    let label = match card.team_number {
+                        Some(team_number) => {
+                            tr!(
+                                "netconfig-card-team",
+                                drive = card.drive.picker_label(),
+                                team = team_number
+                            )
+                        }
+                        None => card.drive.picker_label(),
+                    };
+                    let is_selected = self
+                        .selected_card
+                        .as_ref()
+                        .is_some_and(|selected| selected.drive == card.drive);
+                    let response = ui
+                        .add_enabled(
+                            card.field_settings.is_some(),
+                            egui::Button::selectable(is_selected, label),
+                        )
+                        .on_disabled_hover_text(tr!(
+                            "netconfig-no-config",
+                            file = GIZMO_CONFIG_FILE
+                        ));
+                    if response.clicked() {
+                        self.selected_card = Some(card.clone());
+                    }
+                }
+                if ui.button(tr!("common-refresh")).clicked() {
+                    self.available_cards = None;
+                    self.selected_card = None;
+                }
+            } else {
+                ui.spinner();
+                ui.label(tr!("drives-searching"));
+            }
+            stretch(ui);
+            if add_next_button(ui, self.selected_card.is_some()).clicked()
+                && let Some(ref card) = self.selected_card
+            {
+                if !self.reuse_settings {
+                    self.field_settings = card.field_settings.clone().unwrap_or_default();
+                }
+                self.set_step(Step::EditSettings);
+            }
+        });
+        Ok(())
+    }
+
+    fn run_edit_settings(
+        &mut self,
+        _app_state: &mut GlobalAppState,
+        ui: &mut egui::Ui,
+    ) -> anyhow::Result<()> {
+        column(ui, egui::Align::LEFT, |ui| {
+            heading(ui, tr!("netconfig-settings"));
+            if self.reuse_settings {
+                ui.label(tr!("netconfig-reused"));
+            } else {
+                ui.label(tr!("netconfig-current"));
+            }
+            egui::Grid::new("network_config_grid")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    add_field_settings_rows(ui, &mut self.field_settings)
+                });
+            let problem = self.field_settings.problem();
+            show_field_settings_problem(ui, &self.field_settings, problem.as_deref());
+            stretch(ui);
+            if add_custom_next_button(ui, tr!("network-write-settings"), problem.is_none())
+                .clicked()
+            {
+                self.set_step(Step::WriteConfig);
+            }
+        });
+        Ok(())
+    }
+
+    fn run_write_config(
+        &mut self,
+        app_state: &mut GlobalAppState,
+        ui: &mut egui::Ui,
+    ) -> anyhow::Result<()> {
+        if self.write_task.is_none() {
+            let drive = self
+                .selected_card
+                .as_ref()
+                .ok_or(anyhow!("Expected selected_card to not be None."))?
+                .drive
+                .clone();
+            let field_settings = self.field_settings.clone();
+            let drives = app_state.drives.clone();
+            self.write_task = Some(Task::spawn(move || {
+                update_field_settings(&drive.drive_path, &field_settings)
+                    .context("Failed to write network settings to the card.")?;
+                drives
+                    .flush(&drive)
+                    .context("Failed to flush network settings to the card.")
+            }));
+        }
+
+        if Task::poll(&mut self.write_task)?.is_some() {
+            play_cue(Cue::Success);
+            self.set_step(Step::Finished);
+        }
+
+        column(ui, egui::Align::Center, |ui| {
+            stretch(ui);
+            ui.spinner();
+            ui.label(tr!("network-writing-settings"));
+            stretch(ui);
+        });
+        Ok(())
+    }
+
+    fn run_finished(
+        &mut self,
+        _app_state: &mut GlobalAppState,
+        ui: &mut egui::Ui,
+    ) -> anyhow::Result<()> {
+        column(ui, egui::Align::LEFT, |ui| {
+            heading(ui, tr!("network-settings-written"));
+            let team_number = self
+                .selected_card
+                .as_ref()
+                .and_then(|card| card.team_number);
+            ui.label(match team_number {
+                Some(team_number) => tr!("netconfig-done-team", team = team_number),
+                None => tr!("netconfig-done"),
+            });
+            ui.label(tr!("netconfig-another-hint"));
+            stretch(ui);
+            if add_custom_next_button(ui, tr!("netconfig-another"), true).clicked() {
+                self.selected_card = None;
+                self.available_cards = None;
+                self.reuse_settings = true;
+                self.set_step(Step::ChooseCard);
+            }
+        });
+        Ok(())
+    }
+}
+
+impl Page for NetworkConfigPage {
+    fn run(&mut self, app_state: &mut GlobalAppState, ui: &mut egui::Ui) -> anyhow::Result<()> {
+        match self.current_step {
+            Step::ChooseCard => self.run_choose_card(app_state, ui).map_err(recoverable),
+            Step::EditSettings => self.run_edit_settings(app_state, ui),
+            Step::WriteConfig => self.run_write_config(app_state, ui).map_err(recoverable),
+            Step::Finished => self.run_finished(app_state, ui),
+        }
+    }
+
+    fn get_title(&self) -> String {
+        tr!("netconfig-title")
+    }
+
+    fn step_progress(&self) -> Option<StepProgress> {
+        StepProgress::from_steps(
+            &[
+                Step::ChooseCard,
+                Step::EditSettings,
+                Step::WriteConfig,
+                Step::Finished,
+            ],
+            self.current_step,
+            |step| match step {
+                Step::ChooseCard => tr!("step-card"),
+                Step::EditSettings => tr!("step-settings"),
+                Step::WriteConfig => tr!("step-write"),
+                Step::Finished => tr!("step-done"),
+            },
+        )
+    }
+
+    fn retry_step(&mut self) {
+        self.card_list_task = None;
+        self.write_task = None;
+        if self.current_step == Step::ChooseCard {
+            self.available_cards = None;
+        }
+    }
+
+    fn handle_event(&mut self, _app_state: &mut GlobalAppState, event: &AppEvent) {
+        if let AppEvent::DriveAdded(_) | AppEvent::DriveRemoved(_) = event {
+            if let AppEvent::DriveRemoved(drive) = event
+                && self
+                    .selected_card
+                    .as_ref()
+                    .is_some_and(|card| &card.drive == drive)
+                && self.current_step == Step::ChooseCard
+            {
+                self.selected_card = None;
+            }
+            if self.current_step == Step::ChooseCard && self.card_list_task.is_none() {
+                self.available_cards = None;
+            }
+        }
+    }
+}
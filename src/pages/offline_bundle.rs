@@ -0,0 +1,391 @@
+use crate::app::GlobalAppState;
+use crate::i18n::tr;
+use crate::pages::{Page, StepProgress, add_custom_next_button, add_next_button, recoverable};
+use crate::settings::{SoftwareRepo, SoftwareSources};
+use crate::theme::palette;
+use crate::utils::github::{GithubRelease, get_releases};
+use crate::utils::offline::{BundleItem, export_bundle};
+use crate::utils::release_manifest::RELEASE_MANIFEST_ASSET;
+use crate::utils::sounds::{Cue, play_cue};
+use crate::utils::tasks::Task;
+use crate::widgets::accessibility::heading;
+use anyhow::{Context, anyhow};
+use egui_alignments::{column, stretch};
+use egui_file_dialog::FileDialog;
+use std::sync::mpsc::Receiver;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    ChooseVersions,
+    ChooseDestination,
+    DownloadBundle,
+    Finished,
+}
+
+struct AvailableReleases {
+    driver_station: Vec<GithubRelease>,
+    firmware: Vec<GithubRelease>,
+    starter_code: Vec<GithubRelease>,
+    errors: Vec<String>,
+}
+
+struct BundleProgress {
+    finished: usize,
+    total: usize,
+    current_asset: String,
+}
+
+pub struct OfflineBundlePage {
+    current_step: Step,
+    available_releases: Option<AvailableReleases>,
+    driver_station_version: Option<GithubRelease>,
+    firmware_version: Option<GithubRelease>,
+    starter_code_version: Option<GithubRelease>,
+    destination: Option<std::path::PathBuf>,
+    progress: Option<BundleProgress>,
+
+    releases_task: Option<Task<AvailableReleases>>,
+    export_task: Option<Task<()>>,
+    progress_receiver: Option<Receiver<BundleProgress>>,
+
+    file_dialog: FileDialog,
+}
+
+impl OfflineBundlePage {
+    pub fn new() -> Self {
+        Self {
+            current_step: Step::ChooseVersions,
+            available_releases: None,
+            driver_station_version: None,
+            firmware_version: None,
+            starter_code_version: None,
+            destination: None,
+            progress: None,
+
+            releases_task: None,
+            export_task: None,
+            progress_receiver: None,
+
+            file_dialog: FileDialog::new().title(&tr!("dialog-bundle-folder")),
+        }
+    }
+
+    fn set_step(&mut self, step: Step) {
+        log::info!("Offline bundle: {:?} -> {:?}", self.current_step, step);
+        self.current_step = step;
+    }
+
+    fn run_choose_versions(
+        &mut self,
+        app_state: &mut GlobalAppState,
+        ui: &mut egui::Ui,
+    ) -> anyhow::Result<()> {
+        if self.available_releases.is_none() && self.releases_task.is_none() {
+            let sources = app_state.sources.clone();
+            let network = app_state.network();
+            self.releases_task = Some(Task::spawn(move || {
+                let mut errors = vec![];
+                let mut fetch = |repo: &SoftwareRepo| {
+                    get_releases(&network, &repo.owner, &repo.name).unwrap_or_else(|e| {
+                        errors.push(tr!(
+                            "bundle-fetch-failed",
+                            repo = repo.name.as_str(),
+                            error = format!("{e:#}")
+                        ));
+                        vec![]
+                    })
+                };
+                let driver_station = fetch(&sources.driver_station);
+                let firmware = fetch(&sources.firmware);
+                let starter_code = fetch(&sources.starter_code);
+                Ok(AvailableReleases {
+                    driver_station,
+                    firmware,
+                    starter_code,
+                    errors,
+                })
+            }));
+        }
+        if let Some(releases) = Task::poll(&mut self.releases_task)? {
+            let latest =
+                |releases: &Vec<GithubRelease>| releases.iter().find(|r| r.latest).cloned();
+            self.driver_station_version = latest(&releases.driver_station);
+            self.firmware_version = latest(&releases.firmware);
+            self.starter_code_version = latest(&releases.starter_code);
+            self.available_releases = Some(releases);
+        }
+
+        column(ui, egui::Align::LEFT, |ui| {
+            heading(ui, tr!("bundle-contents"));
+            ui.label(tr!("bundle-contents-intro"));
+            if let Some(ref releases) = self.available_releases {
+                add_release_picker(
+                    ui,
+                    &tr!("bundle-driver-station"),
+                    &releases.driver_station,
+                    &mut self.driver_station_version,
+                );
+                add_release_picker(
+                    ui,
+                    &tr!("bundle-system-firmware"),
+                    &releases.firmware,
+                    &mut self.firmware_version,
+                );
+                add_release_picker(
+                    ui,
+                    &tr!("bundle-student-program"),
+                    &releases.starter_code,
+                    &mut self.starter_code_version,
+                );
+                for error in &releases.errors {
+                    ui.colored_label(palette(ui).error, error);
+                }
+            } else {
+                ui.spinner();
+                ui.label(tr!("releases-fetching"));
+            }
+            stretch(ui);
+            let next_enabled = self.driver_station_version.is_some()
+                || self.firmware_version.is_some()
+                || self.starter_code_version.is_some();
+            if add_next_button(ui, next_enabled).clicked() {
+                self.set_step(Step::ChooseDestination);
+            }
+        });
+        Ok(())
+    }
+
+    fn run_choose_destination(
+        &mut self,
+        _app_state: &mut GlobalAppState,
+        ui: &mut egui::Ui,
+    ) -> anyhow::Result<()> {
+        self.file_dialog.update(ui.ctx());
+        if let Some(selected_dir) = self.file_dialog.take_picked() {
+            self.destination = Some(selected_dir);
+        }
+
+        column(ui, egui::Align::LEFT, |ui| {
+            heading(ui, tr!("bundle-location"));
+            ui.label(tr!("bundle-location-intro"));
+            if ui.button(tr!("bundle-choose-folder")).clicked() {
+                self.file_dialog.pick_directory();
+            }
+            match self.destination {
+                Some(ref destination) => ui.label(format!("{}", destination.display())),
+                None => ui.label(tr!("bundle-no-folder")),
+            };
+            stretch(ui);
+            if add_custom_next_button(ui, tr!("bundle-download"), self.destination.is_some())
+                .clicked()
+            {
+                self.set_step(Step::DownloadBundle);
+            }
+        });
+        Ok(())
+    }
+
+    fn bundle_items(&self, sources: &SoftwareSources) -> Vec<BundleItem> {
+        let mut items = vec![];
+        if let Some(ref release) = self.driver_station_version {
+            items.push(BundleItem {
+                repo_owner: sources.driver_station.owner.clone(),
+                repo_name: sources.driver_station.name.clone(),
+                release: release.clone(),
+                assets: release
+                    .assets
+                    .iter()
+                    .filter(|a| {
+                        // PDFs are included so documentation can still be copied onto cards.
+                        release
+                            .driver_station_asset()
+                            .is_some_and(|ds| ds.name == a.name)
+                            || a.name == RELEASE_MANIFEST_ASSET
+                            || a.name.to_lowercase().ends_with(".pdf")
+                    })
+                    .cloned()
+                    .collect(),
+            });
+        }
+        if let Some(ref release) = self.firmware_version {
+            items.push(BundleItem {
+                repo_owner: sources.firmware.owner.clone(),
+                repo_name: sources.firmware.name.clone(),
+                release: release.clone(),
+                assets: release
+                    .assets
+                    .iter()
+                    .filter(|a| a.name.starts_with("gss-") && a.name.ends_with(".uf2"))
+                    .cloned()
+                    .collect(),
+            });
+        }
+        if let Some(ref release) = self.starter_code_version {
+            items.push(BundleItem {
+                repo_owner: sources.starter_code.owner.clone(),
+                repo_name: sources.starter_code.name.clone(),
+                release: release.clone(),
+                assets: release
+                    .assets
+                    .iter()
+                    .filter(|a| a.name == "best_default_program.uf2")
+                    .cloned()
+                    .collect(),
+            });
+        }
+        items
+    }
+
+    fn run_download_bundle(
+        &mut self,
+        app_state: &mut GlobalAppState,
+        ui: &mut egui::Ui,
+    ) -> anyhow::Result<()> {
+        if self.export_task.is_none() {
+            let destination = self
+                .destination
+                .clone()
+                .ok_or(anyhow!("Expected destination to not be None."))?;
+            let items = self.bundle_items(&app_state.sources);
+            let network = app_state.network();
+            let ctx = ui.ctx().clone();
+            let (tx, rx) = std::sync::mpsc::channel();
+            self.progress_receiver = Some(rx);
+            self.export_task = Some(Task::spawn(move || {
+                export_bundle(
+                    &network,
+                    &items,
+                    &destination,
+                    |finished, total, current_asset| {
+                        // The receiver is dropped if the step is retried, which is fine.
+                        let _ = tx.send(BundleProgress {
+                            finished,
+                            total,
+                            current_asset: current_asset.to_string(),
+                        });
+                        ctx.request_repaint();
+                    },
+                )
+                .context("Failed to export offline bundle.")?;
+                Ok(())
+            }));
+        }
+
+        if let Some(receiver) = &self.progress_receiver {
+            while let Ok(progress) = receiver.try_recv() {
+                self.progress = Some(progress);
+            }
+        }
+
+        if Task::poll(&mut self.export_task)?.is_some() {
+            self.progress_receiver = None;
+            play_cue(Cue::Success);
+            self.set_step(Step::Finished);
+        }
+
+        column(ui, egui::Align::Center, |ui| {
+            stretch(ui);
+            match self.progress {
+                Some(ref progress) if progress.total > 0 => {
+                    ui.add(
+                        egui::ProgressBar::new(progress.finished as f32 / progress.total as f32)
+                            .show_percentage(),
+                    );
+                    ui.label(tr!(
+                        "bundle-downloading",
+                        asset = &progress.current_asset,
+                        number = progress.finished + 1,
+                        total = progress.total
+                    ));
+                }
+                _ => {
+                    ui.spinner();
+                    ui.label(tr!("bundle-preparing"));
+                }
+            }
+            stretch(ui);
+        });
+        Ok(())
+    }
+
+    fn run_finished(
+        &mut self,
+        _app_state: &mut GlobalAppState,
+        ui: &mut egui::Ui,
+    ) -> anyhow::Result<()> {
+        column(ui, egui::Align::LEFT, |ui| {
+            heading(ui, tr!("bundle-ready"));
+            if let Some(ref destination) = self.destination {
+                ui.label(tr!(
+                    "bundle-saved-to",
+                    path = destination.display().to_string()
+                ));
+            }
+            ui.label(tr!("bundle-copy-hint"));
+        });
+        Ok(())
+    }
+}
+
+fn add_release_picker(
+    ui: &mut egui::Ui,
+    label: &str,
+    releases: &[GithubRelease],
+    selected: &mut Option<GithubRelease>,
+) {
+    egui::ComboBox::from_label(label)
+        .selected_text(match selected {
+            Some(version) => version.display_name(),
+            None => tr!("bundle-do-not-include"),
+        })
+        .show_ui(ui, |ui| {
+            ui.selectable_value(selected, None, tr!("bundle-do-not-include"));
+            for release in releases {
+                ui.selectable_value(selected, Some(release.clone()), release.display_name());
+            }
+        });
+}
+
+impl Page for OfflineBundlePage {
+    fn run(&mut self, app_state: &mut GlobalAppState, ui: &mut egui::Ui) -> anyhow::Result<()> {
+        match self.current_step {
+            Step::ChooseVersions => self.run_choose_versions(app_state, ui).map_err(recoverable),
+            Step::ChooseDestination => self.run_choose_destination(app_state, ui),
+            Step::DownloadBundle => self.run_download_bundle(app_state, ui).map_err(recoverable),
+            Step::Finished => self.run_finished(app_state, ui),
+        }
+    }
+
+    fn get_title(&self) -> String {
+        tr!("bundle-title")
+    }
+
+    fn step_progress(&self) -> Option<StepProgress> {
+        StepProgress::from_steps(
+            &[
+                Step::ChooseVersions,
+                Step::ChooseDestination,
+                Step::DownloadBundle,
+                Step::Finished,
+            ],
+            self.current_step,
+            |step| match step {
+                Step::ChooseVersions => tr!("step-versions"),
+                Step::ChooseDestination => tr!("step-location"),
+                Step::DownloadBundle => tr!("step-download"),
+                Step::Finished => tr!("step-done"),
+            },
+        )
+    }
+
+    fn retry_step(&mut self) {
+        self.releases_task = None;
+        self.export_task = None;
+        self.progress_receiver = None;
+        match self.current_step {
+            Step::ChooseVersions => self.available_releases = None,
+            Step::DownloadBundle => self.progress = None,
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,950 @@
+use crate::app::GlobalAppState;
+use crate::i18n::tr;
+use crate::pages::Page;
+use crate::settings::{SoftwareSources, SoundVolume, TeamNumberRules};
+use crate::theme::{ThemeSetting, apply_theme, palette};
+use crate::utils::cache::{
+    CacheVerification, CachedVersion, cache_size, clear_cache, format_size, list_cached_versions,
+    persistent_cache_dir, remove_cached_version, verify_cached_version,
+};
+use crate::utils::demo::set_demo_mode;
+use crate::utils::drive_management::drive_manager;
+use crate::utils::file_download::MAX_DOWNLOAD_CONNECTIONS;
+use crate::utils::github::{AUTH_TOKEN_ENV_VAR, GithubAccess};
+use crate::utils::release_poller::MIN_POLL_INTERVAL_MINUTES;
+use crate::utils::session_report::verify_report;
+use crate::utils::signing::{generate_signing_key, load_signing_key, public_key_text};
+use crate::utils::sounds::{Cue, play_cue_with, set_sound_settings};
+use crate::utils::tasks::Task;
+use crate::widgets::accessibility::{MAX_ZOOM, MIN_ZOOM, heading, set_accessible_name};
+use egui_file_dialog::FileDialog;
+use std::collections::{HashMap, HashSet};
+use std::sync::mpsc::Receiver;
+
+/// Top of the download limit slider. Faster limits are rarely useful at venues.
+const MAX_DOWNLOAD_LIMIT_MBPS: u32 = 200;
+
+pub struct SettingsPage {
+    cache_size: Option<u64>,
+    cached_versions: Option<Vec<CachedVersion>>,
+    /// Keys of the cached versions ticked for deletion.
+    selected_versions: HashSet<String>,
+    verifications: HashMap<String, Result<CacheVerification, String>>,
+    status: Option<String>,
+    logo_dialog: FileDialog,
+    /// Public half of the hub's signing key. The outer `None` means it has not been loaded yet.
+    public_key: Option<Option<String>>,
+    verify_dialog: FileDialog,
+    status_export_dialog: FileDialog,
+    sound_dialog: FileDialog,
+    /// Which cue the sound dialog is choosing a file for.
+    picking_sound: Option<Cue>,
+    ca_bundle_dialog: FileDialog,
+    /// Why the network settings could not be used.
+    network_error: Option<String>,
+
+    verification_task: Option<Task<()>>,
+    verification_receiver: Option<Receiver<(String, Result<CacheVerification, String>)>>,
+}
+
+impl SettingsPage {
+    pub fn new() -> Self {
+        Self {
+            cache_size: None,
+            cached_versions: None,
+            selected_versions: HashSet::new(),
+            verifications: HashMap::new(),
+            status: None,
+            logo_dialog: FileDialog::new()
+                .title(&tr!("dialog-hub-logo"))
+                .add_file_filter_extensions(
+                    &tr!("file-images-filter"),
+                    vec!["png", "jpg", "jpeg", "svg"],
+                )
+                .default_file_filter(&tr!("file-images-filter")),
+            public_key: None,
+            verify_dialog: FileDialog::new()
+                .title(&tr!("dialog-session-report"))
+                .add_file_filter_extensions(&tr!("file-session-reports-filter"), vec!["json"])
+                .default_file_filter(&tr!("file-session-reports-filter")),
+            status_export_dialog: FileDialog::new().title(&tr!("dialog-dashboard-folder")),
+            sound_dialog: FileDialog::new()
+                .title(&tr!("dialog-sound"))
+                .add_file_filter_extensions(&tr!("file-wav-filter"), vec!["wav"])
+                .default_file_filter(&tr!("file-wav-filter")),
+            picking_sound: None,
+            ca_bundle_dialog: FileDialog::new()
+                .title(&tr!("dialog-ca-bundle"))
+                .add_file_filter_extensions(
+                    &tr!("file-certificates-filter"),
+                    vec!["pem", "crt", "cer"],
+                )
+                .default_file_filter(&tr!("file-certificates-filter")),
+            network_error: None,
+
+            verification_task: None,
+            verification_receiver: None,
+        }
+    }
+
+    fn add_cache_section(&mut self, app_state: &mut GlobalAppState, ui: &mut egui::Ui) {
+        heading(ui, tr!("settings-cache"));
+        let Some(cache_dir) = persistent_cache_dir() else {
+            ui.label(tr!("settings-no-cache-dir"));
+            return;
+        };
+
+        let mut use_persistent_cache = app_state.settings.use_persistent_cache;
+        if ui
+            .checkbox(&mut use_persistent_cache, tr!("settings-keep-downloads"))
+            .changed()
+        {
+            app_state.settings.use_persistent_cache = use_persistent_cache;
+            self.save_settings(app_state);
+        }
+
+        let size = *self
+            .cache_size
+            .get_or_insert_with(|| cache_size(&cache_dir));
+        ui.label(tr!(
+            "settings-cache-size",
+            size = format_size(size),
+            path = cache_dir.display().to_string()
+        ));
+        let pinned = &app_state.settings.pinned_cache_versions;
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(size > 0, egui::Button::new(tr!("settings-clear-cache")))
+                .clicked()
+            {
+                self.status = Some(match clear_cache(&cache_dir, pinned) {
+                    Ok(()) => tr!("settings-cache-cleared"),
+                    Err(e) => format!("{e}"),
+                });
+                self.refresh_cache();
+            }
+            if !pinned.is_empty() {
+                ui.label(tr!("settings-pinned-kept"));
+            }
+        });
+
+        self.add_cached_versions(app_state, &cache_dir, ui);
+    }
+
+    /// Lists the cached versions of each tool so they can be pinned, checked, or deleted.
+    fn add_cached_versions(
+        &mut self,
+        app_state: &mut GlobalAppState,
+        cache_dir: &std::path::Path,
+        ui: &mut egui::Ui,
+    ) {
+        let versions = self
+            .cached_versions
+            .get_or_insert_with(|| list_cached_versions(cache_dir));
+        if versions.is_empty() {
+            return;
+        }
+        let mut pins_changed = false;
+        let mut tools: Vec<&str> = versions.iter().map(|v| v.tool.as_str()).collect();
+        tools.dedup();
+        for tool in tools {
+            egui::CollapsingHeader::new(tool)
+                .default_open(true)
+                .show(ui, |ui| {
+                    egui::Grid::new(("cached_versions", tool))
+                        .num_columns(4)
+                        .show(ui, |ui| {
+                            for version in versions.iter().filter(|v| v.tool == tool) {
+                                let key = version.key();
+                                let pinned = &mut app_state.settings.pinned_cache_versions;
+                                let mut is_pinned = pinned.contains(&key);
+                                let mut selected = self.selected_versions.contains(&key);
+                                let response = ui
+                                    .add_enabled(
+                                        !is_pinned,
+                                        egui::Checkbox::new(&mut selected, &version.version),
+                                    )
+                                    .on_disabled_hover_text(tr!("settings-unpin-to-delete"));
+                                if response.changed() {
+                                    if selected {
+                                        self.selected_versions.insert(key.clone());
+                                    } else {
+                                        self.selected_versions.remove(&key);
+                                    }
+                                }
+                                ui.label(format_size(version.size));
+                                if ui
+                                    .checkbox(&mut is_pinned, tr!("settings-pinned"))
+                                    .changed()
+                                {
+                                    if is_pinned {
+                                        pinned.push(key.clone());
+                                        self.selected_versions.remove(&key);
+                                    } else {
+                                        pinned.retain(|k| *k != key);
+                                    }
+                                    pins_changed = true;
+                                }
+                                match self.verifications.get(&key) {
+                                    Some(Ok(verification)) => add_verification(ui, verification),
+                                    Some(Err(e)) => {
+                                        ui.colored_label(palette(ui).error, e);
+                                    }
+                                    None if self.verification_task.is_some() => {
+                                        ui.spinner();
+                                    }
+                                    None => {}
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+        }
+
+        let mut delete = false;
+        let mut verify = false;
+        ui.horizontal(|ui| {
+            delete = ui
+                .add_enabled(
+                    !self.selected_versions.is_empty(),
+                    egui::Button::new(tr!("settings-delete-selected")),
+                )
+                .clicked();
+            verify = ui
+                .add_enabled(
+                    self.verification_task.is_none(),
+                    egui::Button::new(tr!("settings-verify-checksums")),
+                )
+                .on_hover_text(tr!("settings-verify-checksums-hint"))
+                .clicked();
+        });
+        if pins_changed {
+            self.save_settings(app_state);
+        }
+        if delete {
+            self.delete_selected_versions();
+        }
+        if verify {
+            self.start_verification(ui.ctx());
+        }
+    }
+
+    fn delete_selected_versions(&mut self) {
+        let versions = self.cached_versions.take().unwrap_or_default();
+        let mut deleted = 0;
+        for version in versions
+            .iter()
+            .filter(|v| self.selected_versions.contains(&v.key()))
+        {
+            match remove_cached_version(version) {
+                Ok(()) => deleted += 1,
+                Err(e) => {
+                    self.status = Some(format!("{e:#}"));
+                    break;
+                }
+            }
+        }
+        if deleted > 0 {
+            log::info!("Deleted {deleted} version(s) from the download cache");
+        }
+        self.refresh_cache();
+    }
+
+    fn start_verification(&mut self, ctx: &egui::Context) {
+        let versions = self.cached_versions.clone().unwrap_or_default();
+        self.verifications.clear();
+        let ctx = ctx.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.verification_receiver = Some(rx);
+        self.verification_task = Some(Task::spawn(move || {
+            for version in versions {
+                let result = verify_cached_version(&version).map_err(|e| format!("{e:#}"));
+                // The receiver is dropped if the user leaves the page, which stops the check.
+                if tx.send((version.key(), result)).is_err() {
+                    break;
+                }
+                ctx.request_repaint();
+            }
+            Ok(())
+        }));
+    }
+
+    fn poll_verification(&mut self) -> anyhow::Result<()> {
+        if let Some(ref receiver) = self.verification_receiver {
+            self.verifications.extend(receiver.try_iter());
+        }
+        if Task::poll(&mut self.verification_task)?.is_some()
+            && let Some(receiver) = self.verification_receiver.take()
+        {
+            self.verifications.extend(receiver.try_iter());
+        }
+        Ok(())
+    }
+
+    fn refresh_cache(&mut self) {
+        self.cache_size = None;
+        self.cached_versions = None;
+        self.selected_versions.clear();
+    }
+
+    fn add_updates_section(&mut self, app_state: &mut GlobalAppState, ui: &mut egui::Ui) {
+        heading(ui, tr!("settings-notifications"));
+        ui.label(tr!("settings-notifications-intro"));
+        let mut interval = app_state.settings.release_poll_interval_minutes;
+        ui.horizontal(|ui| {
+            ui.label(tr!("settings-check-every"));
+            let response = ui.add(
+                egui::DragValue::new(&mut interval)
+                    .range(0..=24 * 60)
+                    .suffix(format!(" {}", tr!("settings-minutes-suffix"))),
+            );
+            ui.label(tr!("settings-turn-off-hint"));
+            if response.changed() {
+                if interval > 0 {
+                    interval = interval.max(MIN_POLL_INTERVAL_MINUTES);
+                }
+                app_state.settings.release_poll_interval_minutes = interval;
+                app_state
+                    .release_poll_interval
+                    .store(interval, std::sync::atomic::Ordering::Relaxed);
+                self.save_settings(app_state);
+            }
+        });
+    }
+
+    fn add_github_section(&mut self, app_state: &mut GlobalAppState, ui: &mut egui::Ui) {
+        heading(ui, tr!("settings-github"));
+        ui.label(tr!("settings-github-intro"));
+        ui.horizontal(|ui| {
+            ui.label(tr!("settings-access-token"));
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut app_state.settings.github_token).password(true),
+            );
+            if response.changed() {
+                self.apply_github_settings(app_state);
+            }
+        });
+        if app_state.settings.github_token.is_empty() && std::env::var(AUTH_TOKEN_ENV_VAR).is_ok() {
+            ui.label(tr!(
+                "settings-token-from-env",
+                variable = AUTH_TOKEN_ENV_VAR
+            ));
+        }
+        ui.horizontal(|ui| {
+            ui.label(tr!("settings-list-at-most"));
+            let response = ui.add(
+                egui::DragValue::new(&mut app_state.settings.max_releases)
+                    .range(0..=5000)
+                    .suffix(format!(" {}", tr!("settings-releases-suffix"))),
+            );
+            ui.label(tr!("settings-all-hint"))
+                .on_hover_text(tr!("settings-max-releases-hint"));
+            if response.changed() {
+                self.apply_github_settings(app_state);
+            }
+        });
+    }
+
+    fn add_network_section(&mut self, app_state: &mut GlobalAppState, ui: &mut egui::Ui) {
+        self.ca_bundle_dialog.update(ui.ctx());
+        if let Some(path) = self.ca_bundle_dialog.take_picked() {
+            app_state.settings.network.ca_bundle_path = Some(path);
+            self.apply_network_settings(app_state);
+        }
+
+        heading(ui, tr!("settings-network"));
+        ui.label(tr!("settings-network-intro"));
+        let mut changed = false;
+        let mut limits_changed = false;
+        egui::Grid::new("network").num_columns(2).show(ui, |ui| {
+            let network = &mut app_state.settings.network;
+            ui.label(tr!("settings-proxy"));
+            // Applied when editing finishes, so a half-typed URL isn't reported as invalid.
+            changed |= ui
+                .add(
+                    egui::TextEdit::singleline(&mut network.proxy_url)
+                        .hint_text(tr!("settings-proxy-hint")),
+                )
+                .lost_focus();
+            ui.end_row();
+            ui.label(tr!("settings-ca-bundle"));
+            ui.horizontal(|ui| {
+                if ui.button(tr!("settings-choose-pem")).clicked() {
+                    self.ca_bundle_dialog.pick_file();
+                }
+                if let Some(ref path) = network.ca_bundle_path {
+                    ui.label(format!("{}", path.display()));
+                    if ui.small_button(tr!("settings-remove")).clicked() {
+                        network.ca_bundle_path = None;
+                        changed = true;
+                    }
+                }
+            });
+            ui.end_row();
+            ui.label(tr!("settings-download-connections"));
+            let response = ui
+                .add(
+                    egui::DragValue::new(&mut network.download_connections)
+                        .range(1..=MAX_DOWNLOAD_CONNECTIONS),
+                )
+                .on_hover_text(tr!("settings-download-connections-hint"));
+            limits_changed |= response.changed();
+            ui.end_row();
+            ui.label(tr!("settings-download-limit"));
+            let response = ui
+                .add(
+                    egui::Slider::new(
+                        &mut network.download_limit_mbps,
+                        0..=MAX_DOWNLOAD_LIMIT_MBPS,
+                    )
+                    .custom_formatter(|mbps, _| match mbps as u32 {
+                        0 => tr!("settings-no-limit"),
+                        mbps => tr!("settings-mbps", mbps = mbps),
+                    }),
+                )
+                .on_hover_text(tr!("settings-download-limit-hint"));
+            limits_changed |= response.changed();
+            ui.end_row();
+        });
+        if limits_changed {
+            let network = &app_state.settings.network;
+            app_state.update_network(|n| {
+                n.download_connections = network.download_connections;
+                n.rate_limiter.set_limit(network.download_limit_mbps);
+            });
+            self.save_settings(app_state);
+        }
+        if changed {
+            self.apply_network_settings(app_state);
+        }
+        if let Some(ref error) = self.network_error {
+            ui.colored_label(palette(ui).error, error);
+        }
+    }
+
+    fn add_mirror_section(&mut self, app_state: &mut GlobalAppState, ui: &mut egui::Ui) {
+        heading(ui, tr!("settings-mirror"));
+        ui.label(tr!("settings-mirror-intro"));
+        let mirror = &mut app_state.settings.mirror;
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            ui.label(tr!("settings-mirror-url"));
+            changed |= ui
+                .add(
+                    egui::TextEdit::singleline(&mut mirror.url)
+                        .hint_text(tr!("settings-mirror-url-hint")),
+                )
+                .changed();
+        });
+        ui.add_enabled_ui(!mirror.url.trim().is_empty(), |ui| {
+            changed |= ui
+                .checkbox(&mut mirror.prefer_mirror, tr!("settings-prefer-mirror"))
+                .changed();
+        });
+        if changed {
+            self.apply_github_settings(app_state);
+        }
+    }
+
+    /// Lets advanced users install from forks. Edits are only used once they are applied, so
+    /// pages never look up a half-typed repository.
+    fn add_sources_section(&mut self, app_state: &mut GlobalAppState, ui: &mut egui::Ui) {
+        heading(ui, tr!("settings-sources"));
+        ui.label(tr!("settings-sources-intro"));
+        let sources = &mut app_state.settings.software_sources;
+        let mut changed = false;
+        egui::Grid::new("software_sources")
+            .num_columns(2)
+            .show(ui, |ui| {
+                for (label, repo) in [
+                    (tr!("start-driver-station"), &mut sources.driver_station),
+                    (tr!("start-system-firmware"), &mut sources.firmware),
+                    (tr!("start-student-program"), &mut sources.starter_code),
+                ] {
+                    ui.label(label);
+                    ui.horizontal(|ui| {
+                        changed |= ui
+                            .add(egui::TextEdit::singleline(&mut repo.owner).desired_width(140.0))
+                            .changed();
+                        ui.label("/");
+                        changed |= ui
+                            .add(egui::TextEdit::singleline(&mut repo.name).desired_width(160.0))
+                            .changed();
+                    });
+                    ui.end_row();
+                }
+            });
+        let problem = sources.problem();
+        if let Some(ref problem) = problem {
+            ui.colored_label(palette(ui).error, problem);
+        }
+        let mut apply = false;
+        ui.horizontal(|ui| {
+            apply = ui
+                .add_enabled(
+                    problem.is_none() && *sources != app_state.sources,
+                    egui::Button::new(tr!("settings-apply-sources")),
+                )
+                .clicked();
+            if ui.button(tr!("settings-reset-sources")).clicked() {
+                *sources = SoftwareSources::default();
+                changed = true;
+                apply = true;
+            }
+        });
+        ui.weak(tr!("settings-sources-restart"));
+        if apply {
+            log::info!("Installing from {:?}", sources);
+            app_state.sources = sources.clone();
+        }
+        if changed || apply {
+            self.save_settings(app_state);
+        }
+    }
+
+    fn apply_github_settings(&mut self, app_state: &GlobalAppState) {
+        app_state.update_network(|network| network.github = GithubAccess::new(&app_state.settings));
+        self.save_settings(app_state);
+    }
+
+    fn apply_network_settings(&mut self, app_state: &GlobalAppState) {
+        let mut result = Ok(());
+        app_state.update_network(|network| {
+            result = network.apply_network_settings(&app_state.settings.network);
+        });
+        self.network_error = result.err().map(|e| format!("{e:#}"));
+        self.save_settings(app_state);
+    }
+
+    fn add_branding_section(&mut self, app_state: &mut GlobalAppState, ui: &mut egui::Ui) {
+        self.logo_dialog.update(ui.ctx());
+        if let Some(logo_path) = self.logo_dialog.take_picked() {
+            app_state.settings.branding.logo_path = Some(logo_path);
+            self.save_settings(app_state);
+        }
+
+        heading(ui, tr!("settings-branding"));
+        ui.label(tr!("settings-branding-intro"));
+        let mut changed = false;
+        egui::Grid::new("branding").num_columns(2).show(ui, |ui| {
+            let branding = &mut app_state.settings.branding;
+            ui.label(tr!("settings-hub-name"));
+            changed |= ui.text_edit_singleline(&mut branding.hub_name).changed();
+            ui.end_row();
+            ui.label(tr!("settings-support-contact"));
+            changed |= ui
+                .text_edit_singleline(&mut branding.support_contact)
+                .changed();
+            ui.end_row();
+            ui.label(tr!("settings-logo"));
+            ui.horizontal(|ui| {
+                if ui.button(tr!("settings-choose-image")).clicked() {
+                    self.logo_dialog.pick_file();
+                }
+                if let Some(ref logo_path) = branding.logo_path {
+                    ui.label(format!("{}", logo_path.display()));
+                    if ui.small_button(tr!("settings-remove")).clicked() {
+                        branding.logo_path = None;
+                        changed = true;
+                    }
+                }
+            });
+            ui.end_row();
+        });
+        if changed {
+            self.save_settings(app_state);
+        }
+    }
+
+    fn add_cards_section(&mut self, app_state: &mut GlobalAppState, ui: &mut egui::Ui) {
+        heading(ui, tr!("settings-cards"));
+        let mut changed = ui
+            .checkbox(
+                &mut app_state.settings.copy_docs_to_cards,
+                tr!("settings-copy-docs"),
+            )
+            .changed();
+        ui.add_enabled_ui(app_state.settings.copy_docs_to_cards, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(tr!("settings-docs-url"));
+                changed |= ui
+                    .text_edit_singleline(&mut app_state.settings.docs_url)
+                    .changed();
+            });
+            ui.label(tr!("settings-docs-url-hint"));
+        });
+        if ui
+            .checkbox(
+                &mut app_state.settings.native_formatting,
+                tr!("settings-native-formatting"),
+            )
+            .on_hover_text(tr!("settings-native-formatting-hint"))
+            .changed()
+        {
+            app_state.drives = drive_manager(&app_state.settings);
+            changed = true;
+        }
+        ui.horizontal(|ui| {
+            ui.label(tr!("settings-write-warning"));
+            changed |= ui
+                .add(
+                    egui::DragValue::new(&mut app_state.settings.card_write_warning_threshold)
+                        .range(0..=10_000)
+                        .suffix(format!(" {}", tr!("settings-times-suffix"))),
+                )
+                .changed();
+            ui.label(tr!("settings-turn-off-hint"));
+        });
+        if changed {
+            self.save_settings(app_state);
+        }
+    }
+
+    fn add_team_numbers_section(&mut self, app_state: &mut GlobalAppState, ui: &mut egui::Ui) {
+        heading(ui, tr!("settings-team-numbers"));
+        ui.label(tr!("settings-team-numbers-intro"));
+        let rules = &mut app_state.settings.team_number_rules;
+        let mut changed = false;
+        egui::Grid::new("team_number_rules")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label(tr!("settings-team-digits"));
+                ui.horizontal(|ui| {
+                    changed |= ui
+                        .add(
+                            egui::DragValue::new(&mut rules.min_digits).range(1..=rules.max_digits),
+                        )
+                        .changed();
+                    ui.label(tr!("settings-to"));
+                    changed |= ui
+                        .add(
+                            egui::DragValue::new(&mut rules.max_digits).range(rules.min_digits..=9),
+                        )
+                        .changed();
+                });
+                ui.end_row();
+                ui.label(tr!("settings-team-range"));
+                ui.horizontal(|ui| {
+                    changed |= ui
+                        .add(egui::DragValue::new(&mut rules.lowest).range(0..=rules.highest))
+                        .changed();
+                    ui.label(tr!("settings-to"));
+                    changed |= ui
+                        .add(
+                            egui::DragValue::new(&mut rules.highest)
+                                .range(rules.lowest..=999_999_999),
+                        )
+                        .changed();
+                });
+                ui.end_row();
+            });
+        if ui.button(tr!("settings-reset-team-rules")).clicked() {
+            *rules = TeamNumberRules::default();
+            changed = true;
+        }
+        if changed {
+            self.save_settings(app_state);
+        }
+    }
+
+    fn add_sounds_section(&mut self, app_state: &mut GlobalAppState, ui: &mut egui::Ui) {
+        let sounds = &mut app_state.settings.sounds;
+        let mut changed = false;
+        self.sound_dialog.update(ui.ctx());
+        if let Some(path) = self.sound_dialog.take_picked()
+            && let Some(cue) = self.picking_sound.take()
+        {
+            match cue {
+                Cue::Success => sounds.success_sound = Some(path),
+                Cue::Failure => sounds.failure_sound = Some(path),
+            }
+            changed = true;
+        }
+
+        heading(ui, tr!("settings-sounds"));
+        ui.label(tr!("settings-sounds-intro"));
+        ui.horizontal(|ui| {
+            for (volume, name) in [
+                (SoundVolume::Off, tr!("settings-volume-off")),
+                (SoundVolume::Quiet, tr!("settings-volume-quiet")),
+                (SoundVolume::Loud, tr!("settings-volume-loud")),
+            ] {
+                changed |= ui.radio_value(&mut sounds.volume, volume, name).changed();
+            }
+        });
+        ui.add_enabled_ui(sounds.volume != SoundVolume::Off, |ui| {
+            egui::Grid::new("sounds").num_columns(2).show(ui, |ui| {
+                for (cue, name) in [
+                    (Cue::Success, tr!("settings-cue-success")),
+                    (Cue::Failure, tr!("settings-cue-failure")),
+                ] {
+                    ui.label(name);
+                    ui.horizontal(|ui| {
+                        let custom_sound = match cue {
+                            Cue::Success => &mut sounds.success_sound,
+                            Cue::Failure => &mut sounds.failure_sound,
+                        };
+                        match custom_sound {
+                            Some(path) => ui.label(format!("{}", path.display())),
+                            None => ui.label(tr!("settings-built-in-sound")),
+                        };
+                        if ui.small_button(tr!("settings-choose-wav")).clicked() {
+                            self.picking_sound = Some(cue);
+                            self.sound_dialog.pick_file();
+                        }
+                        if custom_sound.is_some()
+                            && ui.small_button(tr!("settings-use-built-in")).clicked()
+                        {
+                            *custom_sound = None;
+                            changed = true;
+                        }
+                        if ui.small_button(tr!("settings-test-sound")).clicked() {
+                            play_cue_with(sounds, cue);
+                        }
+                    });
+                    ui.end_row();
+                }
+            });
+            ui.label(tr!("settings-custom-sound-volume"));
+        });
+        if changed {
+            set_sound_settings(&app_state.settings.sounds);
+            self.save_settings(app_state);
+        }
+    }
+
+    fn add_student_section(&mut self, app_state: &mut GlobalAppState, ui: &mut egui::Ui) {
+        heading(ui, tr!("settings-student-program"));
+        if ui
+            .checkbox(
+                &mut app_state.settings.simple_student_mode,
+                tr!("settings-simple-mode"),
+            )
+            .changed()
+        {
+            self.save_settings(app_state);
+        }
+        ui.label(tr!("settings-simple-mode-hint"));
+    }
+
+    fn add_accessibility_section(&mut self, app_state: &mut GlobalAppState, ui: &mut egui::Ui) {
+        heading(ui, tr!("settings-accessibility"));
+        let zoom_factor = ui.ctx().zoom_factor();
+        ui.horizontal(|ui| {
+            ui.label(tr!(
+                "settings-zoom",
+                percent = format!("{:.0}", zoom_factor * 100.0)
+            ));
+            let zoom_out = ui.add_enabled(zoom_factor > MIN_ZOOM, egui::Button::new("-"));
+            set_accessible_name(&zoom_out, &tr!("settings-zoom-out"));
+            if zoom_out.clicked() {
+                ui.ctx().set_zoom_factor((zoom_factor - 0.1).max(MIN_ZOOM));
+            }
+            let zoom_in = ui.add_enabled(zoom_factor < MAX_ZOOM, egui::Button::new("+"));
+            set_accessible_name(&zoom_in, &tr!("settings-zoom-in"));
+            if zoom_in.clicked() {
+                ui.ctx().set_zoom_factor((zoom_factor + 0.1).min(MAX_ZOOM));
+            }
+            if ui.button(tr!("settings-zoom-reset")).clicked() {
+                ui.ctx().set_zoom_factor(1.0);
+            }
+        });
+        ui.label(tr!("settings-zoom-hint"));
+        ui.label(tr!("settings-keyboard-hint"));
+        ui.horizontal(|ui| {
+            ui.label(tr!("settings-theme"));
+            let mut changed = false;
+            for theme in ThemeSetting::ALL {
+                changed |= ui
+                    .radio_value(&mut app_state.settings.theme, theme, theme.label())
+                    .changed();
+            }
+            if changed {
+                apply_theme(ui.ctx(), app_state.settings.theme);
+                self.save_settings(app_state);
+            }
+        });
+        if ui
+            .checkbox(
+                &mut app_state.settings.large_touch_targets,
+                tr!("settings-large-buttons"),
+            )
+            .on_hover_text(tr!("settings-large-buttons-hint"))
+            .changed()
+        {
+            self.save_settings(app_state);
+        }
+    }
+
+    fn add_demo_section(&mut self, app_state: &mut GlobalAppState, ui: &mut egui::Ui) {
+        heading(ui, tr!("settings-demo"));
+        ui.label(tr!("settings-demo-intro"));
+        if app_state.dry_run {
+            ui.label(tr!("settings-demo-dry-run"));
+            return;
+        }
+        if ui
+            .checkbox(&mut app_state.settings.demo_mode, tr!("settings-demo-mode"))
+            .changed()
+        {
+            log::info!(
+                "Demonstration mode {}",
+                if app_state.settings.demo_mode {
+                    "on"
+                } else {
+                    "off"
+                }
+            );
+            set_demo_mode(app_state.settings.demo_mode);
+            app_state.drives = drive_manager(&app_state.settings);
+            self.save_settings(app_state);
+        }
+    }
+
+    fn add_reports_section(&mut self, app_state: &mut GlobalAppState, ui: &mut egui::Ui) {
+        self.verify_dialog.update(ui.ctx());
+        if let Some(report_path) = self.verify_dialog.take_picked() {
+            self.status = Some(match verify_report(&report_path) {
+                Ok(public_key) => tr!("settings-report-valid", key = public_key),
+                Err(e) => format!("{e:#}"),
+            });
+        }
+
+        heading(ui, tr!("settings-reports"));
+        ui.label(tr!("settings-reports-intro"));
+        if ui
+            .checkbox(
+                &mut app_state.settings.sign_session_reports,
+                tr!("settings-sign-reports"),
+            )
+            .changed()
+        {
+            self.save_settings(app_state);
+        }
+        let public_key = self.public_key.get_or_insert_with(|| {
+            load_signing_key()
+                .inspect_err(|e| log::warn!("Could not load signing key: {e:#}"))
+                .ok()
+                .flatten()
+                .map(|key| public_key_text(&key))
+        });
+        let mut generate = false;
+        ui.horizontal(|ui| match public_key {
+            Some(public_key) => {
+                ui.label(tr!("settings-public-key", key = &*public_key));
+                if ui.small_button(tr!("settings-copy")).clicked() {
+                    ui.ctx().copy_text(public_key.clone());
+                }
+                generate = ui.small_button(tr!("settings-replace-key")).clicked();
+            }
+            None => {
+                ui.label(tr!("settings-no-signing-key"));
+                generate = ui.small_button(tr!("settings-generate-key")).clicked();
+            }
+        });
+        if generate {
+            match generate_signing_key() {
+                Ok(key) => self.public_key = Some(Some(public_key_text(&key))),
+                Err(e) => self.status = Some(format!("{e:#}")),
+            }
+        }
+        if ui.button(tr!("settings-verify-report")).clicked() {
+            self.verify_dialog.pick_file();
+        }
+    }
+
+    fn add_dashboard_section(&mut self, app_state: &mut GlobalAppState, ui: &mut egui::Ui) {
+        self.status_export_dialog.update(ui.ctx());
+        if let Some(dir) = self.status_export_dialog.take_picked() {
+            app_state.settings.status_export_dir = Some(dir);
+            self.save_settings(app_state);
+        }
+
+        heading(ui, tr!("settings-dashboard"));
+        ui.label(tr!("settings-dashboard-intro"));
+        ui.horizontal(|ui| match app_state.settings.status_export_dir {
+            Some(ref dir) => {
+                ui.label(tr!(
+                    "settings-writing-status",
+                    path = dir.display().to_string()
+                ));
+                if ui.small_button(tr!("settings-stop")).clicked() {
+                    app_state.settings.status_export_dir = None;
+                    self.save_settings(app_state);
+                }
+            }
+            None => {
+                if ui.button(tr!("settings-choose-shared-folder")).clicked() {
+                    self.status_export_dialog.pick_directory();
+                }
+            }
+        });
+    }
+
+    fn save_settings(&mut self, app_state: &GlobalAppState) {
+        if let Err(e) = app_state.settings.save() {
+            self.status = Some(format!("{e}"));
+        }
+    }
+}
+
+fn add_verification(ui: &mut egui::Ui, verification: &CacheVerification) {
+    if !verification.problems.is_empty() {
+        ui.colored_label(palette(ui).error, verification.problems.join("\n"));
+    } else if verification.unrecorded > 0 {
+        ui.label(tr!(
+            "settings-files-ok-unrecorded",
+            verified = verification.verified,
+            unrecorded = verification.unrecorded
+        ));
+    } else {
+        ui.colored_label(
+            palette(ui).success,
+            tr!("settings-files-ok", verified = verification.verified),
+        );
+    }
+}
+
+impl Page for SettingsPage {
+    fn run(&mut self, app_state: &mut GlobalAppState, ui: &mut egui::Ui) -> anyhow::Result<()> {
+        self.poll_verification()?;
+        self.add_cache_section(app_state, ui);
+        ui.separator();
+        self.add_updates_section(app_state, ui);
+        ui.separator();
+        self.add_cards_section(app_state, ui);
+        ui.separator();
+        self.add_team_numbers_section(app_state, ui);
+        ui.separator();
+        self.add_sounds_section(app_state, ui);
+        ui.separator();
+        self.add_student_section(app_state, ui);
+        ui.separator();
+        self.add_github_section(app_state, ui);
+        ui.separator();
+        self.add_network_section(app_state, ui);
+        ui.separator();
+        self.add_mirror_section(app_state, ui);
+        ui.separator();
+        self.add_sources_section(app_state, ui);
+        ui.separator();
+        self.add_branding_section(app_state, ui);
+        ui.separator();
+        self.add_accessibility_section(app_state, ui);
+        ui.separator();
+        self.add_reports_section(app_state, ui);
+        ui.separator();
+        self.add_dashboard_section(app_state, ui);
+        ui.separator();
+        self.add_demo_section(app_state, ui);
+        if let Some(ref status) = self.status {
+            ui.separator();
+            ui.label(status);
+        }
+        Ok(())
+    }
+
+    fn get_title(&self) -> String {
+        tr!("settings-title")
+    }
+}
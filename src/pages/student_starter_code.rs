@@ -1,36 +1,103 @@
 use crate::app::GlobalAppState;
-use crate::pages::{Page, add_custom_next_button, add_next_button};
-use crate::utils::drive_management::{DriveInfo, list_drives};
+use crate::events::AppEvent;
+use crate::i18n::tr;
+use crate::pages::{Page, StepProgress, add_custom_next_button, add_next_button, recoverable};
+use crate::settings::SoftwareRepo;
+use crate::theme::palette;
+use crate::utils::circuitpython::{
+    CIRCUITPY_VOLUME_LABEL, CIRCUITPYTHON_VERSION, download_runtime, find_library_bundle,
+    install_library_bundle, wait_for_circuitpy,
+};
+use crate::utils::compatibility::{Component, preferred_release};
+use crate::utils::drive_management::{DriveInfo, is_rp2040_bootloader, is_uf2_bootloader};
 use crate::utils::github::{GithubRelease, download_versioned_asset};
-use crate::utils::threads::join_thread;
-use anyhow::anyhow;
+use crate::utils::github_artifacts::add_development_builds;
+use crate::utils::sounds::{Cue, play_cue};
+use crate::utils::tasks::Task;
+use crate::widgets::accessibility::heading;
+use crate::widgets::compatibility_warnings::show_compatibility_warnings;
+use crate::widgets::fetch_error_card::{FetchErrorAction, show_fetch_error_card};
+use crate::widgets::version_picker::show_version_picker;
+use anyhow::{Context, anyhow};
 use egui_alignments::{column, stretch};
 use egui_file_dialog::FileDialog;
-use std::sync::mpsc::Receiver;
 use std::time::Duration;
 
-enum Step {
-    ChooseVersion,
-    DownloadFirmware,
-    ChooseDrive,
-    InstallFirmware,
-    PostInstall,
+mod flow;
+
+use flow::{FlowState, Step};
+
+/// Release asset flashed onto boards that already run CircuitPython.
+const DEFAULT_PROGRAM_ASSET: &str = "best_default_program.uf2";
+/// Release asset copied onto CIRCUITPY when CircuitPython is installed first.
+const STARTER_CODE_ASSET: &str = "code.py";
+/// How long CircuitPython gets to boot and mount CIRCUITPY after flashing.
+const CIRCUITPY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Files fetched by the download step.
+struct Downloads {
+    firmware: std::path::PathBuf,
+    code: Option<std::path::PathBuf>,
+    library_bundle: Option<std::path::PathBuf>,
+}
+
+/// Returns true if this page can install something from the release.
+fn is_compatible_release(release: &GithubRelease) -> bool {
+    release
+        .assets
+        .iter()
+        .any(|a| a.name == DEFAULT_PROGRAM_ASSET || a.name == STARTER_CODE_ASSET)
+}
+
+/// Checks whether any starter code release has something this page can install, so the start
+/// page can disable the tile instead of leading teams to an empty version list.
+pub fn starter_code_release_available(
+    network: &crate::utils::http::Network,
+    offline_source: Option<&std::path::Path>,
+    repo: &SoftwareRepo,
+) -> anyhow::Result<bool> {
+    let releases = crate::utils::offline::get_releases_for_source(
+        network,
+        offline_source,
+        &repo.owner,
+        &repo.name,
+    )?;
+    Ok(releases.iter().any(is_compatible_release))
 }
 
 pub struct StudentStarterCodePage {
     current_step: Step,
+    /// Student-facing mode: always installs the latest release onto the first board found in
+    /// BOOTSEL mode, with no version or drive choices.
+    simplified: bool,
+    /// Major version to preselect, so a kit gets starter code from the same release family.
+    release_family: Option<u32>,
     available_releases: Option<Vec<GithubRelease>>,
+    releases_error: Option<String>,
     software_version: Option<GithubRelease>,
+    /// The .uf2 copied onto the bootloader drive. This is CircuitPython itself if
+    /// `install_runtime` is set, and the default program otherwise.
     firmware_path: Option<std::path::PathBuf>,
+    code_path: Option<std::path::PathBuf>,
+    library_bundle_path: Option<std::path::PathBuf>,
+    use_local_file: bool,
+    install_runtime: bool,
+    install_libraries: bool,
+    libraries_copied: Option<usize>,
     available_drives: Option<Vec<DriveInfo>>,
     selected_drive: Option<DriveInfo>,
+    /// The selected drive is not a UF2 bootloader, so the install is waiting on the user.
+    drive_refused: bool,
+    allow_non_bootloader_drive: bool,
 
-    available_releases_receiver: Option<Receiver<Vec<GithubRelease>>>,
-    download_finished_receiver: Option<Receiver<std::path::PathBuf>>,
-    drive_list_receiver: Option<Receiver<Vec<DriveInfo>>>,
-    install_finished_receiver: Option<Receiver<()>>,
+    releases_task: Option<Task<anyhow::Result<Vec<GithubRelease>>>>,
+    download_task: Option<Task<Downloads>>,
+    drive_list_task: Option<Task<Vec<DriveInfo>>>,
+    /// Gives false if the install was refused because the drive is not a UF2 bootloader.
+    install_task: Option<Task<bool>>,
+    code_task: Option<Task<()>>,
+    libraries_task: Option<Task<usize>>,
 
-    background_thread: Option<std::thread::JoinHandle<()>>,
     file_dialog: FileDialog,
 }
 
@@ -38,150 +105,330 @@ impl StudentStarterCodePage {
     pub fn new() -> Self {
         Self {
             current_step: Step::ChooseVersion,
+            simplified: false,
+            release_family: None,
             available_releases: None,
+            releases_error: None,
             software_version: None,
             firmware_path: None,
+            code_path: None,
+            library_bundle_path: None,
+            use_local_file: false,
+            install_runtime: false,
+            install_libraries: true,
+            libraries_copied: None,
             available_drives: None,
             selected_drive: None,
+            drive_refused: false,
+            allow_non_bootloader_drive: false,
 
-            available_releases_receiver: None,
-            download_finished_receiver: None,
-            drive_list_receiver: None,
-            install_finished_receiver: None,
+            releases_task: None,
+            download_task: None,
+            drive_list_task: None,
+            install_task: None,
+            code_task: None,
+            libraries_task: None,
 
-            background_thread: None,
             file_dialog: FileDialog::new()
-                .add_file_filter_extensions("UF2 Firmware", vec!["uf2"])
-                .default_file_filter("UF2 Firmware"),
+                .add_file_filter_extensions(&tr!("file-uf2-filter"), vec!["uf2"])
+                .default_file_filter(&tr!("file-uf2-filter")),
         }
     }
 
+    pub fn simplified() -> Self {
+        Self {
+            simplified: true,
+            ..Self::new()
+        }
+    }
+
+    /// Preselects the newest release from the same family as the rest of a kit.
+    pub fn with_release_family(release_family: Option<u32>) -> Self {
+        Self {
+            release_family,
+            ..Self::new()
+        }
+    }
+
+    /// Whether a device has been set up, for flows that continue to another page.
+    pub fn is_finished(&self) -> bool {
+        self.current_step == Step::PostInstall
+    }
+
+    fn flow_state(&self) -> FlowState {
+        FlowState {
+            use_local_file: self.use_local_file,
+            choose_other_drive: self.drive_refused,
+            install_runtime: self.install_runtime && !self.use_local_file,
+            install_libraries: self.library_bundle_path.is_some() && !self.use_local_file,
+        }
+    }
+
+    fn advance(&mut self) {
+        let next = self.current_step.next(&self.flow_state());
+        log::info!(
+            "Student starter code: {:?} -> {:?}",
+            self.current_step,
+            next
+        );
+        if next == Step::PostInstall {
+            play_cue(Cue::Success);
+        }
+        self.current_step = next;
+    }
+
     fn run_choose_version(
         &mut self,
-        _app_state: &mut GlobalAppState,
+        app_state: &mut GlobalAppState,
         ui: &mut egui::Ui,
     ) -> anyhow::Result<()> {
-        if self.available_releases.is_none() && self.background_thread.is_none() {
-            let (tx, rx) = std::sync::mpsc::channel();
-            self.available_releases_receiver = Some(rx);
-            self.background_thread = Some(std::thread::spawn(move || {
-                let releases =
-                    crate::utils::github::get_releases("gizmo-platform", "CircuitPython_Gizmo")
-                        .unwrap_or(Vec::new());
-                tx.send(releases)
-                    .expect("Failed to send releases to main thread.");
+        if self.available_releases.is_none() && self.releases_task.is_none() {
+            let offline_source = app_state.offline_source.clone();
+            let repo = app_state.sources.starter_code.clone();
+            let channel = app_state.release_channel;
+            let network = app_state.network();
+            self.releases_task = Some(Task::spawn(move || {
+                // Failing to fetch releases is shown on the page rather than as an error.
+                Ok(crate::utils::offline::get_releases_for_source(
+                    &network,
+                    offline_source.as_deref(),
+                    &repo.owner,
+                    &repo.name,
+                )
+                .map(|mut releases| {
+                    add_development_builds(
+                        &network,
+                        &mut releases,
+                        channel,
+                        offline_source.as_deref(),
+                        &repo,
+                    );
+                    releases
+                }))
             }));
         }
-        if let Some(thread) = self.background_thread.take_if(|t| t.is_finished()) {
-            join_thread(thread)?;
-            let receiver = self.available_releases_receiver.take().ok_or(anyhow!(
-                "Expected available_releases_receiver to not be None."
-            ))?;
-            self.available_releases = Some(receiver.recv_timeout(Duration::from_secs(1))?);
-        }
-        if let Some(releases) = self.available_releases.as_ref() {
-            if !releases.is_empty() && self.software_version.is_none() {
-                self.software_version = Some(
-                    releases
-                        .iter()
-                        .find(|r| r.latest)
-                        .ok_or(anyhow!("Latest release not found"))?
-                        .clone(),
-                );
+        if let Some(releases) = Task::poll(&mut self.releases_task)? {
+            match releases {
+                Ok(releases) => {
+                    self.releases_error = None;
+                    self.available_releases = Some(releases);
+                }
+                Err(e) => {
+                    self.releases_error =
+                        Some(tr!("releases-fetch-failed", error = format!("{e:#}")));
+                    self.available_releases = Some(vec![]);
+                }
             }
         }
+        if let Some(releases) = self.available_releases.as_ref()
+            && !releases.is_empty()
+            && self.software_version.is_none()
+        {
+            self.software_version = Some(
+                preferred_release(releases, self.release_family)
+                    .ok_or(anyhow!("Latest release not found"))?
+                    .clone(),
+            );
+        }
+        if self.simplified {
+            self.show_simple_release_status(ui);
+            return Ok(());
+        }
         let next_button_enabled = self.software_version.is_some();
 
         self.file_dialog.update(ui.ctx());
 
         if let Some(selected_file) = self.file_dialog.take_picked() {
             self.firmware_path = Some(selected_file);
-            self.current_step = Step::ChooseDrive;
+            self.use_local_file = true;
+            self.advance();
         }
 
+        let can_work_offline =
+            app_state.offline_source.is_none() && app_state.download_cache_dir().is_dir();
+        let mut fetch_error_action = None;
+        let mut compatible = true;
+        let mut channel_changed = false;
         column(ui, egui::Align::LEFT, |ui| {
-            ui.heading("Software Version");
-            ui.label("Select the version of the starter code you want to install. Usually, this should be the latest version.");
+            heading(ui, tr!("releases-software-version"));
+            ui.label(tr!("code-version-intro"));
             if let Some(ref releases) = self.available_releases {
-                egui::ComboBox::from_label("Pick a version")
-                    .selected_text(match self.software_version {
-                        Some(ref version) => version.display_name(),
-                        None => "Select Version".to_string(),
-                    })
-                    .show_ui(ui, |ui| {
-                        for release in releases {
-                            ui.selectable_value(
-                                &mut self.software_version,
-                                Some(release.clone()),
-                                release.display_name(),
-                            );
-                        }
-                    });
+                channel_changed = show_version_picker(
+                    ui,
+                    releases,
+                    &mut self.software_version,
+                    &mut app_state.release_channel,
+                );
+                compatible = show_compatibility_warnings(
+                    ui,
+                    &app_state.compatibility,
+                    Component::StarterCode,
+                    self.software_version.as_ref(),
+                );
             } else {
                 ui.spinner();
-                ui.label("Fetching available releases...");
+                ui.label(tr!("releases-fetching"));
             }
-            if ui.link("Use local file instead").clicked() {
+            if let Some(ref error) = self.releases_error {
+                fetch_error_action = show_fetch_error_card(ui, error, can_work_offline);
+            }
+            ui.checkbox(
+                &mut self.install_runtime,
+                tr!("code-install-runtime", version = CIRCUITPYTHON_VERSION),
+            );
+            ui.label(tr!("code-install-runtime-hint"));
+            ui.checkbox(&mut self.install_libraries, tr!("code-install-libraries"));
+            if ui.link(tr!("releases-use-local-file")).clicked() {
                 self.file_dialog.pick_file();
             }
             stretch(ui);
-            if add_next_button(ui, next_button_enabled).clicked() {
-                self.current_step = Step::DownloadFirmware;
+            if add_next_button(ui, next_button_enabled && compatible).clicked() {
+                self.advance();
             }
         });
+        if channel_changed {
+            self.available_releases = None;
+        }
+        match fetch_error_action {
+            Some(FetchErrorAction::Retry) => self.available_releases = None,
+            Some(FetchErrorAction::WorkOffline) => {
+                let cache_dir = app_state.download_cache_dir();
+                log::info!("Working offline from {:?}", cache_dir);
+                app_state.offline_source = Some(cache_dir);
+                self.available_releases = None;
+            }
+            None => {}
+        }
         Ok(())
     }
 
+    /// Simplified mode skips the version picker and moves on as soon as the latest release is
+    /// known.
+    fn show_simple_release_status(&mut self, ui: &mut egui::Ui) {
+        if self.software_version.is_some() {
+            self.advance();
+            return;
+        }
+        let no_release = self.releases_error.is_some()
+            || self
+                .available_releases
+                .as_ref()
+                .is_some_and(|r| r.is_empty());
+        let mut retry = false;
+        column(ui, egui::Align::Center, |ui| {
+            stretch(ui);
+            if no_release {
+                ui.label(tr!("code-download-failed"));
+                retry = ui.button(tr!("common-try-again")).clicked();
+            } else {
+                ui.spinner();
+                ui.label(tr!("code-getting-latest"));
+            }
+            stretch(ui);
+        });
+        if retry {
+            self.available_releases = None;
+        }
+    }
+
     fn run_download_firmware(
         &mut self,
         app_state: &mut GlobalAppState,
         ui: &mut egui::Ui,
     ) -> anyhow::Result<()> {
-        if self.firmware_path.is_none() && self.background_thread.is_none() {
+        if self.firmware_path.is_none() && self.download_task.is_none() {
             let release = self
                 .software_version
                 .clone()
                 .ok_or(anyhow!("Expected software_version to not be None"))?;
-            let asset_name = "best_default_program.uf2";
-            let firmware_asset = release
+            let asset_name = if self.install_runtime {
+                STARTER_CODE_ASSET
+            } else {
+                DEFAULT_PROGRAM_ASSET
+            };
+            let asset = release
                 .assets
                 .iter()
                 .find(|a| a.name == asset_name)
                 .ok_or(anyhow!("Could not find {asset_name} in release assets."))?
                 .clone();
-            let cache_path = app_state.tmp_dir.path().join("github_downloads");
-            let (tx, rx) = std::sync::mpsc::channel();
-            self.download_finished_receiver = Some(rx);
-            self.background_thread = Some(std::thread::spawn(move || {
+            let library_bundle_asset = if self.install_libraries {
+                let asset = find_library_bundle(&release.assets).cloned();
+                if asset.is_none() {
+                    log::warn!(
+                        "No library bundle found in {}, skipping libraries",
+                        release.tag_name
+                    );
+                }
+                asset
+            } else {
+                None
+            };
+            let install_runtime = self.install_runtime;
+            let cache_path = app_state.download_cache_dir();
+            let offline_source = app_state.offline_source.clone();
+            let repo = app_state.sources.starter_code.clone();
+            let events = app_state.events.clone();
+            let network = app_state.network();
+            self.download_task = Some(Task::spawn(move || {
                 let download_path = download_versioned_asset(
-                    &firmware_asset,
-                    "gizmo-platform",
-                    "CircuitPython_Gizmo",
+                    &network,
+                    &asset,
+                    &repo.owner,
+                    &repo.name,
                     &release,
                     &cache_path,
                 )
-                .expect("Failed to download asset from GitHub.");
-                tx.send(download_path)
-                    .expect("Failed to send download path to main thread.");
+                .context("Failed to download asset from GitHub.")?;
+                events.publish(AppEvent::DownloadFinished(download_path.clone()));
+                let mut downloads = if install_runtime {
+                    let runtime_path =
+                        download_runtime(&network, &cache_path, offline_source.as_deref())
+                            .context("Failed to download CircuitPython.")?;
+                    events.publish(AppEvent::DownloadFinished(runtime_path.clone()));
+                    Downloads {
+                        firmware: runtime_path,
+                        code: Some(download_path),
+                        library_bundle: None,
+                    }
+                } else {
+                    Downloads {
+                        firmware: download_path,
+                        code: None,
+                        library_bundle: None,
+                    }
+                };
+                if let Some(asset) = library_bundle_asset {
+                    let bundle_path = download_versioned_asset(
+                        &network,
+                        &asset,
+                        &repo.owner,
+                        &repo.name,
+                        &release,
+                        &cache_path,
+                    )
+                    .context("Failed to download library bundle from GitHub.")?;
+                    events.publish(AppEvent::DownloadFinished(bundle_path.clone()));
+                    downloads.library_bundle = Some(bundle_path);
+                }
+                Ok(downloads)
             }));
         }
 
-        if let Some(thread) = self.background_thread.take_if(|t| t.is_finished()) {
-            join_thread(thread)?;
-            let receiver = self.download_finished_receiver.take().ok_or(anyhow!(
-                "Expected download_finished_receiver to not be None."
-            ))?;
-            self.firmware_path = Some(receiver.recv_timeout(Duration::from_secs(1))?);
+        if let Some(downloads) = Task::poll(&mut self.download_task)? {
+            self.firmware_path = Some(downloads.firmware);
+            self.code_path = downloads.code;
+            self.library_bundle_path = downloads.library_bundle;
         }
 
         if self.firmware_path.is_some() {
-            self.current_step = Step::ChooseDrive;
+            self.advance();
         }
 
         column(ui, egui::Align::Center, |ui| {
             stretch(ui);
             ui.spinner();
-            ui.label("Downloading starter program file...");
+            ui.label(tr!("code-downloading"));
             stretch(ui);
         });
         Ok(())
@@ -189,77 +436,115 @@ impl StudentStarterCodePage {
 
     fn run_choose_drive(
         &mut self,
-        _app_state: &mut GlobalAppState,
+        app_state: &mut GlobalAppState,
         ui: &mut egui::Ui,
     ) -> anyhow::Result<()> {
-        if self.available_drives.is_none() && self.background_thread.is_none() {
-            let (tx, rx) = std::sync::mpsc::channel();
-            self.drive_list_receiver = Some(rx);
-            self.background_thread = Some(std::thread::spawn(move || {
-                let drives = list_drives().expect("Failed to get list of available drives.");
-                tx.send(drives)
-                    .expect("Failed to send drive list to main thread.");
+        if self.available_drives.is_none() && self.drive_list_task.is_none() {
+            let drives = app_state.drives.clone();
+            self.drive_list_task = Some(Task::spawn(move || {
+                drives
+                    .list()
+                    .context("Failed to get list of available drives.")
             }));
         }
 
-        if let Some(thread) = self.background_thread.take_if(|t| t.is_finished()) {
-            join_thread(thread)?;
-            let receiver = self
-                .drive_list_receiver
-                .take()
-                .ok_or(anyhow!("Expected drive_list_receiver to not be None."))?;
-            self.available_drives = Some(receiver.recv_timeout(Duration::from_secs(1))?);
+        if let Some(drives) = Task::poll(&mut self.drive_list_task)? {
+            if self.simplified {
+                self.selected_drive = drives.iter().find(|d| is_rp2040_bootloader(d)).cloned();
+            }
+            self.available_drives = Some(drives);
+        }
+
+        if self.simplified {
+            self.show_simple_choose_drive(ui);
+            return Ok(());
         }
 
         column(ui, egui::Align::LEFT, |ui| {
-            ui.heading("Choose Device");
+            heading(ui, tr!("drives-choose-device"));
             ui.label(
-                r#"1. Press and hold the BOOTSEL button on the student processor.
-2. Connect the student processor to your computer with the USB cable.
-3. Release the BOOTSEL button.
-4. Click the "Refresh" button to update the list below.
-5. Select the drive from the list and click "Install Program". The drive should be named "RPI-RP2".
-"#,
+                [
+                    tr!("code-step-bootsel"),
+                    tr!("code-step-connect"),
+                    tr!("code-step-release"),
+                    tr!("code-step-refresh"),
+                    tr!("code-step-select"),
+                ]
+                .join("\n"),
             );
             if let Some(ref drives) = self.available_drives {
                 if drives.is_empty() {
-                    ui.label("No removable drives found.");
+                    ui.label(tr!("drives-none"));
                 } else {
                     for drive in drives {
                         ui.selectable_value(
                             &mut self.selected_drive,
                             Some(drive.clone()),
-                            format!("{drive}"),
+                            drive.picker_label(),
                         );
                     }
                 }
 
-                if ui.button("Refresh").clicked() {
+                if ui.button(tr!("common-refresh")).clicked() {
                     self.available_drives = None;
                     self.selected_drive = None;
                 }
             } else {
                 ui.spinner();
-                ui.label("Searching for removable drives...");
+                ui.label(tr!("drives-searching"));
             }
             stretch(ui);
-            if add_custom_next_button(ui, "Install Program", self.selected_drive.is_some())
-                .clicked()
+            if add_custom_next_button(
+                ui,
+                tr!("code-install-program"),
+                self.selected_drive.is_some(),
+            )
+            .clicked()
             {
-                self.current_step = Step::InstallFirmware;
+                self.advance();
             }
         });
         Ok(())
     }
 
+    /// Waits for a board in BOOTSEL mode. The drive list is refreshed whenever a drive is plugged
+    /// in, so students only need to plug in the board and click Install.
+    fn show_simple_choose_drive(&mut self, ui: &mut egui::Ui) {
+        let mut install = false;
+        column(ui, egui::Align::LEFT, |ui| {
+            heading(ui, tr!("code-plug-in-heading"));
+            ui.label(
+                [
+                    tr!("code-simple-step-hold"),
+                    tr!("code-simple-step-plug"),
+                    tr!("code-simple-step-release"),
+                ]
+                .join("\n"),
+            );
+            if self.selected_drive.is_some() {
+                ui.colored_label(palette(ui).success, tr!("code-board-found"));
+            } else {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(tr!("code-looking-for-board"));
+                });
+            }
+            stretch(ui);
+            install =
+                add_custom_next_button(ui, tr!("code-install"), self.selected_drive.is_some())
+                    .clicked();
+        });
+        if install {
+            self.advance();
+        }
+    }
+
     fn run_install_firmware(
         &mut self,
         _app_state: &mut GlobalAppState,
         ui: &mut egui::Ui,
     ) -> anyhow::Result<()> {
-        if self.install_finished_receiver.is_none() {
-            let (tx, rx) = std::sync::mpsc::channel();
-            self.install_finished_receiver = Some(rx);
+        if self.install_task.is_none() && !self.drive_refused {
             let firmware_path = self
                 .firmware_path
                 .clone()
@@ -268,30 +553,153 @@ impl StudentStarterCodePage {
                 .selected_drive
                 .clone()
                 .ok_or(anyhow!("Expected selected_drive to not be None."))?;
-            self.background_thread = Some(std::thread::spawn(move || {
+            let allow_non_bootloader_drive = self.allow_non_bootloader_drive;
+            self.install_task = Some(Task::spawn(move || {
+                if !allow_non_bootloader_drive && !is_uf2_bootloader(&drive) {
+                    return Ok(false);
+                }
                 let filename = firmware_path
                     .file_name()
-                    .expect("Could not get filename from firmware path.")
+                    .context("Could not get filename from firmware path.")?
                     .to_str()
-                    .expect("Could not convert filename to string.");
+                    .context("Could not convert filename to string.")?;
                 let destination = drive.drive_path.join(filename);
-                std::fs::copy(firmware_path, destination)
-                    .expect("Failed to copy firmware to drive.");
-                tx.send(())
-                    .expect("Failed to signal install done to main thread.");
+                std::fs::copy(&firmware_path, destination)
+                    .context("Failed to copy firmware to drive.")?;
+                Ok(true)
+            }));
+        }
+
+        if let Some(installed) = Task::poll(&mut self.install_task)? {
+            if installed {
+                self.allow_non_bootloader_drive = false;
+                self.advance();
+            } else {
+                self.drive_refused = true;
+            }
+        }
+
+        if self.drive_refused {
+            self.add_not_bootloader_warning(ui);
+            return Ok(());
+        }
+
+        column(ui, egui::Align::Center, |ui| {
+            stretch(ui);
+            ui.spinner();
+            if self.code_path.is_some() {
+                ui.label(tr!("code-installing-runtime"));
+            } else {
+                ui.label(tr!("code-installing-program"));
+            }
+            stretch(ui);
+        });
+        Ok(())
+    }
+
+    fn run_install_code(
+        &mut self,
+        app_state: &mut GlobalAppState,
+        ui: &mut egui::Ui,
+    ) -> anyhow::Result<()> {
+        if self.code_task.is_none() {
+            let code_path = self
+                .code_path
+                .clone()
+                .ok_or(anyhow!("Expected code_path to not be None."))?;
+            let drives = app_state.drives.clone();
+            self.code_task = Some(Task::spawn(move || {
+                let drive = wait_for_circuitpy(drives.as_ref(), CIRCUITPY_TIMEOUT)
+                    .context("CircuitPython did not start after flashing.")?;
+                std::fs::copy(&code_path, drive.drive_path.join(STARTER_CODE_ASSET))
+                    .context("Failed to copy starter code to drive.")?;
+                drives
+                    .flush(&drive)
+                    .context("Failed to flush starter code to drive.")
+            }));
+        }
+
+        if Task::poll(&mut self.code_task)?.is_some() {
+            self.advance();
+        }
+
+        column(ui, egui::Align::Center, |ui| {
+            stretch(ui);
+            ui.spinner();
+            ui.label(tr!("code-copying-code", volume = CIRCUITPY_VOLUME_LABEL));
+            stretch(ui);
+        });
+        Ok(())
+    }
+
+    /// Explains that the selected drive does not look like a board in BOOTSEL mode and lets the
+    /// user pick another drive or install anyway.
+    fn add_not_bootloader_warning(&mut self, ui: &mut egui::Ui) {
+        let drive = self
+            .selected_drive
+            .as_ref()
+            .map(|d| d.to_string())
+            .unwrap_or_default();
+        column(ui, egui::Align::LEFT, |ui| {
+            heading(ui, tr!("bootsel-not-gizmo"));
+            ui.colored_label(
+                palette(ui).error,
+                tr!("bootsel-not-bootloader", drive = &drive),
+            );
+            ui.label(tr!("bootsel-hold"));
+            stretch(ui);
+            egui_alignments::row(ui, egui::Align::Center, |ui| {
+                stretch(ui);
+                if !self.simplified && ui.button(tr!("bootsel-install-anyway")).clicked() {
+                    log::warn!("Installing onto {drive}, which is not a UF2 bootloader");
+                    self.allow_non_bootloader_drive = true;
+                    self.drive_refused = false;
+                }
+                if ui.button(tr!("bootsel-choose-another-drive")).clicked() {
+                    self.advance();
+                    self.drive_refused = false;
+                    self.selected_drive = None;
+                    self.available_drives = None;
+                }
+            });
+        });
+    }
+
+    fn run_install_libraries(
+        &mut self,
+        app_state: &mut GlobalAppState,
+        ui: &mut egui::Ui,
+    ) -> anyhow::Result<()> {
+        if self.libraries_task.is_none() {
+            let bundle_path = self
+                .library_bundle_path
+                .clone()
+                .ok_or(anyhow!("Expected library_bundle_path to not be None."))?;
+            let drives = app_state.drives.clone();
+            self.libraries_task = Some(Task::spawn(move || {
+                let drive = wait_for_circuitpy(drives.as_ref(), CIRCUITPY_TIMEOUT)
+                    .context("CircuitPython did not start after flashing.")?;
+                let copied = install_library_bundle(&bundle_path, &drive.drive_path)
+                    .context("Failed to copy libraries to drive.")?;
+                drives
+                    .flush(&drive)
+                    .context("Failed to flush libraries to drive.")?;
+                Ok(copied)
             }));
         }
 
-        if let Some(thread) = self.background_thread.take_if(|t| t.is_finished()) {
-            join_thread(thread)?;
-            self.install_finished_receiver = None;
-            self.current_step = Step::PostInstall;
+        if let Some(copied) = Task::poll(&mut self.libraries_task)? {
+            self.libraries_copied = Some(copied);
+            self.advance();
         }
 
         column(ui, egui::Align::Center, |ui| {
             stretch(ui);
             ui.spinner();
-            ui.label("Installing starter program...");
+            ui.label(tr!(
+                "code-copying-libraries",
+                volume = CIRCUITPY_VOLUME_LABEL
+            ));
             stretch(ui);
         });
         Ok(())
@@ -299,18 +707,33 @@ impl StudentStarterCodePage {
 
     fn run_post_install(
         &mut self,
-        _app_state: &mut GlobalAppState,
+        app_state: &mut GlobalAppState,
         ui: &mut egui::Ui,
     ) -> anyhow::Result<()> {
+        if let Some(ref release) = self.software_version
+            && !self.use_local_file
+        {
+            app_state
+                .compatibility
+                .record_install(Component::StarterCode, &release.tag_name);
+        }
         column(ui, egui::Align::LEFT, |ui| {
-            ui.heading("Installation Complete");
-            ui.label("You can now disconnect the device from the computer.");
-            ui.label("To install the starter program onto another device, click \"Setup Another Device\". If you are done installing starter code onto Gizmos, you can close the wizard or click \"Start Over\".");
+            heading(ui, tr!("install-complete"));
+            if let Some(copied) = self.libraries_copied {
+                ui.label(tr!(
+                    "code-libraries-copied",
+                    count = copied,
+                    volume = CIRCUITPY_VOLUME_LABEL
+                ));
+            }
+            ui.label(tr!("install-disconnect"));
+            ui.label(tr!("code-another-hint"));
             stretch(ui);
-            if add_custom_next_button(ui, "Setup Another Device", true).clicked() {
+            if add_custom_next_button(ui, tr!("install-another-device"), true).clicked() {
                 self.selected_drive = None;
+                self.libraries_copied = None;
                 self.available_drives = None;
-                self.current_step = Step::ChooseDrive
+                self.advance();
             }
         });
         Ok(())
@@ -320,15 +743,72 @@ impl StudentStarterCodePage {
 impl Page for StudentStarterCodePage {
     fn run(&mut self, app_state: &mut GlobalAppState, ui: &mut egui::Ui) -> anyhow::Result<()> {
         match self.current_step {
-            Step::ChooseVersion => self.run_choose_version(app_state, ui),
-            Step::DownloadFirmware => self.run_download_firmware(app_state, ui),
-            Step::ChooseDrive => self.run_choose_drive(app_state, ui),
-            Step::InstallFirmware => self.run_install_firmware(app_state, ui),
+            Step::ChooseVersion => self.run_choose_version(app_state, ui).map_err(recoverable),
+            Step::DownloadFirmware => self
+                .run_download_firmware(app_state, ui)
+                .map_err(recoverable),
+            Step::ChooseDrive => self.run_choose_drive(app_state, ui).map_err(recoverable),
+            Step::InstallFirmware => self
+                .run_install_firmware(app_state, ui)
+                .map_err(recoverable),
+            Step::InstallCode => self.run_install_code(app_state, ui).map_err(recoverable),
+            Step::InstallLibraries => self
+                .run_install_libraries(app_state, ui)
+                .map_err(recoverable),
             Step::PostInstall => self.run_post_install(app_state, ui),
         }
     }
 
     fn get_title(&self) -> String {
-        "BEST Default Program Install".to_string()
+        tr!("code-title")
+    }
+
+    fn step_progress(&self) -> Option<StepProgress> {
+        let state = self.flow_state();
+        StepProgress::from_flow(
+            Step::ChooseVersion,
+            self.current_step,
+            |step| Some(step.next(&state)),
+            Step::label,
+        )
+    }
+
+    fn retry_step(&mut self) {
+        self.releases_task = None;
+        self.download_task = None;
+        self.drive_list_task = None;
+        self.install_task = None;
+        self.code_task = None;
+        self.libraries_task = None;
+        match self.current_step {
+            Step::ChooseVersion => self.available_releases = None,
+            Step::ChooseDrive => self.available_drives = None,
+            _ => {}
+        }
+    }
+
+    fn handle_event(&mut self, app_state: &mut GlobalAppState, event: &AppEvent) {
+        match event {
+            AppEvent::DriveAdded(_) | AppEvent::DriveRemoved(_) => {
+                if let AppEvent::DriveRemoved(drive) = event
+                    && self.selected_drive.as_ref() == Some(drive)
+                {
+                    self.selected_drive = None;
+                }
+                // Re-list drives so the picker reflects what is plugged in right now.
+                if self.current_step == Step::ChooseDrive && self.drive_list_task.is_none() {
+                    self.available_drives = None;
+                }
+            }
+            // Fetch the release list again so the new version shows up in the picker.
+            AppEvent::NewReleaseAvailable { repo, .. }
+                if *repo == app_state.sources.starter_code
+                    && self.current_step == Step::ChooseVersion
+                    && self.releases_task.is_none() =>
+            {
+                self.available_releases = None;
+            }
+            _ => {}
+        }
     }
 }
@@ -0,0 +1,125 @@
+//! Step transitions for the student starter code page, kept separate from the egui rendering code.
+
+use crate::i18n::tr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    ChooseVersion,
+    DownloadFirmware,
+    ChooseDrive,
+    InstallFirmware,
+    /// Copies the starter code onto CIRCUITPY after CircuitPython has been flashed.
+    InstallCode,
+    /// Copies the library bundle into CIRCUITPY/lib.
+    InstallLibraries,
+    PostInstall,
+}
+
+/// The parts of the page state that decide which step comes next.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FlowState {
+    pub use_local_file: bool,
+    /// The selected drive is not a UF2 bootloader and the user chose to pick another one.
+    pub choose_other_drive: bool,
+    /// CircuitPython is flashed first and the starter code is copied onto it afterwards.
+    pub install_runtime: bool,
+    /// A library bundle was downloaded and should be copied onto CIRCUITPY.
+    pub install_libraries: bool,
+}
+
+impl Step {
+    /// Returns the step after this one. After installing, the page loops back to flash another
+    /// device.
+    pub fn next(self, state: &FlowState) -> Step {
+        match self {
+            Step::ChooseVersion if state.use_local_file => Step::ChooseDrive,
+            Step::ChooseVersion => Step::DownloadFirmware,
+            Step::DownloadFirmware => Step::ChooseDrive,
+            Step::ChooseDrive => Step::InstallFirmware,
+            Step::InstallFirmware if state.choose_other_drive => Step::ChooseDrive,
+            Step::InstallFirmware if state.install_runtime => Step::InstallCode,
+            Step::InstallFirmware if state.install_libraries => Step::InstallLibraries,
+            Step::InstallFirmware => Step::PostInstall,
+            Step::InstallCode if state.install_libraries => Step::InstallLibraries,
+            Step::InstallCode => Step::PostInstall,
+            Step::InstallLibraries => Step::PostInstall,
+            Step::PostInstall => Step::ChooseDrive,
+        }
+    }
+
+    pub fn label(self) -> String {
+        match self {
+            Step::ChooseVersion => tr!("step-version"),
+            Step::DownloadFirmware => tr!("step-download"),
+            Step::ChooseDrive => tr!("step-device"),
+            Step::InstallFirmware => tr!("step-install"),
+            Step::InstallCode => tr!("step-copy-code"),
+            Step::InstallLibraries => tr!("step-copy-libraries"),
+            Step::PostInstall => tr!("step-done"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn local_files_skip_the_download() {
+        let local = FlowState {
+            use_local_file: true,
+            ..Default::default()
+        };
+        assert_eq!(Step::ChooseVersion.next(&local), Step::ChooseDrive);
+        assert_eq!(
+            Step::ChooseVersion.next(&FlowState::default()),
+            Step::DownloadFirmware
+        );
+    }
+
+    #[test]
+    fn steps_without_choices_go_straight_on() {
+        let state = FlowState::default();
+        assert_eq!(Step::DownloadFirmware.next(&state), Step::ChooseDrive);
+        assert_eq!(Step::ChooseDrive.next(&state), Step::InstallFirmware);
+        assert_eq!(Step::InstallLibraries.next(&state), Step::PostInstall);
+        assert_eq!(Step::PostInstall.next(&state), Step::ChooseDrive);
+    }
+
+    #[test]
+    fn install_copies_whatever_else_was_asked_for() {
+        for install_runtime in [false, true] {
+            for install_libraries in [false, true] {
+                let state = FlowState {
+                    install_runtime,
+                    install_libraries,
+                    ..Default::default()
+                };
+                let expected = match (install_runtime, install_libraries) {
+                    (true, _) => Step::InstallCode,
+                    (false, true) => Step::InstallLibraries,
+                    (false, false) => Step::PostInstall,
+                };
+                assert_eq!(Step::InstallFirmware.next(&state), expected, "{state:?}");
+
+                let after_code = if install_libraries {
+                    Step::InstallLibraries
+                } else {
+                    Step::PostInstall
+                };
+                assert_eq!(Step::InstallCode.next(&state), after_code, "{state:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn choosing_another_drive_comes_before_everything_else() {
+        let state = FlowState {
+            choose_other_drive: true,
+            install_runtime: true,
+            install_libraries: true,
+            ..Default::default()
+        };
+        assert_eq!(Step::InstallFirmware.next(&state), Step::ChooseDrive);
+    }
+}
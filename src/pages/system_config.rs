@@ -0,0 +1,293 @@
+use crate::app::GlobalAppState;
+use crate::events::AppEvent;
+use crate::i18n::tr;
+use crate::pages::{Page, StepProgress, add_custom_next_button, add_next_button, recoverable};
+use crate::utils::drive_management::DriveInfo;
+use crate::utils::gizmo_config::{FieldSettings, read_configured_team, write_gizmo_config};
+use crate::utils::sounds::{Cue, play_cue};
+use crate::utils::tasks::Task;
+use crate::widgets::accessibility::heading;
+use crate::widgets::field_settings_editor::{add_field_settings_rows, show_field_settings_problem};
+use anyhow::{Context, anyhow};
+use egui_alignments::{column, stretch};
+
+/// Volume label of the configuration partition the system processor exposes over USB.
+const CONFIG_VOLUME_LABEL: &str = "GIZMO";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    EnterSettings,
+    ChooseDrive,
+    WriteConfig,
+    PostInstall,
+}
+
+/// Writes the team number and radio settings onto a system processor's configuration partition,
+/// so teams don't need the separate gizmo-cli tool.
+pub struct SystemConfigPage {
+    current_step: Step,
+    team_number: String,
+    field_settings: FieldSettings,
+    available_drives: Option<Vec<DriveInfo>>,
+    selected_drive: Option<DriveInfo>,
+
+    drive_list_task: Option<Task<Vec<DriveInfo>>>,
+    /// Gives the team number that was written, or `None` if the user cancelled.
+    write_task: Option<Task<Option<String>>>,
+}
+
+impl SystemConfigPage {
+    pub fn new() -> Self {
+        Self {
+            current_step: Step::EnterSettings,
+            team_number: String::new(),
+            field_settings: FieldSettings::default(),
+            available_drives: None,
+            selected_drive: None,
+
+            drive_list_task: None,
+            write_task: None,
+        }
+    }
+
+    fn set_step(&mut self, step: Step) {
+        log::info!("System config: {:?} -> {:?}", self.current_step, step);
+        self.current_step = step;
+    }
+
+    fn run_enter_settings(
+        &mut self,
+        app_state: &mut GlobalAppState,
+        ui: &mut egui::Ui,
+    ) -> anyhow::Result<()> {
+        column(ui, egui::Align::LEFT, |ui| {
+            heading(ui, tr!("sysconfig-title"));
+            ui.label(tr!("sysconfig-intro"));
+            egui::Grid::new("system_config_grid")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    ui.label(tr!("team-number-label"));
+                    ui.text_edit_singleline(&mut self.team_number);
+                    ui.end_row();
+                    add_field_settings_rows(ui, &mut self.field_settings);
+                });
+            let problem = app_state
+                .settings
+                .team_number_rules
+                .problem(&self.team_number)
+                .or_else(|| self.field_settings.problem());
+            show_field_settings_problem(ui, &self.field_settings, problem.as_deref());
+
+            stretch(ui);
+
+            if add_next_button(ui, problem.is_none()).clicked() {
+                self.set_step(Step::ChooseDrive);
+            }
+        });
+        Ok(())
+    }
+
+    fn run_choose_drive(
+        &mut self,
+        app_state: &mut GlobalAppState,
+        ui: &mut egui::Ui,
+    ) -> anyhow::Result<()> {
+        if self.available_drives.is_none() && self.drive_list_task.is_none() {
+            let drives = app_state.drives.clone();
+            self.drive_list_task = Some(Task::spawn(move || {
+                drives
+                    .list()
+                    .context("Failed to get list of available drives.")
+            }));
+        }
+
+        if let Some(drives) = Task::poll(&mut self.drive_list_task)? {
+            // Pre-select the configuration partition if it is the only one plugged in.
+            let mut config_drives = drives
+                .iter()
+                .filter(|d| d.file_system_label == CONFIG_VOLUME_LABEL);
+            if let (Some(drive), None) = (config_drives.next(), config_drives.next()) {
+                self.selected_drive = Some(drive.clone());
+            }
+            self.available_drives = Some(drives);
+        }
+
+        column(ui, egui::Align::LEFT, |ui| {
+            heading(ui, tr!("drives-choose-device"));
+            ui.label(
+                [
+                    tr!("sysconfig-step-power"),
+                    tr!("sysconfig-step-connect"),
+                    tr!("sysconfig-step-refresh"),
+                    tr!("sysconfig-step-select", label = CONFIG_VOLUME_LABEL),
+                ]
+                .join("\n"),
+            );
+            if let Some(ref drives) = self.available_drives {
+                if drives.is_empty() {
+                    ui.label(tr!("drives-none"));
+                } else {
+                    for drive in drives {
+                        ui.selectable_value(
+                            &mut self.selected_drive,
+                            Some(drive.clone()),
+                            drive.picker_label(),
+                        );
+                    }
+                }
+
+                if ui.button(tr!("common-refresh")).clicked() {
+                    self.available_drives = None;
+                    self.selected_drive = None;
+                }
+            } else {
+                ui.spinner();
+                ui.label(tr!("drives-searching"));
+            }
+            stretch(ui);
+            if add_custom_next_button(
+                ui,
+                tr!("network-write-settings"),
+                self.selected_drive.is_some(),
+            )
+            .clicked()
+            {
+                self.set_step(Step::WriteConfig);
+            }
+        });
+        Ok(())
+    }
+
+    fn run_write_config(
+        &mut self,
+        app_state: &mut GlobalAppState,
+        ui: &mut egui::Ui,
+    ) -> anyhow::Result<()> {
+        if self.write_task.is_none() {
+            let drive = self
+                .selected_drive
+                .clone()
+                .ok_or(anyhow!("Expected selected_drive to not be None."))?;
+            let team_number = self.team_number.clone();
+            let field_settings = self.field_settings.clone();
+            let prompts = app_state.prompts.clone();
+            let drives = app_state.drives.clone();
+            self.write_task = Some(Task::spawn(move || {
+                let team_number = match read_configured_team(&drive.drive_path) {
+                    Some(existing) if existing.to_string() != team_number => {
+                        let options = vec![
+                            tr!("sysconfig-change-team", team = &team_number),
+                            tr!("sysconfig-keep-team", team = existing),
+                        ];
+                        let message = tr!("sysconfig-already-set-up", team = existing);
+                        match prompts.choose(&tr!("sysconfig-different-team"), &message, options) {
+                            Ok(Some(0)) => team_number,
+                            Ok(Some(_)) => existing.to_string(),
+                            _ => return Ok(None),
+                        }
+                    }
+                    _ => team_number,
+                };
+                write_gizmo_config(&drive.drive_path, &team_number, &field_settings)
+                    .context("Failed to write configuration to device.")?;
+                drives
+                    .flush(&drive)
+                    .context("Failed to flush configuration to device.")?;
+                Ok(Some(team_number))
+            }));
+        }
+
+        if let Some(written) = Task::poll(&mut self.write_task)? {
+            match written {
+                Some(team_number) => {
+                    self.team_number = team_number;
+                    play_cue(Cue::Success);
+                    self.set_step(Step::PostInstall);
+                }
+                None => self.set_step(Step::ChooseDrive),
+            }
+        }
+
+        column(ui, egui::Align::Center, |ui| {
+            stretch(ui);
+            ui.spinner();
+            ui.label(tr!("network-writing-settings"));
+            stretch(ui);
+        });
+        Ok(())
+    }
+
+    fn run_post_install(
+        &mut self,
+        _app_state: &mut GlobalAppState,
+        ui: &mut egui::Ui,
+    ) -> anyhow::Result<()> {
+        column(ui, egui::Align::LEFT, |ui| {
+            heading(ui, tr!("network-settings-written"));
+            ui.label(tr!("sysconfig-done", team = &self.team_number));
+            ui.label(tr!("sysconfig-another-hint"));
+            stretch(ui);
+            if add_custom_next_button(ui, tr!("sysconfig-another"), true).clicked() {
+                self.team_number.clear();
+                self.selected_drive = None;
+                self.available_drives = None;
+                self.set_step(Step::EnterSettings);
+            }
+        });
+        Ok(())
+    }
+}
+
+impl Page for SystemConfigPage {
+    fn run(&mut self, app_state: &mut GlobalAppState, ui: &mut egui::Ui) -> anyhow::Result<()> {
+        match self.current_step {
+            Step::EnterSettings => self.run_enter_settings(app_state, ui),
+            Step::ChooseDrive => self.run_choose_drive(app_state, ui).map_err(recoverable),
+            Step::WriteConfig => self.run_write_config(app_state, ui).map_err(recoverable),
+            Step::PostInstall => self.run_post_install(app_state, ui),
+        }
+    }
+
+    fn get_title(&self) -> String {
+        tr!("sysconfig-title")
+    }
+
+    fn step_progress(&self) -> Option<StepProgress> {
+        StepProgress::from_steps(
+            &[
+                Step::EnterSettings,
+                Step::ChooseDrive,
+                Step::WriteConfig,
+                Step::PostInstall,
+            ],
+            self.current_step,
+            |step| match step {
+                Step::EnterSettings => tr!("step-settings"),
+                Step::ChooseDrive => tr!("step-device"),
+                Step::WriteConfig => tr!("step-write"),
+                Step::PostInstall => tr!("step-done"),
+            },
+        )
+    }
+
+    fn retry_step(&mut self) {
+        self.drive_list_task = None;
+        self.write_task = None;
+        if self.current_step == Step::ChooseDrive {
+            self.available_drives = None;
+        }
+    }
+
+    fn handle_event(&mut self, _app_state: &mut GlobalAppState, event: &AppEvent) {
+        if let AppEvent::DriveAdded(_) | AppEvent::DriveRemoved(_) = event {
+            if let AppEvent::DriveRemoved(drive) = event
+                && self.selected_drive.as_ref() == Some(drive)
+            {
+                self.selected_drive = None;
+            }
+            if self.current_step == Step::ChooseDrive && self.drive_list_task.is_none() {
+                self.available_drives = None;
+            }
+        }
+    }
+}
@@ -1,41 +1,97 @@
 use crate::app::GlobalAppState;
-use crate::pages::{Page, add_custom_next_button, add_next_button};
-use crate::utils::drive_management::{DriveInfo, list_drives};
+use crate::events::AppEvent;
+use crate::i18n::tr;
+use crate::pages::{Page, StepProgress, add_custom_next_button, add_next_button, recoverable};
+use crate::theme::palette;
+use crate::utils::compatibility::Component;
+use crate::utils::drive_management::{DriveInfo, is_rp2040_bootloader, is_uf2_bootloader};
 use crate::utils::github::{GithubRelease, GithubReleaseAsset, download_versioned_asset};
-use crate::utils::threads::join_thread;
-use anyhow::anyhow;
+use crate::utils::github_artifacts::add_development_builds;
+use crate::utils::gizmo_serial::{
+    list_serial_ports, query_firmware_version, wait_for_new_serial_port,
+};
+use crate::utils::session_report::{BoardRecord, unix_now};
+use crate::utils::sounds::{Cue, play_cue};
+use crate::utils::tasks::Task;
+use crate::utils::uf2::{
+    BOOTLOADER_INFO_FILE, BootloaderInfo, BuildArtifact, read_bootloader_info, read_uf2_info,
+    scan_build_dir,
+};
+use crate::widgets::accessibility::heading;
+use crate::widgets::compatibility_warnings::show_compatibility_warnings;
+use crate::widgets::fetch_error_card::{FetchErrorAction, show_fetch_error_card};
+use crate::widgets::version_picker::show_version_picker;
+use anyhow::{Context, anyhow, bail};
 use egui_alignments::{column, stretch};
 use egui_file_dialog::FileDialog;
-use std::sync::mpsc::Receiver;
-use std::time::Duration;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 use std::vec;
 
-enum Step {
-    ChooseVersion,
-    ChooseBoardRevision,
-    DownloadFirmware,
-    ChooseDrive,
-    InstallFirmware,
-    PostInstall,
+mod batch;
+mod flow;
+
+use batch::{BatchLog, FlashRecord};
+use flow::{FlowState, Step};
+
+/// How often to look for a newly plugged in board while the user is on the drive step.
+const BOOTLOADER_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// How long a freshly flashed board gets to reboot and show up as a serial port.
+const REBOOT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A board the firmware was just copied onto.
+struct FlashedBoard {
+    /// Serial ports that existed before flashing, so the rebooted board can be told apart.
+    serial_ports: Vec<PathBuf>,
+    bootloader: Option<BootloaderInfo>,
 }
 
 pub struct SystemFirmwarePage {
     current_step: Step,
     available_releases: Option<Vec<GithubRelease>>,
+    releases_error: Option<String>,
     software_version: Option<GithubRelease>,
     available_firmwares: Option<Vec<GithubReleaseAsset>>,
     selected_firmware: Option<GithubReleaseAsset>,
     firmware_path: Option<std::path::PathBuf>,
+    use_local_file: bool,
+    /// Build output directory the firmware is picked from, for firmware developers.
+    build_dir: Option<PathBuf>,
+    build_artifacts: Option<Result<Vec<BuildArtifact>, String>>,
     available_drives: Option<Vec<DriveInfo>>,
     selected_drive: Option<DriveInfo>,
+    /// The selected drive is not a UF2 bootloader, so the install is waiting on the user.
+    drive_refused: bool,
+    allow_non_bootloader_drive: bool,
+    /// Serial ports present before flashing, so the rebooted board can be told apart.
+    serial_ports_before_install: Vec<PathBuf>,
+    /// What the board being flashed said about itself while it was in bootloader mode.
+    bootloader_info: Option<BootloaderInfo>,
+    /// Index of the board being flashed in the session report, so the verified version can be
+    /// added once it is known.
+    session_board_index: Option<usize>,
+    /// The firmware version read back from the board after flashing, or why it couldn't be.
+    verification: Option<Result<String, String>>,
+    /// Boards in BOOTSEL mode seen by the last drive listing. A board missing from here is new.
+    known_bootloaders: Option<Vec<DriveInfo>>,
+    drives_listed_at: Option<Instant>,
+    /// Flash each new board as soon as it shows up and go straight on to the next one, for
+    /// events that flash dozens of boards.
+    batch_mode: bool,
+    batch_log: BatchLog,
+    batch_log_status: Option<String>,
 
-    available_releases_receiver: Option<Receiver<Vec<GithubRelease>>>,
-    download_finished_receiver: Option<Receiver<std::path::PathBuf>>,
-    drive_list_receiver: Option<Receiver<Vec<DriveInfo>>>,
-    install_finished_receiver: Option<Receiver<()>>,
+    releases_task: Option<Task<anyhow::Result<Vec<GithubRelease>>>>,
+    download_task: Option<Task<PathBuf>>,
+    drive_list_task: Option<Task<Vec<(DriveInfo, bool)>>>,
+    /// Gives `None` if the install was refused because the drive is not a UF2 bootloader.
+    install_task: Option<Task<Option<FlashedBoard>>>,
+    verification_task: Option<Task<Result<String, String>>>,
+    build_artifacts_task: Option<Task<Result<Vec<BuildArtifact>, String>>>,
 
-    background_thread: Option<std::thread::JoinHandle<()>>,
     file_dialog: FileDialog,
+    build_dir_dialog: FileDialog,
+    batch_log_dialog: FileDialog,
 }
 
 impl SystemFirmwarePage {
@@ -43,142 +99,250 @@ impl SystemFirmwarePage {
         Self {
             current_step: Step::ChooseVersion,
             available_releases: None,
+            releases_error: None,
             software_version: None,
             available_firmwares: None,
             selected_firmware: None,
             firmware_path: None,
+            use_local_file: false,
+            build_dir: None,
+            build_artifacts: None,
             available_drives: None,
             selected_drive: None,
+            drive_refused: false,
+            allow_non_bootloader_drive: false,
+            serial_ports_before_install: vec![],
+            bootloader_info: None,
+            session_board_index: None,
+            verification: None,
+            known_bootloaders: None,
+            drives_listed_at: None,
+            batch_mode: false,
+            batch_log: BatchLog::default(),
+            batch_log_status: None,
 
-            available_releases_receiver: None,
-            download_finished_receiver: None,
-            drive_list_receiver: None,
-            install_finished_receiver: None,
+            releases_task: None,
+            download_task: None,
+            drive_list_task: None,
+            install_task: None,
+            verification_task: None,
+            build_artifacts_task: None,
 
-            background_thread: None,
             file_dialog: FileDialog::new()
-                .add_file_filter_extensions("UF2 Firmware", vec!["uf2"])
-                .default_file_filter("UF2 Firmware"),
+                .add_file_filter_extensions(&tr!("file-uf2-filter"), vec!["uf2"])
+                .default_file_filter(&tr!("file-uf2-filter")),
+            build_dir_dialog: FileDialog::new().title(&tr!("fw-build-dir-dialog")),
+            batch_log_dialog: FileDialog::new()
+                .title(&tr!("fw-export-log-dialog"))
+                .default_file_name("firmware-log.csv"),
+        }
+    }
+
+    /// Whether the firmware has been installed, for flows that continue to another page.
+    pub fn is_finished(&self) -> bool {
+        self.current_step == Step::PostInstall
+    }
+
+    /// The release being installed, unless it is a local file or build instead.
+    pub fn chosen_release(&self) -> Option<&GithubRelease> {
+        self.software_version
+            .as_ref()
+            .filter(|_| !self.use_local_file)
+    }
+
+    fn flow_state(&self) -> FlowState {
+        FlowState {
+            use_local_file: self.use_local_file,
+            use_build_dir: self.build_dir.is_some(),
+            choose_other_drive: self.drive_refused,
+            single_revision: self
+                .available_firmwares
+                .as_ref()
+                .is_some_and(|f| f.len() == 1),
+            batch_mode: self.batch_mode,
         }
     }
 
+    fn advance(&mut self) {
+        let next = self.current_step.next(&self.flow_state());
+        log::info!("System firmware: {:?} -> {:?}", self.current_step, next);
+        if next == Step::PostInstall {
+            play_cue(Cue::Success);
+        }
+        self.current_step = next;
+    }
+
     fn run_choose_version(
         &mut self,
-        _app_state: &mut GlobalAppState,
+        app_state: &mut GlobalAppState,
         ui: &mut egui::Ui,
     ) -> anyhow::Result<()> {
-        if self.available_releases.is_none() && self.background_thread.is_none() {
-            let (tx, rx) = std::sync::mpsc::channel();
-            self.available_releases_receiver = Some(rx);
-            self.background_thread = Some(std::thread::spawn(move || {
-                let releases = crate::utils::github::get_releases("gizmo-platform", "firmware")
-                    .unwrap_or(Vec::new());
-                tx.send(releases)
-                    .expect("Failed to send release details to main thread.");
+        if self.available_releases.is_none() && self.releases_task.is_none() {
+            let offline_source = app_state.offline_source.clone();
+            let repo = app_state.sources.firmware.clone();
+            let channel = app_state.release_channel;
+            let network = app_state.network();
+            self.releases_task = Some(Task::spawn(move || {
+                // Failing to fetch releases is shown on the page rather than as an error.
+                Ok(crate::utils::offline::get_releases_for_source(
+                    &network,
+                    offline_source.as_deref(),
+                    &repo.owner,
+                    &repo.name,
+                )
+                .map(|mut releases| {
+                    add_development_builds(
+                        &network,
+                        &mut releases,
+                        channel,
+                        offline_source.as_deref(),
+                        &repo,
+                    );
+                    releases
+                }))
             }));
         }
-        if let Some(thread) = self.background_thread.take_if(|t| t.is_finished()) {
-            join_thread(thread)?;
-            let receiver = self.available_releases_receiver.take().ok_or(anyhow!(
-                "Expected available_releases_receiver to not be None."
-            ))?;
-            self.available_releases = Some(receiver.recv_timeout(Duration::from_secs(1))?);
-        }
-        if let Some(releases) = self.available_releases.as_ref() {
-            if !releases.is_empty() && self.software_version.is_none() {
-                self.software_version = Some(
-                    releases
-                        .iter()
-                        .find(|r| r.latest)
-                        .ok_or(anyhow!("Latest release not found"))?
-                        .clone(),
-                );
+        if let Some(releases) = Task::poll(&mut self.releases_task)? {
+            match releases {
+                Ok(releases) => {
+                    self.releases_error = None;
+                    self.available_releases = Some(releases);
+                }
+                Err(e) => {
+                    self.releases_error =
+                        Some(tr!("releases-fetch-failed", error = format!("{e:#}")));
+                    self.available_releases = Some(vec![]);
+                }
             }
         }
+        if let Some(releases) = self.available_releases.as_ref()
+            && !releases.is_empty()
+            && self.software_version.is_none()
+        {
+            self.software_version = Some(
+                releases
+                    .iter()
+                    .find(|r| r.latest)
+                    .ok_or(anyhow!("Latest release not found"))?
+                    .clone(),
+            );
+        }
         let next_button_enabled = self.software_version.is_some();
 
         self.file_dialog.update(ui.ctx());
 
         if let Some(selected_file) = self.file_dialog.take_picked() {
             self.firmware_path = Some(selected_file);
-            self.current_step = Step::ChooseDrive;
+            self.use_local_file = true;
+            self.advance();
         }
 
+        self.build_dir_dialog.update(ui.ctx());
+        if let Some(build_dir) = self.build_dir_dialog.take_picked() {
+            log::info!("Using firmware builds from {:?}", build_dir);
+            self.build_dir = Some(build_dir);
+            self.use_local_file = true;
+            self.advance();
+        }
+
+        let can_work_offline =
+            app_state.offline_source.is_none() && app_state.download_cache_dir().is_dir();
+        let mut fetch_error_action = None;
+        let mut compatible = true;
+        let mut channel_changed = false;
         column(ui, egui::Align::LEFT, |ui| {
-            ui.heading("Firmware Version");
-            ui.label("Select the version of the firmware you want to install. Usually, this should be the latest version.");
+            heading(ui, tr!("fw-version-heading"));
+            ui.label(tr!("fw-version-intro"));
             if let Some(ref releases) = self.available_releases {
-                egui::ComboBox::from_label("Pick a version")
-                    .selected_text(match self.software_version {
-                        Some(ref version) => version.display_name(),
-                        None => "Select Version".to_string(),
-                    })
-                    .show_ui(ui, |ui| {
-                        for release in releases {
-                            ui.selectable_value(
-                                &mut self.software_version,
-                                Some(release.clone()),
-                                release.display_name(),
-                            );
-                        }
-                    });
+                channel_changed = show_version_picker(
+                    ui,
+                    releases,
+                    &mut self.software_version,
+                    &mut app_state.release_channel,
+                );
+                compatible = show_compatibility_warnings(
+                    ui,
+                    &app_state.compatibility,
+                    Component::Firmware,
+                    self.software_version.as_ref(),
+                );
             } else {
                 ui.spinner();
-                ui.label("Fetching available releases...");
+                ui.label(tr!("releases-fetching"));
             }
-            if ui.link("Use local file instead").clicked() {
+            if let Some(ref error) = self.releases_error {
+                fetch_error_action = show_fetch_error_card(ui, error, can_work_offline);
+            }
+            if ui.link(tr!("releases-use-local-file")).clicked() {
                 self.file_dialog.pick_file();
             }
+            if ui
+                .link(tr!("fw-use-build-dir"))
+                .on_hover_text(tr!("fw-use-build-dir-hint"))
+                .clicked()
+            {
+                self.build_dir_dialog.pick_directory();
+            }
             stretch(ui);
-            if add_next_button(ui, next_button_enabled).clicked() {
-                self.current_step = Step::ChooseBoardRevision;
+            if add_next_button(ui, next_button_enabled && compatible).clicked() {
+                self.select_only_revision();
+                self.advance();
             }
         });
+        if channel_changed {
+            self.available_releases = None;
+        }
+        match fetch_error_action {
+            Some(FetchErrorAction::Retry) => self.available_releases = None,
+            Some(FetchErrorAction::WorkOffline) => {
+                let cache_dir = app_state.download_cache_dir();
+                log::info!("Working offline from {:?}", cache_dir);
+                app_state.offline_source = Some(cache_dir);
+                self.available_releases = None;
+            }
+            None => {}
+        }
         Ok(())
     }
 
+    /// Picks the firmware automatically when the chosen release only has one board revision.
+    fn select_only_revision(&mut self) {
+        let Some(ref version) = self.software_version else {
+            return;
+        };
+        let firmwares = flow::firmware_assets(version);
+        if let [firmware] = firmwares.as_slice() {
+            log::info!(
+                "Only one board revision in {}, selecting {}",
+                version.tag_name,
+                firmware.name
+            );
+            self.selected_firmware = Some(firmware.clone());
+        }
+        self.available_firmwares = Some(firmwares);
+    }
+
     fn run_choose_board_revision(
         &mut self,
         _app_state: &mut GlobalAppState,
         ui: &mut egui::Ui,
     ) -> anyhow::Result<()> {
-        let version_name = self
+        let version = self
             .software_version
-            .as_ref()
-            .ok_or(anyhow!("Expected software_version to not be None."))?
-            .tag_name
-            .clone();
-        let prefix = "gss-";
-        let suffix = "-".to_string() + &version_name + ".uf2";
+            .clone()
+            .ok_or(anyhow!("Expected software_version to not be None."))?;
 
         if self.available_firmwares.is_none() {
-            if let Some(ref version) = self.software_version {
-                self.available_firmwares = Some(
-                    version
-                        .assets
-                        .iter()
-                        .filter_map(|asset| {
-                            if asset.name.starts_with(prefix) && asset.name.ends_with(&suffix) {
-                                Some(asset.clone())
-                            } else {
-                                None
-                            }
-                        })
-                        .collect(),
-                );
-            }
+            self.available_firmwares = Some(flow::firmware_assets(&version));
         }
 
         column(ui, egui::Align::LEFT, |ui| {
-            ui.heading("Choose Hardware Version");
-            ui.label("Select the hardware version of the Gizmo PCB you are using. This should be printed on the board and should look something like \"v01.00\" or \"v00.r6b\"");
+            heading(ui, tr!("fw-hardware-heading"));
+            ui.label(tr!("fw-hardware-intro"));
 
             if let Some(ref available_revisions) = self.available_firmwares {
                 for rev in available_revisions {
-                    let display_text = rev
-                        .name
-                        .trim_start_matches(prefix)
-                        .trim_end_matches(&suffix);
+                    let display_text = flow::board_revision_label(rev, &version);
                     ui.selectable_value(
                         &mut self.selected_firmware,
                         Some(rev.clone()),
@@ -186,15 +350,12 @@ impl SystemFirmwarePage {
                     );
                 }
             } else {
-                ui.colored_label(
-                    egui::Color32::DARK_RED,
-                    "Could not recognize any firmware files in the selected release.",
-                );
+                ui.colored_label(palette(ui).error, tr!("fw-no-firmware-files"));
             }
 
             stretch(ui);
             if add_next_button(ui, self.selected_firmware.is_some()).clicked() {
-                self.current_step = Step::DownloadFirmware;
+                self.advance();
             }
         });
         Ok(())
@@ -205,7 +366,7 @@ impl SystemFirmwarePage {
         app_state: &mut GlobalAppState,
         ui: &mut egui::Ui,
     ) -> anyhow::Result<()> {
-        if self.firmware_path.is_none() && self.background_thread.is_none() {
+        if self.firmware_path.is_none() && self.download_task.is_none() {
             let release = self
                 .software_version
                 .clone()
@@ -214,101 +375,229 @@ impl SystemFirmwarePage {
                 .selected_firmware
                 .clone()
                 .ok_or(anyhow!("Expected selected_firmware to not be None."))?;
-            let cache_path = app_state.tmp_dir.path().join("github_downloads");
-            let (tx, rx) = std::sync::mpsc::channel();
-            self.download_finished_receiver = Some(rx);
-            self.background_thread = Some(std::thread::spawn(move || {
+            let cache_path = app_state.download_cache_dir();
+            let repo = app_state.sources.firmware.clone();
+            let events = app_state.events.clone();
+            let network = app_state.network();
+            self.download_task = Some(Task::spawn(move || {
                 let download_path = download_versioned_asset(
+                    &network,
                     &firmware_asset,
-                    "gizmo-platform",
-                    "firmware",
+                    &repo.owner,
+                    &repo.name,
                     &release,
                     &cache_path,
                 )
-                .expect("Falied to fetch GitHub releases.");
-                tx.send(download_path)
-                    .expect("Failed to send release details to main thread.");
+                .context("Failed to download the firmware.")?;
+                events.publish(AppEvent::DownloadFinished(download_path.clone()));
+                Ok(download_path)
             }));
         }
 
-        if let Some(thread) = self.background_thread.take_if(|t| t.is_finished()) {
-            join_thread(thread)?;
-            let receiver = self.download_finished_receiver.take().ok_or(anyhow!(
-                "Expected download_finished_receiver to not be None."
-            ))?;
-            self.firmware_path = Some(receiver.recv_timeout(Duration::from_secs(1))?);
-            self.current_step = Step::ChooseDrive;
+        if let Some(download_path) = Task::poll(&mut self.download_task)? {
+            self.firmware_path = Some(download_path);
+            self.advance();
         }
 
         column(ui, egui::Align::Center, |ui| {
             stretch(ui);
             ui.spinner();
-            ui.label("Downloading firmware file...");
+            ui.label(tr!("fw-downloading"));
             stretch(ui);
         });
         Ok(())
     }
 
-    fn run_choose_drive(
+    fn run_choose_build_artifact(
         &mut self,
         _app_state: &mut GlobalAppState,
         ui: &mut egui::Ui,
     ) -> anyhow::Result<()> {
-        if self.available_drives.is_none() && self.background_thread.is_none() {
-            let (tx, rx) = std::sync::mpsc::channel();
-            self.drive_list_receiver = Some(rx);
-            self.background_thread = Some(std::thread::spawn(move || {
-                let drives = list_drives().expect("Failed to get list of available drives.");
-                tx.send(drives)
-                    .expect("Failed to send drive list to main thread.");
+        let build_dir = self
+            .build_dir
+            .clone()
+            .ok_or(anyhow!("Expected build_dir to not be None."))?;
+        if self.build_artifacts.is_none() && self.build_artifacts_task.is_none() {
+            let scan_dir = build_dir.clone();
+            self.build_artifacts_task = Some(Task::spawn(move || {
+                Ok(scan_build_dir(&scan_dir).map_err(|e| format!("{e:#}")))
+            }));
+        }
+
+        if let Some(artifacts) = Task::poll(&mut self.build_artifacts_task)? {
+            // Select the newest build that can be flashed, which is usually the one just built.
+            if let Ok(ref artifacts) = artifacts
+                && self.firmware_path.is_none()
+            {
+                self.firmware_path = artifacts
+                    .iter()
+                    .find(|a| a.info.as_ref().is_ok_and(|info| info.is_for_rp2040()))
+                    .map(|a| a.path.clone());
+            }
+            self.build_artifacts = Some(artifacts);
+        }
+
+        column(ui, egui::Align::LEFT, |ui| {
+            heading(ui, tr!("fw-build-heading"));
+            ui.label(tr!(
+                "fw-build-files",
+                directory = build_dir.display().to_string()
+            ));
+            match self.build_artifacts {
+                None => {
+                    ui.spinner();
+                }
+                Some(Err(ref message)) => {
+                    ui.colored_label(palette(ui).error, message);
+                }
+                Some(Ok(ref artifacts)) if artifacts.is_empty() => {
+                    ui.label(tr!("fw-build-none"));
+                }
+                Some(Ok(ref artifacts)) => {
+                    egui::ScrollArea::vertical()
+                        .max_height(200.0)
+                        .show(ui, |ui| {
+                            for artifact in artifacts {
+                                add_build_artifact(
+                                    ui,
+                                    &build_dir,
+                                    artifact,
+                                    &mut self.firmware_path,
+                                );
+                            }
+                        });
+                }
+            }
+            if ui
+                .add_enabled(
+                    self.build_artifacts.is_some(),
+                    egui::Button::new(tr!("fw-rescan")),
+                )
+                .clicked()
+            {
+                self.build_artifacts = None;
+            }
+            stretch(ui);
+            if add_next_button(ui, self.firmware_path.is_some()).clicked() {
+                self.advance();
+            }
+        });
+        Ok(())
+    }
+
+    fn run_choose_drive(
+        &mut self,
+        app_state: &mut GlobalAppState,
+        ui: &mut egui::Ui,
+    ) -> anyhow::Result<()> {
+        // Keep polling so the board is picked up as soon as it is plugged in, without the user
+        // having to click Refresh.
+        let poll_due = self
+            .drives_listed_at
+            .is_none_or(|t| t.elapsed() >= BOOTLOADER_POLL_INTERVAL);
+        if (self.available_drives.is_none() || poll_due) && self.drive_list_task.is_none() {
+            let drives = app_state.drives.clone();
+            self.drive_list_task = Some(Task::spawn(move || {
+                let drives = drives
+                    .list()
+                    .context("Failed to get list of available drives.")?
+                    .into_iter()
+                    .map(|drive| {
+                        let is_bootloader = is_rp2040_bootloader(&drive);
+                        (drive, is_bootloader)
+                    })
+                    .collect();
+                Ok(drives)
             }));
         }
+        ui.ctx().request_repaint_after(BOOTLOADER_POLL_INTERVAL);
 
-        if let Some(thread) = self.background_thread.take_if(|t| t.is_finished()) {
-            join_thread(thread)?;
-            let receiver = self
-                .drive_list_receiver
-                .take()
-                .ok_or(anyhow!("Expected drive_list_receiver to not be None."))?;
-            self.available_drives = Some(receiver.recv_timeout(Duration::from_secs(1))?);
+        if let Some(drives) = Task::poll(&mut self.drive_list_task)? {
+            let bootloaders: Vec<DriveInfo> = drives
+                .iter()
+                .filter(|(_, is_bootloader)| *is_bootloader)
+                .map(|(drive, _)| drive.clone())
+                .collect();
+            let new_bootloader = self.known_bootloaders.as_ref().and_then(|known| {
+                bootloaders
+                    .iter()
+                    .find(|drive| !known.contains(drive))
+                    .cloned()
+            });
+            if self.selected_drive.is_none() {
+                self.selected_drive = bootloaders.first().cloned();
+            }
+            self.known_bootloaders = Some(bootloaders);
+            self.available_drives = Some(drives.into_iter().map(|(drive, _)| drive).collect());
+            self.drives_listed_at = Some(Instant::now());
+            if let Some(drive) = new_bootloader {
+                log::info!("Detected RP2040 bootloader at {drive}");
+                self.selected_drive = Some(drive);
+                self.drives_listed_at = None;
+                self.advance();
+                return Ok(());
+            }
         }
 
+        // Confirm the board revision that was picked without asking.
+        let auto_selected_revision = match (&self.software_version, &self.selected_firmware) {
+            (Some(version), Some(firmware))
+                if !self.use_local_file
+                    && self
+                        .available_firmwares
+                        .as_ref()
+                        .is_some_and(|f| f.len() == 1) =>
+            {
+                Some(flow::board_revision_label(firmware, version).to_string())
+            }
+            _ => None,
+        };
+
         column(ui, egui::Align::LEFT, |ui| {
-            ui.heading("Choose Device");
+            heading(ui, tr!("drives-choose-device"));
+            if let Some(revision) = auto_selected_revision {
+                ui.label(tr!("fw-revision-auto", revision = revision));
+            }
             ui.label(
-                r#"1. Press and hold the BOOTSEL button on the system processor.
-2. Connect the system processor to your computer with the USB cable.
-3. Release the BOOTSEL button.
-4. Click the "Refresh" button to update the list below.
-5. Select the drive from the list and click "Install Firmware". The drive should be named "RPI-RP2".
-"#,
+                [
+                    tr!("fw-step-bootsel"),
+                    tr!("fw-step-connect"),
+                    tr!("fw-step-release"),
+                    tr!("fw-step-install"),
+                ]
+                .join("\n"),
             );
             if let Some(ref drives) = self.available_drives {
                 if drives.is_empty() {
-                    ui.label("No removable drives found.");
+                    ui.label(tr!("drives-none"));
                 } else {
                     for drive in drives {
                         ui.selectable_value(
                             &mut self.selected_drive,
                             Some(drive.clone()),
-                            format!("{drive}"),
+                            drive.picker_label(),
                         );
                     }
                 }
 
-                if ui.button("Refresh").clicked() {
+                if ui.button(tr!("common-refresh")).clicked() {
                     self.available_drives = None;
                     self.selected_drive = None;
                 }
             } else {
                 ui.spinner();
-                ui.label("Searching for removable drives...");
+                ui.label(tr!("drives-searching"));
+            }
+            ui.checkbox(&mut self.batch_mode, tr!("fw-batch-mode"))
+                .on_hover_text(tr!("fw-batch-mode-hint"));
+            if self.batch_mode {
+                self.add_batch_log(ui);
             }
             stretch(ui);
-            if add_custom_next_button(ui, "Install Firmware", self.selected_drive.is_some())
+            if add_custom_next_button(ui, tr!("fw-install"), self.selected_drive.is_some())
                 .clicked()
             {
-                self.current_step = Step::InstallFirmware;
+                self.advance();
             }
         });
         Ok(())
@@ -316,12 +605,10 @@ impl SystemFirmwarePage {
 
     fn run_install_firmware(
         &mut self,
-        _app_state: &mut GlobalAppState,
+        app_state: &mut GlobalAppState,
         ui: &mut egui::Ui,
     ) -> anyhow::Result<()> {
-        if self.install_finished_receiver.is_none() {
-            let (tx, rx) = std::sync::mpsc::channel();
-            self.install_finished_receiver = Some(rx);
+        if self.install_task.is_none() && !self.drive_refused {
             let firmware_path = self
                 .firmware_path
                 .clone()
@@ -336,45 +623,324 @@ impl SystemFirmwarePage {
                 .to_str()
                 .ok_or(anyhow!("Could not convert filename to string."))?;
             let destination = drive.drive_path.join(filename);
-            self.background_thread = Some(std::thread::spawn(move || {
+            let allow_non_bootloader_drive = self.allow_non_bootloader_drive;
+            let from_build_dir = self.build_dir.is_some();
+            self.install_task = Some(Task::spawn(move || {
+                // Builds can be rerun while the wizard waits for a board, so check the file
+                // again right before flashing it.
+                if from_build_dir && let Err(e) = read_uf2_info(&firmware_path) {
+                    bail!("The firmware build is no longer a valid UF2 file: {e:#}");
+                }
+                if !allow_non_bootloader_drive && !is_uf2_bootloader(&drive) {
+                    return Ok(None);
+                }
+                // The bootloader drive goes away once the firmware is copied, so read it first.
+                let bootloader = read_bootloader_info(&drive.drive_path)
+                    .inspect_err(|e| log::info!("No bootloader info on {drive}: {e:#}"))
+                    .ok();
+                let serial_ports = list_serial_ports().unwrap_or_default();
                 std::fs::copy(firmware_path, destination)
-                    .expect("Failed to copy firmware to device.");
-                tx.send(())
-                    .expect("Failed to signal install finish to main thread.");
+                    .context("Failed to copy firmware to device.")?;
+                Ok(Some(FlashedBoard {
+                    serial_ports,
+                    bootloader,
+                }))
             }));
         }
 
-        if let Some(thread) = self.background_thread.take_if(|t| t.is_finished()) {
-            join_thread(thread)?;
-            self.install_finished_receiver.take().ok_or(anyhow!(
-                "Expected install_finished_receiver to not be None."
-            ))?;
-            self.current_step = Step::PostInstall;
+        if let Some(board) = Task::poll(&mut self.install_task)? {
+            match board {
+                Some(board) => {
+                    if let Some(ref release) = self.software_version
+                        && !self.use_local_file
+                    {
+                        app_state
+                            .compatibility
+                            .record_install(Component::Firmware, &release.tag_name);
+                    }
+                    let board_id = board
+                        .bootloader
+                        .as_ref()
+                        .and_then(|info| info.identifier())
+                        .unwrap_or_default()
+                        .to_string();
+                    log::info!("Installed firmware on board {board_id:?}");
+                    let boards = &mut app_state.session_report.boards;
+                    boards.push(BoardRecord {
+                        board_id,
+                        firmware: self.firmware_label(),
+                        finished_at: unix_now(),
+                        verified_version: None,
+                    });
+                    self.session_board_index = Some(boards.len() - 1);
+                    self.serial_ports_before_install = board.serial_ports;
+                    self.bootloader_info = board.bootloader;
+                    self.allow_non_bootloader_drive = false;
+                    self.advance();
+                }
+                None => self.drive_refused = true,
+            }
+        }
+
+        if self.drive_refused {
+            self.add_not_bootloader_warning(ui);
+            return Ok(());
         }
 
         column(ui, egui::Align::Center, |ui| {
             stretch(ui);
             ui.spinner();
-            ui.label("Installing firmware...");
+            ui.label(tr!("fw-installing"));
+            stretch(ui);
+        });
+        Ok(())
+    }
+
+    fn run_verify_firmware(
+        &mut self,
+        app_state: &mut GlobalAppState,
+        ui: &mut egui::Ui,
+    ) -> anyhow::Result<()> {
+        if self.verification.is_none() && self.verification_task.is_none() {
+            let known_ports = self.serial_ports_before_install.clone();
+            self.verification_task = Some(Task::spawn(move || {
+                Ok(wait_for_new_serial_port(&known_ports, REBOOT_TIMEOUT)
+                    .and_then(|port| query_firmware_version(&port))
+                    .map_err(|e| format!("{e:#}")))
+            }));
+        }
+
+        if let Some(verification) = Task::poll(&mut self.verification_task)? {
+            log::info!("Firmware verification: {verification:?}");
+            // What the board reports is more certain than what was copied onto it.
+            if let Ok(ref version) = verification {
+                app_state
+                    .compatibility
+                    .record_install(Component::Firmware, version);
+                if let Some(record) = self
+                    .session_board_index
+                    .take()
+                    .and_then(|index| app_state.session_report.boards.get_mut(index))
+                {
+                    record.verified_version = Some(version.clone());
+                }
+            }
+            self.verification = Some(verification);
+        }
+
+        let expected_version = self
+            .software_version
+            .as_ref()
+            .filter(|_| !self.use_local_file)
+            .map(|release| release.tag_name.clone());
+        if self.batch_mode
+            && let Some(verification) = self.verification.take()
+        {
+            self.log_batch_board(verification, expected_version);
+            return Ok(());
+        }
+        let board_id = self
+            .bootloader_info
+            .as_ref()
+            .and_then(|info| info.identifier())
+            .map(str::to_string);
+        column(ui, egui::Align::LEFT, |ui| {
+            heading(ui, tr!("fw-verifying"));
+            if let Some(board_id) = board_id {
+                ui.label(tr!("fw-board-id", board = board_id));
+            }
+            match self.verification {
+                None => {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(tr!("fw-waiting-restart"));
+                    });
+                }
+                Some(Ok(ref version)) => match expected_version {
+                    Some(ref expected) if expected != version => {
+                        ui.colored_label(
+                            palette(ui).error,
+                            tr!("fw-wrong-version", version = version, expected = expected),
+                        );
+                    }
+                    _ => {
+                        ui.colored_label(
+                            palette(ui).success,
+                            tr!("fw-running-version", version = version),
+                        );
+                    }
+                },
+                Some(Err(ref message)) => {
+                    ui.colored_label(palette(ui).error, message);
+                    ui.label(tr!("fw-check-later"));
+                    if ui.button(tr!("common-try-again")).clicked() {
+                        self.serial_ports_before_install.clear();
+                        self.verification = None;
+                    }
+                }
+            }
             stretch(ui);
+            if add_next_button(ui, self.verification.is_some()).clicked() {
+                self.verification = None;
+                self.advance();
+            }
         });
         Ok(())
     }
 
+    /// Names the firmware being installed for the session report.
+    fn firmware_label(&self) -> String {
+        match self.chosen_release() {
+            Some(release) => release.tag_name.clone(),
+            None => self
+                .firmware_path
+                .as_ref()
+                .and_then(|path| path.file_name())
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Records the board that was just flashed and goes back to waiting for the next one.
+    fn log_batch_board(
+        &mut self,
+        verification: Result<String, String>,
+        expected_version: Option<String>,
+    ) {
+        let record = FlashRecord {
+            bootloader: self.bootloader_info.take(),
+            firmware: self
+                .firmware_path
+                .as_ref()
+                .and_then(|path| path.file_name())
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            expected_version,
+            verification,
+            finished_at: unix_now(),
+        };
+        log::info!(
+            "Batch board {}: {}",
+            self.batch_log.records.len() + 1,
+            record.result_label()
+        );
+        play_cue(if record.is_verified() {
+            Cue::Success
+        } else {
+            Cue::Failure
+        });
+        self.batch_log.records.push(record);
+        self.selected_drive = None;
+        self.available_drives = None;
+        self.advance();
+    }
+
+    /// Shows the running count of boards flashed in batch mode, with the most recent ones.
+    fn add_batch_log(&mut self, ui: &mut egui::Ui) {
+        self.batch_log_dialog.update(ui.ctx());
+        if let Some(destination) = self.batch_log_dialog.take_picked() {
+            self.batch_log_status = Some(match self.batch_log.export_csv(&destination) {
+                Ok(()) => tr!("file-saved-to", path = destination.display().to_string()),
+                Err(e) => tr!("fw-log-save-failed", error = format!("{e:#}")),
+            });
+        }
+
+        ui.separator();
+        ui.label(tr!(
+            "fw-batch-summary",
+            flashed = self.batch_log.records.len(),
+            verified = self.batch_log.verified_count(),
+            failed = self.batch_log.failed_count(),
+        ));
+        egui::ScrollArea::vertical()
+            .max_height(120.0)
+            .stick_to_bottom(true)
+            .show(ui, |ui| {
+                for (number, record) in self.batch_log.records.iter().enumerate() {
+                    let board_id = match record.board_id() {
+                        "" => String::new(),
+                        board_id => format!(" ({board_id})"),
+                    };
+                    let color = if record.is_verified() {
+                        palette(ui).success
+                    } else {
+                        palette(ui).error
+                    };
+                    ui.colored_label(
+                        color,
+                        tr!(
+                            "fw-batch-board",
+                            number = number + 1,
+                            board = board_id,
+                            result = record.result_label(),
+                        ),
+                    );
+                }
+            });
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(
+                    !self.batch_log.records.is_empty(),
+                    egui::Button::new(tr!("fw-export-log")),
+                )
+                .on_hover_text(tr!("fw-export-log-hint", file = BOOTLOADER_INFO_FILE))
+                .clicked()
+            {
+                self.batch_log_dialog.save_file();
+            }
+            if let Some(ref status) = self.batch_log_status {
+                ui.label(status);
+            }
+        });
+    }
+
+    /// Explains that the selected drive does not look like a board in BOOTSEL mode and lets the
+    /// user pick another drive or install anyway.
+    fn add_not_bootloader_warning(&mut self, ui: &mut egui::Ui) {
+        let drive = self
+            .selected_drive
+            .as_ref()
+            .map(|d| d.to_string())
+            .unwrap_or_default();
+        column(ui, egui::Align::LEFT, |ui| {
+            heading(ui, tr!("bootsel-not-gizmo"));
+            ui.colored_label(
+                palette(ui).error,
+                tr!("bootsel-not-bootloader", drive = &drive),
+            );
+            ui.label(tr!("bootsel-hold"));
+            stretch(ui);
+            egui_alignments::row(ui, egui::Align::Center, |ui| {
+                stretch(ui);
+                if ui.button(tr!("bootsel-install-anyway")).clicked() {
+                    log::warn!("Installing onto {drive}, which is not a UF2 bootloader");
+                    self.allow_non_bootloader_drive = true;
+                    self.drive_refused = false;
+                }
+                if ui.button(tr!("bootsel-choose-another-drive")).clicked() {
+                    self.advance();
+                    self.drive_refused = false;
+                    self.selected_drive = None;
+                    self.available_drives = None;
+                }
+            });
+        });
+    }
+
     fn run_post_install(
         &mut self,
         _app_state: &mut GlobalAppState,
         ui: &mut egui::Ui,
     ) -> anyhow::Result<()> {
         column(ui, egui::Align::LEFT, |ui| {
-            ui.heading("Installation Complete");
-            ui.label("You can now disconnect the device from the computer.");
-            ui.label("To install system firmware onto another device, click \"Setup Another Device\". If you are done installing system firmware, you can close the wizard or click \"Start Over\".");
+            heading(ui, tr!("install-complete"));
+            ui.label(tr!("install-disconnect"));
+            ui.label(tr!("fw-another-hint"));
             stretch(ui);
-            if add_custom_next_button(ui, "Setup Another Device", true).clicked() {
+            if add_custom_next_button(ui, tr!("install-another-device"), true).clicked() {
                 self.selected_drive = None;
                 self.available_drives = None;
-                self.current_step = Step::ChooseDrive
+                self.advance();
             }
         });
         Ok(())
@@ -384,16 +950,131 @@ impl SystemFirmwarePage {
 impl Page for SystemFirmwarePage {
     fn run(&mut self, app_state: &mut GlobalAppState, ui: &mut egui::Ui) -> anyhow::Result<()> {
         match self.current_step {
-            Step::ChooseVersion => self.run_choose_version(app_state, ui),
+            Step::ChooseVersion => self.run_choose_version(app_state, ui).map_err(recoverable),
+            Step::ChooseBuildArtifact => self
+                .run_choose_build_artifact(app_state, ui)
+                .map_err(recoverable),
             Step::ChooseBoardRevision => self.run_choose_board_revision(app_state, ui),
-            Step::DownloadFirmware => self.run_download_firmware(app_state, ui),
-            Step::ChooseDrive => self.run_choose_drive(app_state, ui),
-            Step::InstallFirmware => self.run_install_firmware(app_state, ui),
+            Step::DownloadFirmware => self
+                .run_download_firmware(app_state, ui)
+                .map_err(recoverable),
+            Step::ChooseDrive => self.run_choose_drive(app_state, ui).map_err(recoverable),
+            Step::InstallFirmware => self
+                .run_install_firmware(app_state, ui)
+                .map_err(recoverable),
+            Step::VerifyFirmware => self.run_verify_firmware(app_state, ui).map_err(recoverable),
             Step::PostInstall => self.run_post_install(app_state, ui),
         }
     }
 
     fn get_title(&self) -> String {
-        "System Firmware Install".to_string()
+        tr!("fw-title")
+    }
+
+    fn step_progress(&self) -> Option<StepProgress> {
+        let state = self.flow_state();
+        StepProgress::from_flow(
+            Step::ChooseVersion,
+            self.current_step,
+            |step| Some(step.next(&state)),
+            Step::label,
+        )
+    }
+
+    fn retry_step(&mut self) {
+        self.releases_task = None;
+        self.download_task = None;
+        self.drive_list_task = None;
+        self.install_task = None;
+        self.verification_task = None;
+        self.build_artifacts_task = None;
+        match self.current_step {
+            Step::ChooseVersion => self.available_releases = None,
+            Step::ChooseBuildArtifact => self.build_artifacts = None,
+            Step::ChooseDrive => self.available_drives = None,
+            _ => {}
+        }
+    }
+
+    fn handle_event(&mut self, app_state: &mut GlobalAppState, event: &AppEvent) {
+        match event {
+            AppEvent::DriveAdded(_) | AppEvent::DriveRemoved(_) => {
+                if let AppEvent::DriveRemoved(drive) = event
+                    && self.selected_drive.as_ref() == Some(drive)
+                {
+                    self.selected_drive = None;
+                }
+                // Re-list drives so the picker reflects what is plugged in right now.
+                if self.current_step == Step::ChooseDrive && self.drive_list_task.is_none() {
+                    self.available_drives = None;
+                }
+            }
+            // Fetch the release list again so the new version shows up in the picker.
+            AppEvent::NewReleaseAvailable { repo, .. }
+                if *repo == app_state.sources.firmware
+                    && self.current_step == Step::ChooseVersion
+                    && self.releases_task.is_none() =>
+            {
+                self.available_releases = None;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Shows one UF2 file from a build directory. Files that can't be flashed onto a Gizmo are shown
+/// with the reason, but can't be selected.
+fn add_build_artifact(
+    ui: &mut egui::Ui,
+    build_dir: &std::path::Path,
+    artifact: &BuildArtifact,
+    firmware_path: &mut Option<PathBuf>,
+) {
+    let name = artifact
+        .path
+        .strip_prefix(build_dir)
+        .unwrap_or(&artifact.path)
+        .display()
+        .to_string();
+    let age = artifact
+        .modified
+        .and_then(|modified| modified.elapsed().ok())
+        .map(|age| match age.as_secs() {
+            0..60 => tr!("fw-built-now"),
+            secs @ 60..3600 => tr!("fw-built-minutes", minutes = secs / 60),
+            secs @ 3600..86400 => tr!("fw-built-hours", hours = secs / 3600),
+            secs => tr!("fw-built-days", days = secs / 86400),
+        })
+        .unwrap_or_default();
+    match artifact.info {
+        Ok(ref info) if info.is_for_rp2040() => {
+            let mut details = vec![
+                crate::utils::cache::format_size(info.payload_bytes),
+                tr!(
+                    "fw-start-address",
+                    address = format!("{:#010x}", info.start_address)
+                ),
+            ];
+            details.extend(
+                info.version
+                    .iter()
+                    .map(|v| tr!("fw-artifact-version", version = v)),
+            );
+            details.extend(info.device.iter().cloned());
+            details.push(age);
+            ui.selectable_value(
+                firmware_path,
+                Some(artifact.path.clone()),
+                format!("{name} ({})", details.join(", ")),
+            );
+        }
+        Ok(ref info) => {
+            ui.add_enabled(false, egui::Button::selectable(false, &name))
+                .on_disabled_hover_text(tr!("fw-wrong-chip", family = info.family_name()));
+        }
+        Err(ref message) => {
+            ui.add_enabled(false, egui::Button::selectable(false, &name))
+                .on_disabled_hover_text(message);
+        }
     }
 }
@@ -0,0 +1,93 @@
+//! The log kept while flashing one board after another in batch mode.
+
+use crate::i18n::tr;
+use crate::utils::csv::csv_field;
+use crate::utils::uf2::BootloaderInfo;
+use anyhow::{Context, Result};
+use std::path::Path;
+
+/// One board flashed in batch mode.
+#[derive(Debug, Clone)]
+pub struct FlashRecord {
+    /// What the board said about itself before it was flashed, if it could be read.
+    pub bootloader: Option<BootloaderInfo>,
+    /// File name of the firmware copied onto the board.
+    pub firmware: String,
+    /// The release being installed, unless it is a local file.
+    pub expected_version: Option<String>,
+    /// The version the board reported after restarting, or why it couldn't be read.
+    pub verification: Result<String, String>,
+    /// Seconds since the Unix epoch.
+    pub finished_at: u64,
+}
+
+impl FlashRecord {
+    pub fn is_verified(&self) -> bool {
+        match (&self.verification, &self.expected_version) {
+            (Ok(version), Some(expected)) => version == expected,
+            (Ok(_), None) => true,
+            (Err(_), _) => false,
+        }
+    }
+
+    pub fn serial_number(&self) -> &str {
+        self.bootloader
+            .as_ref()
+            .and_then(|info| info.serial_number.as_deref())
+            .unwrap_or_default()
+    }
+
+    pub fn board_id(&self) -> &str {
+        self.bootloader
+            .as_ref()
+            .and_then(|info| info.identifier())
+            .unwrap_or_default()
+    }
+
+    pub fn result_label(&self) -> String {
+        match self.verification {
+            Ok(_) if self.is_verified() => tr!("fw-result-verified"),
+            Ok(ref version) => tr!("fw-result-wrong-version", version = version),
+            Err(ref message) => tr!("fw-result-not-verified", error = message),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct BatchLog {
+    pub records: Vec<FlashRecord>,
+}
+
+impl BatchLog {
+    pub fn verified_count(&self) -> usize {
+        self.records.iter().filter(|r| r.is_verified()).count()
+    }
+
+    pub fn failed_count(&self) -> usize {
+        self.records.len() - self.verified_count()
+    }
+
+    /// Writes one row per board, in the order they were flashed.
+    pub fn export_csv(&self, destination: &Path) -> Result<()> {
+        let mut lines = vec![
+            "Board,Serial Number,Board ID,Bootloader,Firmware,Reported Version,Result,Finished At"
+                .to_string(),
+        ];
+        for (number, record) in self.records.iter().enumerate() {
+            let bootloader = record.bootloader.clone().unwrap_or_default();
+            let fields = [
+                (number + 1).to_string(),
+                record.serial_number().to_string(),
+                bootloader.board_id.unwrap_or_default(),
+                bootloader.bootloader,
+                record.firmware.clone(),
+                record.verification.clone().unwrap_or_default(),
+                record.result_label(),
+                record.finished_at.to_string(),
+            ];
+            lines.push(fields.map(|field| csv_field(&field)).join(","));
+        }
+        std::fs::write(destination, lines.join("\n") + "\n")
+            .with_context(|| format!("Failed to write {:?}", destination))
+    }
+}
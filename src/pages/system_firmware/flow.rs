@@ -0,0 +1,205 @@
+//! Step transitions for the system firmware page, kept separate from the egui rendering code.
+
+use crate::i18n::tr;
+use crate::utils::github::{GithubRelease, GithubReleaseAsset};
+
+const FIRMWARE_PREFIX: &str = "gss-";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    ChooseVersion,
+    ChooseBuildArtifact,
+    ChooseBoardRevision,
+    DownloadFirmware,
+    ChooseDrive,
+    InstallFirmware,
+    VerifyFirmware,
+    PostInstall,
+}
+
+/// The parts of the page state that decide which step comes next.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FlowState {
+    pub use_local_file: bool,
+    /// The firmware comes from a developer's local build directory.
+    pub use_build_dir: bool,
+    /// The selected drive is not a UF2 bootloader and the user chose to pick another one.
+    pub choose_other_drive: bool,
+    /// The release only has firmware for one board revision, so there is nothing to choose.
+    pub single_revision: bool,
+    /// Boards are flashed one after another without stopping after each one.
+    pub batch_mode: bool,
+}
+
+impl Step {
+    /// Returns the step after this one. After installing, the page loops back to flash another
+    /// device.
+    pub fn next(self, state: &FlowState) -> Step {
+        match self {
+            Step::ChooseVersion if state.use_build_dir => Step::ChooseBuildArtifact,
+            Step::ChooseVersion if state.use_local_file => Step::ChooseDrive,
+            Step::ChooseBuildArtifact => Step::ChooseDrive,
+            Step::ChooseVersion if state.single_revision => Step::DownloadFirmware,
+            Step::ChooseVersion => Step::ChooseBoardRevision,
+            Step::ChooseBoardRevision => Step::DownloadFirmware,
+            Step::DownloadFirmware => Step::ChooseDrive,
+            Step::ChooseDrive => Step::InstallFirmware,
+            Step::InstallFirmware if state.choose_other_drive => Step::ChooseDrive,
+            Step::InstallFirmware => Step::VerifyFirmware,
+            Step::VerifyFirmware if state.batch_mode => Step::ChooseDrive,
+            Step::VerifyFirmware => Step::PostInstall,
+            Step::PostInstall => Step::ChooseDrive,
+        }
+    }
+
+    pub fn label(self) -> String {
+        match self {
+            Step::ChooseVersion => tr!("step-version"),
+            Step::ChooseBuildArtifact => tr!("step-build"),
+            Step::ChooseBoardRevision => tr!("step-board-revision"),
+            Step::DownloadFirmware => tr!("step-download"),
+            Step::ChooseDrive => tr!("step-device"),
+            Step::InstallFirmware => tr!("step-install"),
+            Step::VerifyFirmware => tr!("step-verify"),
+            Step::PostInstall => tr!("step-done"),
+        }
+    }
+}
+
+fn firmware_suffix(release: &GithubRelease) -> String {
+    format!("-{}.uf2", release.tag_name)
+}
+
+/// Returns the firmware files in `release`, one per supported board revision.
+pub fn firmware_assets(release: &GithubRelease) -> Vec<GithubReleaseAsset> {
+    let suffix = firmware_suffix(release);
+    release
+        .assets
+        .iter()
+        .filter(|asset| asset.name.starts_with(FIRMWARE_PREFIX) && asset.name.ends_with(&suffix))
+        .cloned()
+        .collect()
+}
+
+/// Returns the board revision a firmware file is built for, e.g. "v01.00".
+pub fn board_revision_label<'a>(asset: &'a GithubReleaseAsset, release: &GithubRelease) -> &'a str {
+    asset
+        .name
+        .trim_start_matches(FIRMWARE_PREFIX)
+        .trim_end_matches(&firmware_suffix(release))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn choose_version_depends_on_where_the_firmware_comes_from() {
+        let cases = [
+            (FlowState::default(), Step::ChooseBoardRevision),
+            (
+                FlowState {
+                    single_revision: true,
+                    ..Default::default()
+                },
+                Step::DownloadFirmware,
+            ),
+            (
+                FlowState {
+                    use_local_file: true,
+                    single_revision: true,
+                    ..Default::default()
+                },
+                Step::ChooseDrive,
+            ),
+            (
+                FlowState {
+                    use_build_dir: true,
+                    use_local_file: true,
+                    ..Default::default()
+                },
+                Step::ChooseBuildArtifact,
+            ),
+        ];
+        for (state, expected) in cases {
+            assert_eq!(Step::ChooseVersion.next(&state), expected, "{state:?}");
+        }
+    }
+
+    #[test]
+    fn steps_without_choices_go_straight_on() {
+        let state = FlowState::default();
+        assert_eq!(Step::ChooseBuildArtifact.next(&state), Step::ChooseDrive);
+        assert_eq!(
+            Step::ChooseBoardRevision.next(&state),
+            Step::DownloadFirmware
+        );
+        assert_eq!(Step::DownloadFirmware.next(&state), Step::ChooseDrive);
+        assert_eq!(Step::ChooseDrive.next(&state), Step::InstallFirmware);
+        assert_eq!(Step::PostInstall.next(&state), Step::ChooseDrive);
+    }
+
+    #[test]
+    fn install_goes_back_when_another_drive_is_wanted() {
+        let other_drive = FlowState {
+            choose_other_drive: true,
+            ..Default::default()
+        };
+        assert_eq!(Step::InstallFirmware.next(&other_drive), Step::ChooseDrive);
+        assert_eq!(
+            Step::InstallFirmware.next(&FlowState::default()),
+            Step::VerifyFirmware
+        );
+    }
+
+    #[test]
+    fn batch_mode_skips_the_finished_screen() {
+        let batch = FlowState {
+            batch_mode: true,
+            ..Default::default()
+        };
+        assert_eq!(Step::VerifyFirmware.next(&batch), Step::ChooseDrive);
+        assert_eq!(
+            Step::VerifyFirmware.next(&FlowState::default()),
+            Step::PostInstall
+        );
+    }
+
+    fn release_with_assets(tag_name: &str, asset_names: &[&str]) -> GithubRelease {
+        GithubRelease {
+            name: tag_name.to_string(),
+            tag_name: tag_name.to_string(),
+            assets: asset_names
+                .iter()
+                .map(|name| GithubReleaseAsset {
+                    name: name.to_string(),
+                    browser_download_url: format!("https://example.com/{name}"),
+                    digest: None,
+                    size: None,
+                    local_path: None,
+                })
+                .collect(),
+            prerelease: false,
+            draft: false,
+            body: None,
+            latest: false,
+        }
+    }
+
+    #[test]
+    fn firmware_assets_match_the_release_tag() {
+        let release = release_with_assets(
+            "v1.2",
+            &[
+                "gss-v01.00-v1.2.uf2",
+                "gss-v02.00-v1.2.uf2",
+                "gss-v01.00-v1.1.uf2",
+                "ds-ramdisk.zip",
+            ],
+        );
+        let assets = firmware_assets(&release);
+        let names: Vec<&str> = assets.iter().map(|asset| asset.name.as_str()).collect();
+        assert_eq!(names, ["gss-v01.00-v1.2.uf2", "gss-v02.00-v1.2.uf2"]);
+        assert_eq!(board_revision_label(&assets[1], &release), "v02.00");
+    }
+}
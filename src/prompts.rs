@@ -0,0 +1,182 @@
+use crate::i18n::tr;
+use crate::widgets::accessibility::heading;
+use anyhow::{Result, anyhow};
+use std::collections::VecDeque;
+use std::sync::mpsc::{Receiver, Sender};
+
+/// A question a worker thread needs the user to answer before it can continue.
+#[derive(Debug, Clone)]
+pub enum PromptKind {
+    Confirm,
+    Choose(Vec<String>),
+    Input { default: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PromptAnswer {
+    Confirmed,
+    Chose(usize),
+    Entered(String),
+    Cancelled,
+}
+
+struct PendingPrompt {
+    title: String,
+    message: String,
+    kind: PromptKind,
+    responder: Sender<PromptAnswer>,
+}
+
+/// Cloneable handle worker threads use to ask the user questions. Each call blocks until the
+/// prompt is answered, and fails if the page that started the work is closed first.
+#[derive(Clone)]
+pub struct Prompter {
+    sender: Sender<PendingPrompt>,
+    ctx: egui::Context,
+}
+
+impl Prompter {
+    fn ask(&self, title: &str, message: &str, kind: PromptKind) -> Result<PromptAnswer> {
+        let (responder, answer) = std::sync::mpsc::channel();
+        self.sender
+            .send(PendingPrompt {
+                title: title.to_string(),
+                message: message.to_string(),
+                kind,
+                responder,
+            })
+            .map_err(|_| anyhow!("Could not show prompt \"{title}\"."))?;
+        self.ctx.request_repaint();
+        answer
+            .recv()
+            .map_err(|_| anyhow!("Prompt \"{title}\" was closed without an answer."))
+    }
+
+    /// Returns true if the user clicked "Yes".
+    pub fn confirm(&self, title: &str, message: &str) -> Result<bool> {
+        Ok(self.ask(title, message, PromptKind::Confirm)? == PromptAnswer::Confirmed)
+    }
+
+    /// Returns the index of the chosen option, or `None` if the user cancelled.
+    pub fn choose(
+        &self,
+        title: &str,
+        message: &str,
+        options: Vec<String>,
+    ) -> Result<Option<usize>> {
+        match self.ask(title, message, PromptKind::Choose(options))? {
+            PromptAnswer::Chose(index) => Ok(Some(index)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Returns the text the user entered, or `None` if they cancelled.
+    pub fn input(&self, title: &str, message: &str, default: &str) -> Result<Option<String>> {
+        let kind = PromptKind::Input {
+            default: default.to_string(),
+        };
+        match self.ask(title, message, kind)? {
+            PromptAnswer::Entered(text) => Ok(Some(text)),
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Shows prompts from worker threads one at a time as modals on the UI thread.
+pub struct PromptQueue {
+    prompter: Prompter,
+    receiver: Receiver<PendingPrompt>,
+    pending: VecDeque<PendingPrompt>,
+    input_text: Option<String>,
+}
+
+impl PromptQueue {
+    pub fn new(ctx: &egui::Context) -> Self {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        Self {
+            prompter: Prompter {
+                sender,
+                ctx: ctx.clone(),
+            },
+            receiver,
+            pending: VecDeque::new(),
+            input_text: None,
+        }
+    }
+
+    pub fn prompter(&self) -> Prompter {
+        self.prompter.clone()
+    }
+
+    /// Number of prompts waiting to be shown, including the one on screen.
+    pub fn waiting(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Drops every waiting prompt, which makes the workers that asked them fail.
+    pub fn cancel_all(&mut self) {
+        self.pending.extend(self.receiver.try_iter());
+        if !self.pending.is_empty() {
+            log::info!("Cancelling {} unanswered prompt(s)", self.pending.len());
+        }
+        self.pending.clear();
+        self.input_text = None;
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) {
+        self.pending.extend(self.receiver.try_iter());
+        let Some(prompt) = self.pending.front() else {
+            return;
+        };
+        let mut answer = None;
+        egui::Modal::new(egui::Id::new("PromptModal")).show(ctx, |ui| {
+            ui.set_max_width(400.0);
+            heading(ui, &prompt.title);
+            ui.label(&prompt.message);
+            match &prompt.kind {
+                PromptKind::Confirm => {
+                    egui_alignments::row(ui, egui::Align::Center, |ui| {
+                        egui_alignments::stretch(ui);
+                        if ui.button(tr!("common-yes")).clicked() {
+                            answer = Some(PromptAnswer::Confirmed);
+                        }
+                        if ui.button(tr!("common-no")).clicked() {
+                            answer = Some(PromptAnswer::Cancelled);
+                        }
+                    });
+                }
+                PromptKind::Choose(options) => {
+                    for (index, option) in options.iter().enumerate() {
+                        if ui.button(option).clicked() {
+                            answer = Some(PromptAnswer::Chose(index));
+                        }
+                    }
+                    if ui.button(tr!("common-cancel")).clicked() {
+                        answer = Some(PromptAnswer::Cancelled);
+                    }
+                }
+                PromptKind::Input { default } => {
+                    let text = self.input_text.get_or_insert_with(|| default.clone());
+                    ui.text_edit_singleline(text);
+                    egui_alignments::row(ui, egui::Align::Center, |ui| {
+                        egui_alignments::stretch(ui);
+                        if ui.button(tr!("common-ok")).clicked() {
+                            answer = Some(PromptAnswer::Entered(text.clone()));
+                        }
+                        if ui.button(tr!("common-cancel")).clicked() {
+                            answer = Some(PromptAnswer::Cancelled);
+                        }
+                    });
+                }
+            }
+        });
+        if let Some(answer) = answer
+            && let Some(prompt) = self.pending.pop_front()
+        {
+            log::info!("Prompt \"{}\" answered: {answer:?}", prompt.title);
+            // The worker may have given up waiting, in which case there is nobody to tell.
+            let _ = prompt.responder.send(answer);
+            self.input_text = None;
+        }
+    }
+}
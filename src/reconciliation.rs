@@ -0,0 +1,100 @@
+use crate::settings::Settings;
+use crate::utils::github::AUTH_TOKEN_ENV_VAR;
+use crate::utils::release_poller::MIN_POLL_INTERVAL_MINUTES;
+
+/// Where a configuration directive came from, highest precedence first. When two sources
+/// disagree, the one listed first wins:
+///
+/// 1. Limits built into the wizard, which protect GitHub and the cards from bad values.
+/// 2. Settings saved from the Settings page.
+/// 3. Environment variables of the wizard's process.
+/// 4. Files on disk, such as versions kept in the download cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    BuiltIn,
+    Settings,
+    Environment,
+    Disk,
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Source::BuiltIn => "Built-in limits",
+            Source::Settings => "Settings",
+            Source::Environment => "Environment variables",
+            Source::Disk => "Files on disk",
+        })
+    }
+}
+
+/// Two sources that disagree about one directive, and how the disagreement was settled.
+#[derive(Debug, Clone)]
+pub struct Conflict {
+    pub directive: String,
+    pub winner: Source,
+    pub loser: Source,
+    pub details: String,
+    pub resolution: String,
+}
+
+/// Compares the loaded settings with the other configuration sources and settles each
+/// disagreement by [`Source`] precedence. Called once at startup, after the settings are
+/// loaded and before they are handed out. Resolutions only change the settings in memory.
+pub fn reconcile(settings: &mut Settings) -> Vec<Conflict> {
+    let mut conflicts = vec![];
+
+    let token = settings.github_token.trim();
+    if !token.is_empty()
+        && let Ok(env_token) = std::env::var(AUTH_TOKEN_ENV_VAR)
+        && !env_token.is_empty()
+        && env_token != token
+    {
+        conflicts.push(Conflict {
+            directive: "GitHub token".to_string(),
+            winner: Source::Settings,
+            loser: Source::Environment,
+            details: format!(
+                "Settings and the {AUTH_TOKEN_ENV_VAR} environment variable hold different tokens."
+            ),
+            resolution: "The token from Settings is used.".to_string(),
+        });
+    }
+
+    let interval = settings.release_poll_interval_minutes;
+    if interval != 0 && interval < MIN_POLL_INTERVAL_MINUTES {
+        conflicts.push(Conflict {
+            directive: "Release check interval".to_string(),
+            winner: Source::BuiltIn,
+            loser: Source::Settings,
+            details: format!(
+                "Settings ask to check for releases every {interval} minutes, below the minimum of {MIN_POLL_INTERVAL_MINUTES}."
+            ),
+            resolution: format!("Releases are checked every {MIN_POLL_INTERVAL_MINUTES} minutes."),
+        });
+        settings.release_poll_interval_minutes = MIN_POLL_INTERVAL_MINUTES;
+    }
+
+    if !settings.use_persistent_cache && !settings.pinned_cache_versions.is_empty() {
+        conflicts.push(Conflict {
+            directive: "Cache pins".to_string(),
+            winner: Source::Settings,
+            loser: Source::Disk,
+            details: format!(
+                "{} cached versions are pinned, but the persistent cache is turned off.",
+                settings.pinned_cache_versions.len()
+            ),
+            resolution: "Downloads go to a temporary folder. The pinned versions stay in the cache until it is turned back on.".to_string(),
+        });
+    }
+
+    for conflict in &conflicts {
+        log::warn!(
+            "Configuration conflict in {}: {} {}",
+            conflict.directive,
+            conflict.details,
+            conflict.resolution
+        );
+    }
+    conflicts
+}
@@ -0,0 +1,313 @@
+use crate::i18n::tr;
+use crate::migrations::VersionedFile;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Name of the directory used for this app under the platform config and cache directories.
+pub const APP_DIR_NAME: &str = "best-gizmo-setup-wizard";
+
+/// Describes the settings file to the startup migrations.
+pub const SETTINGS_FILE: VersionedFile = VersionedFile {
+    name: "settings",
+    path: settings_path,
+    migrations: &[],
+};
+
+/// User preferences persisted between runs of the wizard.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct Settings {
+    pub format_version: u32,
+    /// Keep downloaded release assets in the user cache directory instead of a temporary directory.
+    pub use_persistent_cache: bool,
+    /// Cached versions, as `<owner>/<repo>/<version>`, that clearing the cache leaves in place.
+    pub pinned_cache_versions: Vec<String>,
+    /// How often to check GitHub for new releases while the app is open. Zero disables checking.
+    pub release_poll_interval_minutes: u32,
+    /// Personal access token for the GitHub API. Empty means unauthenticated requests.
+    pub github_token: String,
+    /// Most releases listed from each repository, newest first. Zero lists them all.
+    pub max_releases: u32,
+    pub branding: Branding,
+    /// Copy getting-started documentation into a docs folder on each driver station card.
+    pub copy_docs_to_cards: bool,
+    /// Where to download the documentation from. Empty means the PDFs attached to the release.
+    pub docs_url: String,
+    /// Sign exported session reports with the hub's key so officials can check them.
+    pub sign_session_reports: bool,
+    /// Shared folder that this station's status is written to for event dashboards. `None`
+    /// turns the export off.
+    pub status_export_dir: Option<std::path::PathBuf>,
+    /// Open the student program page in its one-click mode, for classrooms where students
+    /// reflash their own boards.
+    pub simple_student_mode: bool,
+    /// Format cards with the wizard's own FAT32 formatter instead of the system's tools.
+    pub native_formatting: bool,
+    /// Warn when a driver station card has been set up more times than this, since worn cards
+    /// fail without warning. Zero turns the warning off.
+    pub card_write_warning_threshold: u32,
+    pub sounds: SoundSettings,
+    /// Show simulated drives and only pretend to write to them, for demonstrating the wizard
+    /// on a projector.
+    pub demo_mode: bool,
+    /// Scale of the whole interface, changed with Ctrl +/-.
+    pub zoom_factor: f32,
+    /// Size of the window when the wizard was last closed, in points at 100% zoom. `None` opens
+    /// it at the default size.
+    pub window_size: Option<[f32; 2]>,
+    /// Enlarge buttons and list rows for touchscreens. They are also enlarged whenever the
+    /// screen is touched.
+    pub large_touch_targets: bool,
+    /// Language of the interface. `None` follows the operating system.
+    pub language: Option<crate::i18n::Language>,
+    pub theme: crate::theme::ThemeSetting,
+    pub network: NetworkSettings,
+    pub mirror: MirrorSettings,
+    pub team_number_rules: TeamNumberRules,
+    pub software_sources: SoftwareSources,
+}
+
+/// Lets a hub put its own name, logo, and support contact on the wizard.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct Branding {
+    pub hub_name: String,
+    pub logo_path: Option<std::path::PathBuf>,
+    /// Who volunteers should contact when something goes wrong, e.g. a name and phone number.
+    pub support_contact: String,
+}
+
+/// How the wizard reaches the internet on networks that only allow traffic through a proxy, such
+/// as many school districts.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct NetworkSettings {
+    /// Proxy used for every request, e.g. `http://proxy.example.org:8080`. Empty uses the
+    /// system's proxy settings.
+    pub proxy_url: String,
+    /// PEM file of certificate authorities to trust as well as the system's, for networks that
+    /// inspect HTTPS traffic with their own certificate.
+    pub ca_bundle_path: Option<std::path::PathBuf>,
+    /// Connections used at once to download large files, which is faster on slow networks like
+    /// hotel Wi-Fi. One downloads each file in a single request.
+    pub download_connections: u32,
+    /// Most all downloads together may use, in megabits per second, to leave room for scoring
+    /// systems and streams on a shared venue network. Zero means no limit.
+    pub download_limit_mbps: u32,
+}
+
+impl Default for NetworkSettings {
+    fn default() -> Self {
+        Self {
+            proxy_url: String::new(),
+            ca_bundle_path: None,
+            download_connections: crate::utils::file_download::DEFAULT_DOWNLOAD_CONNECTIONS,
+            download_limit_mbps: 0,
+        }
+    }
+}
+
+/// A server with copies of the GitHub releases, for venues that block github.com.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct MirrorSettings {
+    /// Base URL of the mirror, e.g. `https://mirror.example.org/gizmo`. Empty means releases
+    /// only come from GitHub.
+    pub url: String,
+    /// Try the mirror before GitHub. Otherwise it is only used when GitHub fails.
+    pub prefer_mirror: bool,
+}
+
+/// A GitHub repository that releases are installed from.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SoftwareRepo {
+    pub owner: String,
+    pub name: String,
+}
+
+impl SoftwareRepo {
+    fn new(owner: &str, name: &str) -> Self {
+        Self {
+            owner: owner.to_string(),
+            name: name.to_string(),
+        }
+    }
+}
+
+impl SoftwareRepo {
+    /// Returns why this can't be a GitHub repository, or `None` if it looks fine.
+    pub fn problem(&self) -> Option<String> {
+        let is_valid = |part: &str| {
+            !part.is_empty()
+                && part
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        };
+        (!is_valid(&self.owner) || !is_valid(&self.name))
+            .then(|| tr!("settings-repo-invalid", repo = self.to_string()))
+    }
+}
+
+impl std::fmt::Display for SoftwareRepo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.owner, self.name)
+    }
+}
+
+/// Where each kind of software is released. Advanced users can point these at a fork to test
+/// their own builds.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct SoftwareSources {
+    pub driver_station: SoftwareRepo,
+    pub firmware: SoftwareRepo,
+    pub starter_code: SoftwareRepo,
+}
+
+impl SoftwareSources {
+    pub fn problem(&self) -> Option<String> {
+        [&self.driver_station, &self.firmware, &self.starter_code]
+            .into_iter()
+            .find_map(SoftwareRepo::problem)
+    }
+}
+
+impl Default for SoftwareSources {
+    fn default() -> Self {
+        Self {
+            driver_station: SoftwareRepo::new("gizmo-platform", "gizmo"),
+            firmware: SoftwareRepo::new("gizmo-platform", "firmware"),
+            starter_code: SoftwareRepo::new("gizmo-platform", "CircuitPython_Gizmo"),
+        }
+    }
+}
+
+/// What a team number registered with the hub looks like, so typos are caught before a card is
+/// set up for a team that doesn't exist.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct TeamNumberRules {
+    pub min_digits: u32,
+    pub max_digits: u32,
+    pub lowest: u32,
+    pub highest: u32,
+}
+
+impl Default for TeamNumberRules {
+    fn default() -> Self {
+        Self {
+            min_digits: 1,
+            max_digits: 4,
+            lowest: 1,
+            highest: 9999,
+        }
+    }
+}
+
+impl TeamNumberRules {
+    /// Returns why `team_number` doesn't follow the rules, or `None` if it does.
+    pub fn problem(&self, team_number: &str) -> Option<String> {
+        if team_number.is_empty() || !team_number.chars().all(|c| c.is_ascii_digit()) {
+            return Some(tr!("team-not-number", team = team_number));
+        }
+        let digits = team_number.len() as u32;
+        if digits < self.min_digits || digits > self.max_digits {
+            return Some(tr!(
+                "team-wrong-digits",
+                team = team_number,
+                min = self.min_digits,
+                max = self.max_digits
+            ));
+        }
+        // Too many digits for a u64 is out of range whatever the limits are.
+        let in_range = team_number
+            .parse::<u64>()
+            .is_ok_and(|number| (self.lowest as u64..=self.highest as u64).contains(&number));
+        if !in_range {
+            return Some(tr!(
+                "team-out-of-range",
+                team = team_number,
+                lowest = self.lowest,
+                highest = self.highest
+            ));
+        }
+        None
+    }
+}
+
+/// How loud the cues played when a long step finishes are.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SoundVolume {
+    Off,
+    #[default]
+    Quiet,
+    Loud,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct SoundSettings {
+    pub volume: SoundVolume,
+    /// WAV files played instead of the built-in cues. They play at their own volume.
+    pub success_sound: Option<std::path::PathBuf>,
+    pub failure_sound: Option<std::path::PathBuf>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            format_version: SETTINGS_FILE.current_version(),
+            use_persistent_cache: true,
+            pinned_cache_versions: vec![],
+            release_poll_interval_minutes: 30,
+            github_token: String::new(),
+            max_releases: 0,
+            branding: Branding::default(),
+            copy_docs_to_cards: false,
+            docs_url: String::new(),
+            sign_session_reports: false,
+            status_export_dir: None,
+            simple_student_mode: false,
+            native_formatting: true,
+            card_write_warning_threshold: 50,
+            sounds: SoundSettings::default(),
+            demo_mode: false,
+            zoom_factor: 1.0,
+            window_size: None,
+            large_touch_targets: false,
+            language: None,
+            theme: crate::theme::ThemeSetting::default(),
+            network: NetworkSettings::default(),
+            mirror: MirrorSettings::default(),
+            team_number_rules: TeamNumberRules::default(),
+            software_sources: SoftwareSources::default(),
+        }
+    }
+}
+
+impl Settings {
+    /// Loads settings from disk, falling back to defaults if none have been saved yet.
+    pub fn load() -> Self {
+        settings_path()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = settings_path().ok_or(anyhow::anyhow!("Could not find config directory."))?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to save settings to {:?}", path))
+    }
+}
+
+pub fn config_dir() -> Option<std::path::PathBuf> {
+    dirs::config_dir().map(|dir| dir.join(APP_DIR_NAME))
+}
+
+fn settings_path() -> Option<std::path::PathBuf> {
+    config_dir().map(|dir| dir.join("settings.json"))
+}
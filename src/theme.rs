@@ -0,0 +1,88 @@
+//! Colors used across the wizard, kept in one place so pages stay readable in both light and
+//! dark mode.
+
+use crate::i18n::tr;
+use egui::Color32;
+use serde::{Deserialize, Serialize};
+
+/// Navy from the BEST Robotics logo, behind the top bar.
+pub const BEST_NAVY: Color32 = Color32::from_rgb(0x00, 0x1E, 0x62);
+/// Fill of the button that moves a page on to its next step.
+pub const NEXT_BUTTON: Color32 = Color32::from_rgb(0x71, 0xCC, 0x98);
+pub const NEXT_BUTTON_DISABLED: Color32 = Color32::from_rgb(0xA0, 0xA0, 0xA0);
+/// Text on buttons with a brand-colored or white fill, which keep their colors in dark mode.
+pub const TEXT_ON_BRAND: Color32 = Color32::from_gray(40);
+
+/// Whether the wizard is shown light or dark.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ThemeSetting {
+    /// The wizard was always light before it could be changed, so it stays the default.
+    #[default]
+    Light,
+    Dark,
+    /// Follows the operating system, switching along with it.
+    System,
+}
+
+impl ThemeSetting {
+    pub const ALL: [ThemeSetting; 3] = [
+        ThemeSetting::Light,
+        ThemeSetting::Dark,
+        ThemeSetting::System,
+    ];
+
+    pub fn label(self) -> String {
+        match self {
+            ThemeSetting::Light => tr!("theme-light"),
+            ThemeSetting::Dark => tr!("theme-dark"),
+            ThemeSetting::System => tr!("theme-system"),
+        }
+    }
+}
+
+pub fn apply_theme(ctx: &egui::Context, theme: ThemeSetting) {
+    ctx.set_theme(match theme {
+        ThemeSetting::Light => egui::ThemePreference::Light,
+        ThemeSetting::Dark => egui::ThemePreference::Dark,
+        ThemeSetting::System => egui::ThemePreference::System,
+    });
+}
+
+/// Colors for status text, in versions that read well on the current background.
+pub struct Palette {
+    pub error: Color32,
+    pub warning: Color32,
+    pub success: Color32,
+    /// Work in progress.
+    pub active: Color32,
+    /// Work that hasn't started yet.
+    pub pending: Color32,
+    /// Behind a block of error text.
+    pub error_background: Color32,
+}
+
+const LIGHT_PALETTE: Palette = Palette {
+    error: Color32::DARK_RED,
+    warning: Color32::ORANGE,
+    success: Color32::DARK_GREEN,
+    active: Color32::DARK_BLUE,
+    pending: Color32::GRAY,
+    error_background: Color32::from_rgb(0xFD, 0xEC, 0xEC),
+};
+
+const DARK_PALETTE: Palette = Palette {
+    error: Color32::from_rgb(0xFF, 0x70, 0x70),
+    warning: Color32::ORANGE,
+    success: Color32::from_rgb(0x70, 0xD0, 0x80),
+    active: Color32::from_rgb(0x80, 0xAA, 0xFF),
+    pending: Color32::GRAY,
+    error_background: Color32::from_rgb(0x4A, 0x1E, 0x1E),
+};
+
+pub fn palette(ui: &egui::Ui) -> &'static Palette {
+    if ui.visuals().dark_mode {
+        &DARK_PALETTE
+    } else {
+        &LIGHT_PALETTE
+    }
+}
@@ -1,5 +1,36 @@
+pub mod archive;
+pub mod cache;
+pub mod capacity_check;
+pub mod card_index;
+pub mod card_manifest;
+pub mod circuitpython;
+pub mod compatibility;
+pub mod csv;
+pub mod demo;
 pub mod drive_management;
+pub mod fat32;
 pub mod file_download;
 pub mod github;
+pub mod github_artifacts;
+pub mod gizmo_config;
+pub mod gizmo_serial;
+pub mod http;
+pub mod imaging;
+pub mod offline;
+pub mod panics;
+pub mod platform;
+pub mod release_manifest;
+pub mod release_poller;
+pub mod self_test;
+pub mod self_update;
+pub mod session_report;
 pub mod shell;
+pub mod signing;
+pub mod sounds;
+pub mod status_export;
+pub mod tasks;
 pub mod threads;
+#[cfg(target_os = "linux")]
+pub mod udisks;
+pub mod uf2;
+pub mod work_dir;
@@ -0,0 +1,573 @@
+use anyhow::{Context, Result, anyhow, bail};
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+
+mod format;
+
+pub use format::{ArchiveFormat, Compression};
+
+const COPY_BUFFER_SIZE: usize = 256 * 1024;
+
+/// Progress of an archive extraction, reported after every chunk written.
+#[derive(Debug, Clone, Default)]
+pub struct ExtractProgress {
+    pub files_done: usize,
+    pub files_total: usize,
+    pub bytes_done: u64,
+    pub bytes_total: u64,
+    pub current_file: String,
+}
+
+impl ExtractProgress {
+    pub fn fraction(&self) -> f32 {
+        if self.bytes_total == 0 {
+            0.0
+        } else {
+            self.bytes_done as f32 / self.bytes_total as f32
+        }
+    }
+}
+
+/// A file or directory in an archive. The path has been checked to stay inside the directory
+/// the archive is extracted to.
+#[derive(Debug, Clone)]
+pub struct ArchiveEntry {
+    pub path: PathBuf,
+    pub is_dir: bool,
+    pub size: u64,
+}
+
+/// Reads each entry of a zip or tar archive in order, passing `visit` the contents of each
+/// file. Directories are passed an empty reader. The format is detected from the file itself.
+pub fn read_entries(
+    archive_path: &Path,
+    mut visit: impl FnMut(&ArchiveEntry, &mut dyn Read) -> Result<()>,
+) -> Result<()> {
+    let format = ArchiveFormat::detect(archive_path)?;
+    let archive_file = std::fs::File::open(archive_path)
+        .with_context(|| format!("Failed to open archive {:?}", archive_path))?;
+    let archive_file = std::io::BufReader::new(archive_file);
+    match format {
+        ArchiveFormat::Zip => {
+            let mut archive = zip::ZipArchive::new(archive_file)
+                .with_context(|| format!("Failed to read archive {:?}", archive_path))?;
+            for i in 0..archive.len() {
+                let mut entry = archive.by_index(i)?;
+                let path = entry.enclosed_name().ok_or(anyhow!(
+                    "Archive entry has an unsafe path: {}",
+                    entry.name()
+                ))?;
+                let info = ArchiveEntry {
+                    path,
+                    is_dir: entry.is_dir(),
+                    size: entry.size(),
+                };
+                visit(&info, &mut entry)?;
+            }
+        }
+        ArchiveFormat::Tar(compression) => {
+            let mut archive = tar::Archive::new(compression.decompress(archive_file)?);
+            let entries = archive
+                .entries()
+                .with_context(|| format!("Failed to read archive {:?}", archive_path))?;
+            for entry in entries {
+                let mut entry =
+                    entry.with_context(|| format!("Failed to read archive {:?}", archive_path))?;
+                let is_dir = match entry.header().entry_type() {
+                    tar::EntryType::Directory => true,
+                    tar::EntryType::Regular | tar::EntryType::Continuous => false,
+                    // FAT32 cards can't hold links or device files, and releases don't use them.
+                    entry_type => {
+                        log::warn!(
+                            "Skipping {:?} in {:?}, which is a {entry_type:?}",
+                            entry.path().unwrap_or_default(),
+                            archive_path
+                        );
+                        continue;
+                    }
+                };
+                let path = enclosed_tar_path(&entry.path()?)?;
+                if path.as_os_str().is_empty() {
+                    continue;
+                }
+                let info = ArchiveEntry {
+                    path,
+                    is_dir,
+                    size: entry.size(),
+                };
+                visit(&info, &mut entry)?;
+            }
+            // Compressed streams end with a checksum, which is only checked once the
+            // decompressor reaches the end.
+            std::io::copy(&mut archive.into_inner(), &mut std::io::sink())
+                .with_context(|| format!("Failed to read archive {:?}", archive_path))?;
+        }
+    }
+    Ok(())
+}
+
+/// Reads every file in the archive without writing it anywhere, so a damaged archive is found
+/// before anything is erased. Zip entries are checked against their CRCs, and compressed tar
+/// archives against the checksum of the compressed stream.
+pub fn verify_archive(archive_path: &Path) -> Result<()> {
+    read_entries(archive_path, |entry, reader| {
+        std::io::copy(reader, &mut std::io::sink()).with_context(|| {
+            format!(
+                "{} is damaged in archive {:?}",
+                entry.path.display(),
+                archive_path
+            )
+        })?;
+        Ok(())
+    })
+}
+
+/// Lists the entries of an archive without extracting it. Tar archives have no index, so this
+/// reads through the whole archive.
+pub fn list_entries(archive_path: &Path) -> Result<Vec<ArchiveEntry>> {
+    let mut entries = vec![];
+    read_entries(archive_path, |entry, _| {
+        entries.push(entry.clone());
+        Ok(())
+    })?;
+    Ok(entries)
+}
+
+/// Drops leading `./` from a tar entry path, and rejects paths that would land outside the
+/// extraction directory, like zip's `enclosed_name`.
+fn enclosed_tar_path(path: &Path) -> Result<PathBuf> {
+    let mut enclosed = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(name) => enclosed.push(name),
+            Component::CurDir => {}
+            _ => bail!("Archive entry has an unsafe path: {}", path.display()),
+        }
+    }
+    Ok(enclosed)
+}
+
+/// Extracts a zip or tar archive into `dest_dir`, streaming each entry to disk.
+///
+/// If `strip_toplevel` is set and every entry is inside the same top-level directory, that
+/// directory is removed from the extracted paths.
+pub fn extract_archive(
+    archive_path: &Path,
+    dest_dir: &Path,
+    strip_toplevel: bool,
+    mut on_progress: impl FnMut(&ExtractProgress),
+) -> Result<()> {
+    let entries = list_entries(archive_path)?;
+    let mut progress = ExtractProgress {
+        files_total: entries.len(),
+        bytes_total: entries.iter().map(|entry| entry.size).sum(),
+        ..Default::default()
+    };
+    let prefix = if strip_toplevel {
+        toplevel_dir_of(&entries)
+    } else {
+        None
+    };
+
+    read_entries(archive_path, |entry, reader| {
+        let relative_path = match &prefix {
+            Some(prefix) => entry.path.strip_prefix(prefix).unwrap_or(&entry.path),
+            None => &entry.path,
+        };
+        let out_path = dest_dir.join(relative_path);
+        progress.current_file = relative_path.to_string_lossy().to_string();
+        on_progress(&progress);
+
+        if entry.is_dir {
+            std::fs::create_dir_all(&out_path)?;
+        } else {
+            if let Some(parent) = out_path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let mut out_file = std::fs::File::create(&out_path)
+                .with_context(|| format!("Failed to create {:?}", out_path))?;
+            let mut buffer = vec![0; COPY_BUFFER_SIZE];
+            loop {
+                let read = reader.read(&mut buffer).with_context(|| {
+                    format!("Failed to read {} from archive", entry.path.display())
+                })?;
+                if read == 0 {
+                    break;
+                }
+                out_file
+                    .write_all(&buffer[..read])
+                    .with_context(|| format!("Failed to write {:?}", out_path))?;
+                progress.bytes_done += read as u64;
+                on_progress(&progress);
+            }
+        }
+        progress.files_done += 1;
+        Ok(())
+    })?;
+    progress.current_file.clear();
+    on_progress(&progress);
+    Ok(())
+}
+
+/// Extracts only the files in `paths` from an archive into `dest_dir`, overwriting what is
+/// there. Paths are relative to `dest_dir`, with the top-level directory stripped as in
+/// [`extract_archive`]. Returns how many files were written.
+pub fn extract_files(
+    archive_path: &Path,
+    dest_dir: &Path,
+    strip_toplevel: bool,
+    paths: &[PathBuf],
+) -> Result<usize> {
+    let prefix = if strip_toplevel {
+        toplevel_dir_of(&list_entries(archive_path)?)
+    } else {
+        None
+    };
+
+    let mut written = 0;
+    read_entries(archive_path, |entry, reader| {
+        let relative_path = match &prefix {
+            Some(prefix) => entry.path.strip_prefix(prefix).unwrap_or(&entry.path),
+            None => &entry.path,
+        };
+        if entry.is_dir || !paths.iter().any(|path| path == relative_path) {
+            return Ok(());
+        }
+        let out_path = dest_dir.join(relative_path);
+        if let Some(parent) = out_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut out_file = std::fs::File::create(&out_path)
+            .with_context(|| format!("Failed to create {:?}", out_path))?;
+        std::io::copy(reader, &mut out_file)
+            .with_context(|| format!("Failed to write {:?}", out_path))?;
+        written += 1;
+        Ok(())
+    })?;
+    Ok(written)
+}
+
+/// Copies an already extracted directory tree into `dest_dir`, reporting progress the same way
+/// as [`extract_archive`].
+pub fn copy_tree(
+    source_dir: &Path,
+    dest_dir: &Path,
+    mut on_progress: impl FnMut(&ExtractProgress),
+) -> Result<()> {
+    let mut relative_paths = vec![];
+    collect_files(source_dir, Path::new(""), &mut relative_paths)?;
+    let mut progress = ExtractProgress {
+        files_total: relative_paths.len(),
+        ..Default::default()
+    };
+    for path in &relative_paths {
+        let metadata = std::fs::metadata(source_dir.join(path))?;
+        if metadata.is_file() {
+            progress.bytes_total += metadata.len();
+        }
+    }
+
+    for relative_path in &relative_paths {
+        let in_path = source_dir.join(relative_path);
+        let out_path = dest_dir.join(relative_path);
+        progress.current_file = relative_path.to_string_lossy().to_string();
+        on_progress(&progress);
+
+        if in_path.is_dir() {
+            std::fs::create_dir_all(&out_path)?;
+        } else {
+            if let Some(parent) = out_path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let mut in_file = std::fs::File::open(&in_path)
+                .with_context(|| format!("Failed to open {:?}", in_path))?;
+            let mut out_file = std::fs::File::create(&out_path)
+                .with_context(|| format!("Failed to create {:?}", out_path))?;
+            let mut buffer = vec![0; COPY_BUFFER_SIZE];
+            loop {
+                let read = in_file
+                    .read(&mut buffer)
+                    .with_context(|| format!("Failed to read {:?}", in_path))?;
+                if read == 0 {
+                    break;
+                }
+                out_file
+                    .write_all(&buffer[..read])
+                    .with_context(|| format!("Failed to write {:?}", out_path))?;
+                progress.bytes_done += read as u64;
+                on_progress(&progress);
+            }
+        }
+        progress.files_done += 1;
+    }
+    progress.current_file.clear();
+    on_progress(&progress);
+    Ok(())
+}
+
+/// Lists every file and directory under `root.join(relative)`, parents before their contents.
+fn collect_files(root: &Path, relative: &Path, paths: &mut Vec<PathBuf>) -> Result<()> {
+    let dir = root.join(relative);
+    let mut entries = std::fs::read_dir(&dir)
+        .with_context(|| format!("Failed to read {:?}", dir))?
+        .collect::<std::io::Result<Vec<_>>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let path = relative.join(entry.file_name());
+        paths.push(path.clone());
+        if entry.file_type()?.is_dir() {
+            collect_files(root, &path, paths)?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns true if the error was caused by a disk running out of space or inodes.
+pub fn is_out_of_space(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        cause.downcast_ref::<std::io::Error>().is_some_and(|e| {
+            matches!(
+                e.kind(),
+                std::io::ErrorKind::StorageFull | std::io::ErrorKind::QuotaExceeded
+            )
+        })
+    })
+}
+
+/// Like [`common_toplevel_dir`], for entries from [`list_entries`].
+pub fn toplevel_dir_of(entries: &[ArchiveEntry]) -> Option<PathBuf> {
+    let paths: Vec<PathBuf> = entries.iter().map(|entry| entry.path.clone()).collect();
+    let is_dir: Vec<bool> = entries.iter().map(|entry| entry.is_dir).collect();
+    common_toplevel_dir(&paths, &is_dir)
+}
+
+/// Returns the directory that contains every entry in the archive, if there is one.
+pub fn common_toplevel_dir(entry_paths: &[PathBuf], entry_is_dir: &[bool]) -> Option<PathBuf> {
+    let toplevel = PathBuf::from(entry_paths.first()?.components().next()?.as_os_str());
+    let mut has_nested_entries = false;
+    for (path, is_dir) in entry_paths.iter().zip(entry_is_dir) {
+        if path == &toplevel {
+            if !is_dir {
+                return None;
+            }
+        } else if path.starts_with(&toplevel) {
+            has_nested_entries = true;
+        } else {
+            return None;
+        }
+    }
+    has_nested_entries.then_some(toplevel)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The files in every test archive, all in one top-level directory.
+    const FILES: [(&str, &[u8]); 2] = [
+        ("release/bin/gizmo", b"#!/bin/sh\necho gizmo\n"),
+        ("release/README.md", b"Driver station software"),
+    ];
+
+    fn tar_bytes(add_extra_entries: impl FnOnce(&mut tar::Builder<Vec<u8>>)) -> Vec<u8> {
+        let mut builder = tar::Builder::new(vec![]);
+        let mut dir = tar::Header::new_gnu();
+        dir.set_entry_type(tar::EntryType::Directory);
+        dir.set_mode(0o755);
+        dir.set_size(0);
+        builder
+            .append_data(&mut dir, "release/", std::io::empty())
+            .unwrap();
+        for (path, contents) in FILES {
+            let mut header = tar::Header::new_gnu();
+            header.set_mode(0o644);
+            header.set_size(contents.len() as u64);
+            builder.append_data(&mut header, path, contents).unwrap();
+        }
+        add_extra_entries(&mut builder);
+        builder.into_inner().unwrap()
+    }
+
+    /// Adds a file at `../escaped`, which the tar crate refuses to write through its usual
+    /// methods.
+    fn add_escaping_entry(builder: &mut tar::Builder<Vec<u8>>) {
+        let mut header = tar::Header::new_gnu();
+        let name = b"../escaped";
+        header.as_old_mut().name[..name.len()].copy_from_slice(name);
+        header.set_entry_type(tar::EntryType::Regular);
+        header.set_mode(0o644);
+        header.set_size(1);
+        header.set_cksum();
+        builder.append(&header, &b"x"[..]).unwrap();
+    }
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = flate2::write::GzEncoder::new(vec![], flate2::Compression::fast());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn xz(data: &[u8]) -> Vec<u8> {
+        let options = lzma_rust2::XzOptions::with_preset(1);
+        let mut writer = lzma_rust2::XzWriter::new(vec![], options).unwrap();
+        writer.write_all(data).unwrap();
+        writer.finish().unwrap()
+    }
+
+    /// Compresses the two halves of `data` as separate frames, like large releases are.
+    fn zstd_two_frames(data: &[u8]) -> Vec<u8> {
+        let (first, second) = data.split_at(data.len() / 2);
+        let level = ruzstd::encoding::CompressionLevel::Fastest;
+        let mut compressed = ruzstd::encoding::compress_to_vec(first, level);
+        compressed.extend(ruzstd::encoding::compress_to_vec(second, level));
+        compressed
+    }
+
+    fn zip_bytes(extra_file: Option<&str>) -> Vec<u8> {
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(vec![]));
+        let options = zip::write::SimpleFileOptions::default();
+        zip.add_directory("release/", options).unwrap();
+        for (path, contents) in FILES
+            .iter()
+            .copied()
+            .chain(extra_file.map(|path| (path, &b"x"[..])))
+        {
+            zip.start_file(path, options).unwrap();
+            zip.write_all(contents).unwrap();
+        }
+        zip.finish().unwrap().into_inner()
+    }
+
+    /// Writes `archive` to a file in a new temporary directory, which also holds the `out`
+    /// directory it is extracted to.
+    fn write_archive(archive: &[u8]) -> (tempfile::TempDir, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        // Named without an extension, since the format is told from the contents.
+        let path = dir.path().join("archive");
+        std::fs::write(&path, archive).unwrap();
+        (dir, path)
+    }
+
+    fn assert_extracts(archive: &[u8]) {
+        let (dir, path) = write_archive(archive);
+        let stripped = dir.path().join("stripped");
+        extract_archive(&path, &stripped, true, |_| {}).unwrap();
+        let whole = dir.path().join("whole");
+        extract_archive(&path, &whole, false, |_| {}).unwrap();
+        for (file, contents) in FILES {
+            assert_eq!(std::fs::read(whole.join(file)).unwrap(), contents);
+            let relative = Path::new(file).strip_prefix("release").unwrap();
+            assert_eq!(std::fs::read(stripped.join(relative)).unwrap(), contents);
+        }
+        verify_archive(&path).unwrap();
+    }
+
+    #[test]
+    fn every_format_extracts() {
+        let tar = tar_bytes(|_| {});
+        assert_extracts(&tar);
+        assert_extracts(&gzip(&tar));
+        assert_extracts(&xz(&tar));
+        assert_extracts(&zstd_two_frames(&tar));
+        assert_extracts(&zip_bytes(None));
+    }
+
+    #[test]
+    fn extraction_reports_every_byte() {
+        let (dir, path) = write_archive(&zstd_two_frames(&tar_bytes(|_| {})));
+        let mut last = ExtractProgress::default();
+        extract_archive(&path, &dir.path().join("out"), true, |progress| {
+            last = progress.clone()
+        })
+        .unwrap();
+        let total: usize = FILES.iter().map(|(_, contents)| contents.len()).sum();
+        assert_eq!(last.bytes_done, total as u64);
+        assert_eq!(last.bytes_total, total as u64);
+        assert_eq!(last.files_done, last.files_total);
+    }
+
+    #[test]
+    fn entries_outside_the_destination_are_rejected() {
+        let escaping_tar = tar_bytes(add_escaping_entry);
+        for archive in [
+            escaping_tar.clone(),
+            gzip(&escaping_tar),
+            zip_bytes(Some("../escaped")),
+        ] {
+            let (dir, path) = write_archive(&archive);
+            let out = dir.path().join("out");
+            assert!(extract_archive(&path, &out, false, |_| {}).is_err());
+            assert!(list_entries(&path).is_err());
+            assert!(!dir.path().join("escaped").exists());
+            assert!(!out.exists());
+        }
+    }
+
+    #[test]
+    fn links_and_devices_are_skipped() {
+        let tar = tar_bytes(|builder| {
+            let mut link = tar::Header::new_gnu();
+            link.set_entry_type(tar::EntryType::Symlink);
+            link.set_size(0);
+            builder
+                .append_link(&mut link, "release/latest", "README.md")
+                .unwrap();
+            let mut device = tar::Header::new_gnu();
+            device.set_entry_type(tar::EntryType::Char);
+            device.set_size(0);
+            device.set_device_major(1).unwrap();
+            device.set_device_minor(3).unwrap();
+            builder
+                .append_data(&mut device, "release/null", std::io::empty())
+                .unwrap();
+        });
+        let (dir, path) = write_archive(&gzip(&tar));
+        let paths: Vec<PathBuf> = list_entries(&path)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.path)
+            .collect();
+        assert_eq!(
+            paths,
+            ["release", "release/bin/gizmo", "release/README.md"].map(PathBuf::from)
+        );
+        let out = dir.path().join("out");
+        extract_archive(&path, &out, true, |_| {}).unwrap();
+        assert!(!out.join("latest").exists());
+        assert!(!out.join("null").exists());
+    }
+
+    #[test]
+    fn tar_paths_stay_inside_the_destination() {
+        assert_eq!(
+            enclosed_tar_path(Path::new("./release/bin")).unwrap(),
+            PathBuf::from("release/bin")
+        );
+        assert_eq!(enclosed_tar_path(Path::new("./")).unwrap(), PathBuf::new());
+        assert!(enclosed_tar_path(Path::new("../escaped")).is_err());
+        assert!(enclosed_tar_path(Path::new("release/../../escaped")).is_err());
+        assert!(enclosed_tar_path(Path::new("/etc/passwd")).is_err());
+    }
+
+    #[test]
+    fn toplevel_dir_is_found_only_when_it_holds_everything() {
+        let paths = |paths: &[&str]| -> Vec<PathBuf> { paths.iter().map(PathBuf::from).collect() };
+        assert_eq!(
+            common_toplevel_dir(&paths(&["release", "release/a"]), &[true, false]),
+            Some(PathBuf::from("release"))
+        );
+        // Tar archives don't always list the directory itself.
+        assert_eq!(
+            common_toplevel_dir(&paths(&["release/a", "release/b/c"]), &[false, false]),
+            Some(PathBuf::from("release"))
+        );
+        assert_eq!(
+            common_toplevel_dir(&paths(&["release/a", "other"]), &[false, false]),
+            None
+        );
+        // A lone file or an empty directory isn't a wrapper to strip.
+        assert_eq!(common_toplevel_dir(&paths(&["release"]), &[false]), None);
+        assert_eq!(common_toplevel_dir(&paths(&["release"]), &[true]), None);
+        assert_eq!(common_toplevel_dir(&[], &[]), None);
+    }
+}
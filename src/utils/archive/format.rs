@@ -0,0 +1,181 @@
+use anyhow::{Context, Result, bail};
+use ruzstd::decoding::{FrameDecoder, StreamingDecoder};
+use std::io::{BufRead, Read};
+use std::path::Path;
+
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const XZ_MAGIC: &[u8] = &[0xfd, b'7', b'z', b'X', b'Z', 0x00];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+/// Uncompressed tar files have no magic at the start, only in the first header.
+const TAR_MAGIC_OFFSET: usize = 257;
+const TAR_MAGIC: &[u8] = b"ustar";
+const HEADER_LEN: usize = 512;
+
+/// How a file is compressed, told from its first bytes rather than its name, since downloaded
+/// and hand-copied files aren't always named the way they were published.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Xz,
+    Zstd,
+}
+
+impl Compression {
+    fn from_header(header: &[u8]) -> Self {
+        if header.starts_with(GZIP_MAGIC) {
+            Compression::Gzip
+        } else if header.starts_with(XZ_MAGIC) {
+            Compression::Xz
+        } else if header.starts_with(ZSTD_MAGIC) {
+            Compression::Zstd
+        } else {
+            Compression::None
+        }
+    }
+
+    pub fn detect(path: &Path) -> Result<Self> {
+        Ok(Self::from_header(&read_header(path)?))
+    }
+
+    /// Wraps `reader` so reading from it returns the decompressed bytes.
+    pub fn decompress<'a>(self, reader: impl BufRead + 'a) -> Result<Box<dyn Read + 'a>> {
+        Ok(match self {
+            Compression::None => Box::new(reader),
+            Compression::Gzip => Box::new(flate2::bufread::MultiGzDecoder::new(reader)),
+            Compression::Xz => Box::new(lzma_rust2::XzReader::new(reader, true)),
+            Compression::Zstd => Box::new(ZstdReader {
+                decoder: Some(
+                    StreamingDecoder::new(reader).context("Failed to read zstd frame header")?,
+                ),
+            }),
+        })
+    }
+}
+
+/// Reads every frame of a zstd stream. Large files are often compressed as several frames one
+/// after another, which the decoder only reads one of at a time.
+struct ZstdReader<R: BufRead> {
+    decoder: Option<StreamingDecoder<R, FrameDecoder>>,
+}
+
+impl<R: BufRead> Read for ZstdReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        while let Some(decoder) = self.decoder.as_mut() {
+            let read = decoder.read(buf)?;
+            if read > 0 || buf.is_empty() {
+                return Ok(read);
+            }
+            let Some(decoder) = self.decoder.take() else {
+                break;
+            };
+            let mut source = decoder.into_inner();
+            if !source.fill_buf()?.is_empty() {
+                self.decoder = Some(StreamingDecoder::new(source).map_err(std::io::Error::other)?);
+            }
+        }
+        Ok(0)
+    }
+}
+
+/// The kinds of archive the driver station software can be published as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Zip,
+    Tar(Compression),
+}
+
+impl ArchiveFormat {
+    /// File name endings of the archives the wizard can read, in the order release assets are
+    /// preferred.
+    pub const EXTENSIONS: [&str; 7] = [
+        ".zip", ".tar.zst", ".tzst", ".tar.xz", ".tar.gz", ".tgz", ".tar",
+    ];
+
+    pub fn detect(path: &Path) -> Result<Self> {
+        let header = read_header(path)?;
+        if header.starts_with(ZIP_MAGIC) {
+            return Ok(ArchiveFormat::Zip);
+        }
+        match Compression::from_header(&header) {
+            Compression::None
+                if header.get(TAR_MAGIC_OFFSET..TAR_MAGIC_OFFSET + TAR_MAGIC.len())
+                    != Some(TAR_MAGIC) =>
+            {
+                bail!(
+                    "{:?} is not a zip or tar archive. Supported archives end in {}.",
+                    path,
+                    Self::EXTENSIONS.join(", ")
+                )
+            }
+            compression => Ok(ArchiveFormat::Tar(compression)),
+        }
+    }
+}
+
+fn read_header(path: &Path) -> Result<Vec<u8>> {
+    let file = std::fs::File::open(path).with_context(|| format!("Failed to open {:?}", path))?;
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    file.take(HEADER_LEN as u64)
+        .read_to_end(&mut header)
+        .with_context(|| format!("Failed to read {:?}", path))?;
+    Ok(header)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(contents: &[u8]) -> Result<ArchiveFormat> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("release.bin");
+        std::fs::write(&path, contents).unwrap();
+        ArchiveFormat::detect(&path)
+    }
+
+    #[test]
+    fn formats_are_told_from_their_first_bytes() {
+        assert_eq!(detect(b"PK\x03\x04rest").unwrap(), ArchiveFormat::Zip);
+        assert_eq!(
+            detect(&[0x1f, 0x8b, 8, 0]).unwrap(),
+            ArchiveFormat::Tar(Compression::Gzip)
+        );
+        assert_eq!(
+            detect(&[0xfd, b'7', b'z', b'X', b'Z', 0x00, 0]).unwrap(),
+            ArchiveFormat::Tar(Compression::Xz)
+        );
+        assert_eq!(
+            detect(&[0x28, 0xb5, 0x2f, 0xfd, 0]).unwrap(),
+            ArchiveFormat::Tar(Compression::Zstd)
+        );
+        let mut tar_header = vec![0; HEADER_LEN];
+        tar_header[TAR_MAGIC_OFFSET..TAR_MAGIC_OFFSET + TAR_MAGIC.len()].copy_from_slice(TAR_MAGIC);
+        assert_eq!(
+            detect(&tar_header).unwrap(),
+            ArchiveFormat::Tar(Compression::None)
+        );
+    }
+
+    #[test]
+    fn other_files_are_not_archives() {
+        assert!(detect(b"<html>Not Found</html>").is_err());
+        assert!(detect(b"").is_err());
+    }
+
+    #[test]
+    fn every_zstd_frame_is_read() {
+        let level = ruzstd::encoding::CompressionLevel::Fastest;
+        let mut compressed = vec![];
+        for frame in [&b"first frame, "[..], b"second frame, ", b"third frame"] {
+            compressed.extend(ruzstd::encoding::compress_to_vec(frame, level));
+        }
+        let mut decompressed = String::new();
+        Compression::Zstd
+            .decompress(compressed.as_slice())
+            .unwrap()
+            .read_to_string(&mut decompressed)
+            .unwrap();
+        assert_eq!(decompressed, "first frame, second frame, third frame");
+    }
+}
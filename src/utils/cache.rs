@@ -0,0 +1,261 @@
+use crate::utils::card_index::hash_file;
+use crate::utils::offline::compare_release_names;
+use anyhow::{Context, Result, bail};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Extension of the file next to each cached asset that records its SHA-256.
+const HASH_FILE_EXTENSION: &str = "sha256";
+
+/// Names of assets downloaded again because their cached copy was damaged, until the app
+/// mentions them.
+static REFRESHED_ASSETS: Mutex<Vec<String>> = Mutex::new(vec![]);
+
+/// Directory for downloads that should survive app restarts, if the platform has a cache directory.
+pub fn persistent_cache_dir() -> Option<PathBuf> {
+    dirs::cache_dir().map(|dir| dir.join(crate::settings::APP_DIR_NAME).join("downloads"))
+}
+
+/// Total size in bytes of all files under `dir`.
+pub fn cache_size(dir: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| match entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => cache_size(&entry.path()),
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+/// Deletes everything in the cache except the versions in `pinned`, given as
+/// [`CachedVersion::key`]s.
+pub fn clear_cache(dir: &Path, pinned: &[String]) -> Result<()> {
+    if !dir.exists() {
+        return Ok(());
+    }
+    let keep: Vec<PathBuf> = list_cached_versions(dir)
+        .into_iter()
+        .filter(|v| pinned.contains(&v.key()))
+        .map(|v| v.path)
+        .collect();
+    if keep.is_empty() {
+        std::fs::remove_dir_all(dir)
+    } else {
+        remove_all_except(dir, &keep)
+    }
+    .with_context(|| format!("Failed to clear download cache at {:?}", dir))
+}
+
+fn remove_all_except(dir: &Path, keep: &[PathBuf]) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if keep.contains(&path) {
+            continue;
+        }
+        if keep.iter().any(|k| k.starts_with(&path)) {
+            remove_all_except(&path, keep)?;
+        } else if path.is_dir() {
+            std::fs::remove_dir_all(&path)?;
+        } else {
+            std::fs::remove_file(&path)?;
+        }
+    }
+    Ok(())
+}
+
+/// The downloads for one release, stored in the cache under `<owner>/<repo>/<version>`.
+#[derive(Debug, Clone)]
+pub struct CachedVersion {
+    /// The repository the release came from, like "gizmo-platform/gizmo".
+    pub tool: String,
+    pub version: String,
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+impl CachedVersion {
+    /// Identifies the version in the pinned versions setting.
+    pub fn key(&self) -> String {
+        format!("{}/{}", self.tool, self.version)
+    }
+}
+
+/// Lists the release versions in the cache, grouped by tool with the newest version first.
+pub fn list_cached_versions(cache_dir: &Path) -> Vec<CachedVersion> {
+    let mut versions = vec![];
+    for (owner, owner_dir) in subdirectories(cache_dir) {
+        for (repo, repo_dir) in subdirectories(&owner_dir) {
+            let mut repo_versions: Vec<CachedVersion> = subdirectories(&repo_dir)
+                .into_iter()
+                .map(|(version, path)| CachedVersion {
+                    tool: format!("{owner}/{repo}"),
+                    version,
+                    size: cache_size(&path),
+                    path,
+                })
+                .collect();
+            repo_versions.sort_by(|a, b| compare_release_names(&b.version, &a.version));
+            versions.extend(repo_versions);
+        }
+    }
+    versions
+}
+
+fn subdirectories(dir: &Path) -> Vec<(String, PathBuf)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut dirs: Vec<(String, PathBuf)> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .filter_map(|path| Some((path.file_name()?.to_str()?.to_string(), path)))
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+/// Deletes a cached version, along with its tool's folders if nothing else is left in them.
+pub fn remove_cached_version(version: &CachedVersion) -> Result<()> {
+    std::fs::remove_dir_all(&version.path)
+        .with_context(|| format!("Failed to delete {} from the cache", version.key()))?;
+    // Fails harmlessly while other versions are still cached.
+    for parent in version.path.ancestors().skip(1).take(2) {
+        if std::fs::remove_dir(parent).is_err() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn hash_file_path(asset_path: &Path) -> PathBuf {
+    let mut path = asset_path.as_os_str().to_owned();
+    path.push(".");
+    path.push(HASH_FILE_EXTENSION);
+    PathBuf::from(path)
+}
+
+/// Returns true for the files [`record_hash`] writes, which are not assets themselves.
+pub fn is_hash_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext == HASH_FILE_EXTENSION)
+}
+
+/// Saves the hash of a freshly downloaded asset so the cache can be checked for damage later.
+/// If the release published a digest for the asset and the download doesn't match it, the
+/// download is deleted.
+pub fn record_hash(asset_path: &Path, expected_digest: Option<&str>) -> Result<()> {
+    let hash = hash_file(asset_path).with_context(|| format!("Failed to read {:?}", asset_path))?;
+    if let Some(expected) = expected_digest.and_then(|d| d.strip_prefix("sha256:"))
+        && !expected.eq_ignore_ascii_case(&hash)
+    {
+        let _ = std::fs::remove_file(asset_path);
+        bail!(
+            "The download of {:?} does not match the checksum published with the release. Try downloading it again.",
+            asset_path.file_name().unwrap_or_default()
+        );
+    }
+    std::fs::write(hash_file_path(asset_path), hash)
+        .with_context(|| format!("Failed to save the checksum of {:?}", asset_path))
+}
+
+/// Checks a cached asset against the size listed with the release and the hash recorded when it
+/// was downloaded. Assets downloaded before hashes were recorded are checked against the digest
+/// published with the release instead, if there is one. Returns false if the file is truncated
+/// or has changed.
+pub fn is_cached_asset_intact(
+    asset_path: &Path,
+    expected_size: Option<u64>,
+    expected_digest: Option<&str>,
+) -> Result<bool> {
+    let size = std::fs::metadata(asset_path)
+        .with_context(|| format!("Failed to read {:?}", asset_path))?
+        .len();
+    if expected_size.is_some_and(|expected| expected != size) {
+        return Ok(false);
+    }
+    let recorded = std::fs::read_to_string(hash_file_path(asset_path)).ok();
+    let expected_hash = match recorded {
+        Some(ref recorded) => Some(recorded.trim()),
+        None => expected_digest.and_then(|d| d.strip_prefix("sha256:")),
+    };
+    let Some(expected_hash) = expected_hash else {
+        return Ok(true);
+    };
+    let hash = hash_file(asset_path).with_context(|| format!("Failed to read {:?}", asset_path))?;
+    Ok(hash.eq_ignore_ascii_case(expected_hash))
+}
+
+/// Records that a damaged cached asset was replaced, so the app can say so.
+pub fn note_refreshed_asset(name: &str) {
+    REFRESHED_ASSETS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(name.to_string());
+}
+
+/// Returns the assets replaced since the last call.
+pub fn take_refreshed_assets() -> Vec<String> {
+    std::mem::take(&mut *REFRESHED_ASSETS.lock().unwrap_or_else(|e| e.into_inner()))
+}
+
+/// Outcome of re-hashing the assets of a cached version.
+#[derive(Debug, Clone)]
+pub struct CacheVerification {
+    pub verified: usize,
+    /// Assets downloaded before checksums were recorded, which can't be checked.
+    pub unrecorded: usize,
+    pub problems: Vec<String>,
+}
+
+/// Hashes every asset of a cached version and compares it with the hash recorded at download.
+pub fn verify_cached_version(version: &CachedVersion) -> Result<CacheVerification> {
+    let mut verification = CacheVerification {
+        verified: 0,
+        unrecorded: 0,
+        problems: vec![],
+    };
+    let entries = std::fs::read_dir(&version.path)
+        .with_context(|| format!("Failed to read {:?}", version.path))?;
+    for entry in entries {
+        let path = entry?.path();
+        if !path.is_file() || is_hash_file(&path) {
+            continue;
+        }
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let Ok(recorded) = std::fs::read_to_string(hash_file_path(&path)) else {
+            verification.unrecorded += 1;
+            continue;
+        };
+        match hash_file(&path) {
+            Ok(hash) if hash == recorded.trim() => verification.verified += 1,
+            Ok(_) => verification
+                .problems
+                .push(format!("{name} has been changed or is corrupt.")),
+            Err(e) => verification
+                .problems
+                .push(format!("{name} could not be read: {e}")),
+        }
+    }
+    Ok(verification)
+}
+
+/// Formats a byte count for display, e.g. "1.5 GB".
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
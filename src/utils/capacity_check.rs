@@ -0,0 +1,265 @@
+use crate::utils::drive_management::{DriveInfo, DriveManager};
+use anyhow::{Context, Result, bail};
+use std::io::{ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+const BLOCK_SIZE: usize = 1024 * 1024;
+// FAT32 cannot hold files larger than 4 GiB, so the test data is split across several files.
+const BLOCKS_PER_FILE: u64 = 256;
+// Unbuffered reads on Windows need a buffer aligned to the sector size.
+const READ_ALIGNMENT: usize = 4096;
+// Leave some room for the file system's own bookkeeping.
+const RESERVED_BYTES: u64 = 4 * BLOCK_SIZE as u64;
+const TEST_DIR_NAME: &str = "capacity-check";
+/// Data written by the quick card test. Large enough to get past the card's own write cache.
+const CARD_TEST_BYTES: u64 = 256 * BLOCK_SIZE as u64;
+/// Slowest sequential write speed accepted, in bytes per second. Class 10 cards promise 10 MB/s.
+pub const MIN_WRITE_SPEED: u64 = 10 * 1000 * 1000;
+/// Volumes on genuine cards are a few percent smaller than the label says, since the card and
+/// the file system keep some space for themselves. Much less than that means the card lies.
+const MIN_CAPACITY_FRACTION: f64 = 0.9;
+/// Sizes SD cards are sold in, in decimal gigabytes as printed on the label.
+pub const ADVERTISED_SIZES_GB: [u64; 11] = [1, 2, 4, 8, 16, 32, 64, 128, 256, 512, 1024];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckPhase {
+    Writing,
+    Verifying,
+}
+
+#[derive(Debug, Clone)]
+pub struct CapacityCheckProgress {
+    pub phase: CheckPhase,
+    pub bytes_done: u64,
+    pub bytes_total: u64,
+}
+
+impl CapacityCheckProgress {
+    pub fn fraction(&self) -> f32 {
+        if self.bytes_total == 0 {
+            0.0
+        } else {
+            self.bytes_done as f32 / self.bytes_total as f32
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CapacityCheckResult {
+    pub bytes_tested: u64,
+    pub bad_bytes: u64,
+    /// Offset of the first block that did not read back what was written to it.
+    pub first_bad_offset: Option<u64>,
+    /// Time taken to write the test data and flush it to the card.
+    pub write_duration: std::time::Duration,
+}
+
+impl CapacityCheckResult {
+    pub fn passed(&self) -> bool {
+        self.first_bad_offset.is_none()
+    }
+
+    /// Sequential write speed in bytes per second.
+    pub fn write_speed(&self) -> u64 {
+        (self.bytes_tested as f64 / self.write_duration.as_secs_f64().max(0.001)) as u64
+    }
+
+    pub fn slow(&self) -> bool {
+        self.write_speed() < MIN_WRITE_SPEED
+    }
+}
+
+/// Guesses the size printed on a card's label from the size of its volume.
+pub fn guess_advertised_size(volume_bytes: u64) -> u64 {
+    ADVERTISED_SIZES_GB
+        .iter()
+        .map(|gb| gb * 1000 * 1000 * 1000)
+        .find(|&size| volume_bytes <= size)
+        .unwrap_or(volume_bytes)
+}
+
+/// Whether a volume is too small to be a genuine card of the advertised size.
+pub fn smaller_than_advertised(volume_bytes: u64, advertised_bytes: u64) -> bool {
+    (volume_bytes as f64) < advertised_bytes as f64 * MIN_CAPACITY_FRACTION
+}
+
+/// Fills the free space on a drive with pseudorandom data and reads it back.
+///
+/// Counterfeit cards report more space than they have and silently wrap writes around onto
+/// earlier blocks, so data written past the real capacity overwrites blocks written before it.
+/// Any data already on the card may be lost if the card turns out to be fake.
+pub fn run_capacity_check(
+    drives: &dyn DriveManager,
+    drive: &DriveInfo,
+    cancel: &AtomicBool,
+    on_progress: impl FnMut(&CapacityCheckProgress),
+) -> Result<CapacityCheckResult> {
+    run_check(drives, drive, None, cancel, on_progress)
+}
+
+/// Writes a few hundred megabytes to the free space on a drive and reads them back, to measure
+/// its write speed and find bad sectors in a minute or so. Unlike [`run_capacity_check`], it
+/// cannot prove that a card holds as much as it claims.
+pub fn run_card_test(
+    drives: &dyn DriveManager,
+    drive: &DriveInfo,
+    cancel: &AtomicBool,
+    on_progress: impl FnMut(&CapacityCheckProgress),
+) -> Result<CapacityCheckResult> {
+    run_check(drives, drive, Some(CARD_TEST_BYTES), cancel, on_progress)
+}
+
+fn run_check(
+    drives: &dyn DriveManager,
+    drive: &DriveInfo,
+    max_bytes: Option<u64>,
+    cancel: &AtomicBool,
+    mut on_progress: impl FnMut(&CapacityCheckProgress),
+) -> Result<CapacityCheckResult> {
+    let test_dir = drive.drive_path.join(TEST_DIR_NAME);
+    std::fs::create_dir_all(&test_dir)
+        .with_context(|| format!("Failed to create {:?}", test_dir))?;
+    let result = write_and_verify(
+        drives,
+        drive,
+        &test_dir,
+        max_bytes,
+        cancel,
+        &mut on_progress,
+    );
+    let cleanup = std::fs::remove_dir_all(&test_dir)
+        .with_context(|| format!("Failed to remove test files from {:?}", test_dir));
+    let result = result?;
+    cleanup?;
+    Ok(result)
+}
+
+fn write_and_verify(
+    drives: &dyn DriveManager,
+    drive: &DriveInfo,
+    test_dir: &Path,
+    max_bytes: Option<u64>,
+    cancel: &AtomicBool,
+    on_progress: &mut impl FnMut(&CapacityCheckProgress),
+) -> Result<CapacityCheckResult> {
+    let block_size = BLOCK_SIZE as u64;
+    let free_space = drives.free_space(drive)?.saturating_sub(RESERVED_BYTES);
+    let mut total_blocks = max_bytes.map_or(free_space, |max| max.min(free_space)) / block_size;
+    let mut progress = CapacityCheckProgress {
+        phase: CheckPhase::Writing,
+        bytes_done: 0,
+        bytes_total: total_blocks * block_size,
+    };
+
+    let write_start = std::time::Instant::now();
+    let mut buffer = vec![0; BLOCK_SIZE];
+    let mut block = 0;
+    'files: while block < total_blocks {
+        let file_index = block / BLOCKS_PER_FILE;
+        let path = test_file_path(test_dir, file_index);
+        let mut file =
+            std::fs::File::create(&path).with_context(|| format!("Failed to create {:?}", path))?;
+        let file_end = ((file_index + 1) * BLOCKS_PER_FILE).min(total_blocks);
+        while block < file_end {
+            check_cancelled(cancel)?;
+            fill_block(block, &mut buffer);
+            match file.write_all(&buffer) {
+                Ok(()) => {}
+                Err(e) if e.kind() == ErrorKind::StorageFull => {
+                    // The file system filled up sooner than it said it would. Only check what
+                    // made it onto the card.
+                    total_blocks = block;
+                    break 'files;
+                }
+                Err(e) => return Err(e).with_context(|| format!("Failed to write {:?}", path)),
+            }
+            block += 1;
+            progress.bytes_done += block_size;
+            on_progress(&progress);
+        }
+        file.sync_all()
+            .with_context(|| format!("Failed to flush {:?}", path))?;
+    }
+    drives.flush(drive)?;
+    let write_duration = write_start.elapsed();
+    #[cfg(target_os = "linux")]
+    crate::utils::drive_management::remount_drive(drive)?;
+
+    progress = CapacityCheckProgress {
+        phase: CheckPhase::Verifying,
+        bytes_done: 0,
+        bytes_total: total_blocks * block_size,
+    };
+    on_progress(&progress);
+    let mut result = CapacityCheckResult {
+        bytes_tested: total_blocks * block_size,
+        bad_bytes: 0,
+        first_bad_offset: None,
+        write_duration,
+    };
+    let mut read_storage = vec![0; BLOCK_SIZE + READ_ALIGNMENT];
+    let offset = read_storage.as_ptr().align_offset(READ_ALIGNMENT);
+    let read_buffer = &mut read_storage[offset..offset + BLOCK_SIZE];
+    let mut block = 0;
+    while block < total_blocks {
+        let file_index = block / BLOCKS_PER_FILE;
+        let file_end = ((file_index + 1) * BLOCKS_PER_FILE).min(total_blocks);
+        let mut file = open_uncached(&test_file_path(test_dir, file_index)).ok();
+        while block < file_end {
+            check_cancelled(cancel)?;
+            fill_block(block, &mut buffer);
+            let matches = match file.as_mut() {
+                Some(f) => f.read_exact(read_buffer).is_ok() && *read_buffer == buffer[..],
+                None => false,
+            };
+            if !matches {
+                result.first_bad_offset.get_or_insert(block * block_size);
+                result.bad_bytes += block_size;
+            }
+            block += 1;
+            progress.bytes_done += block_size;
+            on_progress(&progress);
+        }
+    }
+    Ok(result)
+}
+
+fn check_cancelled(cancel: &AtomicBool) -> Result<()> {
+    if cancel.load(Ordering::Relaxed) {
+        bail!("Capacity check cancelled.");
+    }
+    Ok(())
+}
+
+fn test_file_path(test_dir: &Path, file_index: u64) -> PathBuf {
+    test_dir.join(format!("{file_index:05}.bin"))
+}
+
+/// Fills `buffer` with data that is unique to `block`, so a block that was overwritten by a
+/// later one can be told apart from one that was stored correctly.
+fn fill_block(block: u64, buffer: &mut [u8]) {
+    // xorshift64*, seeded from the block number.
+    let mut state = block.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+    for chunk in buffer.chunks_exact_mut(8) {
+        state ^= state >> 12;
+        state ^= state << 25;
+        state ^= state >> 27;
+        chunk.copy_from_slice(&state.wrapping_mul(0x2545_F491_4F6C_DD1D).to_le_bytes());
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn open_uncached(path: &Path) -> std::io::Result<std::fs::File> {
+    use std::os::windows::fs::OpenOptionsExt;
+    const FILE_FLAG_NO_BUFFERING: u32 = 0x2000_0000;
+    std::fs::OpenOptions::new()
+        .read(true)
+        .custom_flags(FILE_FLAG_NO_BUFFERING)
+        .open(path)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn open_uncached(path: &Path) -> std::io::Result<std::fs::File> {
+    std::fs::File::open(path)
+}
@@ -0,0 +1,280 @@
+use crate::settings::SoftwareRepo;
+use crate::utils::archive::{list_entries, read_entries, toplevel_dir_of};
+use crate::utils::github::{GithubRelease, download_versioned_asset};
+use crate::utils::http::Network;
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// Bytes hashed from each end of a file for a quick check.
+const SAMPLE_SIZE: u64 = 64 * 1024;
+const HASH_BUFFER_SIZE: usize = 256 * 1024;
+
+/// What one file from the driver station archive should look like on a card.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct IndexedFile {
+    /// Path relative to the card root, with `/` separators.
+    pub path: String,
+    pub size: u64,
+    /// SHA-256 of the file size and its first and last [`SAMPLE_SIZE`] bytes.
+    pub sample_hash: String,
+    pub sha256: String,
+}
+
+/// Every file a driver station archive puts on a card, so a card can be checked without the
+/// archive.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CardIndex {
+    pub software_version: String,
+    pub files: Vec<IndexedFile>,
+}
+
+fn index_path(cache_dir: &Path, software_version: &str) -> PathBuf {
+    cache_dir.join("card-indexes").join(format!(
+        "{}.json",
+        software_version.replace(['/', '\\'], "_")
+    ))
+}
+
+pub fn load_cached_index(cache_dir: &Path, software_version: &str) -> Option<CardIndex> {
+    let contents = std::fs::read_to_string(index_path(cache_dir, software_version)).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+/// Hashes every file in the archive and saves the index to the cache.
+pub fn build_index(
+    archive_path: &Path,
+    software_version: &str,
+    cache_dir: &Path,
+) -> Result<CardIndex> {
+    // Cards are extracted with the top-level directory stripped, so the index is too.
+    let prefix = toplevel_dir_of(&list_entries(archive_path)?);
+
+    let mut files = vec![];
+    read_entries(archive_path, |entry, reader| {
+        if entry.is_dir {
+            return Ok(());
+        }
+        let relative_path = match &prefix {
+            Some(prefix) => entry.path.strip_prefix(prefix).unwrap_or(&entry.path),
+            None => &entry.path,
+        };
+        // Archive entries can only be read front to back, so the sample is collected while
+        // hashing the whole file.
+        let mut full = Sha256::new();
+        let mut head = vec![];
+        let mut tail = vec![];
+        let mut buffer = vec![0; HASH_BUFFER_SIZE];
+        loop {
+            let read = reader
+                .read(&mut buffer)
+                .with_context(|| format!("Failed to read {} from archive", entry.path.display()))?;
+            if read == 0 {
+                break;
+            }
+            let chunk = &buffer[..read];
+            full.update(chunk);
+            let head_room = (SAMPLE_SIZE as usize).saturating_sub(head.len()).min(read);
+            head.extend_from_slice(&chunk[..head_room]);
+            tail.extend_from_slice(chunk);
+            let excess = tail.len().saturating_sub(SAMPLE_SIZE as usize);
+            tail.drain(..excess);
+        }
+        files.push(IndexedFile {
+            path: relative_path.to_string_lossy().replace('\\', "/"),
+            size: entry.size,
+            sample_hash: sample_hash_from_parts(entry.size, &head, &tail),
+            sha256: to_hex(&full.finalize()),
+        });
+        Ok(())
+    })?;
+
+    let index = CardIndex {
+        software_version: software_version.to_string(),
+        files,
+    };
+    let path = index_path(cache_dir, software_version);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, serde_json::to_string(&index)?)
+        .with_context(|| format!("Failed to save card index to {:?}", path))?;
+    Ok(index)
+}
+
+/// A file on a card that doesn't match the index.
+#[derive(Debug, Clone)]
+pub struct FileProblem {
+    /// Path relative to the card root, with `/` separators, as in [`IndexedFile::path`].
+    pub path: String,
+    pub description: String,
+}
+
+impl std::fmt::Display for FileProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.description)
+    }
+}
+
+/// Checks the files on a card against the index. Returns each problem found, which is empty if
+/// the card matches.
+///
+/// Sizes and the beginning and end of each file are always checked. Reading every byte is only
+/// done if `full_hash` is set, since it takes minutes per card instead of seconds.
+pub fn verify_card(card_root: &Path, index: &CardIndex, full_hash: bool) -> Vec<FileProblem> {
+    let mut problems = vec![];
+    for file in &index.files {
+        let path = card_root.join(&file.path);
+        let problem = |description: String| FileProblem {
+            path: file.path.clone(),
+            description,
+        };
+        let matches = match std::fs::metadata(&path) {
+            Err(_) => {
+                problems.push(problem(format!("{} is missing.", file.path)));
+                continue;
+            }
+            Ok(metadata) if metadata.len() != file.size => {
+                problems.push(problem(format!(
+                    "{} is {} bytes, expected {}.",
+                    file.path,
+                    metadata.len(),
+                    file.size
+                )));
+                continue;
+            }
+            Ok(_) if full_hash => hash_file(&path).map(|hash| hash == file.sha256),
+            Ok(_) => sample_hash_file(&path, file.size).map(|hash| hash == file.sample_hash),
+        };
+        match matches {
+            Ok(true) => {}
+            Ok(false) => problems.push(problem(format!(
+                "{} has been changed or is corrupt.",
+                file.path
+            ))),
+            Err(e) => problems.push(problem(format!("{} could not be read: {e}", file.path))),
+        }
+    }
+    problems
+}
+
+/// Finds the index for each software version, building it from the release archive the first
+/// time a version is seen.
+pub struct IndexSource {
+    network: Network,
+    cache_dir: PathBuf,
+    offline_source: Option<PathBuf>,
+    /// Where the driver station releases come from.
+    repo: SoftwareRepo,
+    releases: Option<Vec<GithubRelease>>,
+    indexes: HashMap<String, CardIndex>,
+}
+
+impl IndexSource {
+    pub fn new(
+        network: Network,
+        cache_dir: PathBuf,
+        offline_source: Option<PathBuf>,
+        repo: SoftwareRepo,
+    ) -> Self {
+        Self {
+            network,
+            cache_dir,
+            offline_source,
+            repo,
+            releases: None,
+            indexes: HashMap::new(),
+        }
+    }
+
+    pub fn get(&mut self, software_version: &str) -> Result<&CardIndex> {
+        if !self.indexes.contains_key(software_version) {
+            let index = match load_cached_index(&self.cache_dir, software_version) {
+                Some(index) => index,
+                None => self.build(software_version)?,
+            };
+            self.indexes.insert(software_version.to_string(), index);
+        }
+        self.indexes
+            .get(software_version)
+            .ok_or(anyhow!("Expected index for {software_version} to exist."))
+    }
+
+    /// Returns the release archive for a software version, downloading it if it isn't cached.
+    pub fn archive(&mut self, software_version: &str) -> Result<PathBuf> {
+        if self.releases.is_none() {
+            self.releases = Some(crate::utils::offline::get_releases_for_source(
+                &self.network,
+                self.offline_source.as_deref(),
+                &self.repo.owner,
+                &self.repo.name,
+            )?);
+        }
+        let release = self
+            .releases
+            .iter()
+            .flatten()
+            .find(|r| r.tag_name == software_version)
+            .ok_or(anyhow!(
+                "Release {software_version} was not found in the available releases."
+            ))?;
+        let asset = release.ramdisk_asset().ok_or(anyhow!(
+            "Could not find a ds-ramdisk archive in release assets."
+        ))?;
+        download_versioned_asset(
+            &self.network,
+            asset,
+            &self.repo.owner,
+            &self.repo.name,
+            release,
+            &self.cache_dir,
+        )
+    }
+
+    fn build(&mut self, software_version: &str) -> Result<CardIndex> {
+        let archive_path = self.archive(software_version)?;
+        log::info!("Building card index for {software_version}");
+        build_index(&archive_path, software_version, &self.cache_dir)
+    }
+}
+
+/// Returns the SHA-256 of a file as lowercase hex.
+pub fn hash_file(path: &Path) -> std::io::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; HASH_BUFFER_SIZE];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(to_hex(&hasher.finalize()))
+}
+
+fn sample_hash_file(path: &Path, size: u64) -> std::io::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut head = vec![0; SAMPLE_SIZE.min(size) as usize];
+    file.read_exact(&mut head)?;
+    let tail_len = SAMPLE_SIZE.min(size);
+    let mut tail = vec![0; tail_len as usize];
+    file.seek(SeekFrom::Start(size - tail_len))?;
+    file.read_exact(&mut tail)?;
+    Ok(sample_hash_from_parts(size, &head, &tail))
+}
+
+fn sample_hash_from_parts(size: u64, head: &[u8], tail: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(size.to_le_bytes());
+    hasher.update(head);
+    hasher.update(tail);
+    to_hex(&hasher.finalize())
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
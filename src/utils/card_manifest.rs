@@ -0,0 +1,60 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Name of the file written to the root of each driver station card after installing.
+pub const CARD_MANIFEST_FILE: &str = "gizmo-wizard.json";
+
+/// Records what the wizard installed on a driver station card, so the card can be checked later.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CardManifest {
+    pub wizard_version: String,
+    /// Release tag of the driver station software, or the archive name for local installs.
+    pub software_version: String,
+    pub team_number: String,
+    /// Seconds since the Unix epoch.
+    pub installed_at: u64,
+    /// Documentation files copied into the card's docs folder.
+    #[serde(default)]
+    pub docs: Vec<String>,
+    /// How many times the wizard has set up this card, counting this time. Carried over from
+    /// the manifest the card had before, so it counts across seasons and hubs.
+    #[serde(default)]
+    pub write_count: u32,
+}
+
+impl CardManifest {
+    pub fn new(software_version: &str, team_number: &str) -> Self {
+        Self {
+            wizard_version: env!("CARGO_PKG_VERSION").to_string(),
+            software_version: software_version.to_string(),
+            team_number: team_number.to_string(),
+            installed_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            docs: vec![],
+            write_count: 1,
+        }
+    }
+
+    /// Counts this install on top of the ones recorded in the card's previous manifest. Cards
+    /// set up before the count was kept have been set up at least once.
+    pub fn count_write_after(&mut self, previous: Option<&CardManifest>) {
+        self.write_count = previous.map_or(1, |previous| previous.write_count.max(1) + 1);
+    }
+}
+
+pub fn write_card_manifest(card_root: &Path, manifest: &CardManifest) -> Result<()> {
+    let path = card_root.join(CARD_MANIFEST_FILE);
+    std::fs::write(&path, serde_json::to_string_pretty(manifest)?)
+        .with_context(|| format!("Failed to write {:?}", path))
+}
+
+pub fn read_card_manifest(card_root: &Path) -> Result<CardManifest> {
+    let path = card_root.join(CARD_MANIFEST_FILE);
+    let contents = std::fs::read_to_string(&path).with_context(|| {
+        format!("Could not find {CARD_MANIFEST_FILE} on the card. Was it set up with this wizard?")
+    })?;
+    serde_json::from_str(&contents).with_context(|| format!("Could not parse {:?}", path))
+}
@@ -0,0 +1,131 @@
+use crate::utils::archive::common_toplevel_dir;
+use crate::utils::drive_management::{DriveInfo, DriveManager};
+use crate::utils::github::GithubReleaseAsset;
+use crate::utils::http::Network;
+use anyhow::{Context, Result, anyhow, bail};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// CircuitPython release the starter code is tested against.
+pub const CIRCUITPYTHON_VERSION: &str = "9.2.8";
+const CIRCUITPYTHON_BOARD: &str = "raspberry_pi_pico";
+/// Volume label of the drive CircuitPython exposes once it is running.
+pub const CIRCUITPY_VOLUME_LABEL: &str = "CIRCUITPY";
+
+const DRIVE_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+fn runtime_file_name() -> String {
+    format!("adafruit-circuitpython-{CIRCUITPYTHON_BOARD}-en_US-{CIRCUITPYTHON_VERSION}.uf2")
+}
+
+/// Returns the pinned CircuitPython firmware for the student processor, downloading it into
+/// `cache_dir` if needed. Offline bundles can include it at the same path as the cache.
+pub fn download_runtime(
+    network: &Network,
+    cache_dir: &Path,
+    offline_source: Option<&Path>,
+) -> Result<PathBuf> {
+    let relative_path = Path::new("adafruit")
+        .join("circuitpython")
+        .join(CIRCUITPYTHON_VERSION)
+        .join(runtime_file_name());
+    if let Some(bundle_dir) = offline_source {
+        let bundled_path = bundle_dir.join(&relative_path);
+        if bundled_path.is_file() {
+            return Ok(bundled_path);
+        }
+    }
+    let dest_path = cache_dir.join(relative_path);
+    if dest_path.is_file() {
+        return Ok(dest_path);
+    }
+    let url = format!(
+        "https://downloads.circuitpython.org/bin/{CIRCUITPYTHON_BOARD}/en_US/{}",
+        runtime_file_name()
+    );
+    crate::utils::file_download::download_file(network, &url, &dest_path)?;
+    Ok(dest_path)
+}
+
+/// Waits for the CIRCUITPY drive to appear after CircuitPython has been flashed.
+pub fn wait_for_circuitpy(drives: &dyn DriveManager, timeout: Duration) -> Result<DriveInfo> {
+    let start = Instant::now();
+    while start.elapsed() < timeout {
+        if let Some(drive) = drives
+            .list()?
+            .into_iter()
+            .find(|d| d.file_system_label == CIRCUITPY_VOLUME_LABEL)
+        {
+            return Ok(drive);
+        }
+        std::thread::sleep(DRIVE_POLL_INTERVAL);
+    }
+    bail!(
+        "The {CIRCUITPY_VOLUME_LABEL} drive did not appear within {} seconds.",
+        timeout.as_secs()
+    )
+}
+
+/// Finds the compiled library bundle among a release's assets. Bundles are named like
+/// `circuitpython-gizmo-9.x-mpy-1.2.0.zip`, with one per CircuitPython major version.
+pub fn find_library_bundle(assets: &[GithubReleaseAsset]) -> Option<&GithubReleaseAsset> {
+    let major_version = CIRCUITPYTHON_VERSION.split('.').next().unwrap_or_default();
+    let pattern = format!("-{major_version}.x-mpy-");
+    assets
+        .iter()
+        .find(|a| a.name.contains(&pattern) && a.name.ends_with(".zip"))
+}
+
+/// Copies the compiled modules under `lib/` in a library bundle into the `lib` folder on
+/// CIRCUITPY, keeping package folders. Returns the number of files copied.
+pub fn install_library_bundle(bundle_path: &Path, circuitpy_root: &Path) -> Result<usize> {
+    let bundle_file = std::fs::File::open(bundle_path)
+        .with_context(|| format!("Failed to open library bundle {:?}", bundle_path))?;
+    let mut bundle = zip::ZipArchive::new(std::io::BufReader::new(bundle_file))
+        .with_context(|| format!("Failed to read library bundle {:?}", bundle_path))?;
+
+    let mut entry_paths = vec![];
+    let mut entry_is_dir = vec![];
+    for i in 0..bundle.len() {
+        let entry = bundle.by_index(i)?;
+        let path = entry.enclosed_name().ok_or(anyhow!(
+            "Library bundle entry has an unsafe path: {}",
+            entry.name()
+        ))?;
+        entry_paths.push(path);
+        entry_is_dir.push(entry.is_dir());
+    }
+    let prefix = common_toplevel_dir(&entry_paths, &entry_is_dir);
+
+    let lib_dir = circuitpy_root.join("lib");
+    let mut copied = 0;
+    for (i, entry_path) in entry_paths.iter().enumerate() {
+        let relative_path = match &prefix {
+            Some(prefix) => entry_path.strip_prefix(prefix).unwrap_or(entry_path),
+            None => entry_path,
+        };
+        let Ok(lib_path) = relative_path.strip_prefix("lib") else {
+            continue;
+        };
+        if entry_is_dir[i] || lib_path.extension().is_none_or(|ext| ext != "mpy") {
+            continue;
+        }
+        let out_path = lib_dir.join(lib_path);
+        if let Some(parent) = out_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut entry = bundle.by_index(i)?;
+        let mut out_file = std::fs::File::create(&out_path)
+            .with_context(|| format!("Failed to create {:?}", out_path))?;
+        std::io::copy(&mut entry, &mut out_file)
+            .with_context(|| format!("Failed to write {:?}", out_path))?;
+        copied += 1;
+    }
+    if copied == 0 {
+        bail!(
+            "The library bundle {:?} has no modules in lib/.",
+            bundle_path
+        );
+    }
+    Ok(copied)
+}
@@ -0,0 +1,274 @@
+use crate::utils::github::GithubRelease;
+use crate::utils::http::Network;
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Receiver;
+
+/// Where the Gizmo project publishes which releases don't work together.
+const MATRIX_URL: &str =
+    "https://raw.githubusercontent.com/gizmo-platform/gizmo/main/compatibility.json";
+/// Name of the matrix in an offline bundle, next to the release directories.
+pub const MATRIX_FILE: &str = "compatibility.json";
+
+/// The pieces of a kit that are installed separately but have to work together.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Component {
+    DriverStation,
+    Firmware,
+    StarterCode,
+}
+
+impl std::fmt::Display for Component {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Component::DriverStation => "driver station",
+            Component::Firmware => "system firmware",
+            Component::StarterCode => "starter code",
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// Works, with known problems. The user can go ahead.
+    #[default]
+    Warn,
+    /// Doesn't work at all, so the wizard won't install it.
+    Block,
+}
+
+/// A combination of versions known not to work together. Each version is either an exact tag
+/// or a prefix ending in `*`, like "v2.*".
+#[derive(Deserialize, Debug, Clone)]
+pub struct IncompatibleRule {
+    pub versions: HashMap<Component, String>,
+    #[serde(default)]
+    pub severity: Severity,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct CompatibilityMatrix {
+    #[serde(default)]
+    pub incompatible: Vec<IncompatibleRule>,
+}
+
+impl CompatibilityMatrix {
+    fn validate(&self) -> Result<()> {
+        if let Some(rule) = self.incompatible.iter().find(|r| r.versions.len() < 2) {
+            bail!("Rule {:?} names fewer than two components.", rule.versions);
+        }
+        Ok(())
+    }
+}
+
+fn version_matches(pattern: &str, version: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => version.starts_with(prefix),
+        None => pattern == version,
+    }
+}
+
+/// Driver station and firmware releases are made together. Releases with the same major version
+/// work with each other.
+pub fn versions_compatible(driver_station_version: &str, firmware_version: &str) -> bool {
+    match (
+        major_version(driver_station_version),
+        major_version(firmware_version),
+    ) {
+        (Some(a), Some(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// The major version of a release, which is also the family of releases it was made with.
+pub fn major_version(version: &str) -> Option<u32> {
+    version
+        .trim_start_matches(|c: char| !c.is_ascii_digit())
+        .split(|c: char| !c.is_ascii_digit())
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// Picks the newest stable release with the given major version, or the latest release if
+/// there is none or no family was chosen.
+pub fn preferred_release(
+    releases: &[GithubRelease],
+    family: Option<u32>,
+) -> Option<&GithubRelease> {
+    family
+        .and_then(|family| {
+            releases
+                .iter()
+                .find(|r| !r.prerelease && !r.draft && major_version(&r.tag_name) == Some(family))
+        })
+        .or_else(|| releases.iter().find(|r| r.latest))
+}
+
+/// A selected version that doesn't work with something installed earlier in the session.
+#[derive(Debug, Clone)]
+pub struct Incompatibility {
+    pub installed: Component,
+    pub installed_version: String,
+    pub severity: Severity,
+    pub reason: Option<String>,
+}
+
+/// Reads the matrix from the offline bundle, or fetches the published one. Bundles without a
+/// matrix get an empty one, so only the built-in major version rule applies.
+fn load_matrix(network: &Network, offline_source: Option<&Path>) -> Result<CompatibilityMatrix> {
+    let matrix: CompatibilityMatrix = match offline_source {
+        Some(bundle_dir) => {
+            let path = bundle_dir.join(MATRIX_FILE);
+            if !path.is_file() {
+                return Ok(CompatibilityMatrix::default());
+            }
+            let contents = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {:?}", path))?;
+            serde_json::from_str(&contents)
+                .with_context(|| format!("Could not parse {:?}", path))?
+        }
+        None => {
+            let response = network.client.get(MATRIX_URL, &[])?;
+            if !response.is_success() {
+                bail!(
+                    "Failed to fetch the compatibility matrix: {}",
+                    response.status
+                );
+            }
+            response
+                .json()
+                .context("Could not parse the compatibility matrix")?
+        }
+    };
+    matrix.validate().context("Invalid compatibility matrix")?;
+    Ok(matrix)
+}
+
+/// Keeps track of what was installed this session, and checks new selections against it.
+pub struct Compatibility {
+    matrix: Option<CompatibilityMatrix>,
+    /// The offline source the matrix was last loaded from.
+    loaded_source: Option<Option<PathBuf>>,
+    matrix_receiver: Option<Receiver<Result<CompatibilityMatrix>>>,
+    installed: HashMap<Component, String>,
+}
+
+impl Compatibility {
+    pub fn new() -> Self {
+        Self {
+            matrix: None,
+            loaded_source: None,
+            matrix_receiver: None,
+            installed: HashMap::new(),
+        }
+    }
+
+    /// Loads the matrix whenever the release source changes. Call once per frame.
+    pub fn update(
+        &mut self,
+        ctx: &egui::Context,
+        network: &Network,
+        offline_source: &Option<PathBuf>,
+    ) {
+        if self.loaded_source.as_ref() != Some(offline_source) {
+            self.loaded_source = Some(offline_source.clone());
+            let offline_source = offline_source.clone();
+            let network = network.clone();
+            let ctx = ctx.clone();
+            let (tx, rx) = std::sync::mpsc::channel();
+            self.matrix_receiver = Some(rx);
+            std::thread::spawn(move || {
+                // The receiver is dropped if the source changed again, which is fine.
+                let _ = tx.send(load_matrix(&network, offline_source.as_deref()));
+                ctx.request_repaint();
+            });
+        }
+        if let Some(ref receiver) = self.matrix_receiver
+            && let Ok(result) = receiver.try_recv()
+        {
+            self.matrix_receiver = None;
+            match result {
+                Ok(matrix) => {
+                    log::info!(
+                        "Loaded compatibility matrix with {} rules",
+                        matrix.incompatible.len()
+                    );
+                    self.matrix = Some(matrix);
+                }
+                // Keep whatever matrix was loaded before. The major version rule still applies.
+                Err(e) => log::warn!("Could not load the compatibility matrix: {e:#}"),
+            }
+        }
+    }
+
+    /// Remembers a version that was installed, so later selections are checked against it.
+    pub fn record_install(&mut self, component: Component, version: &str) {
+        if self.installed.get(&component).map(String::as_str) != Some(version) {
+            log::info!("Installed {component} {version} this session");
+            self.installed.insert(component, version.to_string());
+        }
+    }
+
+    /// Returns what installing `version` of `component` would conflict with.
+    pub fn check(&self, component: Component, version: &str) -> Vec<Incompatibility> {
+        let mut found = vec![];
+        let rules = self.matrix.iter().flat_map(|m| &m.incompatible);
+        for rule in rules {
+            let Some(pattern) = rule.versions.get(&component) else {
+                continue;
+            };
+            if !version_matches(pattern, version) {
+                continue;
+            }
+            let others: Vec<(&Component, &String)> = rule
+                .versions
+                .iter()
+                .filter(|(other, _)| **other != component)
+                .collect();
+            let conflicts = others.iter().all(|(other, pattern)| {
+                self.installed
+                    .get(other)
+                    .is_some_and(|installed| version_matches(pattern, installed))
+            });
+            if !conflicts {
+                continue;
+            }
+            for (other, _) in others {
+                found.push(Incompatibility {
+                    installed: *other,
+                    installed_version: self.installed[other].clone(),
+                    severity: rule.severity,
+                    reason: rule.reason.clone(),
+                });
+            }
+        }
+
+        let counterpart = match component {
+            Component::DriverStation => Some(Component::Firmware),
+            Component::Firmware => Some(Component::DriverStation),
+            Component::StarterCode => None,
+        };
+        if let Some(counterpart) = counterpart
+            && let Some(installed) = self.installed.get(&counterpart)
+            && !versions_compatible(installed, version)
+            && !found.iter().any(|f| f.installed == counterpart)
+        {
+            found.push(Incompatibility {
+                installed: counterpart,
+                installed_version: installed.clone(),
+                severity: Severity::Warn,
+                reason: Some(
+                    "Driver station and system firmware releases only work with releases from the same major version.".to_string(),
+                ),
+            });
+        }
+        found
+    }
+}
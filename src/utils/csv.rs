@@ -0,0 +1,8 @@
+/// Quotes a CSV field if it contains characters that would break the row.
+pub fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
@@ -0,0 +1,160 @@
+use crate::utils::card_manifest::{CardManifest, write_card_manifest};
+use crate::utils::drive_management::{DriveInfo, DriveManager};
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+static DEMO_MODE: AtomicBool = AtomicBool::new(false);
+
+/// How long each simulated hardware step takes, so the audience can follow the progress.
+const SIMULATED_STEP: Duration = Duration::from_secs(2);
+/// Free space reported for simulated drives, so capacity checks don't fill this computer's disk.
+const SIMULATED_FREE_BYTES: u64 = 64 * 1024 * 1024;
+const SAMPLE_CARD_SIZE: u64 = 31_914_983_424;
+
+/// A drive shown in demonstration mode, backed by a folder in the temporary directory.
+struct SampleDrive {
+    label: &'static str,
+    folder: &'static str,
+    total_bytes: u64,
+    file_system: &'static str,
+    /// Fills a freshly created folder with what would be on the real drive.
+    populate: fn(&std::path::Path) -> Result<()>,
+}
+
+const SAMPLE_DRIVES: [SampleDrive; 3] = [
+    SampleDrive {
+        label: "1234",
+        folder: "card-1",
+        total_bytes: SAMPLE_CARD_SIZE,
+        file_system: "FAT32",
+        populate: |path| write_card_manifest(path, &CardManifest::new("v1.0.0", "1234")),
+    },
+    SampleDrive {
+        label: "NO NAME",
+        folder: "card-2",
+        total_bytes: SAMPLE_CARD_SIZE,
+        file_system: "FAT32",
+        populate: |_| Ok(()),
+    },
+    SampleDrive {
+        label: "RPI-RP2",
+        folder: "rp2040",
+        total_bytes: 128 * 1024 * 1024,
+        file_system: "FAT12",
+        populate: |path| {
+            std::fs::write(
+                path.join("INFO_UF2.TXT"),
+                "UF2 Bootloader v3.0\nModel: Raspberry Pi RP2\nBoard-ID: RPI-RP2\n",
+            )?;
+            Ok(())
+        },
+    },
+];
+
+/// Turns demonstration mode on or off. In demonstration mode the wizard lists simulated drives
+/// instead of real ones and only pretends to format, flush, and eject them, so teachers can walk
+/// a class through the screens on a projector without touching any hardware.
+pub fn set_demo_mode(enabled: bool) {
+    DEMO_MODE.store(enabled, Ordering::Relaxed);
+}
+
+pub fn demo_mode_enabled() -> bool {
+    DEMO_MODE.load(Ordering::Relaxed)
+}
+
+fn demo_dir() -> PathBuf {
+    std::env::temp_dir().join("best-gizmo-setup-wizard-demo")
+}
+
+/// Logs a destructive step that demonstration mode skips, so trainers can see in the log what
+/// a real run would have done.
+pub fn log_skipped(action: std::fmt::Arguments) {
+    log::info!("Demonstration mode, skipped: {action}");
+}
+
+/// Waits as long as a real hardware step might, so progress indicators get a chance to show.
+pub fn simulate_step() {
+    std::thread::sleep(SIMULATED_STEP);
+}
+
+/// Lists the simulated drives, creating their folders with sample contents the first time.
+fn demo_drives() -> Result<Vec<DriveInfo>> {
+    SAMPLE_DRIVES
+        .iter()
+        .map(|sample| {
+            let path = demo_dir().join(sample.folder);
+            if !path.is_dir() {
+                std::fs::create_dir_all(&path)
+                    .with_context(|| format!("Failed to create {:?}", path))?;
+                (sample.populate)(&path)?;
+            }
+            Ok(DriveInfo {
+                drive_path: path,
+                file_system_label: sample.label.to_string(),
+                total_bytes: sample.total_bytes,
+                free_bytes: sample.total_bytes,
+                file_system: sample.file_system.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Pretends to format a simulated drive by emptying its folder.
+fn simulate_format(drive: &DriveInfo) -> Result<()> {
+    simulate_step();
+    for entry in std::fs::read_dir(&drive.drive_path)
+        .with_context(|| format!("Failed to read {:?}", drive.drive_path))?
+    {
+        let path = entry?.path();
+        if path.is_dir() {
+            std::fs::remove_dir_all(&path)?;
+        } else {
+            std::fs::remove_file(&path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Simulated drives backed by folders in the temporary directory. Formatting, flushing, and
+/// ejecting only wait a moment and log what a real run would have done.
+pub struct DemoDriveManager;
+
+impl DriveManager for DemoDriveManager {
+    fn list(&self) -> Result<Vec<DriveInfo>> {
+        demo_drives()
+    }
+
+    fn format(
+        &self,
+        drive: &mut DriveInfo,
+        team_number: &str,
+        _allow_suspicious: bool,
+        _cluster_size: Option<u32>,
+    ) -> Result<Option<u32>> {
+        log_skipped(format_args!("formatting {drive} for team {team_number}"));
+        simulate_format(drive)?;
+        Ok(None)
+    }
+
+    fn flush(&self, drive: &DriveInfo) -> Result<()> {
+        log_skipped(format_args!("flushing {drive}"));
+        simulate_step();
+        Ok(())
+    }
+
+    fn eject(&self, drive: &DriveInfo) -> Result<()> {
+        log_skipped(format_args!("ejecting {drive}"));
+        simulate_step();
+        Ok(())
+    }
+
+    fn free_space(&self, _drive: &DriveInfo) -> Result<u64> {
+        Ok(SIMULATED_FREE_BYTES)
+    }
+
+    fn count_io_errors(&self, _drive: &DriveInfo, _since: u64) -> Result<u32> {
+        Ok(0)
+    }
+}
@@ -1,10 +1,50 @@
-use anyhow::{Context, Result, anyhow};
+use crate::i18n::tr;
+use crate::settings::Settings;
+use crate::utils::demo;
+use crate::utils::fat32::{VolumeGeometry, format_fat32};
+use crate::utils::shell::Command;
+use anyhow::{Context, Result, anyhow, bail};
 use serde::Deserialize;
+use std::time::{Duration, Instant};
+
+#[cfg(test)]
+pub mod mock;
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct DriveInfo {
     pub drive_path: std::path::PathBuf,
     pub file_system_label: String,
+    /// Size of the volume in bytes. Zero if it couldn't be read, such as on an unformatted card.
+    #[serde(default)]
+    pub total_bytes: u64,
+    #[serde(default)]
+    pub free_bytes: u64,
+    /// Filesystem type, like "FAT32" or "exFAT". Empty if it isn't known.
+    #[serde(default)]
+    pub file_system: String,
+}
+
+impl DriveInfo {
+    /// Describes the drive along with its size and filesystem, so drive pickers can tell a
+    /// microSD card from a backup hard drive.
+    pub fn picker_label(&self) -> String {
+        let mut details = vec![];
+        if self.total_bytes > 0 {
+            details.push(tr!(
+                "drives-free-of",
+                free = crate::utils::cache::format_size(self.free_bytes),
+                total = crate::utils::cache::format_size(self.total_bytes)
+            ));
+        }
+        if !self.file_system.is_empty() {
+            details.push(self.file_system.clone());
+        }
+        if details.is_empty() {
+            format!("{self}")
+        } else {
+            format!("{self} - {}", details.join(", "))
+        }
+    }
 }
 
 impl PartialEq for DriveInfo {
@@ -31,134 +71,1404 @@ impl std::fmt::Display for DriveInfo {
     }
 }
 
+/// Drives larger than this are more likely to be external hard drives than SD cards.
+const MAX_EXPECTED_CARD_SIZE: u64 = 128 * 1000 * 1000 * 1000;
+const EXPECTED_BUS_TYPES: [&str; 3] = ["usb", "sd", "mmc"];
+
+/// Hardware details used to decide whether a drive looks like a removable card.
+struct DiskDetails {
+    size_bytes: u64,
+    bus_type: String,
+    is_system_disk: bool,
+}
+
+/// Reasons a drive might not be a card meant to be erased by the wizard.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DriveSafety {
+    pub warnings: Vec<String>,
+}
+
+impl DriveSafety {
+    pub fn is_suspicious(&self) -> bool {
+        !self.warnings.is_empty()
+    }
+}
+
+/// Checks whether a drive looks like a removable SD card or USB stick. A drive that cannot be
+/// checked is treated as suspicious.
+pub fn check_drive_safety(drive: &DriveInfo) -> DriveSafety {
+    if demo::demo_mode_enabled() {
+        return DriveSafety::default();
+    }
+    let details = match get_disk_details(drive) {
+        Ok(details) => details,
+        Err(e) => {
+            return DriveSafety {
+                warnings: vec![tr!("drives-check-failed", error = format!("{e:#}"))],
+            };
+        }
+    };
+    let mut warnings = vec![];
+    if details.is_system_disk {
+        warnings.push(tr!("drives-system-disk"));
+    }
+    if !EXPECTED_BUS_TYPES.contains(&details.bus_type.to_lowercase().as_str()) {
+        let bus = if details.bus_type.is_empty() {
+            tr!("drives-unknown-bus")
+        } else {
+            details.bus_type.clone()
+        };
+        warnings.push(tr!("drives-unexpected-bus", bus = bus));
+    }
+    if details.size_bytes > MAX_EXPECTED_CARD_SIZE {
+        warnings.push(tr!(
+            "drives-too-large",
+            size = crate::utils::cache::format_size(details.size_bytes)
+        ));
+    }
+    DriveSafety { warnings }
+}
+
+/// FAT32 needs at least this many clusters, so large clusters don't fit on small cards.
+const MIN_FAT32_CLUSTERS: u64 = 65525;
+
+/// Operations on removable drives. Pages use the one in [`GlobalAppState`], so simulated
+/// drives can stand in for real ones without each page checking which it has.
+///
+/// [`GlobalAppState`]: crate::app::GlobalAppState
+pub trait DriveManager: Send + Sync {
+    /// Lists the removable drives.
+    fn list(&self) -> Result<Vec<DriveInfo>>;
+
+    /// Erases the drive and names it for `team_number`. `drive` is updated if the formatted
+    /// drive is mounted somewhere new.
+    ///
+    /// Drives that fail [`check_drive_safety`] are only formatted if `allow_suspicious` is set,
+    /// which callers should only do after the user has explicitly confirmed.
+    ///
+    /// `cluster_size` requests a FAT32 allocation unit size in bytes. It is dropped in favor of
+    /// the system default if the card is too small for it. Returns the cluster size that was
+    /// requested from the formatter, if any, so the caller can check it with [`cluster_size`].
+    fn format(
+        &self,
+        drive: &mut DriveInfo,
+        team_number: &str,
+        allow_suspicious: bool,
+        cluster_size: Option<u32>,
+    ) -> Result<Option<u32>>;
+
+    /// Makes sure everything written to the drive has reached the card.
+    fn flush(&self, drive: &DriveInfo) -> Result<()>;
+
+    /// Prepares the drive to be pulled out, like "Safely Remove" or "Eject" in the file manager.
+    fn eject(&self, drive: &DriveInfo) -> Result<()>;
+
+    fn free_space(&self, drive: &DriveInfo) -> Result<u64>;
+
+    /// Counts the I/O errors the OS logged for the drive since `since`, in seconds since the
+    /// Unix epoch.
+    fn count_io_errors(&self, drive: &DriveInfo, since: u64) -> Result<u32>;
+}
+
+/// Returns the simulated drives in demonstration mode, or this computer's drives otherwise.
+pub fn drive_manager(settings: &Settings) -> std::sync::Arc<dyn DriveManager> {
+    if demo::demo_mode_enabled() {
+        std::sync::Arc::new(demo::DemoDriveManager)
+    } else {
+        std::sync::Arc::new(SystemDriveManager {
+            native_formatting: settings.native_formatting,
+        })
+    }
+}
+
+/// Lists the simulated drives in demonstration mode, or this computer's drives otherwise.
+pub fn list_drives() -> Result<Vec<DriveInfo>> {
+    if demo::demo_mode_enabled() {
+        demo::DemoDriveManager.list()
+    } else {
+        list_system_drives()
+    }
+}
+
+/// The drives plugged into this computer, managed with the platform's own tools.
+pub struct SystemDriveManager {
+    /// Whether cards are formatted by the wizard itself or with the system's tools
+    /// (Format-Volume or mkfs.vfat). Native formatting still falls back to the system's tools
+    /// when the card can't be opened directly, such as without administrator rights.
+    pub native_formatting: bool,
+}
+
+impl DriveManager for SystemDriveManager {
+    fn list(&self) -> Result<Vec<DriveInfo>> {
+        list_system_drives()
+    }
+
+    fn format(
+        &self,
+        drive: &mut DriveInfo,
+        team_number: &str,
+        allow_suspicious: bool,
+        cluster_size: Option<u32>,
+    ) -> Result<Option<u32>> {
+        let safety = check_drive_safety(drive);
+        if safety.is_suspicious() && !allow_suspicious {
+            bail!(
+                "Refusing to format {drive} without confirmation: {}",
+                safety.warnings.join(" ")
+            );
+        }
+        let cluster_size = cluster_size.filter(|&size| match get_disk_details(drive) {
+            Ok(details) if details.size_bytes / u64::from(size) < MIN_FAT32_CLUSTERS => {
+                log::warn!("{drive} is too small for {size} byte clusters, using the default");
+                false
+            }
+            _ => true,
+        });
+        format_volume(drive, team_number, cluster_size, self.native_formatting)?;
+        Ok(cluster_size)
+    }
+
+    fn flush(&self, drive: &DriveInfo) -> Result<()> {
+        flush_volume(drive)
+    }
+
+    fn eject(&self, drive: &DriveInfo) -> Result<()> {
+        eject_volume(drive)
+    }
+
+    fn free_space(&self, drive: &DriveInfo) -> Result<u64> {
+        free_space_at(&drive.drive_path)
+    }
+
+    fn count_io_errors(&self, drive: &DriveInfo, since: u64) -> Result<u32> {
+        count_disk_errors(drive, since)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct DiskSpace {
+    total_bytes: u64,
+    /// Space this user can write to, which leaves out blocks reserved for the system.
+    free_bytes: u64,
+}
+
+/// Returns the free space on the volume holding `path`, such as the download cache.
+pub fn free_space_at(path: &std::path::Path) -> Result<u64> {
+    Ok(disk_space(path)?.free_bytes)
+}
+
+/// Format-Volume can take minutes on a large, slow card.
+#[cfg(target_os = "windows")]
+const FORMAT_TIMEOUT: Duration = Duration::from_secs(600);
+/// How long to wait for a card's new partitions to show up after a disk image is written.
+const REMOUNT_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// A card's whole disk, opened to write a disk image over its partitions. The card's volumes
+/// stay dismounted while it is open.
+pub struct RawDisk {
+    pub file: std::fs::File,
+    /// Device path of the disk, like "/dev/sdb" or "\\.\PhysicalDrive2".
+    pub device: String,
+    pub size_bytes: u64,
+    /// The filesystem the card was opened through, used to find the card again afterwards.
+    #[cfg(target_os = "linux")]
+    filesystem: crate::utils::udisks::Filesystem,
+    #[cfg(target_os = "windows")]
+    disk_number: u32,
+    /// Handles that keep the card's volumes locked, so Windows doesn't remount them mid-write.
+    #[cfg(target_os = "windows")]
+    locked_volumes: Vec<std::fs::File>,
+}
+
+impl RawDisk {
+    /// Drops the disk's blocks from the system's cache, so reading them back reads the card.
+    pub fn drop_cached_blocks(&self) -> Result<()> {
+        #[cfg(target_os = "linux")]
+        {
+            use std::os::fd::AsRawFd;
+            // SAFETY: `file` is open, and an offset and length of zero cover the whole disk.
+            let result = unsafe {
+                libc::posix_fadvise(self.file.as_raw_fd(), 0, 0, libc::POSIX_FADV_DONTNEED)
+            };
+            if result != 0 {
+                return Err(std::io::Error::from_raw_os_error(result))
+                    .with_context(|| format!("Failed to drop cached blocks of {}", self.device));
+            }
+        }
+        // Windows doesn't cache reads from a raw disk handle.
+        Ok(())
+    }
+}
+
+/// Opens the whole disk holding the drive, so a disk image can be written over it. Like
+/// [`DriveManager::format`], refuses drives that don't look like cards unless `allow_suspicious` is set.
+pub fn open_raw_disk(drive: &DriveInfo, allow_suspicious: bool) -> Result<RawDisk> {
+    // Imaging is simulated before it gets here, but a raw disk is the one thing demonstration
+    // mode must never open.
+    if demo::demo_mode_enabled() {
+        bail!("Demonstration mode does not open real disks.");
+    }
+    let safety = check_drive_safety(drive);
+    if safety.is_suspicious() && !allow_suspicious {
+        bail!(
+            "Refusing to write an image to {drive} without confirmation: {}",
+            safety.warnings.join(" ")
+        );
+    }
+    let size_bytes = get_disk_details(drive)?.size_bytes;
+    open_disk(drive, size_bytes)
+}
+
+/// Finds the FAT volume on a card after a disk image was written over it, mounting it if
+/// needed, so files can be added to it.
+pub fn remount_raw_disk(disk: RawDisk) -> Result<DriveInfo> {
+    let device = disk.device.clone();
+    let deadline = Instant::now() + REMOUNT_TIMEOUT;
+    let closed = close_disk(disk)?;
+    loop {
+        // The system takes a moment to notice the new partitions.
+        std::thread::sleep(Duration::from_secs(1));
+        if let Some(drive) = find_fat_volume(&closed)? {
+            return Ok(drive);
+        }
+        if Instant::now() > deadline {
+            bail!(
+                "The image was written to {device}, but no FAT volume showed up on it. The image may not have one."
+            );
+        }
+    }
+}
+
+/// Returns true if the drive looks like any UF2 bootloader volume. Copying a .uf2 file onto
+/// an ordinary flash drive "succeeds" without flashing anything.
+pub fn is_uf2_bootloader(drive: &DriveInfo) -> bool {
+    drive.drive_path.join("INFO_UF2.TXT").is_file() || drive.drive_path.join("INDEX.HTM").is_file()
+}
+
+/// Returns true if the drive is an RP2040 in BOOTSEL mode, ready to be flashed with a UF2 file.
+pub fn is_rp2040_bootloader(drive: &DriveInfo) -> bool {
+    std::fs::read_to_string(drive.drive_path.join("INFO_UF2.TXT"))
+        .is_ok_and(|info| info.lines().any(|line| line.trim() == "Board-ID: RPI-RP2"))
+}
+
 #[cfg(target_os = "windows")]
 impl DriveInfo {
     fn get_drive_letter(&self) -> Option<String> {
-        match self.drive_path.components().next() {
-            Some(std::path::Component::Prefix(component)) => match component.kind() {
-                std::path::Prefix::Disk(letter) => String::from_utf8(vec![letter]).ok(),
-                _ => None,
-            },
+        drive_letter_of(&self.drive_path)
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn drive_letter_of(path: &std::path::Path) -> Option<String> {
+    match path.components().next() {
+        Some(std::path::Component::Prefix(component)) => match component.kind() {
+            std::path::Prefix::Disk(letter) => String::from_utf8(vec![letter]).ok(),
             _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Converts a path to the NUL-terminated UTF-16 the Windows API takes.
+#[cfg(target_os = "windows")]
+fn to_wide(path: &std::path::Path) -> Vec<u16> {
+    use std::os::windows::ffi::OsStrExt;
+    path.as_os_str().encode_wide().chain([0]).collect()
+}
+
+#[cfg(target_os = "windows")]
+fn list_system_drives() -> Result<Vec<DriveInfo>> {
+    use windows_sys::Win32::Foundation::{ERROR_NOT_READY, MAX_PATH};
+    use windows_sys::Win32::Storage::FileSystem::{
+        GetDriveTypeW, GetLogicalDrives, GetVolumeInformationW,
+    };
+    use windows_sys::Win32::System::Diagnostics::Debug::{
+        SEM_FAILCRITICALERRORS, SetThreadErrorMode,
+    };
+    use windows_sys::Win32::System::WindowsProgramming::DRIVE_REMOVABLE;
+
+    // SAFETY: GetLogicalDrives takes no arguments.
+    let drive_mask = unsafe { GetLogicalDrives() };
+    if drive_mask == 0 {
+        return Err(std::io::Error::last_os_error()).context("Listing drives failed");
+    }
+    // Without this, asking about a card reader with no card in it shows a "No disk" dialog.
+    let mut previous_error_mode = 0;
+    // SAFETY: `previous_error_mode` is a valid place to store the old mode.
+    unsafe { SetThreadErrorMode(SEM_FAILCRITICALERRORS, &mut previous_error_mode) };
+
+    let mut drives = vec![];
+    for (bit, letter) in ('A'..='Z').enumerate() {
+        if drive_mask & (1 << bit) == 0 {
+            continue;
+        }
+        let drive_path = std::path::PathBuf::from(format!("{letter}:\\"));
+        let root = to_wide(&drive_path);
+        // SAFETY: `root` is NUL-terminated.
+        if unsafe { GetDriveTypeW(root.as_ptr()) } != DRIVE_REMOVABLE {
+            continue;
+        }
+        let mut label = [0u16; MAX_PATH as usize + 1];
+        let mut file_system = [0u16; MAX_PATH as usize + 1];
+        // SAFETY: `root` is NUL-terminated, `label` and `file_system` have room for the lengths
+        // passed, and the outputs that aren't needed are null.
+        let has_filesystem = unsafe {
+            GetVolumeInformationW(
+                root.as_ptr(),
+                label.as_mut_ptr(),
+                label.len() as u32,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                file_system.as_mut_ptr(),
+                file_system.len() as u32,
+            )
+        } != 0;
+        // Unformatted cards are still listed so they can be formatted, but empty card reader
+        // slots are not.
+        if !has_filesystem
+            && std::io::Error::last_os_error().raw_os_error() == Some(ERROR_NOT_READY as i32)
+        {
+            continue;
         }
+        let wide_string = |buffer: &[u16]| {
+            let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+            String::from_utf16_lossy(&buffer[..len])
+        };
+        let space = disk_space(&drive_path).unwrap_or_default();
+        drives.push(DriveInfo {
+            file_system_label: wide_string(&label),
+            total_bytes: space.total_bytes,
+            free_bytes: space.free_bytes,
+            file_system: wide_string(&file_system),
+            drive_path,
+        });
     }
+
+    // SAFETY: Restores the mode saved above. The old mode isn't needed.
+    unsafe { SetThreadErrorMode(previous_error_mode, std::ptr::null_mut()) };
+    Ok(drives)
 }
 
 #[cfg(target_os = "windows")]
-pub fn list_drives() -> Result<Vec<DriveInfo>> {
-    let powershell_command = "Get-Volume | Where-Object {$_.DriveType -eq 'Removable'} | Select-Object DriveLetter, FileSystemLabel | ConvertTo-Json";
-    let output = crate::utils::shell::run_powershell_command(powershell_command)
-        .with_context(|| "Running Get-Volume failed")?;
-    let mut drive_info_str = String::from_utf8(output.stdout)?;
-    if !drive_info_str.starts_with("[") {
-        drive_info_str = String::from("[") + &drive_info_str + "]";
-    }
-    let json_val = serde_json::from_str::<serde_json::Value>(&drive_info_str)?;
-    let mut result = vec![];
-    if let serde_json::Value::Array(json_arr) = json_val {
-        for item in json_arr {
-            if let serde_json::Value::Object(json_obj) = item {
-                result.push(DriveInfo {
-                    drive_path: std::path::PathBuf::from(
-                        json_obj["DriveLetter"]
-                            .as_str()
-                            .ok_or(anyhow!("Missing field DriveLetter in PowerShell output."))?
-                            .to_string()
-                            + ":\\",
-                    ),
-                    file_system_label: json_obj["FileSystemLabel"]
-                        .as_str()
-                        .ok_or(anyhow!(
-                            "Missing field FileSystemLabel in PowerShell output."
-                        ))?
-                        .to_string(),
-                });
-            }
+fn format_volume(
+    drive: &mut DriveInfo,
+    team_number: &str,
+    cluster_size: Option<u32>,
+    native_formatting: bool,
+) -> Result<()> {
+    let drive_letter = drive
+        .get_drive_letter()
+        .ok_or(anyhow!("Could not determine drive letter."))?;
+    if native_formatting {
+        match format_volume_natively(&drive_letter, &format!("GIZMO{team_number}"), cluster_size) {
+            Ok(()) => return Ok(()),
+            Err(e) => log::warn!("Formatting {drive} natively failed, using Format-Volume: {e:#}"),
         }
     }
-    Ok(result)
+    Command::powershell(
+        "$options = @{ DriveLetter = $env:WIZARD_DRIVE_LETTER; FileSystem = 'FAT32'; NewFileSystemLabel = $env:WIZARD_LABEL }; if ($env:WIZARD_CLUSTER_SIZE) { $options.AllocationUnitSize = [uint32]$env:WIZARD_CLUSTER_SIZE }; Format-Volume @options",
+    )
+    .param("DRIVE_LETTER", &drive_letter)
+    .param("LABEL", format!("GIZMO{team_number}"))
+    .param(
+        "CLUSTER_SIZE",
+        cluster_size.map(|size| size.to_string()).unwrap_or_default(),
+    )
+    .timeout(Some(FORMAT_TIMEOUT))
+    .run()
+    .with_context(|| "Running Format-Volume failed")?;
+    Ok(())
 }
 
+/// Locks and dismounts the volume, then writes the new filesystem over it. Windows mounts the
+/// new filesystem when the volume handle is closed.
 #[cfg(target_os = "windows")]
-pub fn format_drive(drive: &DriveInfo, team_number: &str) -> Result<()> {
-    let powershell_command = format!(
-        "Format-Volume -DriveLetter {} -FileSystem FAT32 -NewFileSystemLabel 'GIZMO{}'",
-        drive
-            .get_drive_letter()
-            .ok_or(anyhow!("Could not determine drive letter."))?,
-        team_number
-    );
-    crate::utils::shell::run_powershell_command(&powershell_command)
-        .with_context(|| "Running Format-Volume failed")?;
+fn format_volume_natively(
+    drive_letter: &str,
+    label: &str,
+    cluster_size: Option<u32>,
+) -> Result<()> {
+    use crate::utils::fat32::SECTOR_SIZE;
+    use std::ffi::c_void;
+    use std::os::windows::io::AsRawHandle;
+    use windows_sys::Win32::System::IO::DeviceIoControl;
+    use windows_sys::Win32::System::Ioctl::{
+        DISK_GEOMETRY, FSCTL_DISMOUNT_VOLUME, FSCTL_LOCK_VOLUME, IOCTL_DISK_GET_DRIVE_GEOMETRY,
+        IOCTL_DISK_GET_PARTITION_INFO_EX, PARTITION_INFORMATION_EX,
+    };
+
+    let mut volume = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(format!(r"\\.\{drive_letter}:"))
+        .with_context(|| format!("Could not open volume {drive_letter}:"))?;
+    let handle = volume.as_raw_handle();
+    let control = |code: u32, output: *mut c_void, output_size: u32| -> Result<()> {
+        let mut bytes_returned = 0u32;
+        // SAFETY: `handle` stays open for the duration of the call, and `output` points to
+        // `output_size` writable bytes or is null with a size of zero.
+        let succeeded = unsafe {
+            DeviceIoControl(
+                handle,
+                code,
+                std::ptr::null(),
+                0,
+                output,
+                output_size,
+                &mut bytes_returned,
+                std::ptr::null_mut(),
+            )
+        };
+        if succeeded == 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        Ok(())
+    };
+
+    let mut partition = PARTITION_INFORMATION_EX::default();
+    control(
+        IOCTL_DISK_GET_PARTITION_INFO_EX,
+        (&mut partition as *mut PARTITION_INFORMATION_EX).cast(),
+        std::mem::size_of::<PARTITION_INFORMATION_EX>() as u32,
+    )
+    .context("Could not read the partition size")?;
+    let mut disk_geometry = DISK_GEOMETRY::default();
+    control(
+        IOCTL_DISK_GET_DRIVE_GEOMETRY,
+        (&mut disk_geometry as *mut DISK_GEOMETRY).cast(),
+        std::mem::size_of::<DISK_GEOMETRY>() as u32,
+    )
+    .context("Could not read the sector size")?;
+    // Checked before the volume is dismounted, so a card that can't be formatted here is left
+    // as it was for Format-Volume.
+    if u64::from(disk_geometry.BytesPerSector) != SECTOR_SIZE {
+        bail!(
+            "The card has {} byte sectors, which only Format-Volume can format.",
+            disk_geometry.BytesPerSector
+        );
+    }
+    control(FSCTL_LOCK_VOLUME, std::ptr::null_mut(), 0)
+        .context("Could not lock the volume. Close any windows showing the card.")?;
+    control(FSCTL_DISMOUNT_VOLUME, std::ptr::null_mut(), 0)
+        .context("Could not dismount the volume")?;
+
+    let geometry = VolumeGeometry {
+        sector_size: disk_geometry.BytesPerSector,
+        total_sectors: partition.PartitionLength as u64 / SECTOR_SIZE,
+        hidden_sectors: (partition.StartingOffset as u64 / SECTOR_SIZE) as u32,
+    };
+    format_fat32(&mut volume, &geometry, label, cluster_size)?;
+    volume.sync_all()?;
     Ok(())
 }
 
+/// Returns the allocation unit size of the drive's filesystem in bytes.
 #[cfg(target_os = "windows")]
-pub fn write_filesystem_cache(drive: &DriveInfo) -> Result<()> {
-    let powershell_command = format!(
-        "Write-VolumeCache -DriveLetter {}",
-        drive
-            .get_drive_letter()
-            .ok_or(anyhow!("Could not determine drive letter."))?
-    );
-    crate::utils::shell::run_powershell_command(&powershell_command)
-        .with_context(|| "Writing filesystem cache failed")?;
+pub fn cluster_size(drive: &DriveInfo) -> Result<u32> {
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceW;
+    let root = to_wide(&drive.drive_path);
+    let mut sectors_per_cluster = 0u32;
+    let mut bytes_per_sector = 0u32;
+    let mut free_clusters = 0u32;
+    let mut total_clusters = 0u32;
+    // SAFETY: `root` is NUL-terminated and each output points to a u32.
+    let succeeded = unsafe {
+        GetDiskFreeSpaceW(
+            root.as_ptr(),
+            &mut sectors_per_cluster,
+            &mut bytes_per_sector,
+            &mut free_clusters,
+            &mut total_clusters,
+        )
+    };
+    if succeeded == 0 {
+        return Err(std::io::Error::last_os_error())
+            .with_context(|| format!("Failed to look up the cluster size of {drive}"));
+    }
+    Ok(sectors_per_cluster * bytes_per_sector)
+}
+
+/// Flushes everything Windows has cached for the volume out to the card. Opening the volume
+/// directly can be refused on locked-down laptops, in which case Write-VolumeCache is used.
+#[cfg(target_os = "windows")]
+fn flush_volume(drive: &DriveInfo) -> Result<()> {
+    let drive_letter = drive
+        .get_drive_letter()
+        .ok_or(anyhow!("Could not determine drive letter."))?;
+    let flushed = std::fs::OpenOptions::new()
+        .write(true)
+        .open(format!(r"\\.\{drive_letter}:"))
+        .and_then(|volume| volume.sync_all());
+    match flushed {
+        Ok(()) => Ok(()),
+        Err(e) => {
+            log::info!("Flushing {drive} directly failed, using Write-VolumeCache: {e}");
+            Command::powershell("Write-VolumeCache -DriveLetter $env:WIZARD_DRIVE_LETTER")
+                .param("DRIVE_LETTER", &drive_letter)
+                .run()
+                .with_context(|| "Writing filesystem cache failed")?;
+            Ok(())
+        }
+    }
+}
+
+/// Flushes, dismounts, and ejects the volume so the card can be pulled out without losing
+/// writes. Windows shows the drive as empty until the card is reinserted.
+#[cfg(target_os = "windows")]
+fn eject_volume(drive: &DriveInfo) -> Result<()> {
+    use windows_sys::Win32::System::Ioctl::{
+        FSCTL_DISMOUNT_VOLUME, FSCTL_LOCK_VOLUME, IOCTL_STORAGE_EJECT_MEDIA,
+        IOCTL_STORAGE_MEDIA_REMOVAL, PREVENT_MEDIA_REMOVAL,
+    };
+
+    let drive_letter = drive
+        .get_drive_letter()
+        .ok_or(anyhow!("Could not determine drive letter."))?;
+    let volume = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(format!(r"\\.\{drive_letter}:"))
+        .with_context(|| format!("Could not open volume {drive_letter}:"))?;
+    volume.sync_all()?;
+    let control = |code, input, input_size| device_control(&volume, code, input, input_size);
+
+    control(FSCTL_LOCK_VOLUME, std::ptr::null(), 0)
+        .context("Could not lock the volume. Close any windows showing the card.")?;
+    control(FSCTL_DISMOUNT_VOLUME, std::ptr::null(), 0).context("Could not dismount the volume")?;
+    let allow_removal = PREVENT_MEDIA_REMOVAL {
+        PreventMediaRemoval: false,
+    };
+    control(
+        IOCTL_STORAGE_MEDIA_REMOVAL,
+        (&allow_removal as *const PREVENT_MEDIA_REMOVAL).cast(),
+        std::mem::size_of::<PREVENT_MEDIA_REMOVAL>() as u32,
+    )
+    .context("Could not allow the card to be removed")?;
+    control(IOCTL_STORAGE_EJECT_MEDIA, std::ptr::null(), 0).context("Could not eject the card")?;
     Ok(())
 }
 
-#[cfg(target_os = "linux")]
-pub fn list_drives() -> Result<Vec<DriveInfo>> {
-    let username = std::env::var("USER")?;
-    let bash_command = format!("ls /media/{username}");
-    let command_output = crate::utils::shell::run_bash_command(&bash_command)
-        .with_context(|| "Listing removable drives failed.")?;
-    let command_output = String::from_utf8(command_output.stdout)?;
-    let mut drives = vec![];
-    for line in command_output.lines() {
-        drives.push(DriveInfo {
-            drive_path: std::path::PathBuf::from(format!("/media/{username}/{line}")),
-            file_system_label: line.to_string(),
-        });
+/// Sends a control code that returns no output to an open volume or disk.
+#[cfg(target_os = "windows")]
+fn device_control(
+    device: &std::fs::File,
+    code: u32,
+    input: *const std::ffi::c_void,
+    input_size: u32,
+) -> Result<()> {
+    use std::os::windows::io::AsRawHandle;
+    use windows_sys::Win32::System::IO::DeviceIoControl;
+
+    let mut bytes_returned = 0u32;
+    // SAFETY: `device` stays open for the duration of the call, and `input` points to
+    // `input_size` readable bytes or is null with a size of zero.
+    let succeeded = unsafe {
+        DeviceIoControl(
+            device.as_raw_handle(),
+            code,
+            input,
+            input_size,
+            std::ptr::null_mut(),
+            0,
+            &mut bytes_returned,
+            std::ptr::null_mut(),
+        )
+    };
+    if succeeded == 0 {
+        return Err(std::io::Error::last_os_error().into());
     }
-    Ok(drives)
+    Ok(())
 }
 
-#[cfg(target_os = "linux")]
-pub fn format_drive(drive: &DriveInfo, team_number: &str) -> Result<()> {
-    let drive_path_str = drive
-        .drive_path
-        .to_str()
-        .ok_or(anyhow!("Failed to convert disk path to string."))?;
-    let block_device_path = {
-        let cmd_output = crate::utils::shell::run_bash_command(
-            format!("df {drive_path_str} | awk 'NR>1{{print $1}}'").as_str(),
+/// Sends a control code that fills in a `T` to an open volume or disk.
+#[cfg(target_os = "windows")]
+fn device_query<T: Default>(
+    device: &std::fs::File,
+    code: u32,
+    input: *const std::ffi::c_void,
+    input_size: u32,
+) -> Result<T> {
+    use std::os::windows::io::AsRawHandle;
+    use windows_sys::Win32::System::IO::DeviceIoControl;
+
+    let mut output = T::default();
+    let mut bytes_returned = 0u32;
+    // SAFETY: `device` stays open for the duration of the call, `input` points to `input_size`
+    // readable bytes or is null with a size of zero, and `output` is a writable `T`.
+    let succeeded = unsafe {
+        DeviceIoControl(
+            device.as_raw_handle(),
+            code,
+            input,
+            input_size,
+            (&mut output as *mut T).cast(),
+            std::mem::size_of::<T>() as u32,
+            &mut bytes_returned,
+            std::ptr::null_mut(),
         )
-        .with_context(|| "Failed to look up drive block device.")?;
-        String::from_utf8(cmd_output.stdout)?
     };
-    crate::utils::shell::run_bash_command(
-        format!("udisksctl unmount -b {block_device_path}").as_str(),
+    if succeeded == 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    Ok(output)
+}
+
+#[cfg(target_os = "windows")]
+fn open_disk(drive: &DriveInfo, size_bytes: u64) -> Result<RawDisk> {
+    use windows_sys::Win32::System::Ioctl::{FSCTL_DISMOUNT_VOLUME, FSCTL_LOCK_VOLUME};
+
+    let drive_letter = drive
+        .get_drive_letter()
+        .ok_or(anyhow!("Could not determine drive letter."))?;
+    // Images are written over every partition on the card, so all of its volumes are locked.
+    let (disk_number, drive_letters) = match disk_volumes(&drive_letter) {
+        Ok(volumes) => volumes,
+        Err(e) => {
+            log::info!("Looking up {drive}'s disk natively failed, using Get-Partition: {e:#}");
+            disk_volumes_from_powershell(&drive_letter)?
+        }
+    };
+    let mut locked_volumes = vec![];
+    for letter in drive_letters {
+        let volume = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(format!(r"\\.\{letter}:"))
+            .with_context(|| format!("Could not open volume {letter}:"))?;
+        volume.sync_all()?;
+        device_control(&volume, FSCTL_LOCK_VOLUME, std::ptr::null(), 0).with_context(|| {
+            format!("Could not lock volume {letter}:. Close any windows showing the card.")
+        })?;
+        device_control(&volume, FSCTL_DISMOUNT_VOLUME, std::ptr::null(), 0)
+            .with_context(|| format!("Could not dismount volume {letter}:"))?;
+        locked_volumes.push(volume);
+    }
+    let device = format!(r"\\.\PhysicalDrive{disk_number}");
+    let file = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(&device)
+        .with_context(|| {
+            format!("Could not open {device} to write the image. Writing disk images needs the wizard to run as administrator.")
+        })?;
+    Ok(RawDisk {
+        file,
+        device,
+        size_bytes,
+        disk_number,
+        locked_volumes,
+    })
+}
+
+/// Returns the number of the disk holding the volume with the given letter, and the letters
+/// of every volume on that disk.
+#[cfg(target_os = "windows")]
+fn disk_volumes(drive_letter: &str) -> Result<(u32, Vec<char>)> {
+    use windows_sys::Win32::Storage::FileSystem::GetLogicalDrives;
+
+    let disk_number = volume_disk_number(drive_letter)?;
+    // SAFETY: GetLogicalDrives takes no arguments.
+    let drive_mask = unsafe { GetLogicalDrives() };
+    if drive_mask == 0 {
+        return Err(std::io::Error::last_os_error()).context("Listing drives failed");
+    }
+    // Network and optical drives can't be asked for their disk, and aren't on the card.
+    let drive_letters = ('A'..='Z')
+        .enumerate()
+        .filter(|(bit, _)| drive_mask & (1 << bit) != 0)
+        .map(|(_, letter)| letter)
+        .filter(|letter| volume_disk_number(&letter.to_string()).is_ok_and(|n| n == disk_number))
+        .collect();
+    Ok((disk_number, drive_letters))
+}
+
+#[cfg(target_os = "windows")]
+fn disk_volumes_from_powershell(drive_letter: &str) -> Result<(u32, Vec<char>)> {
+    let output = Command::powershell(
+        "$n = (Get-Partition -DriveLetter $env:WIZARD_DRIVE_LETTER).DiskNumber; \"$n|$((Get-Partition -DiskNumber $n | Where-Object DriveLetter).DriveLetter -join '')\"",
     )
-    .with_context(|| "Unmounting disk failed.")?;
-    crate::utils::shell::run_admin_bash_command(
-        format!("mkfs.vfat -F 32 -n 'GIZMO{team_number}' {block_device_path}").as_str(),
+    .param("DRIVE_LETTER", drive_letter)
+    .run()
+    .with_context(|| "Looking up the card's disk failed")?;
+    let output = String::from_utf8(output.stdout)?;
+    let (disk_number, drive_letters) = output
+        .trim()
+        .split_once('|')
+        .ok_or(anyhow!("Unexpected Get-Partition output: {output}"))?;
+    Ok((disk_number.parse()?, drive_letters.chars().collect()))
+}
+
+/// Returns the number of the disk that holds the volume with the given letter.
+#[cfg(target_os = "windows")]
+fn volume_disk_number(drive_letter: &str) -> Result<u32> {
+    use windows_sys::Win32::Storage::FileSystem::IOCTL_VOLUME_GET_VOLUME_DISK_EXTENTS;
+    use windows_sys::Win32::System::Ioctl::VOLUME_DISK_EXTENTS;
+
+    let volume = open_device_for_query(&format!(r"\\.\{drive_letter}:"))?;
+    // Fails with ERROR_MORE_DATA for volumes spread over several disks, which cards never are.
+    let extents: VOLUME_DISK_EXTENTS = device_query(
+        &volume,
+        IOCTL_VOLUME_GET_VOLUME_DISK_EXTENTS,
+        std::ptr::null(),
+        0,
     )
-    .with_context(|| "Formatting disk failed.")?;
-    crate::utils::shell::run_bash_command(
-        format!("udisksctl mount -b {block_device_path}").as_str(),
+    .with_context(|| format!("Could not find the disk holding {drive_letter}:"))?;
+    Ok(extents.Extents[0].DiskNumber)
+}
+
+/// Opens a volume or disk without read or write access. That is enough for control codes that
+/// only describe the device, and doesn't need administrator rights.
+#[cfg(target_os = "windows")]
+fn open_device_for_query(path: &str) -> Result<std::fs::File> {
+    use std::os::windows::fs::OpenOptionsExt;
+    std::fs::OpenOptions::new()
+        .access_mode(0)
+        .open(path)
+        .with_context(|| format!("Could not open {path}"))
+}
+
+#[cfg(target_os = "windows")]
+fn close_disk(disk: RawDisk) -> Result<u32> {
+    disk.file.sync_all()?;
+    // Dropping the handles unlocks the old volumes, which no longer exist.
+    Ok(disk.disk_number)
+}
+
+#[cfg(target_os = "windows")]
+fn find_fat_volume(disk_number: &u32) -> Result<Option<DriveInfo>> {
+    match find_fat_volume_natively(*disk_number) {
+        Ok(drive) => Ok(drive),
+        Err(e) => {
+            log::info!(
+                "Looking up disk {disk_number}'s volumes natively failed, using Get-Volume: {e:#}"
+            );
+            find_fat_volume_from_powershell(disk_number)
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn find_fat_volume_natively(disk_number: u32) -> Result<Option<DriveInfo>> {
+    use windows_sys::Win32::System::Ioctl::IOCTL_DISK_UPDATE_PROPERTIES;
+
+    // Makes Windows read the new partition table and give the volumes letters.
+    let disk = open_device_for_query(&format!(r"\\.\PhysicalDrive{disk_number}"))?;
+    device_control(&disk, IOCTL_DISK_UPDATE_PROPERTIES, std::ptr::null(), 0)
+        .context("Could not make Windows read the new partition table")?;
+    Ok(list_system_drives()?.into_iter().find(|drive| {
+        drive.file_system.to_uppercase().starts_with("FAT")
+            && drive
+                .get_drive_letter()
+                .is_some_and(|letter| volume_disk_number(&letter).is_ok_and(|n| n == disk_number))
+    }))
+}
+
+#[cfg(target_os = "windows")]
+fn find_fat_volume_from_powershell(disk_number: &u32) -> Result<Option<DriveInfo>> {
+    // Update-Disk makes Windows read the new partition table and give the volumes letters.
+    let output = Command::powershell(
+        "Update-Disk -Number $env:WIZARD_DISK_NUMBER; (Get-Partition -DiskNumber $env:WIZARD_DISK_NUMBER | Get-Volume | Where-Object { $_.DriveLetter -and $_.FileSystemType -like 'FAT*' } | Select-Object -First 1).DriveLetter",
+    )
+    .param("DISK_NUMBER", disk_number.to_string())
+    .run()
+    .with_context(|| "Looking up the card's volumes failed")?;
+    let drive_letter = String::from_utf8(output.stdout)?.trim().to_string();
+    if drive_letter.is_empty() {
+        return Ok(None);
+    }
+    Ok(list_system_drives()?
+        .into_iter()
+        .find(|drive| drive.get_drive_letter().as_deref() == Some(drive_letter.as_str())))
+}
+
+#[cfg(target_os = "windows")]
+fn disk_space(path: &std::path::Path) -> Result<DiskSpace> {
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+    let directory = to_wide(path);
+    let mut space = DiskSpace::default();
+    // SAFETY: `directory` is NUL-terminated, the outputs point to u64s, and the total free
+    // space, which ignores quotas, isn't needed.
+    let succeeded = unsafe {
+        GetDiskFreeSpaceExW(
+            directory.as_ptr(),
+            &mut space.free_bytes,
+            &mut space.total_bytes,
+            std::ptr::null_mut(),
+        )
+    };
+    if succeeded == 0 {
+        return Err(std::io::Error::last_os_error())
+            .with_context(|| format!("Failed to look up free space at {:?}", path));
+    }
+    Ok(space)
+}
+
+/// Counts the disk errors Windows logged against the drive's disk since `since` (seconds since
+/// the Unix epoch). Failing cards often get through a copy with retries, leaving only these.
+#[cfg(target_os = "windows")]
+fn count_disk_errors(drive: &DriveInfo, since: u64) -> Result<u32> {
+    let drive_letter = drive
+        .get_drive_letter()
+        .ok_or(anyhow!("Could not determine drive letter."))?;
+    let counted = volume_disk_number(&drive_letter)
+        .and_then(|disk_number| count_disk_errors_natively(disk_number, since));
+    match counted {
+        Ok(count) => Ok(count),
+        Err(e) => {
+            log::info!("Reading the event log natively failed, using Get-WinEvent: {e:#}");
+            count_disk_errors_from_powershell(&drive_letter, since)
+        }
+    }
+}
+
+/// Counts the events the disk driver logged in the System log since `since` that name the
+/// disk, such as "The device, \Device\Harddisk2\DR2, has a bad block."
+#[cfg(target_os = "windows")]
+fn count_disk_errors_natively(disk_number: u32, since: u64) -> Result<u32> {
+    use windows_sys::Win32::Foundation::{ERROR_INSUFFICIENT_BUFFER, ERROR_NO_MORE_ITEMS};
+    use windows_sys::Win32::System::EventLog::{
+        EVT_HANDLE, EvtClose, EvtNext, EvtQuery, EvtQueryChannelPath, EvtRender, EvtRenderEventXml,
+    };
+
+    /// Closes an event log handle when dropped.
+    struct EventHandle(EVT_HANDLE);
+    impl Drop for EventHandle {
+        fn drop(&mut self) {
+            // SAFETY: The handle came from the event log API and is closed only here.
+            unsafe { EvtClose(self.0) };
+        }
+    }
+    let wide = |text: &str| -> Vec<u16> { text.encode_utf16().chain([0]).collect() };
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)?
+        .as_secs();
+    let age_ms = now.saturating_sub(since) * 1000;
+    let channel = wide("System");
+    let query = wide(&format!(
+        "*[System[Provider[@Name='disk'] and TimeCreated[timediff(@SystemTime) <= {age_ms}]]]"
+    ));
+    // SAFETY: `channel` and `query` are NUL-terminated, and a null session is the local computer.
+    let results = unsafe { EvtQuery(0, channel.as_ptr(), query.as_ptr(), EvtQueryChannelPath) };
+    if results == 0 {
+        return Err(std::io::Error::last_os_error()).context("Could not query the System log");
+    }
+    let results = EventHandle(results);
+
+    let disk_path = format!(r"\Harddisk{disk_number}\");
+    let mut count = 0;
+    let mut xml = vec![0u16; 4096];
+    loop {
+        let mut handles = [0 as EVT_HANDLE; 16];
+        let mut returned = 0u32;
+        // SAFETY: `handles` has room for as many events as are asked for. The timeout is
+        // infinite, since reading a local log doesn't hang.
+        let more = unsafe {
+            EvtNext(
+                results.0,
+                handles.len() as u32,
+                handles.as_mut_ptr(),
+                u32::MAX,
+                0,
+                &mut returned,
+            )
+        } != 0;
+        if !more {
+            let error = std::io::Error::last_os_error();
+            if error.raw_os_error() == Some(ERROR_NO_MORE_ITEMS as i32) {
+                return Ok(count);
+            }
+            return Err(error).context("Could not read the System log");
+        }
+        let events: Vec<EventHandle> = handles[..returned as usize]
+            .iter()
+            .map(|&handle| EventHandle(handle))
+            .collect();
+        for event in &events {
+            let mut used_bytes = 0u32;
+            let mut property_count = 0u32;
+            loop {
+                // SAFETY: `xml` has room for the number of bytes passed, and the outputs point
+                // to u32s.
+                let rendered = unsafe {
+                    EvtRender(
+                        0,
+                        event.0,
+                        EvtRenderEventXml,
+                        (xml.len() * 2) as u32,
+                        xml.as_mut_ptr().cast(),
+                        &mut used_bytes,
+                        &mut property_count,
+                    )
+                } != 0;
+                if rendered {
+                    break;
+                }
+                let error = std::io::Error::last_os_error();
+                if error.raw_os_error() != Some(ERROR_INSUFFICIENT_BUFFER as i32) {
+                    return Err(error).context("Could not read an event from the System log");
+                }
+                xml.resize((used_bytes as usize).div_ceil(2), 0);
+            }
+            let len = (used_bytes as usize / 2).min(xml.len());
+            if String::from_utf16_lossy(&xml[..len]).contains(&disk_path) {
+                count += 1;
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn count_disk_errors_from_powershell(drive_letter: &str, since: u64) -> Result<u32> {
+    let output = Command::powershell(
+        "$n = (Get-Partition -DriveLetter $env:WIZARD_DRIVE_LETTER).DiskNumber; $start = [DateTimeOffset]::FromUnixTimeSeconds([int64]$env:WIZARD_SINCE).LocalDateTime; @(Get-WinEvent -FilterHashtable @{LogName='System'; ProviderName='disk'; StartTime=$start} -ErrorAction SilentlyContinue | Where-Object { $_.Message -like \"*\\Harddisk$n\\*\" }).Count",
+    )
+    .param("DRIVE_LETTER", drive_letter)
+    .param("SINCE", since.to_string())
+    .run()
+    .with_context(|| "Reading the system event log failed")?;
+    Ok(String::from_utf8(output.stdout)?.trim().parse()?)
+}
+
+#[cfg(target_os = "windows")]
+fn get_disk_details(drive: &DriveInfo) -> Result<DiskDetails> {
+    let drive_letter = drive
+        .get_drive_letter()
+        .ok_or(anyhow!("Could not determine drive letter."))?;
+    match disk_details_natively(&drive_letter) {
+        Ok(details) => Ok(details),
+        Err(e) => {
+            log::info!("Reading {drive}'s disk details natively failed, using Get-Disk: {e:#}");
+            disk_details_from_powershell(&drive_letter)
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn disk_details_natively(drive_letter: &str) -> Result<DiskDetails> {
+    use windows_sys::Win32::System::Ioctl::{
+        GET_LENGTH_INFORMATION, IOCTL_DISK_GET_LENGTH_INFO, IOCTL_STORAGE_QUERY_PROPERTY,
+        PropertyStandardQuery, STORAGE_DEVICE_DESCRIPTOR, STORAGE_PROPERTY_QUERY,
+        StorageDeviceProperty,
+    };
+
+    let disk_number = volume_disk_number(drive_letter)?;
+    let device = format!(r"\\.\PhysicalDrive{disk_number}");
+    // Reading the length needs read access, unlike the other queries.
+    let disk = std::fs::File::open(&device).with_context(|| format!("Could not open {device}"))?;
+    let query = STORAGE_PROPERTY_QUERY {
+        PropertyId: StorageDeviceProperty,
+        QueryType: PropertyStandardQuery,
+        AdditionalParameters: [0],
+    };
+    // Only the fixed part of the descriptor is read. The vendor and product strings after it
+    // don't fit, which the driver allows.
+    let descriptor: STORAGE_DEVICE_DESCRIPTOR = device_query(
+        &disk,
+        IOCTL_STORAGE_QUERY_PROPERTY,
+        (&query as *const STORAGE_PROPERTY_QUERY).cast(),
+        std::mem::size_of::<STORAGE_PROPERTY_QUERY>() as u32,
     )
-    .with_context(|| "Mounting disk failed.")?;
+    .context("Could not read the disk's bus type")?;
+    let length: GET_LENGTH_INFORMATION =
+        device_query(&disk, IOCTL_DISK_GET_LENGTH_INFO, std::ptr::null(), 0)
+            .context("Could not read the disk's size")?;
+    // The disk holding Windows is the one Get-Disk calls the system and boot disk.
+    let system_letter = std::env::var("SystemDrive")
+        .ok()
+        .and_then(|drive| drive_letter_of(std::path::Path::new(&format!("{drive}\\"))))
+        .unwrap_or("C".to_string());
+    Ok(DiskDetails {
+        size_bytes: length.Length as u64,
+        bus_type: bus_type_name(descriptor.BusType).to_string(),
+        is_system_disk: volume_disk_number(&system_letter).is_ok_and(|n| n == disk_number),
+    })
+}
+
+/// The name Get-Disk gives each STORAGE_BUS_TYPE, or an empty string if it is unknown.
+#[cfg(target_os = "windows")]
+fn bus_type_name(
+    bus_type: windows_sys::Win32::Storage::FileSystem::STORAGE_BUS_TYPE,
+) -> &'static str {
+    use windows_sys::Win32::Storage::FileSystem::*;
+    match bus_type {
+        BusTypeScsi => "SCSI",
+        BusTypeAtapi => "ATAPI",
+        BusTypeAta => "ATA",
+        BusType1394 => "1394",
+        BusTypeSsa => "SSA",
+        BusTypeFibre => "Fibre Channel",
+        BusTypeUsb => "USB",
+        BusTypeRAID => "RAID",
+        BusTypeiScsi => "iSCSI",
+        BusTypeSas => "SAS",
+        BusTypeSata => "SATA",
+        BusTypeSd => "SD",
+        BusTypeMmc => "MMC",
+        BusTypeVirtual => "Virtual",
+        BusTypeFileBackedVirtual => "File Backed Virtual",
+        BusTypeSpaces => "Spaces",
+        BusTypeNvme => "NVMe",
+        BusTypeSCM => "SCM",
+        BusTypeUfs => "UFS",
+        _ => "",
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn disk_details_from_powershell(drive_letter: &str) -> Result<DiskDetails> {
+    let output = Command::powershell(
+        "$disk = Get-Partition -DriveLetter $env:WIZARD_DRIVE_LETTER | Get-Disk; \"$($disk.BusType)|$($disk.Size)|$($disk.IsSystem -or $disk.IsBoot)\"",
+    )
+    .param("DRIVE_LETTER", drive_letter)
+    .run()
+    .with_context(|| "Running Get-Disk failed")?;
+    let output = String::from_utf8(output.stdout)?;
+    let fields: Vec<&str> = output.trim().split('|').collect();
+    let [bus_type, size, is_system] = fields[..] else {
+        bail!("Unexpected Get-Disk output: {output}");
+    };
+    // Depending on the PowerShell version, BusType is either a name or a STORAGE_BUS_TYPE value.
+    let bus_type = match bus_type {
+        "7" => "USB",
+        "12" => "SD",
+        "13" => "MMC",
+        other => other,
+    };
+    Ok(DiskDetails {
+        size_bytes: size.parse()?,
+        bus_type: bus_type.to_string(),
+        is_system_disk: is_system.eq_ignore_ascii_case("true"),
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn list_system_drives() -> Result<Vec<DriveInfo>> {
+    let filesystems = crate::utils::udisks::list_removable_filesystems()
+        .with_context(|| "Listing removable drives failed.")?;
+    Ok(filesystems
+        .into_iter()
+        .flat_map(|filesystem| {
+            let label = filesystem.label;
+            let file_system = filesystem.file_system;
+            filesystem.mount_points.into_iter().map(move |drive_path| {
+                let space = disk_space(&drive_path).unwrap_or_default();
+                DriveInfo {
+                    file_system_label: label.clone(),
+                    total_bytes: space.total_bytes,
+                    free_bytes: space.free_bytes,
+                    file_system: file_system.clone(),
+                    drive_path,
+                }
+            })
+        })
+        .collect())
+}
+
+#[cfg(target_os = "linux")]
+fn get_block_device_path(drive: &DriveInfo) -> Result<String> {
+    Ok(crate::utils::udisks::find_by_mount_point(&drive.drive_path)
+        .with_context(|| "Failed to look up drive block device.")?
+        .device)
+}
+
+#[cfg(target_os = "linux")]
+fn format_volume(
+    drive: &mut DriveInfo,
+    team_number: &str,
+    cluster_size: Option<u32>,
+    native_formatting: bool,
+) -> Result<()> {
+    let filesystem = crate::utils::udisks::find_by_mount_point(&drive.drive_path)
+        .with_context(|| "Failed to look up drive block device.")?;
+    let block_device_path = &filesystem.device;
+    let label = format!("GIZMO{team_number}");
+    // The device is opened before unmounting, so a user without write access to block devices
+    // goes straight to formatting through UDisks2.
+    let native_device = native_formatting
+        .then(|| {
+            std::fs::OpenOptions::new()
+                .write(true)
+                .open(block_device_path)
+                .inspect_err(|e| {
+                    log::info!("Can't open {block_device_path} to format natively: {e}")
+                })
+                .ok()
+        })
+        .flatten();
+    crate::utils::udisks::unmount(&filesystem)?;
+    let formatted_natively = native_device.is_some_and(|mut device| {
+        format_block_device(&mut device, block_device_path, &label, cluster_size)
+            .inspect_err(|e| {
+                log::warn!("Formatting {block_device_path} natively failed, using UDisks2: {e:#}")
+            })
+            .is_ok()
+    });
+    if !formatted_natively {
+        crate::utils::udisks::format_fat32(&filesystem, &label, cluster_size)?;
+    }
+    // The mount point usually follows the label, so it moves when the card is renamed.
+    drive.drive_path = crate::utils::udisks::mount(&filesystem)?;
+    drive.file_system_label = label;
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn format_block_device(
+    device: &mut std::fs::File,
+    block_device_path: &str,
+    label: &str,
+    cluster_size: Option<u32>,
+) -> Result<()> {
+    let name = block_device_path.trim_start_matches("/dev/");
+    // sysfs counts in 512 byte sectors whatever the device's own sector size is.
+    let read_sectors = |attribute: &str| -> Result<u64> {
+        let path = format!("/sys/class/block/{name}/{attribute}");
+        Ok(std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {path}"))?
+            .trim()
+            .parse()?)
+    };
+    // Partitions share the queue attributes of their disk.
+    let sector_size = [
+        format!("/sys/class/block/{name}/queue/logical_block_size"),
+        format!("/sys/class/block/{name}/../queue/logical_block_size"),
+    ]
+    .iter()
+    .find_map(|path| std::fs::read_to_string(path).ok()?.trim().parse().ok())
+    .with_context(|| format!("Failed to read the sector size of {block_device_path}"))?;
+    let geometry = VolumeGeometry {
+        sector_size,
+        total_sectors: read_sectors("size")?,
+        // Whole-disk devices have no start, since they aren't partitions.
+        hidden_sectors: read_sectors("start").unwrap_or(0) as u32,
+    };
+    format_fat32(device, &geometry, label, cluster_size)?;
+    device.sync_all()?;
     Ok(())
 }
 
+/// Returns the allocation unit size of the drive's filesystem in bytes. For FAT filesystems,
+/// Linux reports the cluster size as the block size.
+#[cfg(target_os = "linux")]
+pub fn cluster_size(drive: &DriveInfo) -> Result<u32> {
+    let output = Command::new("stat")
+        .args(["-f", "-c", "%S"])
+        .arg(&drive.drive_path)
+        .run()
+        .with_context(|| "Failed to look up the drive's cluster size.")?;
+    Ok(String::from_utf8(output.stdout)?.trim().parse()?)
+}
+
 #[cfg(target_os = "linux")]
-pub fn write_filesystem_cache(drive: &DriveInfo) -> Result<()> {
-    let drive_path_str = drive
-        .drive_path
-        .to_str()
-        .ok_or(anyhow!("Failed to convert disk path to string."))?;
-    let bash_command = format!("sync {drive_path_str}");
-    crate::utils::shell::run_bash_command(&bash_command)
+fn flush_volume(drive: &DriveInfo) -> Result<()> {
+    Command::new("sync")
+        .arg(&drive.drive_path)
+        .run()
         .with_context(|| "Writing filesystem cache failed")?;
     Ok(())
 }
+
+#[cfg(target_os = "linux")]
+fn disk_space(path: &std::path::Path) -> Result<DiskSpace> {
+    use std::os::unix::ffi::OsStrExt;
+    let c_path = std::ffi::CString::new(path.as_os_str().as_bytes())?;
+    // SAFETY: statvfs is plain data, and is only read after the call fills it in.
+    let mut stats: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: `c_path` is NUL-terminated and `stats` is a valid statvfs to fill in.
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stats) } != 0 {
+        return Err(std::io::Error::last_os_error())
+            .with_context(|| format!("Failed to look up free space at {:?}", path));
+    }
+    // The field types are narrower on 32-bit systems.
+    #[allow(clippy::unnecessary_cast)]
+    let (blocks, available_blocks, fragment_size) = (
+        stats.f_blocks as u64,
+        stats.f_bavail as u64,
+        stats.f_frsize as u64,
+    );
+    Ok(DiskSpace {
+        total_bytes: blocks * fragment_size,
+        free_bytes: available_blocks * fragment_size,
+    })
+}
+
+/// Flushes and unmounts the drive, then powers off its reader if it can be, so the card can be
+/// pulled out without losing writes.
+#[cfg(target_os = "linux")]
+fn eject_volume(drive: &DriveInfo) -> Result<()> {
+    flush_volume(drive)?;
+    let filesystem = crate::utils::udisks::find_by_mount_point(&drive.drive_path)?;
+    crate::utils::udisks::unmount(&filesystem)?;
+    // Once unmounted, the card is safe to remove even if its reader stays powered.
+    if let Err(e) = crate::utils::udisks::power_off(&filesystem) {
+        log::info!("{drive} was unmounted but not powered off: {e:#}");
+    }
+    Ok(())
+}
+
+/// Unmounts and mounts the drive again so later reads come from the card instead of the page
+/// cache.
+#[cfg(target_os = "linux")]
+pub fn remount_drive(drive: &DriveInfo) -> Result<()> {
+    if demo::demo_mode_enabled() {
+        demo::log_skipped(format_args!("remounting {drive}"));
+        return Ok(());
+    }
+    let filesystem = crate::utils::udisks::find_by_mount_point(&drive.drive_path)?;
+    crate::utils::udisks::unmount(&filesystem)?;
+    let mount_point = crate::utils::udisks::mount(&filesystem)?;
+    if mount_point != drive.drive_path {
+        bail!("{drive} came back at {:?} after remounting.", mount_point);
+    }
+    Ok(())
+}
+
+/// Counts the kernel log errors mentioning the drive's disk since `since` (seconds since the Unix
+/// epoch). Failing cards often get through a copy with retries, leaving only these.
+#[cfg(target_os = "linux")]
+fn count_disk_errors(drive: &DriveInfo, since: u64) -> Result<u32> {
+    let block_device_path = get_block_device_path(drive)?;
+    let disk_name = match run_lsblk(&["-ndo", "PKNAME", &block_device_path])?.as_str() {
+        "" => block_device_path.trim_start_matches("/dev/").to_string(),
+        name => name.to_string(),
+    };
+    let output = Command::new("journalctl")
+        .args(["-k", "--no-pager", "-o", "cat", "--since"])
+        .arg(format!("@{since}"))
+        .run()
+        .with_context(|| "Reading the kernel log failed.")?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| line.to_lowercase().contains("error"))
+        .filter(|line| mentions_disk(line, &disk_name))
+        .count() as u32)
+}
+
+/// True if the log line names the disk or one of its partitions, like "sdb1" or "mmcblk0p1".
+#[cfg(target_os = "linux")]
+fn mentions_disk(line: &str, disk_name: &str) -> bool {
+    line.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter_map(|word| word.strip_prefix(disk_name))
+        .any(|rest| {
+            let rest = rest.strip_prefix('p').unwrap_or(rest);
+            rest.chars().all(|c| c.is_ascii_digit())
+        })
+}
+
+#[cfg(target_os = "linux")]
+fn run_lsblk(args: &[&str]) -> Result<String> {
+    let output = Command::new("lsblk")
+        .args(args)
+        .run()
+        .with_context(|| "Running lsblk failed.")?;
+    Ok(String::from_utf8(output.stdout)?.trim().to_string())
+}
+
+/// Returns the whole disk holding a partition, or the device itself if it isn't a partition.
+#[cfg(target_os = "linux")]
+fn get_disk_path(block_device_path: &str) -> Result<String> {
+    let disk_name = run_lsblk(&["-ndo", "PKNAME", block_device_path])?;
+    Ok(if disk_name.is_empty() {
+        block_device_path.to_string()
+    } else {
+        format!("/dev/{disk_name}")
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn get_disk_details(drive: &DriveInfo) -> Result<DiskDetails> {
+    let disk_path = get_disk_path(&get_block_device_path(drive)?)?;
+    let disk_info = run_lsblk(&["-ndbo", "SIZE,TRAN", &disk_path])?;
+    let mut fields = disk_info.split_whitespace();
+    let size_bytes = fields
+        .next()
+        .ok_or(anyhow!("Missing size in lsblk output."))?
+        .parse()?;
+    // Built-in card readers often report no transport, but show up as MMC block devices.
+    let bus_type = match fields.next() {
+        Some(transport) => transport.to_string(),
+        None if disk_path.starts_with("/dev/mmcblk") => "mmc".to_string(),
+        None => String::new(),
+    };
+    let mount_points = run_lsblk(&["-nro", "MOUNTPOINTS", &disk_path])?;
+    let is_system_disk = mount_points
+        .lines()
+        .any(|m| ["/", "/boot", "/boot/efi", "/home", "[SWAP]"].contains(&m));
+    Ok(DiskDetails {
+        size_bytes,
+        bus_type,
+        is_system_disk,
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn open_disk(drive: &DriveInfo, size_bytes: u64) -> Result<RawDisk> {
+    let filesystem = crate::utils::udisks::find_by_mount_point(&drive.drive_path)
+        .with_context(|| "Failed to look up drive block device.")?;
+    let device = get_disk_path(&filesystem.device)?;
+    // The disk is opened before unmounting, so a user without write access to block devices
+    // finds out before the card disappears from the file manager.
+    let file = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(&device)
+        .with_context(|| {
+            format!("Could not open {device} to write the image. Writing disk images needs write access to the card itself, such as running the wizard with sudo.")
+        })?;
+    for mounted in crate::utils::udisks::filesystems_on_same_drive(&filesystem)?
+        .iter()
+        .filter(|f| !f.mount_points.is_empty())
+    {
+        crate::utils::udisks::unmount(mounted)?;
+    }
+    Ok(RawDisk {
+        file,
+        device,
+        size_bytes,
+        filesystem,
+    })
+}
+
+/// Asks the kernel to read a disk's partition table again. Not in the libc crate.
+#[cfg(target_os = "linux")]
+const BLKRRPART: u64 = 0x125F;
+
+#[cfg(target_os = "linux")]
+fn close_disk(disk: RawDisk) -> Result<crate::utils::udisks::Filesystem> {
+    use std::os::fd::AsRawFd;
+    disk.file.sync_all()?;
+    // SAFETY: `file` is an open block device, and BLKRRPART takes no argument.
+    if unsafe { libc::ioctl(disk.file.as_raw_fd(), BLKRRPART as _) } != 0 {
+        // udev also reads the partition table once the disk is closed, just not right away.
+        log::warn!(
+            "Could not reread the partition table of {}: {}",
+            disk.device,
+            std::io::Error::last_os_error()
+        );
+    }
+    Ok(disk.filesystem)
+}
+
+#[cfg(target_os = "linux")]
+fn find_fat_volume(filesystem: &crate::utils::udisks::Filesystem) -> Result<Option<DriveInfo>> {
+    let Some(fat) = crate::utils::udisks::filesystems_on_same_drive(filesystem)?
+        .into_iter()
+        .find(|f| f.file_system.to_uppercase().starts_with("FAT") || f.file_system == "vfat")
+    else {
+        return Ok(None);
+    };
+    let drive_path = match fat.mount_points.first() {
+        Some(mount_point) => mount_point.clone(),
+        None => crate::utils::udisks::mount(&fat)?,
+    };
+    let space = disk_space(&drive_path).unwrap_or_default();
+    Ok(Some(DriveInfo {
+        drive_path,
+        file_system_label: fat.label,
+        total_bytes: space.total_bytes,
+        free_bytes: space.free_bytes,
+        file_system: fat.file_system,
+    }))
+}
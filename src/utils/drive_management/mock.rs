@@ -0,0 +1,115 @@
+//! A [`DriveManager`] for tests, which records what it was asked to do instead of touching any
+//! drives, and can be told to fail.
+
+use crate::utils::drive_management::{DriveInfo, DriveManager};
+use anyhow::{Result, bail};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// A call made to [`MockDriveManager`], with the drive it was made for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DriveCall {
+    List,
+    Format { drive: PathBuf, team_number: String },
+    Flush(PathBuf),
+    Eject(PathBuf),
+    FreeSpace(PathBuf),
+    CountIoErrors(PathBuf),
+}
+
+/// The kinds of [`DriveCall`], for choosing which ones fail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DriveOperation {
+    List,
+    Format,
+    Flush,
+    Eject,
+    FreeSpace,
+    CountIoErrors,
+}
+
+impl DriveCall {
+    pub fn operation(&self) -> DriveOperation {
+        match self {
+            DriveCall::List => DriveOperation::List,
+            DriveCall::Format { .. } => DriveOperation::Format,
+            DriveCall::Flush(_) => DriveOperation::Flush,
+            DriveCall::Eject(_) => DriveOperation::Eject,
+            DriveCall::FreeSpace(_) => DriveOperation::FreeSpace,
+            DriveCall::CountIoErrors(_) => DriveOperation::CountIoErrors,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct MockDriveManager {
+    /// Returned by [`DriveManager::list`].
+    pub drives: Vec<DriveInfo>,
+    /// Returned by [`DriveManager::free_space`].
+    pub free_bytes: u64,
+    /// Returned by [`DriveManager::count_io_errors`].
+    pub io_errors: u32,
+    calls: Mutex<Vec<DriveCall>>,
+    failing: Mutex<Vec<DriveOperation>>,
+}
+
+impl MockDriveManager {
+    /// Makes every later call of `operation` fail.
+    pub fn fail(&self, operation: DriveOperation) {
+        self.failing.lock().unwrap().push(operation);
+    }
+
+    /// The calls made so far, oldest first.
+    pub fn calls(&self) -> Vec<DriveCall> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    fn record(&self, call: DriveCall) -> Result<()> {
+        let operation = call.operation();
+        self.calls.lock().unwrap().push(call);
+        if self.failing.lock().unwrap().contains(&operation) {
+            bail!("Simulated {operation:?} failure");
+        }
+        Ok(())
+    }
+}
+
+impl DriveManager for MockDriveManager {
+    fn list(&self) -> Result<Vec<DriveInfo>> {
+        self.record(DriveCall::List)?;
+        Ok(self.drives.clone())
+    }
+
+    fn format(
+        &self,
+        drive: &mut DriveInfo,
+        team_number: &str,
+        _allow_suspicious: bool,
+        _cluster_size: Option<u32>,
+    ) -> Result<Option<u32>> {
+        self.record(DriveCall::Format {
+            drive: drive.drive_path.clone(),
+            team_number: team_number.to_string(),
+        })?;
+        drive.file_system_label = team_number.to_string();
+        Ok(None)
+    }
+
+    fn flush(&self, drive: &DriveInfo) -> Result<()> {
+        self.record(DriveCall::Flush(drive.drive_path.clone()))
+    }
+
+    fn eject(&self, drive: &DriveInfo) -> Result<()> {
+        self.record(DriveCall::Eject(drive.drive_path.clone()))
+    }
+
+    fn free_space(&self, drive: &DriveInfo) -> Result<u64> {
+        self.record(DriveCall::FreeSpace(drive.drive_path.clone()))?;
+        Ok(self.free_bytes)
+    }
+
+    fn count_io_errors(&self, drive: &DriveInfo, _since: u64) -> Result<u32> {
+        self.record(DriveCall::CountIoErrors(drive.drive_path.clone()))?;
+        Ok(self.io_errors)
+    }
+}
@@ -0,0 +1,341 @@
+//! Writes an empty FAT32 filesystem directly to a volume, so cards can be formatted without
+//! relying on Format-Volume or mkfs.vfat.
+
+use anyhow::{Result, anyhow, bail};
+use std::io::{Seek, SeekFrom, Write};
+
+pub const SECTOR_SIZE: u64 = 512;
+const RESERVED_SECTORS: u32 = 32;
+const FAT_COUNT: u32 = 2;
+const FSINFO_SECTOR: u32 = 1;
+const BACKUP_BOOT_SECTOR: u32 = 6;
+const ROOT_DIR_CLUSTER: u32 = 2;
+/// FAT32 needs at least this many clusters, or it is read as FAT16.
+const MIN_CLUSTERS: u32 = 65525;
+const MAX_CLUSTERS: u32 = 0x0FFF_FFF5;
+const ZERO_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Where the volume is and how big it is, in sectors.
+pub struct VolumeGeometry {
+    /// The device's logical sector size in bytes. Only 512 byte sectors are supported.
+    pub sector_size: u32,
+    pub total_sectors: u64,
+    /// Sectors before the volume on the disk, i.e. the partition's starting sector.
+    pub hidden_sectors: u32,
+}
+
+/// Cluster size Windows picks for FAT32 volumes of this size.
+pub fn default_cluster_size(volume_bytes: u64) -> u32 {
+    const GB: u64 = 1024 * 1024 * 1024;
+    match volume_bytes {
+        size if size <= 8 * GB => 4096,
+        size if size <= 16 * GB => 8192,
+        size if size <= 32 * GB => 16384,
+        _ => 32768,
+    }
+}
+
+/// Formats `device`, which must be positioned over exactly the volume, as FAT32 with the given
+/// label. Uses `cluster_size` bytes per cluster, or the Windows default if `None`. Returns the
+/// cluster size used.
+pub fn format_fat32<D: Write + Seek>(
+    device: &mut D,
+    geometry: &VolumeGeometry,
+    label: &str,
+    cluster_size: Option<u32>,
+) -> Result<u32> {
+    if u64::from(geometry.sector_size) != SECTOR_SIZE {
+        bail!(
+            "The volume has {} byte sectors, and only {SECTOR_SIZE} byte sectors are supported.",
+            geometry.sector_size
+        );
+    }
+    let total_sectors = u32::try_from(geometry.total_sectors)
+        .map_err(|_| anyhow!("The volume is too large for FAT32."))?;
+    let cluster_size =
+        cluster_size.unwrap_or_else(|| default_cluster_size(geometry.total_sectors * SECTOR_SIZE));
+    let sectors_per_cluster = cluster_size / SECTOR_SIZE as u32;
+    if !sectors_per_cluster.is_power_of_two() || sectors_per_cluster > 128 {
+        bail!("{cluster_size} bytes is not a valid FAT32 cluster size.");
+    }
+
+    let fat_sectors = fat_size(total_sectors, sectors_per_cluster);
+    let data_start = RESERVED_SECTORS + FAT_COUNT * fat_sectors;
+    let cluster_count = total_sectors
+        .checked_sub(data_start)
+        .ok_or(anyhow!("The volume is too small for FAT32."))?
+        / sectors_per_cluster;
+    if !(MIN_CLUSTERS..=MAX_CLUSTERS).contains(&cluster_count) {
+        bail!(
+            "A {} MB volume can't be formatted as FAT32 with {cluster_size} byte clusters.",
+            geometry.total_sectors * SECTOR_SIZE / 1_000_000
+        );
+    }
+    log::info!(
+        "Formatting FAT32: {total_sectors} sectors, {cluster_size} byte clusters, {fat_sectors} sectors per FAT"
+    );
+
+    let label = volume_label(label);
+    let boot_sector = boot_sector(
+        geometry,
+        total_sectors,
+        sectors_per_cluster,
+        fat_sectors,
+        &label,
+    );
+    let fsinfo_sector = fsinfo_sector(cluster_count - 1);
+
+    zero_sectors(device, 0, RESERVED_SECTORS)?;
+    write_sector(device, 0, &boot_sector)?;
+    write_sector(device, FSINFO_SECTOR, &fsinfo_sector)?;
+    write_sector(device, BACKUP_BOOT_SECTOR, &boot_sector)?;
+    write_sector(device, BACKUP_BOOT_SECTOR + FSINFO_SECTOR, &fsinfo_sector)?;
+
+    for fat in 0..FAT_COUNT {
+        let fat_start = RESERVED_SECTORS + fat * fat_sectors;
+        zero_sectors(device, fat_start, fat_sectors)?;
+        write_sector(device, fat_start, &first_fat_sector())?;
+    }
+
+    // Cluster 2 holds the root directory, which only contains the volume label.
+    zero_sectors(device, data_start, sectors_per_cluster)?;
+    write_sector(device, data_start, &root_dir_sector(&label))?;
+
+    device.flush()?;
+    Ok(cluster_size)
+}
+
+/// Sectors per FAT, using the calculation from Microsoft's FAT specification.
+fn fat_size(total_sectors: u32, sectors_per_cluster: u32) -> u32 {
+    let sectors_to_map = u64::from(total_sectors - RESERVED_SECTORS);
+    let divisor = (256 * u64::from(sectors_per_cluster) + u64::from(FAT_COUNT)) / 2;
+    sectors_to_map.div_ceil(divisor) as u32
+}
+
+/// Converts a label to the 11 space-padded characters FAT stores.
+fn volume_label(label: &str) -> [u8; 11] {
+    let mut bytes = [b' '; 11];
+    for (byte, c) in bytes.iter_mut().zip(
+        label
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || *c == '_' || *c == '-')
+            .map(|c| c.to_ascii_uppercase()),
+    ) {
+        *byte = c as u8;
+    }
+    bytes
+}
+
+fn boot_sector(
+    geometry: &VolumeGeometry,
+    total_sectors: u32,
+    sectors_per_cluster: u32,
+    fat_sectors: u32,
+    label: &[u8; 11],
+) -> [u8; SECTOR_SIZE as usize] {
+    let mut sector = [0; SECTOR_SIZE as usize];
+    sector[0..3].copy_from_slice(&[0xEB, 0x58, 0x90]);
+    sector[3..11].copy_from_slice(b"MSWIN4.1");
+    sector[11..13].copy_from_slice(&(SECTOR_SIZE as u16).to_le_bytes());
+    sector[13] = sectors_per_cluster as u8;
+    sector[14..16].copy_from_slice(&(RESERVED_SECTORS as u16).to_le_bytes());
+    sector[16] = FAT_COUNT as u8;
+    // Media descriptor for fixed disks.
+    sector[21] = 0xF8;
+    sector[24..26].copy_from_slice(&63u16.to_le_bytes());
+    sector[26..28].copy_from_slice(&255u16.to_le_bytes());
+    sector[28..32].copy_from_slice(&geometry.hidden_sectors.to_le_bytes());
+    sector[32..36].copy_from_slice(&total_sectors.to_le_bytes());
+    sector[36..40].copy_from_slice(&fat_sectors.to_le_bytes());
+    sector[44..48].copy_from_slice(&ROOT_DIR_CLUSTER.to_le_bytes());
+    sector[48..50].copy_from_slice(&(FSINFO_SECTOR as u16).to_le_bytes());
+    sector[50..52].copy_from_slice(&(BACKUP_BOOT_SECTOR as u16).to_le_bytes());
+    sector[64] = 0x80;
+    sector[66] = 0x29;
+    sector[67..71].copy_from_slice(&volume_id().to_le_bytes());
+    sector[71..82].copy_from_slice(label);
+    sector[82..90].copy_from_slice(b"FAT32   ");
+    sector[510..512].copy_from_slice(&[0x55, 0xAA]);
+    sector
+}
+
+fn fsinfo_sector(free_clusters: u32) -> [u8; SECTOR_SIZE as usize] {
+    let mut sector = [0; SECTOR_SIZE as usize];
+    sector[0..4].copy_from_slice(&0x4161_5252u32.to_le_bytes());
+    sector[484..488].copy_from_slice(&0x6141_7272u32.to_le_bytes());
+    sector[488..492].copy_from_slice(&free_clusters.to_le_bytes());
+    sector[492..496].copy_from_slice(&(ROOT_DIR_CLUSTER + 1).to_le_bytes());
+    sector[508..512].copy_from_slice(&0xAA55_0000u32.to_le_bytes());
+    sector
+}
+
+/// The reserved FAT entries, followed by the end-of-chain marker for the root directory.
+fn first_fat_sector() -> [u8; SECTOR_SIZE as usize] {
+    let mut sector = [0; SECTOR_SIZE as usize];
+    sector[0..4].copy_from_slice(&0x0FFF_FFF8u32.to_le_bytes());
+    sector[4..8].copy_from_slice(&0x0FFF_FFFFu32.to_le_bytes());
+    sector[8..12].copy_from_slice(&0x0FFF_FFFFu32.to_le_bytes());
+    sector
+}
+
+fn root_dir_sector(label: &[u8; 11]) -> [u8; SECTOR_SIZE as usize] {
+    let mut sector = [0; SECTOR_SIZE as usize];
+    sector[0..11].copy_from_slice(label);
+    // Volume label attribute.
+    sector[11] = 0x08;
+    sector
+}
+
+/// Serial number shown by `vol`, derived from the current time like other formatters do.
+fn volume_id() -> u32 {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default();
+    (now.as_secs() as u32) ^ now.subsec_nanos()
+}
+
+fn write_sector<D: Write + Seek>(
+    device: &mut D,
+    sector: u32,
+    data: &[u8; SECTOR_SIZE as usize],
+) -> Result<()> {
+    device.seek(SeekFrom::Start(u64::from(sector) * SECTOR_SIZE))?;
+    device.write_all(data)?;
+    Ok(())
+}
+
+fn zero_sectors<D: Write + Seek>(device: &mut D, start: u32, count: u32) -> Result<()> {
+    device.seek(SeekFrom::Start(u64::from(start) * SECTOR_SIZE))?;
+    let zeros = vec![0; ZERO_CHUNK_SIZE];
+    let mut remaining = u64::from(count) * SECTOR_SIZE;
+    while remaining > 0 {
+        let chunk = remaining.min(ZERO_CHUNK_SIZE as u64) as usize;
+        device.write_all(&zeros[..chunk])?;
+        remaining -= chunk as u64;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Read};
+
+    /// Just big enough for FAT32 with one sector per cluster.
+    const TOTAL_SECTORS: u64 = 70_000;
+    const HIDDEN_SECTORS: u32 = 2048;
+
+    fn geometry() -> VolumeGeometry {
+        VolumeGeometry {
+            sector_size: SECTOR_SIZE as u32,
+            total_sectors: TOTAL_SECTORS,
+            hidden_sectors: HIDDEN_SECTORS,
+        }
+    }
+
+    fn format(cluster_size: u32) -> Vec<u8> {
+        let mut volume = Cursor::new(vec![0; (TOTAL_SECTORS * SECTOR_SIZE) as usize]);
+        format_fat32(&mut volume, &geometry(), "gizmo42", Some(cluster_size)).unwrap();
+        volume.into_inner()
+    }
+
+    fn u16_at(bytes: &[u8], offset: usize) -> u16 {
+        u16::from_le_bytes(bytes[offset..offset + 2].try_into().unwrap())
+    }
+
+    fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn boot_sector_describes_the_volume() {
+        let volume = format(512);
+        assert_eq!(u16_at(&volume, 11), SECTOR_SIZE as u16);
+        assert_eq!(volume[13], 1);
+        assert_eq!(u16_at(&volume, 14), RESERVED_SECTORS as u16);
+        assert_eq!(volume[16], FAT_COUNT as u8);
+        // FAT32 has no fixed root directory and keeps the sector count in the 32-bit field.
+        assert_eq!(u16_at(&volume, 17), 0);
+        assert_eq!(u16_at(&volume, 19), 0);
+        assert_eq!(u32_at(&volume, 28), HIDDEN_SECTORS);
+        assert_eq!(u32_at(&volume, 32), TOTAL_SECTORS as u32);
+        assert_eq!(u32_at(&volume, 44), ROOT_DIR_CLUSTER);
+        assert_eq!(&volume[71..82], b"GIZMO42    ");
+        assert_eq!(&volume[510..512], &[0x55, 0xAA]);
+        let backup = BACKUP_BOOT_SECTOR as usize * SECTOR_SIZE as usize;
+        assert_eq!(volume[..512], volume[backup..backup + 512]);
+    }
+
+    #[test]
+    fn fat_size_matches_the_specification() {
+        for (total_sectors, sectors_per_cluster) in [(70_000, 1), (16_777_216, 8), (62_333_952, 64)]
+        {
+            let fat_sectors = fat_size(total_sectors, sectors_per_cluster);
+            // The formula from Microsoft's FAT specification, section 6.5.
+            let tmp1 = total_sectors - RESERVED_SECTORS;
+            let tmp2 = (256 * sectors_per_cluster + FAT_COUNT) / 2;
+            assert_eq!(fat_sectors, tmp1.div_ceil(tmp2));
+            // Every cluster, plus the two reserved entries, needs a 4 byte entry in the FAT.
+            let clusters =
+                (total_sectors - RESERVED_SECTORS - FAT_COUNT * fat_sectors) / sectors_per_cluster;
+            assert!(u64::from(fat_sectors) * SECTOR_SIZE >= (u64::from(clusters) + 2) * 4);
+        }
+    }
+
+    #[test]
+    fn cluster_count_is_in_the_fat32_range() {
+        let volume = format(512);
+        let fat_sectors = u32_at(&volume, 36);
+        let clusters = TOTAL_SECTORS as u32 - RESERVED_SECTORS - FAT_COUNT * fat_sectors;
+        assert!((MIN_CLUSTERS..=MAX_CLUSTERS).contains(&clusters));
+    }
+
+    #[test]
+    fn fsinfo_counts_every_cluster_but_the_root_directory_as_free() {
+        let volume = format(512);
+        let fat_sectors = u32_at(&volume, 36);
+        let clusters = TOTAL_SECTORS as u32 - RESERVED_SECTORS - FAT_COUNT * fat_sectors;
+        let fsinfo = &volume[FSINFO_SECTOR as usize * SECTOR_SIZE as usize..];
+        assert_eq!(u32_at(fsinfo, 0), 0x4161_5252);
+        assert_eq!(u32_at(fsinfo, 484), 0x6141_7272);
+        assert_eq!(u32_at(fsinfo, 488), clusters - 1);
+    }
+
+    #[test]
+    fn formatted_volumes_can_be_mounted() {
+        let mut volume = Cursor::new(format(512));
+        {
+            let fs = fatfs::FileSystem::new(&mut volume, fatfs::FsOptions::new()).unwrap();
+            assert_eq!(fs.fat_type(), fatfs::FatType::Fat32);
+            assert_eq!(fs.volume_label(), "GIZMO42");
+            let mut file = fs.root_dir().create_file("hello.txt").unwrap();
+            file.write_all(b"hello").unwrap();
+        }
+        volume.set_position(0);
+        let fs = fatfs::FileSystem::new(&mut volume, fatfs::FsOptions::new()).unwrap();
+        let mut contents = String::new();
+        fs.root_dir()
+            .open_file("hello.txt")
+            .unwrap()
+            .read_to_string(&mut contents)
+            .unwrap();
+        assert_eq!(contents, "hello");
+    }
+
+    #[test]
+    fn other_sector_sizes_are_refused() {
+        let mut volume = Cursor::new(vec![]);
+        let geometry = VolumeGeometry {
+            sector_size: 4096,
+            ..geometry()
+        };
+        assert!(format_fat32(&mut volume, &geometry, "GIZMO", None).is_err());
+        assert!(volume.into_inner().is_empty());
+    }
+
+    #[test]
+    fn volumes_too_small_for_fat32_are_refused() {
+        let mut volume = Cursor::new(vec![]);
+        assert!(format_fat32(&mut volume, &geometry(), "GIZMO", Some(4096)).is_err());
+    }
+}
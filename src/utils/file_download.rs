@@ -1,18 +1,699 @@
-use anyhow::{Result, bail};
-use std::io::Write;
+use crate::utils::http::{HttpClient, Network};
+use crate::utils::panics::report_from_payload;
+use crate::utils::shell::Command;
+use anyhow::{Result, anyhow, bail};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 
-pub fn download_file(url: &str, dest_path: &std::path::Path) -> Result<()> {
-    let response = reqwest::blocking::get(url)?;
-    if !response.status().is_success() {
-        bail!("Failed to download file: {}", response.status());
+/// Large files are fetched in pieces of this size, several at a time.
+const SEGMENT_SIZE: u64 = 8 * 1024 * 1024;
+/// Files smaller than this are fetched in one request, since splitting them gains little.
+const MIN_SEGMENTED_SIZE: u64 = 2 * SEGMENT_SIZE;
+/// Times a segment is requested before the download gives up. Busy networks sometimes drop a
+/// connection partway through one.
+const SEGMENT_ATTEMPTS: u32 = 3;
+const SEGMENT_RETRY_DELAY: Duration = Duration::from_millis(500);
+/// Bytes read between progress reports, so a fast download doesn't flood the page with them.
+const PROGRESS_INTERVAL: u64 = 256 * 1024;
+pub const DEFAULT_DOWNLOAD_CONNECTIONS: u32 = 4;
+pub const MAX_DOWNLOAD_CONNECTIONS: u32 = 16;
+const STATUS_PARTIAL_CONTENT: u16 = 206;
+
+/// Limits how fast downloads together read from the network, to leave room for other traffic
+/// on a shared network. One is shared by every request made with a [`Network`], so the limit
+/// holds however many are running.
+#[derive(Default)]
+pub struct RateLimiter {
+    bucket: Mutex<TokenBucket>,
+}
+
+impl RateLimiter {
+    /// A limit in megabits per second. Zero means no limit.
+    pub fn new(megabits_per_second: u32) -> Self {
+        let limiter = Self::default();
+        limiter.set_limit(megabits_per_second);
+        limiter
+    }
+
+    pub fn set_limit(&self, megabits_per_second: u32) {
+        *self.bucket.lock().unwrap_or_else(|e| e.into_inner()) = TokenBucket {
+            bytes_per_second: megabits_per_second as u64 * 1_000_000 / 8,
+            ..Default::default()
+        };
+    }
+
+    /// Waits until `bytes` more can be read without going over the limit.
+    pub fn pace(&self, bytes: usize) {
+        let wait = self
+            .bucket
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take(bytes);
+        if !wait.is_zero() {
+            std::thread::sleep(wait);
+        }
     }
-    let content = response.bytes()?;
+
+    /// The limit in bytes per second, for the system downloaders.
+    fn bytes_per_second(&self) -> Option<u64> {
+        let bucket = self.bucket.lock().unwrap_or_else(|e| e.into_inner());
+        Some(bucket.bytes_per_second).filter(|&rate| rate > 0)
+    }
+}
+
+#[derive(Default)]
+struct TokenBucket {
+    /// Zero means no limit.
+    bytes_per_second: u64,
+    /// Bytes that can be read before waiting. Negative when readers have taken more than there
+    /// was, and are waiting for it to be paid back.
+    available: f64,
+    last_refill: Option<Instant>,
+}
+
+impl TokenBucket {
+    /// Takes `bytes` from the bucket, returning how long to wait before reading them.
+    fn take(&mut self, bytes: usize) -> Duration {
+        if self.bytes_per_second == 0 {
+            return Duration::ZERO;
+        }
+        let rate = self.bytes_per_second as f64;
+        let now = Instant::now();
+        let elapsed = self
+            .last_refill
+            .map_or(0.0, |last| (now - last).as_secs_f64());
+        // Up to a second's worth can build up, so short bursts don't go over the limit by much.
+        self.available = (self.available + elapsed * rate).min(rate);
+        self.last_refill = Some(now);
+        self.available -= bytes as f64;
+        if self.available < 0.0 {
+            Duration::from_secs_f64(-self.available / rate)
+        } else {
+            Duration::ZERO
+        }
+    }
+}
+
+/// Progress of a download, reported after each piece of the file arrives.
+#[derive(Debug, Clone, Default)]
+pub struct DownloadProgress {
+    pub bytes_done: u64,
+    pub bytes_total: u64,
+}
+
+impl DownloadProgress {
+    pub fn fraction(&self) -> f32 {
+        if self.bytes_total == 0 {
+            0.0
+        } else {
+            self.bytes_done as f32 / self.bytes_total as f32
+        }
+    }
+}
+
+/// Reads a download's body, keeping to the rate limit and reporting progress as it arrives.
+struct ProgressReader<'a, F: FnMut(&DownloadProgress)> {
+    body: Box<dyn Read + Send>,
+    rate_limiter: &'a RateLimiter,
+    progress: DownloadProgress,
+    last_reported: u64,
+    on_progress: F,
+}
+
+impl<F: FnMut(&DownloadProgress)> Read for ProgressReader<'_, F> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.body.read(buf)?;
+        self.rate_limiter.pace(read);
+        self.progress.bytes_done += read as u64;
+        if self.progress.bytes_done - self.last_reported >= PROGRESS_INTERVAL {
+            self.last_reported = self.progress.bytes_done;
+            (self.on_progress)(&self.progress);
+        }
+        Ok(read)
+    }
+}
+
+pub fn download_file(network: &Network, url: &str, dest_path: &Path) -> Result<()> {
+    download_file_with_progress(network, url, dest_path, |_| {})
+}
+
+/// [`download_file`], calling `on_progress` as the file arrives. Large files are fetched over
+/// the number of connections in the network settings. The system downloaders are tried if the
+/// client can't connect.
+pub fn download_file_with_progress(
+    network: &Network,
+    url: &str,
+    dest_path: &Path,
+    on_progress: impl FnMut(&DownloadProgress),
+) -> Result<()> {
     let dest_dir = dest_path.parent().ok_or(anyhow::Error::msg(format!(
         "Could not get parent of download destination from: {:?}",
         dest_path
     )))?;
     std::fs::create_dir_all(dest_dir)?;
-    let mut dest = std::fs::File::create(dest_path)?;
-    dest.write_all(&content)?;
+    // Write to a uniquely named temporary file first so an interrupted download is never
+    // mistaken for a complete file in the download cache, and two operations fetching the same
+    // asset don't write over each other.
+    let mut dest = tempfile::NamedTempFile::with_prefix_in(".download-", dest_dir)?;
+    match download_with_client(network, url, &mut dest, on_progress) {
+        Ok(()) => {
+            dest.persist(dest_path)?;
+        }
+        Err(e) if should_try_system_downloaders(&e) => {
+            log::warn!(
+                "Downloading {url} failed with a TLS or connection error, trying the system downloaders instead: {e:#}"
+            );
+            // The system tools open the file themselves, so only the path is kept.
+            let partial_path = dest.into_temp_path();
+            let rate_limit = network.rate_limiter.bytes_per_second();
+            download_with_system_tools(url, &partial_path, rate_limit)
+                .map_err(|fallback_error| anyhow!("{e:#}. {fallback_error:#}"))?;
+            partial_path.persist(dest_path)?;
+        }
+        Err(e) => return Err(e),
+    }
     Ok(())
 }
+
+fn download_with_client(
+    network: &Network,
+    url: &str,
+    dest: &mut tempfile::NamedTempFile,
+    mut on_progress: impl FnMut(&DownloadProgress),
+) -> Result<()> {
+    let client = network.client.as_ref();
+    let connections = network
+        .download_connections
+        .clamp(1, MAX_DOWNLOAD_CONNECTIONS);
+    let response = if connections > 1 {
+        // Asking for the first byte tells whether the server accepts ranges and how big the
+        // file is. Servers that don't accept ranges send the whole file instead, which is read
+        // from this response.
+        let probe = client.get_stream(url, &[("range", "bytes=0-0")])?;
+        match content_range_total(probe.status, probe.header("content-range")) {
+            Some(total) if total >= MIN_SEGMENTED_SIZE => {
+                download_segments(client, url, dest.path(), total, connections, on_progress)?;
+                dest.as_file().sync_all()?;
+                return Ok(());
+            }
+            Some(_) => client.get_stream(url, &[])?,
+            None => probe,
+        }
+    } else {
+        client.get_stream(url, &[])?
+    };
+    if !response.is_success() {
+        bail!("Failed to download file: {}", response.status);
+    }
+    let bytes_total = response
+        .header("content-length")
+        .and_then(|length| length.parse().ok())
+        .unwrap_or(0);
+    let mut body = ProgressReader {
+        body: response.body,
+        rate_limiter: &network.rate_limiter,
+        progress: DownloadProgress {
+            bytes_done: 0,
+            bytes_total,
+        },
+        last_reported: 0,
+        on_progress: &mut on_progress,
+    };
+    std::io::copy(&mut body, dest)?;
+    dest.as_file().sync_all()?;
+    // Also finishes the bar for servers that didn't send the size.
+    let size = body.progress.bytes_done;
+    on_progress(&DownloadProgress {
+        bytes_done: size,
+        bytes_total: size,
+    });
+    Ok(())
+}
+
+/// Reads the size of the whole file from a partial response, like `bytes 0-0/1234`. Returns
+/// `None` if the server sent something other than part of the file.
+fn content_range_total(status: u16, content_range: Option<&str>) -> Option<u64> {
+    if status != STATUS_PARTIAL_CONTENT {
+        return None;
+    }
+    content_range?.rsplit_once('/')?.1.parse().ok()
+}
+
+/// Fetches the file in pieces over several connections at once, writing each piece into place
+/// in the file at `path`.
+fn download_segments(
+    client: &dyn HttpClient,
+    url: &str,
+    path: &Path,
+    total: u64,
+    connections: u32,
+    mut on_progress: impl FnMut(&DownloadProgress),
+) -> Result<()> {
+    log::info!("Downloading {url} ({total} bytes) over {connections} connections");
+    std::fs::OpenOptions::new()
+        .write(true)
+        .open(path)?
+        .set_len(total)?;
+    let next_start = AtomicU64::new(0);
+    let failed = AtomicBool::new(false);
+    let (sender, receiver) = std::sync::mpsc::channel();
+    std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..connections)
+            .map(|_| {
+                let sender = sender.clone();
+                let (next_start, failed) = (&next_start, &failed);
+                scope.spawn(move || -> Result<()> {
+                    // Each connection has its own handle so their seeks don't interfere.
+                    let mut file = std::fs::OpenOptions::new().write(true).open(path)?;
+                    while !failed.load(Ordering::Relaxed) {
+                        let start = next_start.fetch_add(SEGMENT_SIZE, Ordering::Relaxed);
+                        if start >= total {
+                            break;
+                        }
+                        let end = (start + SEGMENT_SIZE).min(total) - 1;
+                        let result = download_segment_with_retries(client, url, start, end)
+                            .and_then(|body| {
+                                file.seek(SeekFrom::Start(start))?;
+                                file.write_all(&body)?;
+                                Ok(body.len() as u64)
+                            });
+                        match result {
+                            Ok(size) => {
+                                let _ = sender.send(size);
+                            }
+                            Err(e) => {
+                                failed.store(true, Ordering::Relaxed);
+                                return Err(e);
+                            }
+                        }
+                    }
+                    Ok(())
+                })
+            })
+            .collect();
+        // The receiver ends once every connection is finished with its sender.
+        drop(sender);
+        let mut progress = DownloadProgress {
+            bytes_done: 0,
+            bytes_total: total,
+        };
+        for size in receiver {
+            progress.bytes_done += size;
+            on_progress(&progress);
+        }
+        workers.into_iter().try_for_each(|worker| {
+            worker
+                .join()
+                .map_err(|payload| report_from_payload(payload.as_ref()))?
+        })
+    })
+}
+
+/// [`download_segment`], asking again after a short wait if it fails.
+fn download_segment_with_retries(
+    client: &dyn HttpClient,
+    url: &str,
+    start: u64,
+    end: u64,
+) -> Result<Vec<u8>> {
+    let mut attempt = 1;
+    loop {
+        match download_segment(client, url, start, end) {
+            Ok(body) => return Ok(body),
+            Err(e) if attempt < SEGMENT_ATTEMPTS => {
+                log::warn!("Downloading bytes {start}-{end} of {url} failed, trying again: {e:#}");
+                std::thread::sleep(SEGMENT_RETRY_DELAY * attempt);
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Fetches bytes `start` to `end` of the file, inclusive.
+fn download_segment(client: &dyn HttpClient, url: &str, start: u64, end: u64) -> Result<Vec<u8>> {
+    let range = format!("bytes={start}-{end}");
+    let response = client.get(url, &[("range", &range)])?;
+    let expected = format!("bytes {start}-{end}/");
+    let matches = response.status == STATUS_PARTIAL_CONTENT
+        && response
+            .header("content-range")
+            .is_some_and(|content_range| content_range.starts_with(&expected))
+        && response.body.len() as u64 == end - start + 1;
+    if !matches {
+        bail!(
+            "Failed to download bytes {start}-{end} of the file: {}",
+            response.status
+        );
+    }
+    Ok(response.body)
+}
+
+/// Old school computers sometimes have certificate stores or proxies our TLS stack can't cope
+/// with, while the downloaders that come with the system work. HTTP errors like 404 mean the
+/// server was reached, so those are not retried.
+fn should_try_system_downloaders(e: &anyhow::Error) -> bool {
+    let Some(e) = e.downcast_ref::<reqwest::Error>() else {
+        return false;
+    };
+    if e.is_connect() {
+        return true;
+    }
+    let mut source: Option<&dyn std::error::Error> = Some(e);
+    while let Some(error) = source {
+        let message = error.to_string().to_lowercase();
+        if ["tls", "ssl", "certificate", "handshake"]
+            .iter()
+            .any(|marker| message.contains(marker))
+        {
+            return true;
+        }
+        source = error.source();
+    }
+    false
+}
+
+/// Tries each downloader that comes with the operating system until one succeeds.
+/// `rate_limit` is in bytes per second.
+fn download_with_system_tools(url: &str, dest_path: &Path, rate_limit: Option<u64>) -> Result<()> {
+    let mut failures = vec![];
+    for (name, download) in system_downloaders() {
+        log::info!("Downloading {url} with {name}");
+        match download(url, dest_path, rate_limit) {
+            Ok(()) if dest_path.metadata().is_ok_and(|m| m.len() > 0) => {
+                log::info!("Downloaded {url} with {name}");
+                return Ok(());
+            }
+            Ok(()) => failures.push(format!("{name} wrote an empty file")),
+            Err(e) => {
+                log::warn!("Downloading {url} with {name} failed: {e:#}");
+                failures.push(format!("{name} failed"));
+            }
+        }
+    }
+    bail!("System downloaders also failed: {}", failures.join(", "))
+}
+
+type SystemDownloader = fn(&str, &Path, Option<u64>) -> Result<()>;
+
+#[cfg(target_os = "windows")]
+fn system_downloaders() -> [(&'static str, SystemDownloader); 3] {
+    [
+        ("curl.exe", download_with_curl),
+        ("Invoke-WebRequest", download_with_powershell),
+        ("bitsadmin", download_with_bitsadmin),
+    ]
+}
+
+#[cfg(target_os = "linux")]
+fn system_downloaders() -> [(&'static str, SystemDownloader); 2] {
+    [("curl", download_with_curl), ("wget", download_with_wget)]
+}
+
+fn download_with_curl(url: &str, dest_path: &Path, rate_limit: Option<u64>) -> Result<()> {
+    let program = if cfg!(target_os = "windows") {
+        "curl.exe"
+    } else {
+        "curl"
+    };
+    let rate_limit = rate_limit.map(|rate| format!("--limit-rate={rate}"));
+    Command::new(program)
+        .args(["--fail", "--silent", "--show-error", "--location"])
+        .args(rate_limit)
+        .arg("--output")
+        .arg(dest_path)
+        .arg(url)
+        .timeout(None)
+        .run()?;
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn download_with_powershell(url: &str, dest_path: &Path, _rate_limit: Option<u64>) -> Result<()> {
+    // Windows PowerShell 5.1 only offers TLS 1.0 by default, which GitHub rejects.
+    Command::powershell(
+        "[Net.ServicePointManager]::SecurityProtocol = [Net.SecurityProtocolType]::Tls12; Invoke-WebRequest -UseBasicParsing -Uri $env:WIZARD_URL -OutFile $env:WIZARD_DEST_PATH",
+    )
+    .param("URL", url)
+    .param("DEST_PATH", dest_path)
+    .timeout(None)
+    .run()?;
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn download_with_bitsadmin(url: &str, dest_path: &Path, _rate_limit: Option<u64>) -> Result<()> {
+    // BITS won't replace the empty placeholder file.
+    let _ = std::fs::remove_file(dest_path);
+    Command::new("bitsadmin")
+        .args([
+            "/transfer",
+            "best-gizmo-setup-wizard",
+            "/download",
+            "/priority",
+            "foreground",
+            url,
+        ])
+        .arg(dest_path)
+        .timeout(None)
+        .run()?;
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn download_with_wget(url: &str, dest_path: &Path, rate_limit: Option<u64>) -> Result<()> {
+    let rate_limit = rate_limit.map(|rate| format!("--limit-rate={rate}"));
+    Command::new("wget")
+        .arg("--quiet")
+        .args(rate_limit)
+        .arg("--output-document")
+        .arg(dest_path)
+        .arg(url)
+        .timeout(None)
+        .run()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::http::HttpResponse;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    const URL: &str = "https://example.com/image.img";
+    /// Big enough to be split into three segments, the last one short.
+    const LARGE_FILE_SIZE: usize = 2 * SEGMENT_SIZE as usize + 1000;
+
+    /// Serves one file, answering range requests if `accepts_ranges` is set. Ranges in
+    /// `failures` get an error status that many times before they are served.
+    struct FileServer {
+        file: Vec<u8>,
+        accepts_ranges: bool,
+        failures: Mutex<HashMap<String, u32>>,
+        /// The range header of each request, in order.
+        requests: Mutex<Vec<Option<String>>>,
+    }
+
+    impl FileServer {
+        fn new(size: usize) -> Self {
+            Self {
+                file: (0..size).map(|i| (i % 251) as u8).collect(),
+                accepts_ranges: true,
+                failures: Mutex::default(),
+                requests: Mutex::default(),
+            }
+        }
+
+        fn failing(self, range: &str, times: u32) -> Self {
+            self.failures
+                .lock()
+                .unwrap()
+                .insert(range.to_string(), times);
+            self
+        }
+
+        fn requests_for(&self, range: Option<&str>) -> usize {
+            self.requests
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|requested| requested.as_deref() == range)
+                .count()
+        }
+    }
+
+    impl HttpClient for FileServer {
+        fn get(&self, url: &str, headers: &[(&str, &str)]) -> Result<HttpResponse> {
+            assert_eq!(url, URL);
+            let range = headers
+                .iter()
+                .find(|(name, _)| *name == "range")
+                .map(|(_, value)| value.to_string());
+            self.requests.lock().unwrap().push(range.clone());
+            let mut failures = self.failures.lock().unwrap();
+            if let Some(remaining) = range.as_ref().and_then(|range| failures.get_mut(range))
+                && *remaining > 0
+            {
+                *remaining -= 1;
+                return Ok(HttpResponse {
+                    status: 500,
+                    ..Default::default()
+                });
+            }
+            let total = self.file.len();
+            match range.filter(|_| self.accepts_ranges) {
+                Some(range) => {
+                    let (start, end) = range
+                        .strip_prefix("bytes=")
+                        .and_then(|range| range.split_once('-'))
+                        .unwrap();
+                    let start: usize = start.parse().unwrap();
+                    let end = end.parse::<usize>().unwrap().min(total - 1);
+                    Ok(HttpResponse {
+                        status: STATUS_PARTIAL_CONTENT,
+                        headers: vec![(
+                            "content-range".to_string(),
+                            format!("bytes {start}-{end}/{total}"),
+                        )],
+                        body: self.file[start..=end].to_vec(),
+                    })
+                }
+                None => Ok(HttpResponse {
+                    status: 200,
+                    headers: vec![("content-length".to_string(), total.to_string())],
+                    body: self.file.clone(),
+                }),
+            }
+        }
+    }
+
+    /// Downloads the server's file over `connections` connections, returning what was written
+    /// and every progress report.
+    fn download(
+        server: &Arc<FileServer>,
+        connections: u32,
+    ) -> (Result<Vec<u8>>, Vec<DownloadProgress>) {
+        let mut network = Network::with_client(server.clone());
+        network.download_connections = connections;
+        let dir = tempfile::tempdir().unwrap();
+        let dest_path = dir.path().join("image.img");
+        let mut reports = vec![];
+        let result = download_file_with_progress(&network, URL, &dest_path, |progress| {
+            reports.push(progress.clone())
+        })
+        .and_then(|()| Ok(std::fs::read(&dest_path)?));
+        if result.is_err() {
+            assert!(!dest_path.exists(), "A failed download was left in place");
+        }
+        (result, reports)
+    }
+
+    fn segment_range(index: u64) -> String {
+        let start = index * SEGMENT_SIZE;
+        let end = (start + SEGMENT_SIZE).min(LARGE_FILE_SIZE as u64) - 1;
+        format!("bytes={start}-{end}")
+    }
+
+    fn assert_finished(reports: &[DownloadProgress], size: usize) {
+        let last = reports.last().expect("No progress was reported");
+        assert_eq!(
+            (last.bytes_done, last.bytes_total),
+            (size as u64, size as u64)
+        );
+        assert!(
+            reports
+                .windows(2)
+                .all(|pair| pair[0].bytes_done <= pair[1].bytes_done)
+        );
+    }
+
+    #[test]
+    fn large_files_are_fetched_in_segments() {
+        let server = Arc::new(FileServer::new(LARGE_FILE_SIZE));
+        let (result, reports) = download(&server, 4);
+        assert!(result.unwrap() == server.file);
+        assert_eq!(server.requests_for(Some("bytes=0-0")), 1);
+        for index in 0..3 {
+            assert_eq!(server.requests_for(Some(&segment_range(index))), 1);
+        }
+        assert_eq!(server.requests_for(None), 0);
+        assert_eq!(reports.len(), 3);
+        assert_finished(&reports, LARGE_FILE_SIZE);
+    }
+
+    #[test]
+    fn failed_segments_are_retried() {
+        let server = Arc::new(
+            FileServer::new(LARGE_FILE_SIZE).failing(&segment_range(1), SEGMENT_ATTEMPTS - 1),
+        );
+        let (result, _) = download(&server, 2);
+        assert!(result.unwrap() == server.file);
+        assert_eq!(
+            server.requests_for(Some(&segment_range(1))),
+            SEGMENT_ATTEMPTS as usize
+        );
+    }
+
+    #[test]
+    fn a_segment_that_keeps_failing_fails_the_download() {
+        let server =
+            Arc::new(FileServer::new(LARGE_FILE_SIZE).failing(&segment_range(2), SEGMENT_ATTEMPTS));
+        let (result, _) = download(&server, 2);
+        assert!(result.is_err());
+        assert_eq!(
+            server.requests_for(Some(&segment_range(2))),
+            SEGMENT_ATTEMPTS as usize
+        );
+    }
+
+    #[test]
+    fn servers_without_ranges_send_the_file_in_the_probe() {
+        let server = Arc::new(FileServer {
+            accepts_ranges: false,
+            ..FileServer::new(LARGE_FILE_SIZE)
+        });
+        let (result, reports) = download(&server, 4);
+        assert!(result.unwrap() == server.file);
+        assert_eq!(server.requests.lock().unwrap().len(), 1);
+        // Progress is reported while the body is read, not only at the end.
+        assert!(reports.len() > 2);
+        assert!(
+            reports
+                .iter()
+                .take(reports.len() - 1)
+                .all(|progress| progress.bytes_total == LARGE_FILE_SIZE as u64)
+        );
+        assert_finished(&reports, LARGE_FILE_SIZE);
+    }
+
+    #[test]
+    fn small_files_are_fetched_in_one_request() {
+        let server = Arc::new(FileServer::new(1000));
+        let (result, reports) = download(&server, 4);
+        assert!(result.unwrap() == server.file);
+        assert_eq!(
+            *server.requests.lock().unwrap(),
+            vec![Some("bytes=0-0".to_string()), None]
+        );
+        assert_finished(&reports, 1000);
+    }
+
+    #[test]
+    fn one_connection_skips_the_probe() {
+        let server = Arc::new(FileServer::new(LARGE_FILE_SIZE));
+        let (result, reports) = download(&server, 1);
+        assert!(result.unwrap() == server.file);
+        assert_eq!(*server.requests.lock().unwrap(), vec![None]);
+        assert_finished(&reports, LARGE_FILE_SIZE);
+    }
+
+    #[test]
+    fn error_statuses_fail_without_trying_the_system_downloaders() {
+        let error = anyhow!("Failed to download file: 404");
+        assert!(!should_try_system_downloaders(&error));
+    }
+}
@@ -1,19 +1,118 @@
-use anyhow::{Result, bail};
-use serde::Deserialize;
+use crate::settings::{MirrorSettings, Settings};
+use crate::utils::cache::{is_cached_asset_intact, note_refreshed_asset, record_hash};
+use crate::utils::file_download::{DownloadProgress, download_file_with_progress};
+use crate::utils::github_artifacts::{download_artifact, is_artifact_url, is_development_build};
+use crate::utils::http::{HttpClient, HttpResponse, Network};
+use anyhow::{Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Debug, Clone, PartialEq)]
+/// Environment variable checked for a GitHub token when none is set in the settings.
+pub const AUTH_TOKEN_ENV_VAR: &str = "GITHUB_TOKEN";
+pub const GITHUB_API_URL: &str = "https://api.github.com";
+/// Start of the download URLs of release assets.
+const GITHUB_DOWNLOAD_URL: &str = "https://github.com";
+/// The most GitHub returns in one page.
+const RELEASES_PER_PAGE: usize = 100;
+/// Stops following pages after this many, in case a server keeps linking to more.
+const MAX_RELEASE_PAGES: usize = 50;
+const STATUS_NOT_MODIFIED: u16 = 304;
+const STATUS_FORBIDDEN: u16 = 403;
+const STATUS_TOO_MANY_REQUESTS: u16 = 429;
+
+/// The settings GitHub requests are made with.
+#[derive(Debug, Clone, Default)]
+pub struct GithubAccess {
+    /// Personal access token sent with API requests. Without one, the `GITHUB_TOKEN`
+    /// environment variable is used.
+    token: Option<String>,
+    /// How many releases are fetched from each repository, newest first. `None` fetches them
+    /// all, which takes one request per hundred releases.
+    max_releases: Option<usize>,
+    /// Where releases are also fetched from. A mirror serves the same files at the same paths
+    /// as GitHub: release lists at `<mirror>/repos/<owner>/<repo>/releases`, and assets at
+    /// `<mirror>/<owner>/<repo>/releases/download/<tag>/<name>`.
+    mirror: Option<MirrorSettings>,
+}
+
+impl GithubAccess {
+    pub fn new(settings: &Settings) -> Self {
+        let token = settings.github_token.trim();
+        let mirror = &settings.mirror;
+        Self {
+            token: (!token.is_empty()).then(|| token.to_string()),
+            max_releases: Some(settings.max_releases as usize).filter(|&max| max > 0),
+            mirror: (!mirror.url.trim().is_empty()).then(|| MirrorSettings {
+                url: mirror.url.trim().trim_end_matches('/').to_string(),
+                prefer_mirror: mirror.prefer_mirror,
+            }),
+        }
+    }
+
+    pub fn auth_token(&self) -> Option<String> {
+        self.token
+            .clone()
+            .or_else(|| std::env::var(AUTH_TOKEN_ENV_VAR).ok())
+            .filter(|token| !token.is_empty())
+    }
+
+    /// Returns `github` and its copy on the mirror, in the order they should be tried.
+    fn with_mirror(
+        &self,
+        github: &str,
+        mirror_path: impl FnOnce(&str) -> Option<String>,
+    ) -> Vec<String> {
+        let Some(ref mirror) = self.mirror else {
+            return vec![github.to_string()];
+        };
+        match mirror_path(&mirror.url) {
+            Some(mirrored) if mirror.prefer_mirror => vec![mirrored, github.to_string()],
+            Some(mirrored) => vec![github.to_string(), mirrored],
+            None => vec![github.to_string()],
+        }
+    }
+}
+
+/// Runs `fetch` with each URL until one succeeds. If they all fail, the errors are combined.
+fn try_each<T>(urls: &[String], mut fetch: impl FnMut(&str) -> Result<T>) -> Result<T> {
+    let mut errors: Vec<String> = vec![];
+    for url in urls {
+        match fetch(url) {
+            Ok(value) => return Ok(value),
+            Err(e) if urls.len() == 1 => return Err(e),
+            Err(e) => {
+                log::warn!("Request to {url} failed, trying the next source: {e:#}");
+                errors.push(format!("{e:#}"));
+            }
+        }
+    }
+    Err(anyhow!("{}", errors.join(". ")))
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct GithubReleaseAsset {
     pub name: String,
     pub browser_download_url: String,
+    /// Checksum GitHub publishes for the asset, like "sha256:<hex>". Older releases have none.
+    #[serde(default)]
+    pub digest: Option<String>,
+    /// Size in bytes, as listed by GitHub. Mirrors and old metadata may leave it out.
+    #[serde(default)]
+    pub size: Option<u64>,
+    /// Set when the asset is already on disk, such as in an offline bundle.
+    #[serde(skip)]
+    pub local_path: Option<std::path::PathBuf>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct GithubRelease {
     pub name: String,
     pub tag_name: String,
     pub assets: Vec<GithubReleaseAsset>,
     pub prerelease: bool,
     pub draft: bool,
+    /// Release notes in Markdown. GitHub sends null when a release has none.
+    #[serde(default)]
+    pub body: Option<String>,
     #[serde(skip)]
     pub latest: bool,
 }
@@ -34,44 +133,679 @@ impl GithubRelease {
         };
         format!("{}{}", self.name, suffix)
     }
+
+    /// Returns the driver station ramdisk archive, preferring zip if the release has more than
+    /// one format.
+    pub fn ramdisk_asset(&self) -> Option<&GithubReleaseAsset> {
+        crate::utils::archive::ArchiveFormat::EXTENSIONS
+            .iter()
+            .find_map(|ext| {
+                let name = format!("ds-ramdisk{ext}");
+                self.assets.iter().find(|a| a.name == name)
+            })
+    }
+
+    /// Returns the driver station software in this release: the ramdisk archive, or for
+    /// releases that ship the whole card, a disk image.
+    pub fn driver_station_asset(&self) -> Option<&GithubReleaseAsset> {
+        self.ramdisk_asset().or_else(|| {
+            self.assets.iter().find(|a| {
+                a.name.starts_with("ds-")
+                    && crate::utils::imaging::is_disk_image(std::path::Path::new(&a.name))
+            })
+        })
+    }
+
+    /// Returns true if this release looks like a demo or single-team build that is not meant for
+    /// provisioning a whole hub's worth of driver stations. Only the asset that
+    /// [`driver_station_asset`](Self::driver_station_asset) picks is checked, so a release that
+    /// also ships a demo ramdisk alongside the competition one isn't flagged.
+    pub fn is_single_team_variant(&self) -> bool {
+        let asset_name = self.driver_station_asset().map(|a| a.name.as_str());
+        [self.name.as_str(), self.tag_name.as_str()]
+            .into_iter()
+            .chain(asset_name)
+            .any(is_single_team_name)
+    }
 }
 
-pub fn get_releases(repo_owner: &str, repo_name: &str) -> Result<Vec<GithubRelease>> {
-    let request_url = format!(
-        "https://api.github.com/repos/{}/{}/releases",
-        repo_owner, repo_name
-    );
-    let client = reqwest::blocking::Client::new();
-    let response = client
-        .get(request_url)
-        .header(reqwest::header::USER_AGENT, "rust-web-api-client")
-        .send()?;
+/// Which releases the version pickers offer. Prereleases and drafts are for trying out upcoming
+/// software, so volunteers only see stable releases unless they ask for more.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReleaseChannel {
+    #[default]
+    Stable,
+    /// Stable releases plus prereleases and drafts.
+    Prerelease,
+    /// Everything in [`ReleaseChannel::Prerelease`] plus builds from GitHub Actions.
+    Development,
+}
 
-    if !response.status().is_success() {
-        bail!("Failed to fetch releases: {}", response.status());
+impl ReleaseChannel {
+    pub fn includes(self, release: &GithubRelease) -> bool {
+        match self {
+            ReleaseChannel::Stable => !release.prerelease && !release.draft,
+            ReleaseChannel::Prerelease => !is_development_build(release),
+            ReleaseChannel::Development => true,
+        }
     }
+}
+
+/// Returns the releases offered in `channel`, keeping GitHub's newest-first order.
+pub fn releases_in_channel(
+    releases: &[GithubRelease],
+    channel: ReleaseChannel,
+) -> Vec<&GithubRelease> {
+    releases.iter().filter(|r| channel.includes(r)).collect()
+}
+
+/// Checks a release, tag, or file name for the markers used to label demo and single-team builds.
+pub fn is_single_team_name(name: &str) -> bool {
+    let name = name.to_lowercase();
+    ["demo", "single-team", "single_team", "singleteam"]
+        .iter()
+        .any(|marker| name.contains(marker))
+}
+
+pub fn get_releases(
+    network: &Network,
+    repo_owner: &str,
+    repo_name: &str,
+) -> Result<Vec<GithubRelease>> {
+    get_releases_if_changed(network, repo_owner, repo_name, None)?
+        .map(|(releases, _)| releases)
+        .ok_or(anyhow::Error::msg("GitHub returned no release data"))
+}
+
+/// Fetches releases unless they are unchanged since the response tagged with `etag`. They come
+/// from the mirror if GitHub fails, or first if the mirror is preferred.
+///
+/// Returns `None` if GitHub reports the releases have not changed, otherwise the releases and the
+/// ETag of the new response. Conditional requests that return "Not Modified" do not count against
+/// the GitHub API rate limit.
+///
+/// GitHub returns releases a page at a time, so pages are fetched until there are no more or
+/// the limit in the settings is reached. The ETag is the first page's, which
+/// changes whenever a release is added.
+pub fn get_releases_if_changed(
+    network: &Network,
+    repo_owner: &str,
+    repo_name: &str,
+    etag: Option<&str>,
+) -> Result<Option<(Vec<GithubRelease>, Option<String>)>> {
+    let api_urls = network
+        .github
+        .with_mirror(GITHUB_API_URL, |mirror| Some(mirror.to_string()));
+    try_each(&api_urls, |api_base_url| {
+        fetch_releases(
+            network.client.as_ref(),
+            &network.github,
+            api_base_url,
+            repo_owner,
+            repo_name,
+            etag,
+        )
+    })
+}
 
-    let mut releases: Vec<GithubRelease> = response.json()?;
+/// [`get_releases_if_changed`] with the client and API server passed in, so it can be run
+/// against a mock server or a canned client.
+pub fn fetch_releases(
+    client: &dyn HttpClient,
+    access: &GithubAccess,
+    api_base_url: &str,
+    repo_owner: &str,
+    repo_name: &str,
+    etag: Option<&str>,
+) -> Result<Option<(Vec<GithubRelease>, Option<String>)>> {
+    // The token is only for GitHub, so it isn't sent to mirrors.
+    let token = access
+        .auth_token()
+        .filter(|_| api_base_url == GITHUB_API_URL)
+        .map(|token| format!("Bearer {token}"));
+    let max_releases = access.max_releases;
+    let per_page = max_releases.map_or(RELEASES_PER_PAGE, |max| max.min(RELEASES_PER_PAGE));
+    let mut next_url = Some(format!(
+        "{}/repos/{}/{}/releases?per_page={per_page}",
+        api_base_url.trim_end_matches('/'),
+        repo_owner,
+        repo_name
+    ));
+    let mut first_page_etag = None;
+    let mut releases: Vec<GithubRelease> = vec![];
+    for page in 0..MAX_RELEASE_PAGES {
+        let Some(url) = next_url.take() else {
+            break;
+        };
+        let mut headers = vec![];
+        if let Some(ref token) = token {
+            headers.push(("authorization", token.as_str()));
+        }
+        if page == 0
+            && let Some(etag) = etag
+        {
+            headers.push(("if-none-match", etag));
+        }
+        let response = client.get(&url, &headers)?;
+        if page == 0 && response.status == STATUS_NOT_MODIFIED {
+            return Ok(None);
+        }
+        check_rate_limit(&response, access)?;
+        if !response.is_success() {
+            bail!("Failed to fetch releases: {}", response.status);
+        }
+        if page == 0 {
+            first_page_etag = response.header("etag").map(str::to_string);
+        }
+        next_url = response.header("link").and_then(next_page_url);
+        releases.extend(response.json::<Vec<GithubRelease>>()?);
+        if let Some(max) = max_releases
+            && releases.len() >= max
+        {
+            releases.truncate(max);
+            break;
+        }
+    }
     releases
         .iter_mut()
         .find(|r| !r.prerelease && !r.draft)
         .ok_or(anyhow::Error::msg("No stable releases found"))?
         .latest = true;
-    Ok(releases)
+    Ok(Some((releases, first_page_etag)))
+}
+
+/// Reads the URL of the next page from a `Link` header like
+/// `<https://api.github.com/...&page=2>; rel="next", <...>; rel="last"`.
+fn next_page_url(link_header: &str) -> Option<String> {
+    link_header.split(',').find_map(|link| {
+        let (url, params) = link.split_once(';')?;
+        params
+            .split(';')
+            .any(|param| param.trim() == "rel=\"next\"")
+            .then(|| {
+                url.trim()
+                    .trim_start_matches('<')
+                    .trim_end_matches('>')
+                    .to_string()
+            })
+    })
+}
+
+/// GitHub answers with 403 or 429 once the hourly API limit is used up. Turns that into an
+/// error that says when the limit resets, since the status code alone is not helpful.
+fn check_rate_limit(response: &HttpResponse, access: &GithubAccess) -> Result<()> {
+    if response.status != STATUS_FORBIDDEN && response.status != STATUS_TOO_MANY_REQUESTS {
+        return Ok(());
+    }
+    let header = |name: &str| response.header(name).and_then(|v| v.parse::<u64>().ok());
+    if header("x-ratelimit-remaining") != Some(0) {
+        return Ok(());
+    }
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)?
+        .as_secs();
+    let minutes_until_reset = header("x-ratelimit-reset")
+        .map(|reset| reset.saturating_sub(now).div_ceil(60))
+        .unwrap_or(60);
+    let hint = if access.auth_token().is_some() {
+        ""
+    } else {
+        " Add a GitHub access token in Settings to raise the limit."
+    };
+    bail!(
+        "GitHub API rate limit reached. It resets in {} minute(s).{}",
+        minutes_until_reset,
+        hint
+    );
+}
+
+/// Downloads a release asset to `dest_path`, from the mirror if GitHub fails, or first if the
+/// mirror is preferred. Mirrors that list their own download URLs are only used for their
+/// release lists.
+pub fn download_asset(
+    network: &Network,
+    asset: &GithubReleaseAsset,
+    dest_path: &std::path::Path,
+    mut on_progress: impl FnMut(&DownloadProgress),
+) -> Result<()> {
+    // Artifacts only exist on GitHub, so they are never looked for on the mirror.
+    if is_artifact_url(&asset.browser_download_url) {
+        return download_artifact(network, asset, dest_path, on_progress);
+    }
+    let urls = network
+        .github
+        .with_mirror(&asset.browser_download_url, |mirror| {
+            asset
+                .browser_download_url
+                .strip_prefix(GITHUB_DOWNLOAD_URL)
+                .map(|path| format!("{mirror}{path}"))
+        });
+    try_each(&urls, |url| {
+        download_file_with_progress(network, url, dest_path, &mut on_progress)
+    })
 }
 
 pub fn download_versioned_asset(
+    network: &Network,
     asset: &GithubReleaseAsset,
     repo_owner: &str,
     repo_name: &str,
     release: &GithubRelease,
     cache_dir: &std::path::Path,
 ) -> Result<std::path::PathBuf> {
+    download_versioned_asset_with_progress(
+        network,
+        asset,
+        repo_owner,
+        repo_name,
+        release,
+        cache_dir,
+        |_| {},
+    )
+}
+
+/// [`download_versioned_asset`], calling `on_progress` as the asset arrives. Nothing is
+/// reported if the asset is already on disk.
+pub fn download_versioned_asset_with_progress(
+    network: &Network,
+    asset: &GithubReleaseAsset,
+    repo_owner: &str,
+    repo_name: &str,
+    release: &GithubRelease,
+    cache_dir: &std::path::Path,
+    on_progress: impl FnMut(&DownloadProgress),
+) -> Result<std::path::PathBuf> {
+    if let Some(local_path) = &asset.local_path {
+        return Ok(local_path.clone());
+    }
     let dest_path = cache_dir
         .join(repo_owner)
         .join(repo_name)
         .join(&release.name)
         .join(&asset.name);
-    crate::utils::file_download::download_file(&asset.browser_download_url, &dest_path)?;
+    // Past the check below, a cached copy is one that was damaged.
+    let was_cached = dest_path.is_file();
+    if was_cached {
+        if is_cached_asset_intact(&dest_path, asset.size, asset.digest.as_deref())? {
+            return Ok(dest_path);
+        }
+        log::warn!("Cached {:?} is damaged, downloading it again", dest_path);
+        std::fs::remove_file(&dest_path)?;
+    }
+    download_asset(network, asset, &dest_path, on_progress)?;
+    record_hash(&dest_path, asset.digest.as_deref())?;
+    if was_cached {
+        note_refreshed_asset(&asset.name);
+    }
     Ok(dest_path)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    const API_URL: &str = "http://mock.test";
+    const FIRST_PAGE: &str = "http://mock.test/repos/owner/repo/releases?per_page=100";
+
+    type Headers = Vec<(String, String)>;
+
+    /// Answers each URL with a fixed response, or 404 for URLs it doesn't know, and keeps the
+    /// requests it was sent.
+    #[derive(Default)]
+    struct CannedClient {
+        responses: HashMap<String, HttpResponse>,
+        requests: Mutex<Vec<(String, Headers)>>,
+    }
+
+    impl CannedClient {
+        fn respond(mut self, url: &str, response: HttpResponse) -> Self {
+            self.responses.insert(url.to_string(), response);
+            self
+        }
+
+        fn requested_urls(&self) -> Vec<String> {
+            self.requests
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(url, _)| url.clone())
+                .collect()
+        }
+
+        fn request_header(&self, index: usize, name: &str) -> Option<String> {
+            self.requests.lock().unwrap()[index]
+                .1
+                .iter()
+                .find(|(header, _)| header == name)
+                .map(|(_, value)| value.clone())
+        }
+    }
+
+    impl HttpClient for CannedClient {
+        fn get(&self, url: &str, headers: &[(&str, &str)]) -> Result<HttpResponse> {
+            self.requests.lock().unwrap().push((
+                url.to_string(),
+                headers
+                    .iter()
+                    .map(|(name, value)| (name.to_string(), value.to_string()))
+                    .collect(),
+            ));
+            Ok(self.responses.get(url).cloned().unwrap_or(HttpResponse {
+                status: 404,
+                ..Default::default()
+            }))
+        }
+    }
+
+    fn release_json(tag_name: &str, prerelease: bool) -> serde_json::Value {
+        serde_json::json!({
+            "name": tag_name,
+            "tag_name": tag_name,
+            "prerelease": prerelease,
+            "draft": false,
+            "body": null,
+            "assets": [],
+        })
+    }
+
+    fn page(releases: &[serde_json::Value], headers: &[(&str, &str)]) -> HttpResponse {
+        HttpResponse {
+            status: 200,
+            headers: headers
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            body: serde_json::to_vec(releases).unwrap(),
+        }
+    }
+
+    fn rate_limited(status: u16, remaining: &str) -> HttpResponse {
+        let reset = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            + 90;
+        HttpResponse {
+            status,
+            headers: vec![
+                ("x-ratelimit-remaining".to_string(), remaining.to_string()),
+                ("x-ratelimit-reset".to_string(), reset.to_string()),
+            ],
+            body: vec![],
+        }
+    }
+
+    fn fetch(client: &CannedClient, etag: Option<&str>) -> Result<Option<Vec<GithubRelease>>> {
+        let access = GithubAccess::default();
+        Ok(
+            fetch_releases(client, &access, API_URL, "owner", "repo", etag)?
+                .map(|(releases, _)| releases),
+        )
+    }
+
+    #[test]
+    fn next_page_url_reads_the_next_link() {
+        assert_eq!(
+            next_page_url(
+                r#"<https://api.github.com/x?page=1>; rel="prev", <https://api.github.com/x?page=3>; rel="next", <https://api.github.com/x?page=9>; rel="last""#
+            )
+            .as_deref(),
+            Some("https://api.github.com/x?page=3")
+        );
+        assert_eq!(
+            next_page_url(r#"<https://api.github.com/x?page=1>; rel="first""#),
+            None
+        );
+        assert_eq!(next_page_url(""), None);
+    }
+
+    #[test]
+    fn releases_are_gathered_from_every_page() {
+        let client = CannedClient::default()
+            .respond(
+                FIRST_PAGE,
+                page(
+                    &[release_json("v3", true), release_json("v2", false)],
+                    &[
+                        ("etag", "\"first\""),
+                        ("link", r#"<http://mock.test/page2>; rel="next""#),
+                    ],
+                ),
+            )
+            .respond(
+                "http://mock.test/page2",
+                page(
+                    &[release_json("v1", false)],
+                    &[
+                        ("etag", "\"second\""),
+                        ("link", r#"<http://mock.test/page1>; rel="prev""#),
+                    ],
+                ),
+            );
+        let (releases, etag) = fetch_releases(
+            &client,
+            &GithubAccess::default(),
+            API_URL,
+            "owner",
+            "repo",
+            Some("\"old\""),
+        )
+        .unwrap()
+        .unwrap();
+        let tags: Vec<&str> = releases.iter().map(|r| r.tag_name.as_str()).collect();
+        assert_eq!(tags, ["v3", "v2", "v1"]);
+        assert!(releases[1].latest);
+        assert_eq!(etag.as_deref(), Some("\"first\""));
+        assert_eq!(
+            client.requested_urls(),
+            [FIRST_PAGE, "http://mock.test/page2"]
+        );
+        // Only the first page is conditional, since its ETag is the one kept.
+        assert_eq!(
+            client.request_header(0, "if-none-match").as_deref(),
+            Some("\"old\"")
+        );
+        assert_eq!(client.request_header(1, "if-none-match"), None);
+    }
+
+    #[test]
+    fn pages_stop_at_the_limit() {
+        let client = CannedClient::default().respond(
+            FIRST_PAGE,
+            page(
+                &[release_json("v1", false)],
+                &[("link", &format!("<{FIRST_PAGE}>; rel=\"next\""))],
+            ),
+        );
+        let releases = fetch(&client, None).unwrap().unwrap();
+        assert_eq!(releases.len(), MAX_RELEASE_PAGES);
+        assert_eq!(client.requested_urls().len(), MAX_RELEASE_PAGES);
+    }
+
+    #[test]
+    fn pages_stop_at_the_release_limit() {
+        let client = CannedClient::default().respond(
+            "http://mock.test/repos/owner/repo/releases?per_page=2",
+            page(
+                &[release_json("v3", false), release_json("v2", false)],
+                &[("link", &format!("<{FIRST_PAGE}>; rel=\"next\""))],
+            ),
+        );
+        let access = GithubAccess {
+            max_releases: Some(2),
+            ..Default::default()
+        };
+        let (releases, _) = fetch_releases(&client, &access, API_URL, "owner", "repo", None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(releases.len(), 2);
+        assert_eq!(client.requested_urls().len(), 1);
+    }
+
+    #[test]
+    fn the_mirror_is_tried_when_github_fails() {
+        let mirror_page = "http://mirror.test/repos/owner/repo/releases?per_page=100";
+        let client = std::sync::Arc::new(
+            CannedClient::default().respond(mirror_page, page(&[release_json("v1", false)], &[])),
+        );
+        let mut network = Network::with_client(client.clone());
+        network.github = GithubAccess {
+            token: Some("token".to_string()),
+            mirror: Some(MirrorSettings {
+                url: "http://mirror.test".to_string(),
+                prefer_mirror: false,
+            }),
+            ..Default::default()
+        };
+        let releases = get_releases(&network, "owner", "repo").unwrap();
+        assert_eq!(releases[0].tag_name, "v1");
+        assert_eq!(
+            client.requested_urls(),
+            [
+                format!("{GITHUB_API_URL}/repos/owner/repo/releases?per_page=100"),
+                mirror_page.to_string(),
+            ]
+        );
+        // The token is only sent to GitHub.
+        assert_eq!(
+            client.request_header(0, "authorization").as_deref(),
+            Some("Bearer token")
+        );
+        assert_eq!(client.request_header(1, "authorization"), None);
+    }
+
+    #[test]
+    fn access_is_read_from_the_settings() {
+        let settings = Settings {
+            github_token: " token ".to_string(),
+            max_releases: 0,
+            mirror: MirrorSettings {
+                url: " http://mirror.test/ ".to_string(),
+                prefer_mirror: false,
+            },
+            ..Default::default()
+        };
+        let access = GithubAccess::new(&settings);
+        assert_eq!(access.auth_token().as_deref(), Some("token"));
+        assert_eq!(access.max_releases, None);
+        assert_eq!(access.mirror.unwrap().url, "http://mirror.test");
+    }
+
+    #[test]
+    fn unchanged_releases_are_not_returned() {
+        let client = CannedClient::default().respond(
+            FIRST_PAGE,
+            HttpResponse {
+                status: STATUS_NOT_MODIFIED,
+                ..Default::default()
+            },
+        );
+        assert!(fetch(&client, Some("\"same\"")).unwrap().is_none());
+    }
+
+    #[test]
+    fn rate_limits_say_when_they_reset() {
+        for status in [STATUS_FORBIDDEN, STATUS_TOO_MANY_REQUESTS] {
+            let client = CannedClient::default().respond(FIRST_PAGE, rate_limited(status, "0"));
+            let error = fetch(&client, None).unwrap_err().to_string();
+            assert!(
+                error.starts_with("GitHub API rate limit reached. It resets in 2 minute(s)."),
+                "{error}"
+            );
+        }
+    }
+
+    #[test]
+    fn forbidden_with_requests_left_is_not_a_rate_limit() {
+        let client =
+            CannedClient::default().respond(FIRST_PAGE, rate_limited(STATUS_FORBIDDEN, "12"));
+        assert_eq!(
+            fetch(&client, None).unwrap_err().to_string(),
+            "Failed to fetch releases: 403"
+        );
+    }
+
+    #[test]
+    fn a_failed_later_page_fails_the_fetch() {
+        let client = CannedClient::default().respond(
+            FIRST_PAGE,
+            page(
+                &[release_json("v1", false)],
+                &[("link", r#"<http://mock.test/missing>; rel="next""#)],
+            ),
+        );
+        assert_eq!(
+            fetch(&client, None).unwrap_err().to_string(),
+            "Failed to fetch releases: 404"
+        );
+    }
+
+    #[test]
+    fn releases_without_a_stable_one_are_an_error() {
+        let client =
+            CannedClient::default().respond(FIRST_PAGE, page(&[release_json("v2", true)], &[]));
+        assert!(fetch(&client, None).is_err());
+    }
+
+    fn release_with_assets(name: &str, asset_names: &[&str]) -> GithubRelease {
+        GithubRelease {
+            name: name.to_string(),
+            tag_name: name.to_string(),
+            assets: asset_names
+                .iter()
+                .map(|asset_name| GithubReleaseAsset {
+                    name: asset_name.to_string(),
+                    browser_download_url: format!("{GITHUB_DOWNLOAD_URL}/{asset_name}"),
+                    digest: None,
+                    size: None,
+                    local_path: None,
+                })
+                .collect(),
+            prerelease: false,
+            draft: false,
+            body: None,
+            latest: false,
+        }
+    }
+
+    fn driver_station_asset_name(release: &GithubRelease) -> Option<&str> {
+        release.driver_station_asset().map(|a| a.name.as_str())
+    }
+
+    #[test]
+    fn ramdisk_prefers_zip() {
+        let release = release_with_assets(
+            "v1",
+            &["ds-ramdisk.tar.gz", "ds-ramdisk.zip", "ds-card.img.xz"],
+        );
+        assert_eq!(driver_station_asset_name(&release), Some("ds-ramdisk.zip"));
+        let release = release_with_assets("v1", &["ds-ramdisk.tar.gz", "ds-ramdisk.tar.zst"]);
+        assert_eq!(
+            driver_station_asset_name(&release),
+            Some("ds-ramdisk.tar.zst")
+        );
+    }
+
+    #[test]
+    fn disk_images_are_used_without_a_ramdisk() {
+        let release = release_with_assets("v1", &["ds-card.img.xz", "gss-v01.00-v1.uf2"]);
+        assert_eq!(driver_station_asset_name(&release), Some("ds-card.img.xz"));
+        let release = release_with_assets("v1", &["card.img", "ds-ramdisk.rar"]);
+        assert_eq!(driver_station_asset_name(&release), None);
+    }
+
+    #[test]
+    fn single_team_builds_are_found_by_name() {
+        assert!(release_with_assets("v1-demo", &["ds-ramdisk.zip"]).is_single_team_variant());
+        assert!(release_with_assets("v1", &["ds-single-team.img"]).is_single_team_variant());
+        assert!(!release_with_assets("v1", &["ds-ramdisk.zip"]).is_single_team_variant());
+    }
+
+    #[test]
+    fn unused_demo_assets_are_ignored() {
+        let release = release_with_assets("v1", &["ds-ramdisk.zip", "ds-ramdisk-demo.zip"]);
+        assert!(!release.is_single_team_variant());
+    }
+}
@@ -0,0 +1,239 @@
+//! Development builds from GitHub Actions, so firmware developers can try a CI build before it
+//! is tagged. Each recent successful workflow run is offered as a prerelease whose assets are the
+//! run's artifacts. CI is expected to upload each file as an artifact named after the file, like
+//! `ds-ramdisk.zip` or `gss-rev3.uf2`, so pages find them the same way as release assets.
+//!
+//! GitHub only serves artifacts to signed-in users, so these need an access token.
+
+use crate::settings::SoftwareRepo;
+use crate::utils::file_download::DownloadProgress;
+use crate::utils::github::{
+    GITHUB_API_URL, GithubAccess, GithubRelease, GithubReleaseAsset, ReleaseChannel,
+};
+use crate::utils::http::{HttpClient, Network};
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, de::DeserializeOwned};
+use std::path::Path;
+
+/// Starts the tag of every development build, followed by the workflow run ID.
+const TAG_PREFIX: &str = "ci-run-";
+/// Most recent successful workflow runs offered. Each one costs an API request to list its
+/// artifacts.
+const MAX_RUNS: usize = 10;
+
+#[derive(Deserialize)]
+struct WorkflowRuns {
+    workflow_runs: Vec<WorkflowRun>,
+}
+
+#[derive(Deserialize)]
+struct WorkflowRun {
+    id: u64,
+    run_number: u64,
+    #[serde(default)]
+    head_branch: Option<String>,
+    head_sha: String,
+    /// Usually the commit message's first line.
+    #[serde(default)]
+    display_title: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Artifacts {
+    artifacts: Vec<Artifact>,
+}
+
+#[derive(Deserialize)]
+struct Artifact {
+    name: String,
+    archive_download_url: String,
+    expired: bool,
+}
+
+pub fn is_development_build(release: &GithubRelease) -> bool {
+    release.tag_name.starts_with(TAG_PREFIX)
+}
+
+/// Artifact downloads need the token and arrive zipped, unlike release assets.
+pub fn is_artifact_url(url: &str) -> bool {
+    url.contains("/actions/artifacts/")
+}
+
+/// Puts the development builds in front of `releases` when the development channel is picked.
+/// Offline bundles have no builds to add. A failure to list them is logged instead of hiding
+/// the releases that were found.
+pub fn add_development_builds(
+    network: &Network,
+    releases: &mut Vec<GithubRelease>,
+    channel: ReleaseChannel,
+    offline_source: Option<&Path>,
+    repo: &SoftwareRepo,
+) {
+    if channel != ReleaseChannel::Development || offline_source.is_some() {
+        return;
+    }
+    match get_development_builds(network, &repo.owner, &repo.name) {
+        Ok(builds) => {
+            log::info!("Found {} development builds of {}", builds.len(), repo);
+            releases.splice(0..0, builds);
+        }
+        Err(e) => log::warn!("Could not list development builds of {repo}: {e:#}"),
+    }
+}
+
+pub fn get_development_builds(
+    network: &Network,
+    repo_owner: &str,
+    repo_name: &str,
+) -> Result<Vec<GithubRelease>> {
+    fetch_development_builds(
+        network.client.as_ref(),
+        &network.github,
+        GITHUB_API_URL,
+        repo_owner,
+        repo_name,
+    )
+}
+
+/// [`get_development_builds`] with the client and API server passed in, so it can be run
+/// against a mock server or a canned client.
+pub fn fetch_development_builds(
+    client: &dyn HttpClient,
+    access: &GithubAccess,
+    api_base_url: &str,
+    repo_owner: &str,
+    repo_name: &str,
+) -> Result<Vec<GithubRelease>> {
+    let authorization = authorization(access)?;
+    let headers = [("authorization", authorization.as_str())];
+    let actions_url = format!(
+        "{}/repos/{}/{}/actions",
+        api_base_url.trim_end_matches('/'),
+        repo_owner,
+        repo_name
+    );
+    let runs: WorkflowRuns = get_json(
+        client,
+        &format!("{actions_url}/runs?status=success&per_page={MAX_RUNS}"),
+        &headers,
+    )?;
+    let mut builds = vec![];
+    for run in runs.workflow_runs {
+        let artifacts: Artifacts = get_json(
+            client,
+            &format!("{actions_url}/runs/{}/artifacts", run.id),
+            &headers,
+        )?;
+        let assets: Vec<GithubReleaseAsset> = artifacts
+            .artifacts
+            .into_iter()
+            .filter(|artifact| !artifact.expired)
+            .map(|artifact| GithubReleaseAsset {
+                name: artifact.name,
+                browser_download_url: artifact.archive_download_url,
+                digest: None,
+                // GitHub lists the size of the zip, not of the file inside.
+                size: None,
+                local_path: None,
+            })
+            .collect();
+        if assets.is_empty() {
+            continue;
+        }
+        let commit = &run.head_sha[..run.head_sha.len().min(7)];
+        builds.push(GithubRelease {
+            name: match run.head_branch {
+                Some(branch) => format!("Build {} ({branch} {commit})", run.run_number),
+                None => format!("Build {} ({commit})", run.run_number),
+            },
+            tag_name: format!("{TAG_PREFIX}{}", run.id),
+            assets,
+            prerelease: true,
+            draft: false,
+            body: run.display_title,
+            latest: false,
+        });
+    }
+    Ok(builds)
+}
+
+/// Downloads an artifact and unpacks the file inside it to `dest_path`. An artifact with more
+/// than one file must contain one named like the artifact.
+pub fn download_artifact(
+    network: &Network,
+    asset: &GithubReleaseAsset,
+    dest_path: &Path,
+    mut on_progress: impl FnMut(&DownloadProgress),
+) -> Result<()> {
+    let authorization = authorization(&network.github)?;
+    let response = network.client.get(
+        &asset.browser_download_url,
+        &[("authorization", authorization.as_str())],
+    )?;
+    if !response.is_success() {
+        bail!(
+            "Failed to download artifact {}: {}",
+            asset.name,
+            response.status
+        );
+    }
+    let bytes_total = response.body.len() as u64;
+    on_progress(&DownloadProgress {
+        bytes_done: bytes_total,
+        bytes_total,
+    });
+
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(response.body))
+        .with_context(|| format!("Artifact {} is not a zip file", asset.name))?;
+    let file_names: Vec<String> = archive
+        .file_names()
+        .filter(|name| !name.ends_with('/'))
+        .map(str::to_string)
+        .collect();
+    let inner_name = match file_names.iter().find(|name| **name == asset.name) {
+        Some(name) => name.clone(),
+        None if file_names.len() == 1 => file_names[0].clone(),
+        None => bail!(
+            "Artifact {} holds {} files and none of them is named {}",
+            asset.name,
+            file_names.len(),
+            asset.name
+        ),
+    };
+    if let Some(parent) = dest_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    // Unpacked beside the destination first, so a failed download never looks cached.
+    let partial_path = dest_path.with_extension("partial");
+    let unpacked = (|| -> Result<()> {
+        let mut entry = archive.by_name(&inner_name)?;
+        let mut file = std::fs::File::create(&partial_path)?;
+        std::io::copy(&mut entry, &mut file)?;
+        Ok(())
+    })();
+    if let Err(e) = unpacked {
+        let _ = std::fs::remove_file(&partial_path);
+        return Err(e.context(format!("Failed to unpack artifact {}", asset.name)));
+    }
+    std::fs::rename(&partial_path, dest_path)
+        .with_context(|| format!("Failed to write {:?}", dest_path))
+}
+
+fn authorization(access: &GithubAccess) -> Result<String> {
+    let token = access
+        .auth_token()
+        .context("Development builds need a GitHub access token. Add one in Settings.")?;
+    Ok(format!("Bearer {token}"))
+}
+
+fn get_json<T: DeserializeOwned>(
+    client: &dyn HttpClient,
+    url: &str,
+    headers: &[(&str, &str)],
+) -> Result<T> {
+    let response = client.get(url, headers)?;
+    if !response.is_success() {
+        bail!("GitHub returned {} for {}", response.status, url);
+    }
+    response.json()
+}
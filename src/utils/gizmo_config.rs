@@ -0,0 +1,168 @@
+use crate::i18n::tr;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Name of the configuration file the driver station image reads at boot.
+pub const GIZMO_CONFIG_FILE: &str = "gsscfg.json";
+
+/// Longest SSID 802.11 allows, in bytes.
+const MAX_SSID_BYTES: usize = 32;
+/// Channels the Gizmo's 2.4 GHz radio can use everywhere BEST runs.
+pub const WIFI_CHANNELS: std::ops::RangeInclusive<u8> = 1..=11;
+/// Network keys shorter than this are accepted, but are easy to guess at a crowded event.
+const STRONG_PSK_LENGTH: usize = 12;
+
+/// Network and field settings shared by every card in a batch.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FieldSettings {
+    /// Name of the team network. Empty leaves the name the driver station picks from its
+    /// team number.
+    pub network_ssid: String,
+    /// WPA2 passphrase for the team networks.
+    pub network_psk: String,
+    /// Address of the field management server.
+    pub server_ip: String,
+    /// Wi-Fi channel the field runs on.
+    pub wifi_channel: u8,
+}
+
+impl Default for FieldSettings {
+    fn default() -> Self {
+        Self {
+            network_ssid: String::new(),
+            network_psk: String::new(),
+            server_ip: "10.0.0.2".to_string(),
+            wifi_channel: 1,
+        }
+    }
+}
+
+impl FieldSettings {
+    /// Returns why these settings can't be written to a card, or `None` if they are valid.
+    pub fn problem(&self) -> Option<String> {
+        if self.network_ssid.len() > MAX_SSID_BYTES {
+            return Some(tr!("network-name-too-long"));
+        }
+        if self.network_ssid.trim() != self.network_ssid {
+            return Some(tr!("network-name-spaces"));
+        }
+        let psk_length = self.network_psk.chars().count();
+        if !self.network_psk.is_ascii() || !(8..=63).contains(&psk_length) {
+            return Some(tr!("network-key-invalid"));
+        }
+        if self.server_ip.trim().parse::<std::net::Ipv4Addr>().is_err() {
+            return Some(tr!("network-server-invalid"));
+        }
+        if !WIFI_CHANNELS.contains(&self.wifi_channel) {
+            return Some(tr!("network-channel-invalid"));
+        }
+        None
+    }
+
+    /// Returns why the network key is easy to guess, for settings that are valid but weak.
+    pub fn psk_warning(&self) -> Option<String> {
+        let psk = &self.network_psk;
+        if psk.chars().count() < STRONG_PSK_LENGTH {
+            return Some(tr!("network-key-short"));
+        }
+        let all_digits = psk.chars().all(|c| c.is_ascii_digit());
+        let all_letters = psk.chars().all(|c| c.is_ascii_alphabetic());
+        if all_digits || all_letters {
+            return Some(tr!("network-key-simple"));
+        }
+        None
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct GizmoConfig<'a> {
+    team: u32,
+    #[serde(rename = "NetSSID", skip_serializing_if = "str::is_empty")]
+    net_ssid: &'a str,
+    #[serde(rename = "NetPSK")]
+    net_psk: &'a str,
+    #[serde(rename = "ServerIP")]
+    server_ip: &'a str,
+    field_channel: u8,
+}
+
+/// Writes the configuration for one team to the root of a driver station card.
+pub fn write_gizmo_config(
+    card_root: &Path,
+    team_number: &str,
+    settings: &FieldSettings,
+) -> Result<()> {
+    let config = GizmoConfig {
+        team: team_number
+            .parse()
+            .with_context(|| format!("Invalid team number {team_number:?}"))?,
+        net_ssid: &settings.network_ssid,
+        net_psk: &settings.network_psk,
+        server_ip: settings.server_ip.trim(),
+        field_channel: settings.wifi_channel,
+    };
+    let path = card_root.join(GIZMO_CONFIG_FILE);
+    std::fs::write(&path, serde_json::to_string_pretty(&config)?)
+        .with_context(|| format!("Failed to write {:?}", path))
+}
+
+#[derive(Deserialize)]
+struct ConfiguredTeam {
+    #[serde(rename = "Team")]
+    team: u32,
+}
+
+/// Returns the team number in the configuration already on a card, if there is one.
+pub fn read_configured_team(card_root: &Path) -> Option<u32> {
+    let contents = std::fs::read_to_string(card_root.join(GIZMO_CONFIG_FILE)).ok()?;
+    serde_json::from_str::<ConfiguredTeam>(&contents)
+        .ok()
+        .map(|config| config.team)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ConfiguredFieldSettings {
+    #[serde(default, rename = "NetSSID")]
+    net_ssid: String,
+    #[serde(rename = "NetPSK")]
+    net_psk: String,
+    #[serde(rename = "ServerIP")]
+    server_ip: String,
+    field_channel: u8,
+}
+
+/// Returns the field settings in the configuration already on a card, if there is one.
+pub fn read_field_settings(card_root: &Path) -> Option<FieldSettings> {
+    let contents = std::fs::read_to_string(card_root.join(GIZMO_CONFIG_FILE)).ok()?;
+    serde_json::from_str::<ConfiguredFieldSettings>(&contents)
+        .ok()
+        .map(|config| FieldSettings {
+            network_ssid: config.net_ssid,
+            network_psk: config.net_psk,
+            server_ip: config.server_ip,
+            wifi_channel: config.field_channel,
+        })
+}
+
+/// Replaces the network and field settings in the configuration already on a card. The team
+/// number and anything else the driver station keeps in the file are left as they are.
+pub fn update_field_settings(card_root: &Path, settings: &FieldSettings) -> Result<()> {
+    let path = card_root.join(GIZMO_CONFIG_FILE);
+    let contents =
+        std::fs::read_to_string(&path).with_context(|| format!("Failed to read {:?}", path))?;
+    let mut config: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_str(&contents).with_context(|| format!("Could not parse {:?}", path))?;
+    if settings.network_ssid.is_empty() {
+        config.remove("NetSSID");
+    } else {
+        config.insert("NetSSID".to_string(), settings.network_ssid.clone().into());
+    }
+    config.insert("NetPSK".to_string(), settings.network_psk.clone().into());
+    config.insert("ServerIP".to_string(), settings.server_ip.trim().into());
+    config.insert("FieldChannel".to_string(), settings.wifi_channel.into());
+    std::fs::write(&path, serde_json::to_string_pretty(&config)?)
+        .with_context(|| format!("Failed to write {:?}", path))
+}
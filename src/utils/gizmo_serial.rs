@@ -0,0 +1,142 @@
+use crate::utils::shell::Command;
+use anyhow::{Context, Result, anyhow};
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const QUERY_TIMEOUT: Duration = Duration::from_secs(3);
+const PORT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Sent to a running system processor to ask for its firmware version. The firmware answers with
+/// a line like "version v1.2.3".
+const VERSION_QUERY: &[u8] = b"version\n";
+
+#[cfg(target_os = "windows")]
+pub fn list_serial_ports() -> Result<Vec<PathBuf>> {
+    let output = Command::powershell("[System.IO.Ports.SerialPort]::GetPortNames()")
+        .run()
+        .with_context(|| "Listing serial ports failed")?;
+    Ok(String::from_utf8(output.stdout)?
+        .lines()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| PathBuf::from(format!(r"\\.\{name}")))
+        .collect())
+}
+
+#[cfg(target_os = "linux")]
+pub fn list_serial_ports() -> Result<Vec<PathBuf>> {
+    let mut ports: Vec<PathBuf> = std::fs::read_dir("/dev")?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with("ttyACM"))
+        })
+        .collect();
+    ports.sort();
+    Ok(ports)
+}
+
+#[cfg(target_os = "linux")]
+fn configure_port(port: &Path) -> Result<()> {
+    // Without raw mode, the terminal driver echoes the firmware's output back to it.
+    Command::new("stty")
+        .arg("-F")
+        .arg(port)
+        .args(["raw", "-echo"])
+        .run()
+        .with_context(|| "Configuring serial port failed.")?;
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn configure_port(_port: &Path) -> Result<()> {
+    Ok(())
+}
+
+/// Waits for a serial port that is not in `known_ports` to appear, such as a board that is
+/// rebooting into newly flashed firmware.
+pub fn wait_for_new_serial_port(known_ports: &[PathBuf], timeout: Duration) -> Result<PathBuf> {
+    let start = std::time::Instant::now();
+    while start.elapsed() < timeout {
+        if let Some(port) = list_serial_ports()?
+            .into_iter()
+            .find(|port| !known_ports.contains(port))
+        {
+            return Ok(port);
+        }
+        std::thread::sleep(PORT_POLL_INTERVAL);
+    }
+    Err(anyhow!(
+        "The system processor did not reconnect within {} seconds.",
+        timeout.as_secs()
+    ))
+}
+
+/// Reboots a running RP2040 into its UF2 bootloader. The Pico SDK's USB serial driver treats
+/// the port being opened at 1200 baud as a request to reset into BOOTSEL mode.
+#[cfg(target_os = "linux")]
+pub fn reset_to_bootloader(port: &Path) -> Result<()> {
+    Command::new("stty")
+        .arg("-F")
+        .arg(port)
+        .arg("1200")
+        .run()
+        .with_context(|| "Resetting the board into its bootloader failed.")?;
+    Ok(())
+}
+
+/// Reboots a running RP2040 into its UF2 bootloader. The Pico SDK's USB serial driver treats
+/// the port being opened at 1200 baud as a request to reset into BOOTSEL mode.
+#[cfg(target_os = "windows")]
+pub fn reset_to_bootloader(port: &Path) -> Result<()> {
+    let port_str = port
+        .to_str()
+        .ok_or(anyhow!("Failed to convert port path to string."))?
+        .trim_start_matches(r"\\.\");
+    Command::new("mode")
+        .args([format!("{port_str}:"), "BAUD=1200".to_string()])
+        .run()
+        .with_context(|| "Resetting the board into its bootloader failed.")?;
+    Ok(())
+}
+
+/// Asks a running system processor for its firmware version over USB serial.
+pub fn query_firmware_version(port: &Path) -> Result<String> {
+    configure_port(port)?;
+    let mut file = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(port)
+        .with_context(|| format!("Could not open serial port {}", port.display()))?;
+    let reader = file.try_clone()?;
+    file.write_all(VERSION_QUERY)?;
+
+    // Reads block until the device sends something, so they happen on their own thread. If the
+    // device never answers, the thread is left waiting until the port is unplugged.
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        for line in std::io::BufReader::new(reader).lines() {
+            let Ok(line) = line else { break };
+            if let Some(version) = parse_version_line(&line) {
+                let _ = tx.send(version);
+                break;
+            }
+        }
+    });
+    rx.recv_timeout(QUERY_TIMEOUT).map_err(|_| {
+        anyhow!(
+            "The system processor on {} did not report a firmware version. Make sure it is powered on and running.",
+            port.display()
+        )
+    })
+}
+
+fn parse_version_line(line: &str) -> Option<String> {
+    let pattern = regex::Regex::new(r"^\s*version[:\s]+(\S+)\s*$").ok()?;
+    pattern
+        .captures(line)
+        .map(|captures| captures[1].to_string())
+}
@@ -0,0 +1,242 @@
+//! The one place the wizard makes HTTP requests.
+//!
+//! Functions that make requests take a [`Network`], or an [`HttpClient`] directly, so release
+//! lookups and downloads can be run against a local server or a canned client instead of GitHub.
+
+use crate::settings::{NetworkSettings, Settings};
+use crate::utils::file_download::RateLimiter;
+use crate::utils::github::GithubAccess;
+use anyhow::{Context, Result, bail};
+use std::io::Read;
+use std::sync::Arc;
+
+/// Sent with every request. GitHub's API rejects requests without one.
+pub const USER_AGENT: &str = "rust-web-api-client";
+const READ_CHUNK_SIZE: usize = 64 * 1024;
+
+/// A response with its body read in full.
+#[derive(Debug, Clone, Default)]
+pub struct HttpResponse {
+    pub status: u16,
+    /// Header names are lowercase.
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+
+    pub fn json<T: serde::de::DeserializeOwned>(&self) -> Result<T> {
+        Ok(serde_json::from_slice(&self.body)?)
+    }
+}
+
+/// A response whose body is read as it arrives, so large downloads aren't held in memory.
+pub struct HttpStream {
+    pub status: u16,
+    /// Header names are lowercase.
+    pub headers: Vec<(String, String)>,
+    /// Not held to the download rate limit. Readers pace themselves with the [`RateLimiter`].
+    pub body: Box<dyn Read + Send>,
+}
+
+impl HttpStream {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+}
+
+fn find_header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    let name = name.to_lowercase();
+    headers
+        .iter()
+        .find(|(header, _)| *header == name)
+        .map(|(_, value)| value.as_str())
+}
+
+pub trait HttpClient: Send + Sync {
+    /// Sends a GET request with the given extra headers. Only failures to get a response are
+    /// errors. Error statuses are returned for the caller to handle.
+    fn get(&self, url: &str, headers: &[(&str, &str)]) -> Result<HttpResponse>;
+
+    /// Like [`HttpClient::get`], but returns as soon as the headers arrive. By default the body
+    /// is read in full first, which is fine for clients that already have it.
+    fn get_stream(&self, url: &str, headers: &[(&str, &str)]) -> Result<HttpStream> {
+        let response = self.get(url, headers)?;
+        Ok(HttpStream {
+            status: response.status,
+            headers: response.headers,
+            body: Box::new(std::io::Cursor::new(response.body)),
+        })
+    }
+}
+
+/// Sends requests over the network.
+pub struct ReqwestClient {
+    client: reqwest::blocking::Client,
+    rate_limiter: Arc<RateLimiter>,
+}
+
+impl ReqwestClient {
+    pub fn new(rate_limiter: Arc<RateLimiter>) -> Self {
+        Self {
+            client: reqwest::blocking::Client::new(),
+            rate_limiter,
+        }
+    }
+
+    /// A client that goes through the configured proxy and trusts the extra certificates.
+    /// Without a proxy URL, the system's proxy settings are used.
+    pub fn with_settings(
+        settings: &NetworkSettings,
+        rate_limiter: Arc<RateLimiter>,
+    ) -> Result<Self> {
+        let mut builder = reqwest::blocking::Client::builder();
+        let proxy_url = settings.proxy_url.trim();
+        if !proxy_url.is_empty() {
+            let proxy = reqwest::Proxy::all(proxy_url)
+                .with_context(|| format!("Invalid proxy URL {proxy_url:?}"))?;
+            builder = builder.proxy(proxy);
+        }
+        if let Some(ref path) = settings.ca_bundle_path {
+            let pem = std::fs::read(path).with_context(|| format!("Failed to read {:?}", path))?;
+            let certificates = reqwest::Certificate::from_pem_bundle(&pem)
+                .with_context(|| format!("Failed to read certificates from {:?}", path))?;
+            if certificates.is_empty() {
+                bail!("No certificates found in {:?}", path);
+            }
+            for certificate in certificates {
+                builder = builder.add_root_certificate(certificate);
+            }
+        }
+        Ok(Self {
+            client: builder
+                .build()
+                .context("Failed to set up the HTTP client.")?,
+            rate_limiter,
+        })
+    }
+
+    /// Sends the request and returns the response with its headers, leaving the body unread.
+    fn send(
+        &self,
+        url: &str,
+        headers: &[(&str, &str)],
+    ) -> Result<(reqwest::blocking::Response, Vec<(String, String)>)> {
+        let mut request = self
+            .client
+            .get(url)
+            .header(reqwest::header::USER_AGENT, USER_AGENT);
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        log::debug!("GET {url}");
+        let response = request.send()?;
+        log::debug!("GET {url} returned {}", response.status());
+        let headers = response
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| {
+                Some((
+                    name.as_str().to_lowercase(),
+                    value.to_str().ok()?.to_string(),
+                ))
+            })
+            .collect();
+        Ok((response, headers))
+    }
+}
+
+impl HttpClient for ReqwestClient {
+    fn get(&self, url: &str, headers: &[(&str, &str)]) -> Result<HttpResponse> {
+        let (response, headers) = self.send(url, headers)?;
+        Ok(HttpResponse {
+            status: response.status().as_u16(),
+            headers,
+            body: read_body(response, &self.rate_limiter)?,
+        })
+    }
+
+    fn get_stream(&self, url: &str, headers: &[(&str, &str)]) -> Result<HttpStream> {
+        let (response, headers) = self.send(url, headers)?;
+        Ok(HttpStream {
+            status: response.status().as_u16(),
+            headers,
+            body: Box::new(response),
+        })
+    }
+}
+
+/// Reads the body a chunk at a time, keeping to the download rate limit.
+fn read_body(
+    mut response: reqwest::blocking::Response,
+    rate_limiter: &RateLimiter,
+) -> Result<Vec<u8>> {
+    let mut body = Vec::with_capacity(response.content_length().unwrap_or(0) as usize);
+    let mut chunk = vec![0; READ_CHUNK_SIZE];
+    loop {
+        let read = response.read(&mut chunk)?;
+        if read == 0 {
+            return Ok(body);
+        }
+        body.extend_from_slice(&chunk[..read]);
+        rate_limiter.pace(read);
+    }
+}
+
+/// The client requests are made with and the settings they follow. Cheap to clone, so each task
+/// takes its own copy and the client's connections are still reused.
+#[derive(Clone)]
+pub struct Network {
+    pub client: Arc<dyn HttpClient>,
+    pub github: GithubAccess,
+    /// How many connections large downloads use at once. One fetches every file in a single
+    /// request.
+    pub download_connections: u32,
+    /// Shared with the client, so changes to the limit apply to downloads already under way.
+    pub rate_limiter: Arc<RateLimiter>,
+}
+
+impl Network {
+    /// Uses everything in `settings` except the proxy and certificates, which can fail to load
+    /// and are applied with [`Network::apply_network_settings`].
+    pub fn new(settings: &Settings) -> Self {
+        let rate_limiter = Arc::new(RateLimiter::new(settings.network.download_limit_mbps));
+        Self {
+            client: Arc::new(ReqwestClient::new(rate_limiter.clone())),
+            github: GithubAccess::new(settings),
+            download_connections: settings.network.download_connections,
+            rate_limiter,
+        }
+    }
+
+    /// Makes requests with `client`, and otherwise the default settings.
+    #[cfg(test)]
+    pub fn with_client(client: Arc<dyn HttpClient>) -> Self {
+        Self {
+            client,
+            github: GithubAccess::default(),
+            download_connections: crate::utils::file_download::DEFAULT_DOWNLOAD_CONNECTIONS,
+            rate_limiter: Arc::default(),
+        }
+    }
+
+    /// Replaces the client with one going through the proxy and trusting the certificates in
+    /// `settings`. If they can't be used, the current client is kept and the error returned.
+    /// Requests already under way finish with the old client.
+    pub fn apply_network_settings(&mut self, settings: &NetworkSettings) -> Result<()> {
+        let client = ReqwestClient::with_settings(settings, self.rate_limiter.clone())?;
+        self.client = Arc::new(client);
+        Ok(())
+    }
+}
@@ -0,0 +1,217 @@
+use crate::utils::archive::Compression;
+use crate::utils::cache::format_size;
+use crate::utils::demo;
+use crate::utils::drive_management::{DriveInfo, RawDisk, open_raw_disk, remount_raw_disk};
+use crate::utils::threads::CancellationToken;
+use anyhow::{Context, Result, bail};
+use sha2::{Digest, Sha256};
+use std::cell::Cell;
+use std::io::{Read, Seek, Write};
+use std::path::Path;
+use std::rc::Rc;
+
+/// Images are copied in chunks this big, a whole number of sectors on any card.
+const CHUNK_SIZE: usize = 4 * 1024 * 1024;
+const SECTOR_SIZE: usize = 512;
+
+/// Returns true if the file is a raw disk image, compressed or not, rather than an archive of
+/// files to copy onto a card.
+pub fn is_disk_image(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    [".img", ".img.gz", ".img.xz", ".img.zst"]
+        .iter()
+        .any(|ext| name.ends_with(ext))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImagePhase {
+    Writing,
+    Verifying,
+}
+
+/// Progress of writing an image to a card, reported after every chunk.
+#[derive(Debug, Clone)]
+pub struct ImageProgress {
+    pub phase: ImagePhase,
+    /// Bytes written to the card, or read back from it while verifying.
+    pub card_bytes: u64,
+    fraction: f32,
+}
+
+impl ImageProgress {
+    pub fn fraction(&self) -> f32 {
+        self.fraction
+    }
+}
+
+/// What was written to a card, so it can be read back and compared.
+struct WrittenImage {
+    bytes: u64,
+    digest: Vec<u8>,
+}
+
+/// Counts the bytes read from the image file. For compressed images, this is the only way to
+/// tell how far along the write is without decompressing the image twice.
+struct CountingReader<R> {
+    inner: R,
+    bytes_read: Rc<Cell<u64>>,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let len = self.inner.read(buf)?;
+        self.bytes_read.set(self.bytes_read.get() + len as u64);
+        Ok(len)
+    }
+}
+
+fn open_image(path: &Path, bytes_read: Rc<Cell<u64>>) -> Result<Box<dyn Read>> {
+    let file =
+        std::fs::File::open(path).with_context(|| format!("Failed to open image {:?}", path))?;
+    let reader = std::io::BufReader::new(CountingReader {
+        inner: file,
+        bytes_read,
+    });
+    Compression::detect(path)?.decompress(reader)
+}
+
+/// Reads until `buffer` is full or the image ends, since decompressors return short reads.
+fn read_chunk(image: &mut impl Read, buffer: &mut [u8]) -> std::io::Result<usize> {
+    let mut len = 0;
+    while len < buffer.len() {
+        match image.read(&mut buffer[len..])? {
+            0 => break,
+            read => len += read,
+        }
+    }
+    Ok(len)
+}
+
+fn write_image(
+    image_path: &Path,
+    disk: &mut RawDisk,
+    cancel: &CancellationToken,
+    on_progress: &impl Fn(&ImageProgress),
+) -> Result<WrittenImage> {
+    let file_bytes = std::fs::metadata(image_path)
+        .with_context(|| format!("Failed to read {:?}", image_path))?
+        .len()
+        .max(1);
+    let bytes_read = Rc::new(Cell::new(0));
+    let mut image = open_image(image_path, bytes_read.clone())?;
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    let mut hasher = Sha256::new();
+    let mut written = 0u64;
+    disk.file.rewind()?;
+    loop {
+        cancel
+            .check()
+            .context("Stopped partway through writing the image")?;
+        let len = read_chunk(&mut image, &mut buffer)
+            .with_context(|| format!("Failed to read image {:?}", image_path))?;
+        if len == 0 {
+            break;
+        }
+        if written + len as u64 > disk.size_bytes {
+            bail!(
+                "{:?} is larger than the card, which holds {}.",
+                image_path,
+                format_size(disk.size_bytes)
+            );
+        }
+        hasher.update(&buffer[..len]);
+        // Disks are written in whole sectors, so a partial last sector is padded with zeros.
+        let padded_len = len.next_multiple_of(SECTOR_SIZE);
+        buffer[len..padded_len].fill(0);
+        disk.file
+            .write_all(&buffer[..padded_len])
+            .with_context(|| format!("Failed to write to {}", disk.device))?;
+        written += len as u64;
+        on_progress(&ImageProgress {
+            phase: ImagePhase::Writing,
+            card_bytes: written,
+            fraction: bytes_read.get() as f32 / file_bytes as f32,
+        });
+    }
+    if written == 0 {
+        bail!("{:?} is empty.", image_path);
+    }
+    disk.file
+        .sync_all()
+        .with_context(|| format!("Failed to finish writing to {}", disk.device))?;
+    Ok(WrittenImage {
+        bytes: written,
+        digest: hasher.finalize().to_vec(),
+    })
+}
+
+/// Reads the image back from the card and checks it matches what was written. Cards that
+/// lie about their size, or are failing, accept writes they don't keep.
+fn verify_image(
+    disk: &mut RawDisk,
+    written: &WrittenImage,
+    on_progress: &impl Fn(&ImageProgress),
+) -> Result<()> {
+    disk.drop_cached_blocks()?;
+    disk.file.rewind()?;
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    let mut hasher = Sha256::new();
+    let mut verified = 0u64;
+    while verified < written.bytes {
+        let len = (written.bytes - verified).min(CHUNK_SIZE as u64) as usize;
+        disk.file
+            .read_exact(&mut buffer[..len.next_multiple_of(SECTOR_SIZE)])
+            .with_context(|| format!("Failed to read back {}", disk.device))?;
+        hasher.update(&buffer[..len]);
+        verified += len as u64;
+        on_progress(&ImageProgress {
+            phase: ImagePhase::Verifying,
+            card_bytes: verified,
+            fraction: verified as f32 / written.bytes as f32,
+        });
+    }
+    if hasher.finalize().as_slice() != written.digest {
+        bail!(
+            "{} doesn't hold the image that was written to it. The card may be failing or smaller than it claims, so consider replacing it.",
+            disk.device
+        );
+    }
+    Ok(())
+}
+
+/// Writes a disk image, optionally gzip, xz, or zstd compressed, over the whole card and reads
+/// it back to check it. Returns the FAT volume from the image's partition table, where files
+/// for the team can be added.
+pub fn write_image_to_drive(
+    drive: &DriveInfo,
+    image_path: &Path,
+    allow_suspicious: bool,
+    cancel: &CancellationToken,
+    on_progress: impl Fn(&ImageProgress),
+) -> Result<DriveInfo> {
+    if demo::demo_mode_enabled() {
+        demo::log_skipped(format_args!("writing {:?} to {drive}", image_path));
+        for phase in [ImagePhase::Writing, ImagePhase::Verifying] {
+            on_progress(&ImageProgress {
+                phase,
+                card_bytes: 0,
+                fraction: 0.0,
+            });
+            demo::simulate_step();
+        }
+        return Ok(drive.clone());
+    }
+    let mut disk = open_raw_disk(drive, allow_suspicious)?;
+    log::info!("Writing {:?} to {}", image_path, disk.device);
+    let written = write_image(image_path, &mut disk, cancel, &on_progress)?;
+    verify_image(&mut disk, &written, &on_progress)?;
+    log::info!(
+        "Verified {} written to {}",
+        format_size(written.bytes),
+        disk.device
+    );
+    remount_raw_disk(disk)
+}
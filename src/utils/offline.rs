@@ -0,0 +1,367 @@
+use crate::utils::cache::is_hash_file;
+use crate::utils::github::{GithubRelease, GithubReleaseAsset};
+use crate::utils::http::Network;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::path::Path;
+
+/// Name of the optional metadata file stored alongside the assets of each release in a bundle.
+pub const RELEASE_METADATA_FILE: &str = "release.json";
+
+/// Fetches releases from the offline bundle if one is in use, or from GitHub otherwise.
+pub fn get_releases_for_source(
+    network: &Network,
+    offline_source: Option<&Path>,
+    repo_owner: &str,
+    repo_name: &str,
+) -> Result<Vec<GithubRelease>> {
+    match offline_source {
+        Some(bundle_dir) => get_local_releases(bundle_dir, repo_owner, repo_name),
+        None => crate::utils::github::get_releases(network, repo_owner, repo_name),
+    }
+}
+
+/// Lists the releases stored in an offline bundle.
+///
+/// Bundles use the same layout as the download cache: `<owner>/<repo>/<release>/<asset>`. Each
+/// release directory may contain a `release.json` with the original GitHub metadata. Without it,
+/// the directory name is used as both the release name and tag.
+pub fn get_local_releases(
+    bundle_dir: &Path,
+    repo_owner: &str,
+    repo_name: &str,
+) -> Result<Vec<GithubRelease>> {
+    let repo_dir = bundle_dir.join(repo_owner).join(repo_name);
+    if !repo_dir.is_dir() {
+        return Ok(vec![]);
+    }
+    let mut releases = vec![];
+    for entry in std::fs::read_dir(&repo_dir)
+        .with_context(|| format!("Could not read offline bundle directory {:?}", repo_dir))?
+    {
+        let release_dir = entry?.path();
+        if release_dir.is_dir() {
+            releases.push(read_local_release(&release_dir)?);
+        }
+    }
+    releases.sort_by(|a, b| compare_release_names(&b.tag_name, &a.tag_name));
+    if let Some(latest) = releases.iter_mut().find(|r| !r.prerelease && !r.draft) {
+        latest.latest = true;
+    }
+    Ok(releases)
+}
+
+fn read_local_release(release_dir: &Path) -> Result<GithubRelease> {
+    let dir_name = release_dir
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or(anyhow::anyhow!(
+            "Invalid release directory {:?}",
+            release_dir
+        ))?
+        .to_string();
+    let metadata_path = release_dir.join(RELEASE_METADATA_FILE);
+    let mut release = if metadata_path.is_file() {
+        let metadata = std::fs::read_to_string(&metadata_path)?;
+        serde_json::from_str::<GithubRelease>(&metadata)
+            .with_context(|| format!("Could not parse {:?}", metadata_path))?
+    } else {
+        GithubRelease {
+            name: dir_name.clone(),
+            tag_name: dir_name,
+            assets: vec![],
+            prerelease: false,
+            draft: false,
+            body: None,
+            latest: false,
+        }
+    };
+
+    // Only offer assets that are actually present in the bundle.
+    let mut assets = vec![];
+    for entry in std::fs::read_dir(release_dir)? {
+        let path = entry?.path();
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        if !path.is_file() || name == RELEASE_METADATA_FILE || is_hash_file(&path) {
+            continue;
+        }
+        let original = release.assets.iter().find(|a| a.name == name);
+        assets.push(GithubReleaseAsset {
+            name: name.to_string(),
+            browser_download_url: original
+                .map(|a| a.browser_download_url.clone())
+                .unwrap_or_default(),
+            digest: original.and_then(|a| a.digest.clone()),
+            size: original.and_then(|a| a.size),
+            local_path: Some(path.clone()),
+        });
+    }
+    assets.sort_by(|a, b| a.name.cmp(&b.name));
+    release.assets = assets;
+    Ok(release)
+}
+
+/// Compares release names so that numeric parts are ordered by value (v1.10 sorts after v1.9).
+/// A prerelease suffix after a hyphen sorts before the release it leads up to, so v1.2.0-rc1
+/// comes before v1.2.0.
+pub fn compare_release_names(a: &str, b: &str) -> Ordering {
+    let mut a_chars = a.chars().peekable();
+    let mut b_chars = b.chars().peekable();
+    loop {
+        match (a_chars.peek().copied(), b_chars.peek().copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some('-')) => return Ordering::Greater,
+            (Some('-'), None) => return Ordering::Less,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let a_num = take_number(&mut a_chars);
+                let b_num = take_number(&mut b_chars);
+                match a_num.cmp(&b_num) {
+                    Ordering::Equal => {}
+                    other => return other,
+                }
+            }
+            (Some(x), Some(y)) => {
+                a_chars.next();
+                b_chars.next();
+                match x.cmp(&y) {
+                    Ordering::Equal => {}
+                    other => return other,
+                }
+            }
+        }
+    }
+}
+
+fn take_number(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) -> u64 {
+    let mut value = 0u64;
+    while let Some(digit) = chars.peek().and_then(|c| c.to_digit(10)) {
+        value = value.saturating_mul(10).saturating_add(digit as u64);
+        chars.next();
+    }
+    value
+}
+
+/// Name of the manifest file written to the root of an exported bundle.
+pub const BUNDLE_MANIFEST_FILE: &str = "manifest.json";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BundleManifest {
+    pub wizard_version: String,
+    pub releases: Vec<BundleManifestEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BundleManifestEntry {
+    pub repo_owner: String,
+    pub repo_name: String,
+    pub release_name: String,
+    pub tag_name: String,
+    pub assets: Vec<String>,
+}
+
+/// A release and the subset of its assets to include in an exported bundle.
+pub struct BundleItem {
+    pub repo_owner: String,
+    pub repo_name: String,
+    pub release: GithubRelease,
+    pub assets: Vec<GithubReleaseAsset>,
+}
+
+/// Downloads the given releases into `dest_dir` using the layout read by [`get_local_releases`]
+/// and writes a manifest describing the bundle. `on_progress` is called before each asset with
+/// the number of assets finished, the total, and the name of the asset being downloaded.
+pub fn export_bundle(
+    network: &Network,
+    items: &[BundleItem],
+    dest_dir: &Path,
+    mut on_progress: impl FnMut(usize, usize, &str),
+) -> Result<BundleManifest> {
+    let total = items.iter().map(|i| i.assets.len()).sum();
+    let mut finished = 0;
+    let mut manifest = BundleManifest {
+        wizard_version: env!("CARGO_PKG_VERSION").to_string(),
+        releases: vec![],
+    };
+    for item in items {
+        for asset in &item.assets {
+            on_progress(finished, total, &asset.name);
+            crate::utils::github::download_versioned_asset(
+                network,
+                asset,
+                &item.repo_owner,
+                &item.repo_name,
+                &item.release,
+                dest_dir,
+            )
+            .with_context(|| format!("Failed to download {}", asset.name))?;
+            finished += 1;
+        }
+
+        let release_dir = dest_dir
+            .join(&item.repo_owner)
+            .join(&item.repo_name)
+            .join(&item.release.name);
+        std::fs::create_dir_all(&release_dir)?;
+        let mut release = item.release.clone();
+        release.assets = item.assets.clone();
+        std::fs::write(
+            release_dir.join(RELEASE_METADATA_FILE),
+            serde_json::to_string_pretty(&release)?,
+        )?;
+
+        manifest.releases.push(BundleManifestEntry {
+            repo_owner: item.repo_owner.clone(),
+            repo_name: item.repo_name.clone(),
+            release_name: item.release.name.clone(),
+            tag_name: item.release.tag_name.clone(),
+            assets: item.assets.iter().map(|a| a.name.clone()).collect(),
+        });
+    }
+    std::fs::write(
+        dest_dir.join(BUNDLE_MANIFEST_FILE),
+        serde_json::to_string_pretty(&manifest)?,
+    )?;
+    on_progress(finished, total, "");
+    Ok(manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_compare_by_value() {
+        assert_eq!(compare_release_names("v1.10", "v1.9"), Ordering::Greater);
+        assert_eq!(compare_release_names("v1.9", "v1.10"), Ordering::Less);
+        assert_eq!(compare_release_names("v2.0.0", "v10.0.0"), Ordering::Less);
+        assert_eq!(compare_release_names("v1.2.3", "v1.2.3"), Ordering::Equal);
+        assert_eq!(compare_release_names("1.2.10", "1.2.2"), Ordering::Greater);
+    }
+
+    #[test]
+    fn longer_versions_are_newer() {
+        assert_eq!(compare_release_names("v1.2.1", "v1.2"), Ordering::Greater);
+        assert_eq!(compare_release_names("v1.2", "v1.2.1"), Ordering::Less);
+    }
+
+    #[test]
+    fn prereleases_come_before_their_release() {
+        assert_eq!(
+            compare_release_names("v1.2.0-rc1", "v1.2.0"),
+            Ordering::Less
+        );
+        assert_eq!(
+            compare_release_names("v1.2.0", "v1.2.0-beta"),
+            Ordering::Greater
+        );
+        assert_eq!(
+            compare_release_names("v1.2.0-rc10", "v1.2.0-rc9"),
+            Ordering::Greater
+        );
+        assert_eq!(
+            compare_release_names("v1.2.0-rc1", "v1.1.9"),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn huge_numbers_do_not_overflow() {
+        assert_eq!(
+            compare_release_names("v99999999999999999999999", "v1"),
+            Ordering::Greater
+        );
+    }
+
+    fn write(path: &Path, contents: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn bundles_list_releases_newest_first() {
+        let bundle = tempfile::tempdir().unwrap();
+        let repo_dir = bundle.path().join("owner").join("repo");
+        for version in ["v1.9", "v1.10", "v1.10-rc1"] {
+            write(&repo_dir.join(version).join("ds-ramdisk.zip"), version);
+        }
+        let releases = get_local_releases(bundle.path(), "owner", "repo").unwrap();
+        let tags: Vec<&str> = releases.iter().map(|r| r.tag_name.as_str()).collect();
+        assert_eq!(tags, ["v1.10", "v1.10-rc1", "v1.9"]);
+        assert!(releases[0].latest);
+        assert!(!releases[1].latest);
+    }
+
+    #[test]
+    fn bundles_only_offer_assets_that_are_present() {
+        let bundle = tempfile::tempdir().unwrap();
+        let release_dir = bundle.path().join("owner").join("repo").join("Release 2");
+        let metadata = serde_json::json!({
+            "name": "Release 2",
+            "tag_name": "v2",
+            "prerelease": true,
+            "draft": false,
+            "assets": [
+                {
+                    "name": "ds-ramdisk.zip",
+                    "browser_download_url": "https://github.com/owner/repo/ds-ramdisk.zip",
+                    "size": 7,
+                },
+                {
+                    "name": "missing.uf2",
+                    "browser_download_url": "https://github.com/owner/repo/missing.uf2",
+                },
+            ],
+        });
+        write(
+            &release_dir.join(RELEASE_METADATA_FILE),
+            &metadata.to_string(),
+        );
+        write(&release_dir.join("ds-ramdisk.zip"), "ramdisk");
+        write(&release_dir.join("extra.txt"), "extra");
+        std::fs::create_dir(release_dir.join("subdir")).unwrap();
+
+        let releases = get_local_releases(bundle.path(), "owner", "repo").unwrap();
+        assert_eq!(releases.len(), 1);
+        let release = &releases[0];
+        assert_eq!(release.tag_name, "v2");
+        assert!(release.prerelease);
+        // A bundle of only prereleases has no latest release.
+        assert!(!release.latest);
+        let names: Vec<&str> = release.assets.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, ["ds-ramdisk.zip", "extra.txt"]);
+        let ramdisk = &release.assets[0];
+        assert_eq!(ramdisk.size, Some(7));
+        assert_eq!(
+            ramdisk.browser_download_url,
+            "https://github.com/owner/repo/ds-ramdisk.zip"
+        );
+        assert_eq!(
+            ramdisk.local_path.as_deref(),
+            Some(release_dir.join("ds-ramdisk.zip").as_path())
+        );
+        assert_eq!(release.assets[1].browser_download_url, "");
+    }
+
+    #[test]
+    fn missing_repositories_have_no_releases() {
+        let bundle = tempfile::tempdir().unwrap();
+        assert!(
+            get_local_releases(bundle.path(), "owner", "repo")
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn broken_metadata_is_an_error() {
+        let bundle = tempfile::tempdir().unwrap();
+        let release_dir = bundle.path().join("owner").join("repo").join("v1");
+        write(&release_dir.join(RELEASE_METADATA_FILE), "{");
+        assert!(get_local_releases(bundle.path(), "owner", "repo").is_err());
+    }
+}
@@ -0,0 +1,113 @@
+//! Turns panics into errors the wizard can show. A panic is written to the log with its
+//! backtrace as it happens, and a panic in a page or its background work comes back as a
+//! [`PanicReport`] error instead of closing the app.
+
+use anyhow::Result;
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::cell::RefCell;
+use std::panic::{AssertUnwindSafe, PanicHookInfo};
+
+thread_local! {
+    /// The last panic on this thread, kept by the hook until [`catch_panic`] picks it up.
+    static LAST_PANIC: RefCell<Option<PanicReport>> = const { RefCell::new(None) };
+}
+
+/// What is known about a panic, for the log and the error dialog.
+#[derive(Debug, Clone)]
+pub struct PanicReport {
+    pub message: String,
+    /// Source file and line, if the panic came from a known place.
+    pub location: Option<String>,
+    /// Empty if the thread isn't known.
+    pub thread: String,
+    /// Empty if the panic wasn't seen by the hook.
+    pub backtrace: String,
+}
+
+impl PanicReport {
+    fn from_hook(info: &PanicHookInfo) -> Self {
+        Self {
+            message: payload_message(info.payload()),
+            location: info
+                .location()
+                .map(|location| format!("{}:{}", location.file(), location.line())),
+            thread: std::thread::current()
+                .name()
+                .unwrap_or("unnamed")
+                .to_string(),
+            backtrace: Backtrace::force_capture().to_string(),
+        }
+    }
+
+    /// Everything support needs to find the cause, as plain text to paste into a message.
+    pub fn details(&self) -> String {
+        let mut details = format!("Panic in thread '{}': {}\n", self.thread, self.message);
+        if let Some(ref location) = self.location {
+            details.push_str(&format!("At {location}\n"));
+        }
+        if !self.backtrace.is_empty() {
+            details.push_str(&format!("Backtrace:\n{}", self.backtrace));
+        }
+        details
+    }
+}
+
+impl std::fmt::Display for PanicReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Internal error: {}", self.message)
+    }
+}
+
+impl std::error::Error for PanicReport {}
+
+/// Logs every panic with its backtrace before the default hook runs, and keeps it for
+/// [`catch_panic`]. Call once at startup.
+pub fn install_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let report = PanicReport::from_hook(info);
+        log::error!("{}", report.details());
+        log::logger().flush();
+        LAST_PANIC.with(|last| *last.borrow_mut() = Some(report));
+        default_hook(info);
+    }));
+}
+
+/// Runs `work`, returning a panic in it as a [`PanicReport`] error.
+pub fn catch_panic<T>(work: impl FnOnce() -> Result<T>) -> Result<T> {
+    std::panic::catch_unwind(AssertUnwindSafe(work))
+        .unwrap_or_else(|payload| Err(take_report(payload.as_ref()).into()))
+}
+
+/// The report the hook kept for the panic on this thread that unwound with `payload`.
+fn take_report(payload: &(dyn Any + Send)) -> PanicReport {
+    LAST_PANIC
+        .with(|last| last.borrow_mut().take())
+        .unwrap_or_else(|| report_from_payload(payload))
+}
+
+/// A report with only what the panic payload says, for panics seen from another thread.
+pub fn report_from_payload(payload: &(dyn Any + Send)) -> PanicReport {
+    PanicReport {
+        message: payload_message(payload),
+        location: None,
+        thread: String::new(),
+        backtrace: String::new(),
+    }
+}
+
+/// Finds the panic behind an error, if it came from one.
+pub fn find_report(error: &anyhow::Error) -> Option<&PanicReport> {
+    error.chain().find_map(|e| e.downcast_ref::<PanicReport>())
+}
+
+fn payload_message(payload: &(dyn Any + Send)) -> String {
+    match payload.downcast_ref::<&'static str>() {
+        Some(s) => s.to_string(),
+        None => match payload.downcast_ref::<String>() {
+            Some(s) => s.clone(),
+            None => "unknown error type".to_string(),
+        },
+    }
+}
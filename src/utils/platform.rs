@@ -0,0 +1,93 @@
+use anyhow::Result;
+
+/// Architecture this copy of the wizard was built for, such as "x86_64" or "aarch64".
+pub const BUILD_ARCH: &str = std::env::consts::ARCH;
+
+/// Returns the architecture of the computer itself, which differs from [`BUILD_ARCH`] when an
+/// x64 build runs under emulation on Windows on Arm.
+#[cfg(target_os = "windows")]
+pub fn host_arch() -> Result<String> {
+    // Emulated processes see an emulated PROCESSOR_ARCHITECTURE, but the identifier still
+    // describes the real CPU.
+    let identifier = std::env::var("PROCESSOR_IDENTIFIER")?;
+    Ok(if identifier.to_uppercase().starts_with("ARM") {
+        "aarch64".to_string()
+    } else {
+        std::env::var("PROCESSOR_ARCHITECTURE")
+            .map(|arch| match arch.as_str() {
+                "AMD64" => "x86_64".to_string(),
+                "ARM64" => "aarch64".to_string(),
+                other => other.to_lowercase(),
+            })
+            .unwrap_or_else(|_| BUILD_ARCH.to_string())
+    })
+}
+
+#[cfg(target_os = "linux")]
+pub fn host_arch() -> Result<String> {
+    let output = crate::utils::shell::Command::new("uname").arg("-m").run()?;
+    Ok(match String::from_utf8(output.stdout)?.trim() {
+        "arm64" => "aarch64".to_string(),
+        other => other.to_string(),
+    })
+}
+
+/// Commands the drive and serial code relies on. Some are missing from minimal installs, or
+/// from the Windows on Arm images some schools use.
+#[cfg(target_os = "windows")]
+const REQUIRED_COMMANDS: [&str; 4] = [
+    "Format-Volume",
+    "Write-VolumeCache",
+    "Get-Partition",
+    "Get-Disk",
+];
+
+#[cfg(target_os = "linux")]
+const REQUIRED_COMMANDS: [&str; 5] = ["mkfs.vfat", "lsblk", "df", "sync", "stty"];
+
+/// Runs a harmless query with the drive tools to check they work for this user, not just that
+/// they are installed.
+#[cfg(target_os = "windows")]
+pub fn check_drive_tools() -> Result<()> {
+    crate::utils::shell::Command::powershell("Get-Disk | Out-Null")
+        .run()
+        .map_err(|e| anyhow::anyhow!("Get-Disk failed: {e:#}"))?;
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+pub fn check_drive_tools() -> Result<()> {
+    crate::utils::udisks::check_available()
+}
+
+/// Returns each command the wizard needs and whether it is available on this computer.
+#[cfg(target_os = "windows")]
+pub fn check_required_commands() -> Result<Vec<(&'static str, bool)>> {
+    // Get-Command fails if any of the names are missing, even with SilentlyContinue on some
+    // PowerShell versions, so the output is read either way.
+    let found = crate::utils::shell::Command::powershell(
+        "Get-Command $env:WIZARD_COMMANDS.Split(',') -ErrorAction SilentlyContinue | Select-Object -ExpandProperty Name",
+    )
+    .param("COMMANDS", REQUIRED_COMMANDS.join(","))
+    .run()
+        .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
+        .unwrap_or_default();
+    let found: Vec<&str> = found.lines().map(str::trim).collect();
+    Ok(REQUIRED_COMMANDS
+        .iter()
+        .map(|command| {
+            (
+                *command,
+                found.iter().any(|f| f.eq_ignore_ascii_case(command)),
+            )
+        })
+        .collect())
+}
+
+#[cfg(target_os = "linux")]
+pub fn check_required_commands() -> Result<Vec<(&'static str, bool)>> {
+    Ok(REQUIRED_COMMANDS
+        .iter()
+        .map(|command| (*command, crate::utils::shell::is_on_path(command)))
+        .collect())
+}
@@ -0,0 +1,55 @@
+use crate::settings::SoftwareRepo;
+use crate::utils::github::{GithubRelease, download_versioned_asset};
+use crate::utils::http::Network;
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use std::path::Path;
+
+/// Optional release asset describing how cards should be prepared for the release.
+pub const RELEASE_MANIFEST_ASSET: &str = "gizmo-release.json";
+
+/// Card preparation settings published with a driver station release.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct ReleaseManifest {
+    /// FAT32 allocation unit size in bytes. Larger clusters make the Pi read the image faster.
+    pub cluster_size: Option<u32>,
+}
+
+impl ReleaseManifest {
+    fn validate(&self) -> Result<()> {
+        if let Some(cluster_size) = self.cluster_size
+            && (!cluster_size.is_power_of_two() || !(512..=65536).contains(&cluster_size))
+        {
+            bail!("{cluster_size} bytes is not a valid FAT32 cluster size.");
+        }
+        Ok(())
+    }
+}
+
+/// Downloads and reads the release's manifest. Releases without one get the defaults.
+pub fn load_release_manifest(
+    network: &Network,
+    release: &GithubRelease,
+    repo: &SoftwareRepo,
+    cache_dir: &Path,
+) -> Result<ReleaseManifest> {
+    let Some(asset) = release
+        .assets
+        .iter()
+        .find(|a| a.name == RELEASE_MANIFEST_ASSET)
+    else {
+        return Ok(ReleaseManifest::default());
+    };
+    let path =
+        download_versioned_asset(network, asset, &repo.owner, &repo.name, release, cache_dir)?;
+    let contents =
+        std::fs::read_to_string(&path).with_context(|| format!("Failed to read {:?}", path))?;
+    let manifest: ReleaseManifest = serde_json::from_str(&contents)
+        .with_context(|| format!("Could not parse {RELEASE_MANIFEST_ASSET}"))?;
+    manifest
+        .validate()
+        .with_context(|| format!("Invalid {RELEASE_MANIFEST_ASSET}"))?;
+    log::info!("Release {} manifest: {:?}", release.tag_name, manifest);
+    Ok(manifest)
+}
@@ -0,0 +1,74 @@
+use crate::events::{AppEvent, EventPublisher};
+use crate::settings::{SoftwareRepo, SoftwareSources};
+use crate::utils::github::get_releases_if_changed;
+use crate::utils::http::Network;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+/// Repositories watched for new releases, with the names shown to users.
+fn watched_repos(sources: SoftwareSources) -> [(SoftwareRepo, &'static str); 3] {
+    [
+        (sources.driver_station, "Driver Station"),
+        (sources.firmware, "System Firmware"),
+        (sources.starter_code, "Default Student Program"),
+    ]
+}
+
+/// Polling more often than this would quickly use up the unauthenticated GitHub rate limit.
+pub const MIN_POLL_INTERVAL_MINUTES: u32 = 5;
+
+/// Starts a background thread that periodically checks the watched repositories and publishes
+/// [`AppEvent::NewReleaseAvailable`] when the latest stable release changes.
+///
+/// `interval_minutes` is read before every poll so the interval can be changed while the app is
+/// running. A value of zero pauses polling. `network` is also read before every poll, but
+/// changes to `sources` apply after a restart.
+pub fn start_release_poller(
+    publisher: EventPublisher,
+    network: Arc<RwLock<Network>>,
+    interval_minutes: Arc<AtomicU32>,
+    sources: SoftwareSources,
+) {
+    std::thread::spawn(move || {
+        let watched_repos = watched_repos(sources);
+        let mut etags: HashMap<&SoftwareRepo, String> = HashMap::new();
+        let mut latest_tags: HashMap<&SoftwareRepo, String> = HashMap::new();
+        let mut last_poll: Option<Instant> = None;
+        loop {
+            let interval = interval_minutes.load(Ordering::Relaxed);
+            let due = last_poll.is_none_or(|t| {
+                t.elapsed()
+                    >= Duration::from_secs(60 * interval.max(MIN_POLL_INTERVAL_MINUTES) as u64)
+            });
+            if interval > 0 && due {
+                last_poll = Some(Instant::now());
+                let network = network.read().unwrap_or_else(|e| e.into_inner()).clone();
+                for (repo, display_name) in &watched_repos {
+                    let etag = etags.get(repo).map(String::as_str);
+                    let Ok(Some((releases, new_etag))) =
+                        get_releases_if_changed(&network, &repo.owner, &repo.name, etag)
+                    else {
+                        continue;
+                    };
+                    if let Some(new_etag) = new_etag {
+                        etags.insert(repo, new_etag);
+                    }
+                    let Some(latest) = releases.into_iter().find(|r| r.latest) else {
+                        continue;
+                    };
+                    let previous = latest_tags.insert(repo, latest.tag_name.clone());
+                    if previous.is_some_and(|tag| tag != latest.tag_name) {
+                        publisher.publish(AppEvent::NewReleaseAvailable {
+                            repo: repo.clone(),
+                            display_name: display_name.to_string(),
+                            release: latest,
+                        });
+                    }
+                }
+            }
+            std::thread::sleep(Duration::from_secs(30));
+        }
+    });
+}
@@ -0,0 +1,32 @@
+use anyhow::{Result, bail};
+use std::io::Write;
+use std::path::Path;
+
+const SAMPLE_FILES: [(&str, &str); 2] = [
+    ("gizmo/config.json", "{\"team\": \"0\"}"),
+    ("gizmo/docs/readme.txt", "Self-test file"),
+];
+
+/// Exercises the file handling used during installs in `work_dir`: writes a small archive,
+/// extracts it the way driver station archives are extracted, and checks the files come back
+/// unchanged.
+pub fn run_self_tests(work_dir: &Path) -> Result<()> {
+    let archive_path = work_dir.join("self-test.zip");
+    let mut writer = zip::ZipWriter::new(std::fs::File::create(&archive_path)?);
+    for (name, contents) in SAMPLE_FILES {
+        writer.start_file(name, zip::write::SimpleFileOptions::default())?;
+        writer.write_all(contents.as_bytes())?;
+    }
+    writer.finish()?;
+
+    let extract_dir = work_dir.join("extracted");
+    crate::utils::archive::extract_archive(&archive_path, &extract_dir, true, |_| {})?;
+    for (name, contents) in SAMPLE_FILES {
+        let relative_path = Path::new(name).strip_prefix("gizmo")?;
+        let extracted = std::fs::read_to_string(extract_dir.join(relative_path))?;
+        if extracted != contents {
+            bail!("{name} changed when it was extracted.");
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use crate::events::{AppEvent, EventPublisher};
+use crate::utils::github::{GithubRelease, get_releases};
+use crate::utils::http::Network;
+use crate::utils::offline::compare_release_names;
+use anyhow::Result;
+use std::cmp::Ordering;
+
+const WIZARD_REPO_OWNER: &str = "barulicm";
+const WIZARD_REPO_NAME: &str = "best-gizmo-setup-wizard";
+
+/// Checks the wizard's own releases once in the background and publishes
+/// [`AppEvent::WizardUpdateAvailable`] if a newer stable version is out.
+pub fn start_update_check(publisher: EventPublisher, network: Network) {
+    std::thread::spawn(move || match check_for_update(&network) {
+        Ok(Some(release)) => {
+            log::info!("Wizard update available: {}", release.tag_name);
+            publisher.publish(AppEvent::WizardUpdateAvailable(release));
+        }
+        Ok(None) => {}
+        Err(e) => log::warn!("Could not check for wizard updates: {e:#}"),
+    });
+}
+
+/// Returns the latest stable release if it is newer than this build.
+pub fn check_for_update(network: &Network) -> Result<Option<GithubRelease>> {
+    let latest = get_releases(network, WIZARD_REPO_OWNER, WIZARD_REPO_NAME)?
+        .into_iter()
+        .find(|r| r.latest);
+    Ok(latest.filter(|release| {
+        compare_release_names(
+            release.tag_name.trim_start_matches('v'),
+            env!("CARGO_PKG_VERSION"),
+        ) == Ordering::Greater
+    }))
+}
+
+/// Page users can download the release from.
+pub fn release_page_url(release: &GithubRelease) -> String {
+    format!(
+        "https://github.com/{WIZARD_REPO_OWNER}/{WIZARD_REPO_NAME}/releases/tag/{}",
+        release.tag_name
+    )
+}
+
+/// Page for opening an issue on the wizard's repository with the title and description filled in.
+pub fn new_issue_url(title: &str, body: &str) -> String {
+    reqwest::Url::parse_with_params(
+        &format!("https://github.com/{WIZARD_REPO_OWNER}/{WIZARD_REPO_NAME}/issues/new"),
+        &[("title", title), ("body", body)],
+    )
+    .expect("The issue URL should be valid.")
+    .into()
+}
+
+/// Downloads the installer for this build's architecture and starts it. The caller should close
+/// the wizard afterwards so the installer can replace it.
+#[cfg(target_os = "windows")]
+pub fn start_installer(
+    network: &Network,
+    release: &GithubRelease,
+    download_dir: &std::path::Path,
+) -> Result<()> {
+    let installer_name = match crate::utils::platform::BUILD_ARCH {
+        "aarch64" => "best_gizmo_setup_wizard_windows_arm64_installer.exe",
+        _ => "best_gizmo_setup_wizard_windows_installer.exe",
+    };
+    let asset = release
+        .assets
+        .iter()
+        .find(|a| a.name == installer_name)
+        .ok_or(anyhow::anyhow!(
+            "Could not find {installer_name} in release assets."
+        ))?;
+    let installer_path = download_dir.join(&release.tag_name).join(installer_name);
+    crate::utils::github::download_asset(network, asset, &installer_path, |_| {})?;
+    log::info!("Starting installer {:?}", installer_path);
+    crate::utils::shell::Command::new(&installer_path).spawn_detached()?;
+    Ok(())
+}
@@ -0,0 +1,150 @@
+use crate::utils::signing::{EmbeddedSignature, sign, verify};
+use anyhow::{Context, Result};
+use ed25519_dalek::SigningKey;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// One driver station card the wizard tried to set up.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CardRecord {
+    pub team_number: String,
+    pub software_version: String,
+    pub drive: String,
+    /// Seconds since the Unix epoch.
+    pub finished_at: u64,
+    /// Why the install failed, or `None` if it succeeded.
+    pub error: Option<String>,
+    /// I/O errors the OS logged for the card during a successful install, if they could be
+    /// counted.
+    #[serde(default)]
+    pub io_errors: Option<u32>,
+}
+
+/// One system processor the wizard installed firmware onto.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BoardRecord {
+    /// See [`BootloaderInfo::identifier`](crate::utils::uf2::BootloaderInfo::identifier). Empty
+    /// if the bootloader couldn't be read.
+    pub board_id: String,
+    /// Release tag of the firmware, or the file name for local files.
+    pub firmware: String,
+    /// Seconds since the Unix epoch.
+    pub finished_at: u64,
+    /// The version the board reported after restarting, or `None` if it couldn't be read.
+    pub verified_version: Option<String>,
+}
+
+/// Everything provisioned since the wizard was opened.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SessionReport {
+    pub wizard_version: String,
+    pub hub_name: String,
+    /// Seconds since the Unix epoch.
+    pub started_at: u64,
+    pub cards: Vec<CardRecord>,
+    #[serde(default)]
+    pub boards: Vec<BoardRecord>,
+}
+
+/// The file written by [`export_report`]. The report is kept as the JSON text that was signed,
+/// so it is verified byte for byte rather than re-encoded.
+#[derive(Serialize, Deserialize)]
+struct ExportedReport {
+    report: String,
+    signature: Option<EmbeddedSignature>,
+}
+
+impl SessionReport {
+    pub fn new() -> Self {
+        Self {
+            wizard_version: env!("CARGO_PKG_VERSION").to_string(),
+            hub_name: String::new(),
+            started_at: unix_now(),
+            cards: vec![],
+            boards: vec![],
+        }
+    }
+}
+
+pub fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Writes the report to `destination`, signed with `key` if one is given.
+pub fn export_report(
+    report: &SessionReport,
+    key: Option<&SigningKey>,
+    destination: &Path,
+) -> Result<()> {
+    let report = serde_json::to_string_pretty(report)?;
+    let exported = ExportedReport {
+        signature: key.map(|key| sign(key, report.as_bytes())),
+        report,
+    };
+    std::fs::write(destination, serde_json::to_string_pretty(&exported)?)
+        .with_context(|| format!("Failed to save session report to {:?}", destination))
+}
+
+/// Checks the signature on an exported report and returns the public key that signed it.
+pub fn verify_report(path: &Path) -> Result<String> {
+    let contents =
+        std::fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+    let exported: ExportedReport =
+        serde_json::from_str(&contents).with_context(|| format!("Could not parse {:?}", path))?;
+    let signature = exported
+        .signature
+        .ok_or(anyhow::anyhow!("This report is not signed."))?;
+    verify(&signature, exported.report.as_bytes())?;
+    Ok(signature.public_key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::signing::public_key_text;
+
+    fn report() -> SessionReport {
+        let mut report = SessionReport::new();
+        report.cards.push(CardRecord {
+            team_number: "42".to_string(),
+            software_version: "v1.0.0".to_string(),
+            drive: "E:".to_string(),
+            finished_at: 1_700_000_000,
+            error: None,
+            io_errors: None,
+        });
+        report
+    }
+
+    #[test]
+    fn signed_reports_verify() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("report.json");
+        let key = SigningKey::from_bytes(&[7; 32]);
+        export_report(&report(), Some(&key), &path).unwrap();
+        assert_eq!(verify_report(&path).unwrap(), public_key_text(&key));
+    }
+
+    #[test]
+    fn edited_reports_fail_to_verify() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("report.json");
+        export_report(&report(), Some(&SigningKey::from_bytes(&[7; 32])), &path).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let edited = contents.replace(r#"\"42\""#, r#"\"43\""#);
+        assert_ne!(edited, contents);
+        std::fs::write(&path, edited).unwrap();
+        assert!(verify_report(&path).is_err());
+    }
+
+    #[test]
+    fn unsigned_reports_fail_to_verify() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("report.json");
+        export_report(&report(), None, &path).unwrap();
+        assert!(verify_report(&path).is_err());
+    }
+}
@@ -1,33 +1,277 @@
-use anyhow::{Result, bail};
+//! The one place the wizard runs other programs.
+//!
+//! Arguments are always passed to the program as a list, never through a shell that would
+//! reinterpret them, so a drive label or path can't change what a command does. PowerShell
+//! scripts are fixed text, with values passed in as [`Command::param`]s.
 
-#[cfg(target_os = "windows")]
-pub fn run_powershell_command(command: &str) -> Result<std::process::Output> {
-    let mut c = std::process::Command::new("powershell");
-    c.arg("-Command").arg(command);
-    run_command(c)
+use anyhow::{Context, Result, anyhow, bail};
+use std::ffi::OsStr;
+use std::io::Read;
+use std::process::{Output, Stdio};
+use std::time::{Duration, Instant};
+
+/// How long a command may run before it is killed. Commands that ask the system about drives
+/// and ports finish in seconds, so one that runs this long is stuck.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// How much of a failed command's error output goes into the error message. The full output
+/// is in the log.
+const MAX_ERROR_OUTPUT_CHARS: usize = 1000;
+
+/// A program to run, and how long to give it.
+///
+/// Output is always captured. A command that exits with an error fails with the end of its
+/// error output as the message, and one that runs past its timeout is killed.
+pub struct Command {
+    command: std::process::Command,
+    timeout: Option<Duration>,
 }
 
-#[cfg(target_os = "linux")]
-pub fn run_bash_command(command: &str) -> Result<std::process::Output> {
-    let mut c = std::process::Command::new("bash");
-    c.arg("-c").arg(command);
-    run_command(c)
+impl Command {
+    pub fn new(program: impl AsRef<OsStr>) -> Self {
+        Self {
+            command: std::process::Command::new(program),
+            timeout: Some(DEFAULT_TIMEOUT),
+        }
+    }
+
+    /// Runs a PowerShell script. The script should be fixed text, with any values it needs
+    /// passed in with [`Command::param`].
+    #[cfg(target_os = "windows")]
+    pub fn powershell(script: &str) -> Self {
+        Self::new(powershell_path()).args(["-NoProfile", "-NonInteractive", "-Command", script])
+    }
+
+    pub fn arg(mut self, arg: impl AsRef<OsStr>) -> Self {
+        self.command.arg(arg);
+        self
+    }
+
+    pub fn args(mut self, args: impl IntoIterator<Item = impl AsRef<OsStr>>) -> Self {
+        self.command.args(args);
+        self
+    }
+
+    /// Passes a value to a PowerShell script, which reads it as `$env:WIZARD_<name>`.
+    /// Environment variables are never parsed as code, whatever quotes the value contains.
+    #[cfg(target_os = "windows")]
+    pub fn param(mut self, name: &str, value: impl AsRef<OsStr>) -> Self {
+        self.command.env(format!("WIZARD_{name}"), value);
+        self
+    }
+
+    /// Changes how long the command may run. `None` is for commands like downloads, which
+    /// take as long as the connection needs.
+    pub fn timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Starts the program and leaves it running without waiting for it or reading its output,
+    /// for programs like installers that take over from the wizard. The timeout doesn't apply.
+    #[cfg(target_os = "windows")]
+    pub fn spawn_detached(mut self) -> Result<()> {
+        log::debug!("Starting {:?}", self.command);
+        let program = self.command.get_program().to_string_lossy().to_string();
+        self.command
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .with_context(|| format!("Could not start {program}"))?;
+        Ok(())
+    }
+
+    pub fn run(mut self) -> Result<Output> {
+        log::debug!("Running {:?}", self.command);
+        let program = self.command.get_program().to_string_lossy().to_string();
+        let mut child = self
+            .command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Could not start {program}"))?;
+        // The pipes are drained while waiting, so a command with a lot of output doesn't fill
+        // them and stall.
+        let stdout = read_pipe(child.stdout.take());
+        let stderr = read_pipe(child.stderr.take());
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if deadline.is_some_and(|deadline| Instant::now() > deadline) {
+                let _ = child.kill();
+                let _ = child.wait();
+                bail!(
+                    "{program} didn't finish within {} seconds and was stopped.",
+                    self.timeout.unwrap_or_default().as_secs()
+                );
+            }
+            std::thread::sleep(WAIT_POLL_INTERVAL);
+        };
+        let output = Output {
+            status,
+            stdout: join_pipe(stdout)?,
+            stderr: join_pipe(stderr)?,
+        };
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stderr = stderr.trim();
+            log::warn!("{:?} exited with {}: {stderr}", self.command, output.status);
+            bail!(
+                "{program} failed with {}: {}",
+                output.status,
+                tail(stderr, MAX_ERROR_OUTPUT_CHARS)
+            );
+        }
+        Ok(output)
+    }
+}
+
+fn read_pipe(
+    pipe: Option<impl Read + Send + 'static>,
+) -> Option<std::thread::JoinHandle<std::io::Result<Vec<u8>>>> {
+    pipe.map(|mut pipe| {
+        std::thread::spawn(move || {
+            let mut output = vec![];
+            pipe.read_to_end(&mut output)?;
+            Ok(output)
+        })
+    })
+}
+
+fn join_pipe(reader: Option<std::thread::JoinHandle<std::io::Result<Vec<u8>>>>) -> Result<Vec<u8>> {
+    match reader {
+        Some(reader) => Ok(reader
+            .join()
+            .map_err(|_| anyhow!("Reading command output failed."))??),
+        None => Ok(vec![]),
+    }
+}
+
+/// Returns the last `max_chars` characters of `text`, where error output says what went wrong.
+fn tail(text: &str, max_chars: usize) -> String {
+    let char_count = text.chars().count();
+    if char_count <= max_chars {
+        text.to_string()
+    } else {
+        let tail: String = text.chars().skip(char_count - max_chars).collect();
+        format!("...{tail}")
+    }
 }
 
+/// Returns true if `program` is in one of the directories on the PATH, like the shell's
+/// `command -v`.
 #[cfg(target_os = "linux")]
-pub fn run_admin_bash_command(command: &str) -> Result<std::process::Output> {
-    let mut c = std::process::Command::new("pkexec");
-    c.arg("bash").arg("-c").arg(command);
-    run_command(c)
+pub fn is_on_path(program: &str) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::env::var_os("PATH").is_some_and(|path| {
+        std::env::split_paths(&path).any(|dir| {
+            std::fs::metadata(dir.join(program)).is_ok_and(|metadata| {
+                metadata.is_file() && metadata.permissions().mode() & 0o111 != 0
+            })
+        })
+    })
 }
 
-fn run_command(mut command: std::process::Command) -> Result<std::process::Output> {
-    let output = command.output()?;
-    if !output.status.success() {
-        bail!(
-            "Running shell command failed: {}",
-            String::from_utf8_lossy(&output.stderr)
-        );
+/// Uses the PowerShell in System32 when it can be found, so the native build is always run. On
+/// Windows on Arm, whatever "powershell" resolves to on the PATH may be an emulated x64 copy
+/// without the Storage cmdlets.
+#[cfg(target_os = "windows")]
+fn powershell_path() -> std::path::PathBuf {
+    std::env::var_os("SystemRoot")
+        .map(|root| {
+            std::path::PathBuf::from(root)
+                .join("System32")
+                .join("WindowsPowerShell")
+                .join("v1.0")
+                .join("powershell.exe")
+        })
+        .filter(|path| path.is_file())
+        .unwrap_or_else(|| std::path::PathBuf::from("powershell"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tail_keeps_short_text() {
+        assert_eq!(tail("no space left", 20), "no space left");
+        assert_eq!(tail("", 0), "");
+    }
+
+    #[test]
+    fn tail_counts_characters_not_bytes() {
+        assert_eq!(tail("año ñandú", 5), "...ñandú");
+        assert_eq!(tail("日本語のエラー", 3), "...エラー");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn successful_command_returns_its_output() {
+        let output = Command::new("sh")
+            .args(["-c", "echo hello"])
+            .run()
+            .expect("sh should succeed");
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "hello\n");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn failed_command_reports_the_end_of_its_error_output() {
+        let error = Command::new("sh")
+            .args(["-c", "echo first >&2; echo 'disk busy' >&2; exit 3"])
+            .run()
+            .expect_err("A non-zero exit should fail");
+        let message = error.to_string();
+        assert!(message.starts_with("sh failed with"), "{message}");
+        assert!(message.ends_with("first\ndisk busy"), "{message}");
+
+        let error = Command::new("false").run().expect_err("false should fail");
+        assert!(error.to_string().starts_with("false failed with"));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn long_error_output_is_cut_to_its_tail() {
+        let error = Command::new("sh")
+            .args([
+                "-c",
+                "printf 'é%.0s' $(seq 1500) >&2; printf 'end' >&2; exit 1",
+            ])
+            .run()
+            .expect_err("A non-zero exit should fail");
+        let message = error.to_string();
+        let (_, stderr) = message
+            .rsplit_once(": ")
+            .expect("Message should have the output");
+        assert!(stderr.starts_with("..."), "{stderr}");
+        assert!(stderr.ends_with("end"), "{stderr}");
+        assert_eq!(stderr.chars().count(), MAX_ERROR_OUTPUT_CHARS + 3);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn command_past_its_timeout_is_killed() {
+        let started = Instant::now();
+        let error = Command::new("sh")
+            .args(["-c", "sleep 30"])
+            .timeout(Some(Duration::from_millis(200)))
+            .run()
+            .expect_err("The command should time out");
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert!(error.to_string().contains("was stopped"), "{error}");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn missing_program_fails_to_start() {
+        let error = Command::new("definitely-not-a-real-program")
+            .run()
+            .expect_err("A missing program should fail");
+        assert!(error.to_string().starts_with("Could not start"));
     }
-    Ok(output)
 }
@@ -0,0 +1,76 @@
+use anyhow::{Context, Result, anyhow};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// An ed25519 signature along with the public key that made it, both base64 encoded.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EmbeddedSignature {
+    pub public_key: String,
+    pub signature: String,
+}
+
+/// The hub's private key is kept in its own file next to the settings, so sharing a settings
+/// file with another hub does not share the key.
+fn signing_key_path() -> Option<PathBuf> {
+    crate::settings::config_dir().map(|dir| dir.join("hub-signing-key"))
+}
+
+/// Loads the hub's signing key, or returns `None` if one has not been generated yet.
+pub fn load_signing_key() -> Result<Option<SigningKey>> {
+    let path = signing_key_path().ok_or(anyhow!("Could not find config directory."))?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents =
+        std::fs::read_to_string(&path).with_context(|| format!("Failed to read {:?}", path))?;
+    let seed: [u8; 32] = BASE64
+        .decode(contents.trim())
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(anyhow!("The signing key in {:?} is corrupt.", path))?;
+    Ok(Some(SigningKey::from_bytes(&seed)))
+}
+
+/// Creates a new signing key for this hub, replacing any existing one.
+pub fn generate_signing_key() -> Result<SigningKey> {
+    let path = signing_key_path().ok_or(anyhow!("Could not find config directory."))?;
+    let mut seed = [0; 32];
+    getrandom::fill(&mut seed).map_err(|e| anyhow!("Failed to generate a random key: {e}"))?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, BASE64.encode(seed))
+        .with_context(|| format!("Failed to save signing key to {:?}", path))?;
+    Ok(SigningKey::from_bytes(&seed))
+}
+
+pub fn public_key_text(key: &SigningKey) -> String {
+    BASE64.encode(key.verifying_key().as_bytes())
+}
+
+pub fn sign(key: &SigningKey, message: &[u8]) -> EmbeddedSignature {
+    EmbeddedSignature {
+        public_key: public_key_text(key),
+        signature: BASE64.encode(key.sign(message).to_bytes()),
+    }
+}
+
+/// Checks that `signature` is a valid signature of `message` by the key embedded with it.
+pub fn verify(signature: &EmbeddedSignature, message: &[u8]) -> Result<()> {
+    let public_key: [u8; 32] = BASE64
+        .decode(&signature.public_key)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(anyhow!("The public key is not valid."))?;
+    let signature_bytes: [u8; 64] = BASE64
+        .decode(&signature.signature)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(anyhow!("The signature is not valid."))?;
+    VerifyingKey::from_bytes(&public_key)?
+        .verify_strict(message, &Signature::from_bytes(&signature_bytes))
+        .map_err(|_| anyhow!("The signature does not match. The report may have been altered."))
+}
@@ -0,0 +1,143 @@
+use crate::settings::{SoundSettings, SoundVolume};
+use anyhow::{Context, Result};
+use std::sync::RwLock;
+
+const SAMPLE_RATE: u32 = 22050;
+/// Fade in and out of each note, so the tones don't click.
+const FADE_SECONDS: f32 = 0.01;
+
+static SOUND_SETTINGS: RwLock<Option<SoundSettings>> = RwLock::new(None);
+
+/// Sounds played when a long step finishes, so volunteers can look away from the screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cue {
+    Success,
+    Failure,
+}
+
+impl Cue {
+    /// Notes of the built-in cue as (frequency in Hz, length in seconds). Success rises and
+    /// failure falls, so they can be told apart across a noisy room.
+    fn notes(self) -> &'static [(f32, f32)] {
+        match self {
+            Cue::Success => &[(659.25, 0.12), (987.77, 0.22)],
+            Cue::Failure => &[(311.13, 0.22), (207.65, 0.4)],
+        }
+    }
+}
+
+/// Sets the sound settings [`play_cue`] follows.
+pub fn set_sound_settings(settings: &SoundSettings) {
+    *SOUND_SETTINGS.write().unwrap_or_else(|e| e.into_inner()) = Some(settings.clone());
+}
+
+/// Plays a cue in the background. Does nothing when sounds are turned off.
+pub fn play_cue(cue: Cue) {
+    let settings = SOUND_SETTINGS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or_default();
+    play_cue_with(&settings, cue);
+}
+
+/// Plays a cue with the given settings rather than the saved ones, such as to try them out.
+pub fn play_cue_with(settings: &SoundSettings, cue: Cue) {
+    if settings.volume == SoundVolume::Off {
+        return;
+    }
+    let custom_sound = match cue {
+        Cue::Success => settings.success_sound.clone(),
+        Cue::Failure => settings.failure_sound.clone(),
+    };
+    let volume = settings.volume;
+    std::thread::spawn(move || {
+        let wav = match custom_sound {
+            Some(path) => {
+                std::fs::read(&path).with_context(|| format!("Failed to read {:?}", path))
+            }
+            None => Ok(built_in_cue(cue, volume)),
+        };
+        if let Err(e) = wav.and_then(|wav| play_wav(&wav)) {
+            log::info!("Could not play the {cue:?} sound: {e:#}");
+        }
+    });
+}
+
+fn built_in_cue(cue: Cue, volume: SoundVolume) -> Vec<u8> {
+    let amplitude = match volume {
+        SoundVolume::Off => 0.0,
+        SoundVolume::Quiet => 0.12,
+        SoundVolume::Loud => 0.8,
+    };
+    let mut samples = vec![];
+    for &(frequency, seconds) in cue.notes() {
+        let count = (seconds * SAMPLE_RATE as f32) as usize;
+        let fade = (FADE_SECONDS * SAMPLE_RATE as f32) as usize;
+        samples.extend((0..count).map(|i| {
+            let envelope = (i.min(count - i) as f32 / fade as f32).min(1.0);
+            let phase = std::f32::consts::TAU * frequency * i as f32 / SAMPLE_RATE as f32;
+            (phase.sin() * envelope * amplitude * i16::MAX as f32) as i16
+        }));
+    }
+    encode_wav(&samples)
+}
+
+/// Wraps 16-bit mono samples in a WAV header.
+fn encode_wav(samples: &[i16]) -> Vec<u8> {
+    let data_len = (samples.len() * 2) as u32;
+    let mut wav = Vec::with_capacity(44 + data_len as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    // PCM, one channel.
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    wav.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes());
+    wav.extend_from_slice(&2u16.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    for sample in samples {
+        wav.extend_from_slice(&sample.to_le_bytes());
+    }
+    wav
+}
+
+/// Plays a WAV file from memory, returning once it has finished.
+#[cfg(target_os = "windows")]
+fn play_wav(wav: &[u8]) -> Result<()> {
+    use windows_sys::Win32::Media::Audio::{PlaySoundW, SND_MEMORY, SND_NODEFAULT, SND_SYNC};
+    // SAFETY: With SND_MEMORY the "name" is the WAV data itself, which outlives the synchronous
+    // call.
+    let played = unsafe {
+        PlaySoundW(
+            wav.as_ptr() as *const u16,
+            std::ptr::null_mut(),
+            SND_MEMORY | SND_SYNC | SND_NODEFAULT,
+        )
+    };
+    if played == 0 {
+        anyhow::bail!("Windows could not play the sound.");
+    }
+    Ok(())
+}
+
+/// Plays a WAV file from memory, returning once it has finished. PulseAudio and PipeWire
+/// desktops have paplay, and plain ALSA systems have aplay.
+#[cfg(target_os = "linux")]
+fn play_wav(wav: &[u8]) -> Result<()> {
+    use crate::utils::shell::Command;
+    use std::io::Write;
+    let mut file = tempfile::Builder::new().suffix(".wav").tempfile()?;
+    file.write_all(wav)?;
+    let path = file.path().as_os_str();
+    Command::new("paplay")
+        .arg(path)
+        .run()
+        .or_else(|_| Command::new("aplay").arg("-q").arg(path).run())
+        .with_context(|| "Neither paplay nor aplay could play the sound.")?;
+    Ok(())
+}
@@ -0,0 +1,178 @@
+use crate::utils::session_report::{SessionReport, unix_now};
+use anyhow::{Context, Result, anyhow};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// How often the status file is rewritten even when nothing changed, so a dashboard can tell a
+/// station that is idle from one that was closed or lost its connection to the share.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TeamState {
+    Done,
+    Failed,
+}
+
+/// The latest result for one team at this station.
+#[derive(Serialize, Debug, Clone)]
+pub struct TeamStatus {
+    pub team_number: String,
+    pub state: TeamState,
+    pub software_version: String,
+    /// Seconds since the Unix epoch.
+    pub finished_at: u64,
+    pub error: Option<String>,
+}
+
+/// What one laptop station has provisioned this session, for event dashboards that collect the
+/// files from every station.
+#[derive(Serialize, Debug, Clone)]
+pub struct StationStatus {
+    pub station: String,
+    pub hub_name: String,
+    pub wizard_version: String,
+    /// Seconds since the Unix epoch.
+    pub session_started_at: u64,
+    /// Seconds since the Unix epoch.
+    pub updated_at: u64,
+    /// How often the file is rewritten. A station that hasn't updated for a few of these is
+    /// closed or has lost the shared folder.
+    pub heartbeat_seconds: u64,
+    pub cards_done: usize,
+    pub cards_failed: usize,
+    pub teams: Vec<TeamStatus>,
+}
+
+impl StationStatus {
+    /// Summarizes a session report. A team that failed and was then set up again counts as done.
+    pub fn from_report(station: &str, hub_name: &str, report: &SessionReport) -> Self {
+        let mut teams: BTreeMap<&str, TeamStatus> = BTreeMap::new();
+        for card in &report.cards {
+            teams.insert(
+                &card.team_number,
+                TeamStatus {
+                    team_number: card.team_number.clone(),
+                    state: match card.error {
+                        Some(_) => TeamState::Failed,
+                        None => TeamState::Done,
+                    },
+                    software_version: card.software_version.clone(),
+                    finished_at: card.finished_at,
+                    error: card.error.clone(),
+                },
+            );
+        }
+        Self {
+            station: station.to_string(),
+            hub_name: hub_name.to_string(),
+            wizard_version: report.wizard_version.clone(),
+            session_started_at: report.started_at,
+            updated_at: unix_now(),
+            heartbeat_seconds: HEARTBEAT_INTERVAL.as_secs(),
+            cards_done: report.cards.iter().filter(|c| c.error.is_none()).count(),
+            cards_failed: report.cards.iter().filter(|c| c.error.is_some()).count(),
+            teams: teams.into_values().collect(),
+        }
+    }
+}
+
+/// Names this laptop in the status file, so stations sharing a folder don't overwrite each
+/// other.
+pub fn station_name() -> String {
+    std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .ok()
+        .or_else(|| {
+            std::fs::read_to_string("/etc/hostname")
+                .ok()
+                .map(|name| name.trim().to_string())
+        })
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "station".to_string())
+}
+
+fn status_path(dir: &Path, station: &str) -> PathBuf {
+    let file_name: String = station
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    dir.join(format!("gizmo-station-{file_name}.json"))
+}
+
+/// Writes the status next to its final name and renames it into place, so a dashboard reading
+/// the folder never sees half a file.
+fn write_status(dir: &Path, status: &StationStatus) -> Result<()> {
+    let path = status_path(dir, &status.station);
+    let partial_path = path.with_extension("json.partial");
+    std::fs::write(&partial_path, serde_json::to_string_pretty(status)?)
+        .with_context(|| format!("Failed to write {:?}", partial_path))?;
+    std::fs::rename(&partial_path, &path).with_context(|| format!("Failed to write {:?}", path))
+}
+
+/// Keeps a JSON status file in a shared folder up to date with the session report. Writes run
+/// on a worker thread, since network shares can be slow to answer.
+pub struct StatusExporter {
+    station: String,
+    written_cards: Option<usize>,
+    written_at: Option<Instant>,
+    writer: Option<JoinHandle<Result<()>>>,
+}
+
+impl StatusExporter {
+    pub fn new() -> Self {
+        Self {
+            station: station_name(),
+            written_cards: None,
+            written_at: None,
+            writer: None,
+        }
+    }
+
+    /// Writes the status if the report changed or the heartbeat is due. Call once per frame.
+    /// Returns the error from the last write, once.
+    pub fn update(
+        &mut self,
+        ctx: &egui::Context,
+        dir: Option<&Path>,
+        hub_name: &str,
+        report: &SessionReport,
+    ) -> Option<anyhow::Error> {
+        let error = self
+            .writer
+            .take_if(|writer| writer.is_finished())
+            .and_then(|writer| {
+                writer
+                    .join()
+                    .unwrap_or_else(|_| Err(anyhow!("Writing the station status failed.")))
+                    .err()
+            });
+        let Some(dir) = dir else {
+            self.written_cards = None;
+            return error;
+        };
+        // The window only redraws on input, so wake it up for the next heartbeat.
+        ctx.request_repaint_after(HEARTBEAT_INTERVAL);
+        let due = self.written_cards != Some(report.cards.len())
+            || self
+                .written_at
+                .is_none_or(|at| at.elapsed() >= HEARTBEAT_INTERVAL);
+        if due && self.writer.is_none() && error.is_none() {
+            let status = StationStatus::from_report(&self.station, hub_name, report);
+            let dir = dir.to_path_buf();
+            self.written_cards = Some(report.cards.len());
+            self.written_at = Some(Instant::now());
+            self.writer = Some(std::thread::spawn(move || write_status(&dir, &status)));
+        }
+        error
+    }
+}
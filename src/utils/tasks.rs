@@ -0,0 +1,55 @@
+//! Work that pages start on a background thread and check on each frame, so the UI keeps
+//! drawing while files are copied or releases are fetched.
+
+use crate::utils::panics::{catch_panic, report_from_payload};
+use crate::utils::threads::CancellationToken;
+use anyhow::Result;
+use std::thread::JoinHandle;
+
+/// Work running on its own thread. Pages keep one in an `Option` field for each kind of work,
+/// start it when the result is needed, and pick up the result with [`Task::poll`].
+pub struct Task<T> {
+    thread: JoinHandle<Result<T>>,
+}
+
+impl<T: Send + 'static> Task<T> {
+    /// Starts `work` on its own thread. A panic in it is caught there, so its backtrace is kept.
+    pub fn spawn(work: impl FnOnce() -> Result<T> + Send + 'static) -> Self {
+        Self {
+            thread: std::thread::spawn(move || catch_panic(work)),
+        }
+    }
+
+    /// Starts `work` along with the token used to cancel it.
+    pub fn spawn_cancellable(
+        work: impl FnOnce(CancellationToken) -> Result<T> + Send + 'static,
+    ) -> (Self, CancellationToken) {
+        let token = CancellationToken::default();
+        let thread_token = token.clone();
+        (Self::spawn(move || work(thread_token)), token)
+    }
+}
+
+impl<T> Task<T> {
+    pub fn is_finished(&self) -> bool {
+        self.thread.is_finished()
+    }
+
+    /// Waits for the work to finish. A panic in the work is returned as a
+    /// [`PanicReport`](crate::utils::panics::PanicReport) error.
+    pub fn join(self) -> Result<T> {
+        self.thread
+            .join()
+            .map_err(|payload| report_from_payload(payload.as_ref()))?
+    }
+
+    /// Takes the result out of `task` once the work has finished, leaving `None` behind so the
+    /// work can be started again. Returns `Ok(None)` while the work is still running, or if
+    /// there is none.
+    pub fn poll(task: &mut Option<Self>) -> Result<Option<T>> {
+        match task.take_if(|t| t.is_finished()) {
+            Some(task) => task.join().map(Some),
+            None => Ok(None),
+        }
+    }
+}
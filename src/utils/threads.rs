@@ -1,13 +1,40 @@
-use anyhow::{Result, anyhow};
-
-pub fn join_thread(thread: std::thread::JoinHandle<()>) -> Result<()> {
-    thread
-        .join()
-        .map_err(|e| match e.downcast_ref::<&'static str>() {
-            Some(s) => anyhow!("Background thread failed: {}", *s),
-            None => match e.downcast_ref::<String>() {
-                Some(s) => anyhow!("Background thread failed: {}", s),
-                None => anyhow!("Background thread failed with unknown error type."),
-            },
-        })
+use anyhow::Result;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Error returned by work that stopped because it was cancelled.
+#[derive(Debug)]
+pub struct Cancelled;
+
+impl std::fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Cancelled.")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// Shared flag a worker checks between phases of its work, so the UI can stop it at a point
+/// where stopping is safe.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Returns a [`Cancelled`] error if the work has been cancelled.
+    pub fn check(&self) -> Result<()> {
+        if self.is_cancelled() {
+            return Err(Cancelled.into());
+        }
+        Ok(())
+    }
 }
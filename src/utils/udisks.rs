@@ -0,0 +1,219 @@
+use anyhow::{Context, Result, anyhow};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use zbus::blocking::{Connection, Proxy};
+use zbus::zvariant::{OwnedObjectPath, OwnedValue, Value};
+
+const UDISKS_SERVICE: &str = "org.freedesktop.UDisks2";
+const UDISKS_PATH: &str = "/org/freedesktop/UDisks2";
+const BLOCK_INTERFACE: &str = "org.freedesktop.UDisks2.Block";
+const FILESYSTEM_INTERFACE: &str = "org.freedesktop.UDisks2.Filesystem";
+const DRIVE_INTERFACE: &str = "org.freedesktop.UDisks2.Drive";
+
+/// Properties of each interface on a UDisks2 object, as returned by `GetManagedObjects`.
+type Interfaces = HashMap<String, HashMap<String, OwnedValue>>;
+
+/// A block device holding a filesystem, such as the partition on an SD card.
+#[derive(Debug, Clone)]
+pub struct Filesystem {
+    object_path: OwnedObjectPath,
+    /// The card reader or USB stick holding the filesystem. "/" if UDisks2 doesn't know it.
+    drive_object_path: Option<OwnedObjectPath>,
+    /// Device node, like "/dev/sdb1".
+    pub device: String,
+    pub label: String,
+    /// Filesystem type, like "FAT32" or "exfat".
+    pub file_system: String,
+    pub mount_points: Vec<PathBuf>,
+}
+
+fn get_managed_objects(connection: &Connection) -> Result<HashMap<OwnedObjectPath, Interfaces>> {
+    let proxy = Proxy::new(
+        connection,
+        UDISKS_SERVICE,
+        UDISKS_PATH,
+        "org.freedesktop.DBus.ObjectManager",
+    )?;
+    Ok(proxy.call("GetManagedObjects", &())?)
+}
+
+fn property<T: TryFrom<OwnedValue>>(
+    interfaces: &Interfaces,
+    interface: &str,
+    name: &str,
+) -> Option<T> {
+    interfaces
+        .get(interface)?
+        .get(name)?
+        .try_clone()
+        .ok()?
+        .try_into()
+        .ok()
+}
+
+/// UDisks2 reports paths as NUL-terminated byte strings.
+fn byte_string(bytes: Vec<u8>) -> String {
+    String::from_utf8_lossy(&bytes)
+        .trim_end_matches('\0')
+        .to_string()
+}
+
+/// FAT filesystems are all "vfat", with the FAT size in the version.
+fn file_system_name(interfaces: &Interfaces) -> String {
+    let id_type: String = property(interfaces, BLOCK_INTERFACE, "IdType").unwrap_or_default();
+    match property::<String>(interfaces, BLOCK_INTERFACE, "IdVersion") {
+        Some(version) if id_type == "vfat" && !version.is_empty() => version,
+        _ => id_type,
+    }
+}
+
+fn connect() -> Result<Connection> {
+    Connection::system().with_context(|| "Could not connect to the system message bus.")
+}
+
+/// Lists the filesystems on removable drives. UDisks2 marks everything that isn't part of the
+/// computer itself as a non-system device, wherever it is mounted.
+pub fn list_removable_filesystems() -> Result<Vec<Filesystem>> {
+    let connection = connect()?;
+    let mut filesystems: Vec<Filesystem> = get_managed_objects(&connection)
+        .with_context(|| "Could not list drives from UDisks2.")?
+        .into_iter()
+        .filter(|(_, interfaces)| interfaces.contains_key(FILESYSTEM_INTERFACE))
+        .filter(|(_, interfaces)| {
+            !property(interfaces, BLOCK_INTERFACE, "HintSystem").unwrap_or(true)
+                && !property(interfaces, BLOCK_INTERFACE, "HintIgnore").unwrap_or(false)
+        })
+        .map(|(object_path, interfaces)| Filesystem {
+            device: property(&interfaces, BLOCK_INTERFACE, "Device")
+                .map(byte_string)
+                .unwrap_or_default(),
+            label: property(&interfaces, BLOCK_INTERFACE, "IdLabel").unwrap_or_default(),
+            file_system: file_system_name(&interfaces),
+            mount_points: property::<Vec<Vec<u8>>>(
+                &interfaces,
+                FILESYSTEM_INTERFACE,
+                "MountPoints",
+            )
+            .unwrap_or_default()
+            .into_iter()
+            .map(|path| PathBuf::from(byte_string(path)))
+            .collect(),
+            drive_object_path: property(&interfaces, BLOCK_INTERFACE, "Drive"),
+            object_path,
+        })
+        .collect();
+    filesystems.sort_by(|a, b| a.device.cmp(&b.device));
+    Ok(filesystems)
+}
+
+/// Finds the removable filesystem mounted at `mount_point`.
+pub fn find_by_mount_point(mount_point: &Path) -> Result<Filesystem> {
+    list_removable_filesystems()?
+        .into_iter()
+        .find(|f| f.mount_points.iter().any(|m| m == mount_point))
+        .ok_or(anyhow!(
+            "No removable drive is mounted at {:?}.",
+            mount_point
+        ))
+}
+
+/// Lists the filesystems on the drive holding `filesystem`, such as the other partitions of a
+/// card, including `filesystem` itself if it is still there.
+pub fn filesystems_on_same_drive(filesystem: &Filesystem) -> Result<Vec<Filesystem>> {
+    let drive = filesystem
+        .drive_object_path
+        .as_ref()
+        .filter(|path| path.as_str() != "/")
+        .ok_or(anyhow!(
+            "UDisks2 doesn't know which drive holds {}.",
+            filesystem.device
+        ))?;
+    Ok(list_removable_filesystems()?
+        .into_iter()
+        .filter(|f| f.drive_object_path.as_ref() == Some(drive))
+        .collect())
+}
+
+fn filesystem_proxy<'a>(
+    connection: &'a Connection,
+    filesystem: &'a Filesystem,
+) -> Result<Proxy<'a>> {
+    Ok(Proxy::new(
+        connection,
+        UDISKS_SERVICE,
+        filesystem.object_path.as_str(),
+        FILESYSTEM_INTERFACE,
+    )?)
+}
+
+pub fn unmount(filesystem: &Filesystem) -> Result<()> {
+    let connection = connect()?;
+    let options: HashMap<&str, Value> = HashMap::new();
+    filesystem_proxy(&connection, filesystem)?
+        .call::<_, _, ()>("Unmount", &(options,))
+        .with_context(|| format!("Unmounting {} failed.", filesystem.device))
+}
+
+/// Mounts the filesystem where the desktop would, and returns where that is.
+pub fn mount(filesystem: &Filesystem) -> Result<PathBuf> {
+    let connection = connect()?;
+    let options: HashMap<&str, Value> = HashMap::new();
+    let mount_point: String = filesystem_proxy(&connection, filesystem)?
+        .call("Mount", &(options,))
+        .with_context(|| format!("Mounting {} failed.", filesystem.device))?;
+    Ok(PathBuf::from(mount_point))
+}
+
+/// Powers off the drive holding an unmounted filesystem, like "Safely Remove" in a file
+/// manager. Card readers built into a laptop usually can't be powered off.
+pub fn power_off(filesystem: &Filesystem) -> Result<()> {
+    let drive = filesystem
+        .drive_object_path
+        .as_ref()
+        .filter(|path| path.as_str() != "/")
+        .ok_or(anyhow!(
+            "UDisks2 doesn't know which drive holds {}.",
+            filesystem.device
+        ))?;
+    let connection = connect()?;
+    let options: HashMap<&str, Value> = HashMap::new();
+    Proxy::new(&connection, UDISKS_SERVICE, drive.as_str(), DRIVE_INTERFACE)?
+        .call::<_, _, ()>("PowerOff", &(options,))
+        .with_context(|| {
+            format!(
+                "Powering off the drive holding {} failed.",
+                filesystem.device
+            )
+        })
+}
+
+/// Formats an unmounted filesystem as FAT32. UDisks2 asks for the user's password through
+/// polkit if this computer requires it. `cluster_size` is in bytes.
+pub fn format_fat32(filesystem: &Filesystem, label: &str, cluster_size: Option<u32>) -> Result<()> {
+    let connection = connect()?;
+    let mut options: HashMap<&str, Value> = HashMap::new();
+    options.insert("label", label.into());
+    // mkfs.vfat picks FAT16 for small cards unless told otherwise, and takes the cluster size
+    // in sectors. SD cards always use 512 byte sectors.
+    let mut mkfs_args = vec!["-F".to_string(), "32".to_string()];
+    if let Some(size) = cluster_size {
+        mkfs_args.extend(["-S".to_string(), "512".to_string()]);
+        mkfs_args.extend(["-s".to_string(), (size / 512).to_string()]);
+    }
+    options.insert("mkfs-args", mkfs_args.into());
+    let proxy = Proxy::new(
+        &connection,
+        UDISKS_SERVICE,
+        filesystem.object_path.as_str(),
+        BLOCK_INTERFACE,
+    )?;
+    proxy
+        .call::<_, _, ()>("Format", &("vfat", options))
+        .with_context(|| format!("Formatting {} failed.", filesystem.device))
+}
+
+/// Checks that UDisks2 is running and answering this user's requests.
+pub fn check_available() -> Result<()> {
+    get_managed_objects(&connect()?).with_context(|| "UDisks2 is not responding.")?;
+    Ok(())
+}
@@ -0,0 +1,233 @@
+use anyhow::{Context, Result, bail};
+use std::path::{Path, PathBuf};
+
+const BLOCK_SIZE: usize = 512;
+const MAGIC_START_0: u32 = 0x0A32_4655;
+const MAGIC_START_1: u32 = 0x9E5D_5157;
+const MAGIC_END: u32 = 0x0AB1_6F30;
+const FLAG_NOT_MAIN_FLASH: u32 = 0x0000_0001;
+const FLAG_FAMILY_ID_PRESENT: u32 = 0x0000_2000;
+const FLAG_EXTENSION_TAGS_PRESENT: u32 = 0x0000_8000;
+/// Offset of the payload within a block, after the 32 byte header.
+const DATA_OFFSET: usize = 32;
+const DATA_LEN: usize = 476;
+const TAG_VERSION: u32 = 0x9F_C7BC;
+const TAG_DEVICE: u32 = 0x65_0D9D;
+
+/// Family ID of RP2040 firmware, the chip on the Gizmo.
+pub const RP2040_FAMILY_ID: u32 = 0xE48B_FF56;
+/// Build directories can be deep, but not this deep.
+const MAX_SCAN_DEPTH: usize = 6;
+
+/// What the header blocks of a UF2 file say about the firmware in it.
+#[derive(Debug, Clone)]
+pub struct Uf2Info {
+    pub family_id: Option<u32>,
+    pub block_count: u32,
+    /// Bytes of flash the firmware fills.
+    pub payload_bytes: u64,
+    pub start_address: u32,
+    /// Version string from the UF2 extension tags, if the build tool wrote one.
+    pub version: Option<String>,
+    /// Device description from the UF2 extension tags, if the build tool wrote one.
+    pub device: Option<String>,
+}
+
+impl Uf2Info {
+    pub fn family_name(&self) -> &'static str {
+        match self.family_id {
+            Some(RP2040_FAMILY_ID) => "RP2040",
+            Some(0xE48B_FF57) => "RP2350 (absolute)",
+            Some(0xE48B_FF59) => "RP2350 (Arm)",
+            Some(0xE48B_FF5A) => "RP2350 (RISC-V)",
+            Some(_) => "another chip",
+            None => "an unknown chip",
+        }
+    }
+
+    /// Whether the firmware can be flashed onto a Gizmo. Files without a family ID are allowed,
+    /// since older build tools don't write one.
+    pub fn is_for_rp2040(&self) -> bool {
+        self.family_id.is_none_or(|id| id == RP2040_FAMILY_ID)
+    }
+}
+
+/// A UF2 file found in a build output directory.
+#[derive(Debug, Clone)]
+pub struct BuildArtifact {
+    pub path: PathBuf,
+    pub modified: Option<std::time::SystemTime>,
+    pub info: Result<Uf2Info, String>,
+}
+
+fn read_u32(block: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(
+        block[offset..offset + 4]
+            .try_into()
+            .expect("Slice is 4 bytes."),
+    )
+}
+
+/// Reads the version and device description extension tags that follow a block's payload.
+fn read_extension_tags(block: &[u8], payload_size: usize, info: &mut Uf2Info) {
+    let mut offset = DATA_OFFSET + payload_size.next_multiple_of(4);
+    while offset + 4 <= DATA_OFFSET + DATA_LEN {
+        let header = read_u32(block, offset);
+        let size = (header & 0xFF) as usize;
+        let tag = header >> 8;
+        if size < 4 || offset + size > DATA_OFFSET + DATA_LEN {
+            break;
+        }
+        let value = String::from_utf8_lossy(&block[offset + 4..offset + size])
+            .trim_end_matches('\0')
+            .to_string();
+        match tag {
+            TAG_VERSION => info.version = Some(value),
+            TAG_DEVICE => info.device = Some(value),
+            _ => {}
+        }
+        offset += size.next_multiple_of(4);
+    }
+}
+
+/// Parses a UF2 file and checks that its blocks are complete and in order.
+pub fn read_uf2_info(path: &Path) -> Result<Uf2Info> {
+    let data = std::fs::read(path).with_context(|| format!("Failed to read {:?}", path))?;
+    if data.is_empty() || data.len() % BLOCK_SIZE != 0 {
+        bail!("{:?} is not a whole number of UF2 blocks.", path);
+    }
+    let mut info: Option<Uf2Info> = None;
+    let mut flash_blocks = 0;
+    for (index, block) in data.chunks_exact(BLOCK_SIZE).enumerate() {
+        if read_u32(block, 0) != MAGIC_START_0
+            || read_u32(block, 4) != MAGIC_START_1
+            || read_u32(block, BLOCK_SIZE - 4) != MAGIC_END
+        {
+            bail!("Block {index} of {:?} is not a UF2 block.", path);
+        }
+        let flags = read_u32(block, 8);
+        if flags & FLAG_NOT_MAIN_FLASH != 0 {
+            continue;
+        }
+        let payload_size = read_u32(block, 16) as usize;
+        if payload_size > DATA_LEN {
+            bail!("Block {index} of {:?} has an invalid payload size.", path);
+        }
+        let block_number = read_u32(block, 20);
+        let block_count = read_u32(block, 24);
+        let info = info.get_or_insert_with(|| Uf2Info {
+            family_id: (flags & FLAG_FAMILY_ID_PRESENT != 0).then(|| read_u32(block, 28)),
+            block_count,
+            payload_bytes: 0,
+            start_address: read_u32(block, 12),
+            version: None,
+            device: None,
+        });
+        if block_number != flash_blocks || block_count != info.block_count {
+            bail!("{:?} is missing blocks or has them out of order.", path);
+        }
+        if flags & FLAG_EXTENSION_TAGS_PRESENT != 0 {
+            read_extension_tags(block, payload_size, info);
+        }
+        info.payload_bytes += payload_size as u64;
+        flash_blocks += 1;
+    }
+    let info = info.ok_or(anyhow::anyhow!("{:?} has no blocks for main flash.", path))?;
+    if flash_blocks != info.block_count {
+        bail!(
+            "{:?} is incomplete: it has {flash_blocks} of {} blocks.",
+            path,
+            info.block_count
+        );
+    }
+    Ok(info)
+}
+
+/// Finds the UF2 files under a build output directory, such as a cargo target directory or a
+/// pico-sdk build directory, newest first.
+pub fn scan_build_dir(dir: &Path) -> Result<Vec<BuildArtifact>> {
+    let mut paths = vec![];
+    collect_uf2_files(dir, 0, &mut paths)?;
+    let mut artifacts: Vec<BuildArtifact> = paths
+        .into_iter()
+        .map(|path| BuildArtifact {
+            modified: std::fs::metadata(&path).and_then(|m| m.modified()).ok(),
+            info: read_uf2_info(&path).map_err(|e| format!("{e:#}")),
+            path,
+        })
+        .collect();
+    artifacts.sort_by_key(|a| std::cmp::Reverse(a.modified));
+    Ok(artifacts)
+}
+
+fn collect_uf2_files(dir: &Path, depth: usize, paths: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in std::fs::read_dir(dir).with_context(|| format!("Failed to read {:?}", dir))? {
+        let entry = entry?;
+        let path = entry.path();
+        // Hidden directories hold tool state, like .git and .cache, never build output.
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        if entry.file_type()?.is_dir() {
+            if depth < MAX_SCAN_DEPTH {
+                collect_uf2_files(&path, depth + 1, paths)?;
+            }
+        } else if path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("uf2"))
+        {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Name of the file a UF2 bootloader describes itself in.
+pub const BOOTLOADER_INFO_FILE: &str = "INFO_UF2.TXT";
+/// Keys bootloaders use for the board's serial number. The RP2040 boot ROM doesn't list one.
+const SERIAL_NUMBER_KEYS: [&str; 4] = ["serial", "serial-number", "serial number", "chip-id"];
+
+/// What a board in bootloader mode says about itself in [`BOOTLOADER_INFO_FILE`].
+#[derive(Debug, Clone, Default)]
+pub struct BootloaderInfo {
+    /// The first line, such as "UF2 Bootloader v3.0".
+    pub bootloader: String,
+    pub model: Option<String>,
+    pub board_id: Option<String>,
+    pub serial_number: Option<String>,
+}
+
+impl BootloaderInfo {
+    /// The most specific ID the bootloader gives: its serial number if it lists one, or
+    /// otherwise its Board-ID. The RP2040 boot ROM only gives a Board-ID, which is the same on
+    /// every board of a model.
+    pub fn identifier(&self) -> Option<&str> {
+        self.serial_number.as_deref().or(self.board_id.as_deref())
+    }
+}
+
+/// Reads the bootloader description from the root of a UF2 bootloader drive.
+pub fn read_bootloader_info(drive_path: &Path) -> Result<BootloaderInfo> {
+    let path = drive_path.join(BOOTLOADER_INFO_FILE);
+    let contents =
+        std::fs::read_to_string(&path).with_context(|| format!("Failed to read {:?}", path))?;
+    let mut lines = contents.lines().map(str::trim);
+    let mut info = BootloaderInfo {
+        bootloader: lines.next().unwrap_or_default().to_string(),
+        ..Default::default()
+    };
+    for line in lines {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let key = key.trim().to_lowercase();
+        let value = Some(value.trim().to_string());
+        match key.as_str() {
+            "model" => info.model = value,
+            "board-id" => info.board_id = value,
+            key if SERIAL_NUMBER_KEYS.contains(&key) => info.serial_number = value,
+            _ => {}
+        }
+    }
+    Ok(info)
+}
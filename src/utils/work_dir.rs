@@ -0,0 +1,27 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::sync::Arc;
+
+/// A scratch directory that belongs to one operation, so operations running at the same time
+/// never write to the same paths. Clones share the directory, and it is deleted once the page
+/// and every worker thread holding a clone have dropped it, whether the operation finished or
+/// was abandoned.
+#[derive(Clone)]
+pub struct WorkDir {
+    dir: Arc<tempfile::TempDir>,
+}
+
+impl WorkDir {
+    pub fn new(parent: &Path, operation: &str) -> Result<Self> {
+        let dir = tempfile::Builder::new()
+            .prefix(&format!("{operation}-"))
+            .tempdir_in(parent)
+            .with_context(|| format!("Failed to create a working directory for {operation}"))?;
+        log::debug!("Created working directory {:?}", dir.path());
+        Ok(Self { dir: Arc::new(dir) })
+    }
+
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+}
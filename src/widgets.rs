@@ -0,0 +1,9 @@
+pub mod accessibility;
+pub mod capacity_check_dialog;
+pub mod compatibility_warnings;
+pub mod fetch_error_card;
+pub mod field_settings_editor;
+pub mod language_picker;
+pub mod performance_panel;
+pub mod release_notes;
+pub mod version_picker;
@@ -0,0 +1,114 @@
+use crate::settings::Settings;
+
+/// Zoom factors offered by the Settings page. Ctrl +/- steps through egui's own list.
+pub const MIN_ZOOM: f32 = 0.5;
+pub const MAX_ZOOM: f32 = 3.0;
+/// Smallest size of buttons, checkboxes, and list rows with large touch targets on, in points.
+/// Fingers, especially in gloves, need about this much to hit reliably.
+const TOUCH_TARGET_SIZE: egui::Vec2 = egui::vec2(48.0, 44.0);
+/// Gap between rows with large touch targets on, so neighboring drives aren't picked by mistake.
+const TOUCH_ROW_SPACING: f32 = 10.0;
+const TOUCH_BUTTON_PADDING: egui::Vec2 = egui::vec2(12.0, 8.0);
+
+/// Keeps the zoom level and window size saved across restarts and enlarges widgets on
+/// touchscreens.
+pub struct Accessibility {
+    /// Spacing from before touch targets were enlarged, to restore when they are turned off.
+    default_spacing: Option<egui::style::Spacing>,
+    /// The screen has been touched this session, so touch targets are enlarged regardless of
+    /// the setting.
+    touch_detected: bool,
+}
+
+impl Accessibility {
+    pub fn new(ctx: &egui::Context, settings: &Settings) -> Self {
+        ctx.set_zoom_factor(settings.zoom_factor.clamp(MIN_ZOOM, MAX_ZOOM));
+        Self {
+            default_spacing: None,
+            touch_detected: false,
+        }
+    }
+
+    pub fn touch_targets_enlarged(&self, settings: &Settings) -> bool {
+        self.touch_detected || settings.large_touch_targets
+    }
+
+    /// Applies this frame's accessibility state. Returns true if the settings changed and
+    /// should be saved, such as after zooming with Ctrl +/-.
+    pub fn update(&mut self, ctx: &egui::Context, settings: &mut Settings) -> bool {
+        if !self.touch_detected && ctx.input(|i| i.any_touches()) {
+            log::info!("Touchscreen detected, enlarging touch targets");
+            self.touch_detected = true;
+        }
+        let enlarge = self.touch_targets_enlarged(settings);
+        match (enlarge, self.default_spacing.is_some()) {
+            (true, false) => ctx.style_mut(|style| {
+                self.default_spacing = Some(style.spacing.clone());
+                style.spacing.interact_size = style.spacing.interact_size.max(TOUCH_TARGET_SIZE);
+                style.spacing.item_spacing.y = style.spacing.item_spacing.y.max(TOUCH_ROW_SPACING);
+                style.spacing.button_padding = TOUCH_BUTTON_PADDING;
+            }),
+            (false, true) => {
+                if let Some(spacing) = self.default_spacing.take() {
+                    ctx.style_mut(|style| style.spacing = spacing);
+                }
+            }
+            _ => {}
+        }
+
+        let zoom_factor = ctx.zoom_factor();
+        // Recorded as the window closes rather than while it is resized, which would save the
+        // settings every frame of the drag. Stored at 100% zoom, since that's how the window's
+        // initial size is interpreted.
+        if ctx.input(|i| i.viewport().close_requested())
+            && let Some(rect) = ctx.input(|i| i.viewport().inner_rect)
+        {
+            let size = rect.size() * zoom_factor;
+            settings.window_size = Some([size.x.round(), size.y.round()]);
+            return true;
+        }
+        if (zoom_factor - settings.zoom_factor).abs() > f32::EPSILON {
+            log::info!("Zoom changed to {:.0}%", zoom_factor * 100.0);
+            settings.zoom_factor = zoom_factor;
+            return true;
+        }
+        false
+    }
+}
+
+/// Like [`egui::Ui::heading`], but announced as a heading by screen readers so their users can
+/// jump between the sections of a step.
+pub fn heading(ui: &mut egui::Ui, text: impl Into<egui::RichText>) -> egui::Response {
+    let response = ui.heading(text);
+    mark_as_heading(&response, 2);
+    response
+}
+
+/// Tells screen readers that a label is a heading. Levels follow HTML, with the page title at 1.
+pub fn mark_as_heading(response: &egui::Response, level: usize) {
+    response.ctx.accesskit_node_builder(response.id, |node| {
+        node.set_role(egui::accesskit::Role::Heading);
+        node.set_level(level);
+    });
+}
+
+/// Names a button that only shows an image or symbol, which screen readers would otherwise
+/// announce as just "button".
+pub fn set_accessible_name(response: &egui::Response, name: &str) {
+    response.widget_info(|| {
+        egui::WidgetInfo::labeled(egui::WidgetType::Button, response.enabled(), name)
+    });
+}
+
+/// Outlines a widget while it has keyboard focus. Buttons with a custom fill hide most of egui's
+/// own focus highlight, which leaves keyboard users unable to see where Tab has taken them.
+pub fn show_focus_ring(ui: &egui::Ui, response: &egui::Response, color: egui::Color32) {
+    if response.has_focus() {
+        ui.painter().rect_stroke(
+            response.rect.expand(2.0),
+            ui.visuals().widgets.active.corner_radius,
+            egui::Stroke::new(2.0, color),
+            egui::StrokeKind::Outside,
+        );
+    }
+}
@@ -0,0 +1,284 @@
+use crate::i18n::tr;
+use crate::theme::palette;
+use crate::utils::cache::format_size;
+use crate::utils::capacity_check::{
+    ADVERTISED_SIZES_GB, CapacityCheckProgress, CapacityCheckResult, CheckPhase, MIN_WRITE_SPEED,
+    guess_advertised_size, run_capacity_check, run_card_test, smaller_than_advertised,
+};
+use crate::utils::drive_management::{DriveInfo, DriveManager};
+use crate::utils::tasks::Task;
+use crate::widgets::accessibility::heading;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Receiver;
+
+enum CheckStatus {
+    Confirming { acknowledged: bool },
+    Running(CapacityCheckProgress),
+    Finished(CapacityCheckResult),
+    Failed(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CheckKind {
+    /// Fills the whole card, to catch counterfeits.
+    Deep,
+    /// Tests a small part of the card for speed and bad sectors.
+    CardTest,
+}
+
+/// Modal dialog that tests one drive, either with a deep capacity check to catch counterfeit
+/// cards or with a quick test of its speed and health.
+pub struct CapacityCheckDialog {
+    drives: Arc<dyn DriveManager>,
+    drive: DriveInfo,
+    kind: CheckKind,
+    /// Size printed on the card's label, in bytes, for the card test.
+    advertised_bytes: u64,
+    status: CheckStatus,
+    cancel: Arc<AtomicBool>,
+    status_receiver: Option<Receiver<CheckStatus>>,
+    task: Option<Task<()>>,
+}
+
+impl CapacityCheckDialog {
+    pub fn new(drives: Arc<dyn DriveManager>, drive: DriveInfo) -> Self {
+        Self::with_kind(drives, drive, CheckKind::Deep)
+    }
+
+    pub fn card_test(drives: Arc<dyn DriveManager>, drive: DriveInfo) -> Self {
+        Self::with_kind(drives, drive, CheckKind::CardTest)
+    }
+
+    fn with_kind(drives: Arc<dyn DriveManager>, drive: DriveInfo, kind: CheckKind) -> Self {
+        Self {
+            drives,
+            advertised_bytes: guess_advertised_size(drive.total_bytes),
+            drive,
+            kind,
+            status: CheckStatus::Confirming {
+                acknowledged: false,
+            },
+            cancel: Arc::new(AtomicBool::new(false)),
+            status_receiver: None,
+            task: None,
+        }
+    }
+
+    fn start(&mut self, ctx: &egui::Context) {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let drives = self.drives.clone();
+        let drive = self.drive.clone();
+        let cancel = self.cancel.clone();
+        let kind = self.kind;
+        let ctx = ctx.clone();
+        self.status_receiver = Some(rx);
+        self.status = CheckStatus::Running(CapacityCheckProgress {
+            phase: CheckPhase::Writing,
+            bytes_done: 0,
+            bytes_total: 0,
+        });
+        self.task = Some(Task::spawn(move || {
+            let report = |status: CheckStatus| {
+                // The receiver is dropped if the dialog is closed, which is fine.
+                let _ = tx.send(status);
+                ctx.request_repaint();
+            };
+            let on_progress =
+                |progress: &CapacityCheckProgress| report(CheckStatus::Running(progress.clone()));
+            let result = match kind {
+                CheckKind::Deep => {
+                    run_capacity_check(drives.as_ref(), &drive, &cancel, on_progress)
+                }
+                CheckKind::CardTest => run_card_test(drives.as_ref(), &drive, &cancel, on_progress),
+            };
+            report(match result {
+                Ok(result) => CheckStatus::Finished(result),
+                Err(e) => CheckStatus::Failed(format!("{e:#}")),
+            });
+            Ok(())
+        }));
+    }
+
+    fn update(&mut self) {
+        // Check for the end first, so every status the worker sent before finishing is read.
+        let finished = Task::poll(&mut self.task);
+        if let Some(receiver) = &self.status_receiver
+            && let Some(status) = receiver.try_iter().last()
+        {
+            self.status = status;
+        }
+        if let Err(e) = finished {
+            self.status = CheckStatus::Failed(format!("{e:#}"));
+        }
+    }
+
+    /// Draws the dialog. Returns false once the user has closed it.
+    pub fn show(&mut self, ctx: &egui::Context) -> bool {
+        self.update();
+        let mut open = true;
+        let mut start = false;
+        egui::Modal::new(egui::Id::new("capacity_check_dialog")).show(ctx, |ui| {
+            ui.set_max_width(400.0);
+            heading(
+                ui,
+                match self.kind {
+                    CheckKind::Deep => tr!("check-deep-title"),
+                    CheckKind::CardTest => tr!("check-card-test-title"),
+                },
+            );
+            ui.label(format!("{}", self.drive));
+            match self.status {
+                CheckStatus::Confirming {
+                    ref mut acknowledged,
+                } if self.kind == CheckKind::Deep => {
+                    ui.label(tr!("check-deep-intro"));
+                    ui.colored_label(palette(ui).error, tr!("check-deep-warning"));
+                    ui.checkbox(acknowledged, tr!("check-deep-acknowledge"));
+                    ui.horizontal(|ui| {
+                        if ui
+                            .add_enabled(*acknowledged, egui::Button::new(tr!("check-start-check")))
+                            .clicked()
+                        {
+                            start = true;
+                        }
+                        if ui.button(tr!("common-cancel")).clicked() {
+                            open = false;
+                        }
+                    });
+                }
+                CheckStatus::Confirming { .. } => {
+                    ui.label(tr!("check-card-test-intro"));
+                    ui.horizontal(|ui| {
+                        ui.label(tr!("check-advertised-size"));
+                        egui::ComboBox::from_id_salt("advertised_size")
+                            .selected_text(format_advertised(self.advertised_bytes))
+                            .show_ui(ui, |ui| {
+                                for gb in ADVERTISED_SIZES_GB {
+                                    let bytes = gb * 1000 * 1000 * 1000;
+                                    ui.selectable_value(
+                                        &mut self.advertised_bytes,
+                                        bytes,
+                                        format_advertised(bytes),
+                                    );
+                                }
+                            });
+                    });
+                    ui.horizontal(|ui| {
+                        if ui.button(tr!("check-start-test")).clicked() {
+                            start = true;
+                        }
+                        if ui.button(tr!("common-cancel")).clicked() {
+                            open = false;
+                        }
+                    });
+                }
+                CheckStatus::Running(ref progress) => {
+                    let done = format_size(progress.bytes_done);
+                    let total = format_size(progress.bytes_total);
+                    let text = match progress.phase {
+                        CheckPhase::Writing => tr!("check-writing", done = done, total = total),
+                        CheckPhase::Verifying => tr!("check-verifying", done = done, total = total),
+                    };
+                    ui.add(egui::ProgressBar::new(progress.fraction()).text(text));
+                    let cancelling = self.cancel.load(Ordering::Relaxed);
+                    if ui
+                        .add_enabled(!cancelling, egui::Button::new(tr!("check-stop")))
+                        .clicked()
+                    {
+                        self.cancel.store(true, Ordering::Relaxed);
+                    }
+                }
+                CheckStatus::Finished(ref result) if self.kind == CheckKind::CardTest => {
+                    add_card_test_result(ui, &self.drive, self.advertised_bytes, result);
+                    if ui.button(tr!("common-close")).clicked() {
+                        open = false;
+                    }
+                }
+                CheckStatus::Finished(ref result) => {
+                    if result.passed() {
+                        ui.colored_label(
+                            palette(ui).success,
+                            tr!(
+                                "check-deep-passed",
+                                tested = format_size(result.bytes_tested)
+                            ),
+                        );
+                    } else {
+                        ui.colored_label(
+                            palette(ui).error,
+                            tr!(
+                                "check-deep-failed",
+                                good = format_size(result.first_bad_offset.unwrap_or_default()),
+                                bad = format_size(result.bad_bytes),
+                                tested = format_size(result.bytes_tested),
+                            ),
+                        );
+                    }
+                    if ui.button(tr!("common-close")).clicked() {
+                        open = false;
+                    }
+                }
+                CheckStatus::Failed(ref message) => {
+                    ui.colored_label(palette(ui).error, message);
+                    if ui.button(tr!("common-close")).clicked() {
+                        open = false;
+                    }
+                }
+            }
+        });
+        if start {
+            self.start(ctx);
+        }
+        open
+    }
+}
+
+/// Formats a size the way card labels do, in decimal gigabytes.
+fn format_advertised(bytes: u64) -> String {
+    format!("{} GB", bytes / (1000 * 1000 * 1000))
+}
+
+fn add_card_test_result(
+    ui: &mut egui::Ui,
+    drive: &DriveInfo,
+    advertised_bytes: u64,
+    result: &CapacityCheckResult,
+) {
+    let mut problems = vec![];
+    if !result.passed() {
+        problems.push(tr!(
+            "check-bad-sectors",
+            bad = format_size(result.bad_bytes),
+            tested = format_size(result.bytes_tested),
+        ));
+    }
+    if result.slow() {
+        problems.push(tr!(
+            "check-slow",
+            speed = format_size(result.write_speed()),
+            minimum = format_size(MIN_WRITE_SPEED),
+        ));
+    }
+    if drive.total_bytes > 0 && smaller_than_advertised(drive.total_bytes, advertised_bytes) {
+        problems.push(tr!(
+            "check-too-small",
+            actual = format_size(drive.total_bytes),
+            advertised = format_advertised(advertised_bytes),
+        ));
+    }
+    if problems.is_empty() {
+        ui.colored_label(
+            palette(ui).success,
+            tr!(
+                "check-card-passed",
+                speed = format_size(result.write_speed())
+            ),
+        );
+    } else {
+        for problem in problems {
+            ui.colored_label(palette(ui).error, problem);
+        }
+        ui.label(tr!("check-use-different-card"));
+    }
+}
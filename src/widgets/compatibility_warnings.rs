@@ -0,0 +1,58 @@
+use crate::i18n::tr;
+use crate::theme::palette;
+use crate::utils::compatibility::{Compatibility, Component, Severity};
+use crate::utils::github::GithubRelease;
+
+/// Shows why the selected release doesn't work with what was installed earlier in the session.
+/// Returns false if it is known not to work at all, so the page shouldn't let it be installed.
+pub fn show_compatibility_warnings(
+    ui: &mut egui::Ui,
+    compatibility: &Compatibility,
+    component: Component,
+    selected: Option<&GithubRelease>,
+) -> bool {
+    let Some(selected) = selected else {
+        return true;
+    };
+    let incompatibilities = compatibility.check(component, &selected.tag_name);
+    for incompatibility in &incompatibilities {
+        let version = &selected.tag_name;
+        let component = component_name(incompatibility.installed);
+        let installed = &incompatibility.installed_version;
+        let (color, text) = match incompatibility.severity {
+            Severity::Warn => (
+                palette(ui).warning,
+                tr!(
+                    "compat-may-not-work",
+                    version = version,
+                    component = component,
+                    installed = installed
+                ),
+            ),
+            Severity::Block => (
+                palette(ui).error,
+                tr!(
+                    "compat-does-not-work",
+                    version = version,
+                    component = component,
+                    installed = installed
+                ),
+            ),
+        };
+        ui.colored_label(color, text);
+        if let Some(ref reason) = incompatibility.reason {
+            ui.label(reason);
+        }
+    }
+    !incompatibilities
+        .iter()
+        .any(|i| i.severity == Severity::Block)
+}
+
+fn component_name(component: Component) -> String {
+    match component {
+        Component::DriverStation => tr!("compat-driver-station"),
+        Component::Firmware => tr!("compat-firmware"),
+        Component::StarterCode => tr!("compat-starter-code"),
+    }
+}
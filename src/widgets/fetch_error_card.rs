@@ -0,0 +1,44 @@
+use crate::i18n::tr;
+use crate::theme::palette;
+
+/// What the user asked to do about a failed release fetch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FetchErrorAction {
+    Retry,
+    WorkOffline,
+}
+
+/// Shows why the release list could not be fetched, with buttons to try again or to use the
+/// releases already in the download cache.
+pub fn show_fetch_error_card(
+    ui: &mut egui::Ui,
+    error: &str,
+    can_work_offline: bool,
+) -> Option<FetchErrorAction> {
+    let mut action = None;
+    egui::Frame::new()
+        .fill(palette(ui).error_background)
+        .stroke(egui::Stroke::new(1.0, palette(ui).error))
+        .corner_radius(4)
+        .inner_margin(8)
+        .show(ui, |ui| {
+            ui.colored_label(palette(ui).error, error);
+            ui.horizontal(|ui| {
+                if ui.button(tr!("common-retry")).clicked() {
+                    action = Some(FetchErrorAction::Retry);
+                }
+                if ui
+                    .add_enabled(
+                        can_work_offline,
+                        egui::Button::new(tr!("fetch-work-offline")),
+                    )
+                    .on_hover_text(tr!("fetch-work-offline-hint"))
+                    .on_disabled_hover_text(tr!("fetch-nothing-downloaded"))
+                    .clicked()
+                {
+                    action = Some(FetchErrorAction::WorkOffline);
+                }
+            });
+        });
+    action
+}
@@ -0,0 +1,35 @@
+use crate::i18n::tr;
+use crate::theme::palette;
+use crate::utils::gizmo_config::{FieldSettings, WIFI_CHANNELS};
+
+/// Shows the network and field settings as rows of an existing grid, so pages can add their own
+/// rows around them.
+pub fn add_field_settings_rows(ui: &mut egui::Ui, settings: &mut FieldSettings) {
+    ui.label(tr!("network-name"));
+    ui.add(
+        egui::TextEdit::singleline(&mut settings.network_ssid).hint_text(tr!("network-name-hint")),
+    );
+    ui.end_row();
+    ui.label(tr!("network-key"));
+    ui.add(egui::TextEdit::singleline(&mut settings.network_psk).password(true));
+    ui.end_row();
+    ui.label(tr!("network-server"));
+    ui.text_edit_singleline(&mut settings.server_ip);
+    ui.end_row();
+    ui.label(tr!("network-channel"));
+    ui.add(egui::DragValue::new(&mut settings.wifi_channel).range(WIFI_CHANNELS));
+    ui.end_row();
+}
+
+/// Shows `problem` if there is one, or otherwise any warning about a weak network key.
+pub fn show_field_settings_problem(
+    ui: &mut egui::Ui,
+    settings: &FieldSettings,
+    problem: Option<&str>,
+) {
+    if let Some(problem) = problem {
+        ui.colored_label(palette(ui).error, problem);
+    } else if let Some(warning) = settings.psk_warning() {
+        ui.colored_label(palette(ui).warning, warning);
+    }
+}
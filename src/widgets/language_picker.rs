@@ -0,0 +1,26 @@
+use crate::i18n::{Language, set_language, tr};
+use crate::settings::Settings;
+
+/// Shows a drop-down of the languages the wizard is translated into. Returns true if the
+/// language was changed, so the caller can save the settings.
+pub fn show_language_picker(ui: &mut egui::Ui, settings: &mut Settings) -> bool {
+    let mut language = settings.language;
+    let selected_text = match language {
+        Some(language) => language.native_name().to_string(),
+        None => tr!("language-system"),
+    };
+    egui::ComboBox::from_id_salt("language_picker")
+        .selected_text(selected_text)
+        .show_ui(ui, |ui| {
+            ui.selectable_value(&mut language, None, tr!("language-system"));
+            for option in Language::ALL {
+                ui.selectable_value(&mut language, Some(option), option.native_name());
+            }
+        });
+    if language == settings.language {
+        return false;
+    }
+    settings.language = language;
+    set_language(language);
+    true
+}
@@ -0,0 +1,153 @@
+use crate::i18n::tr;
+use std::collections::{HashMap, VecDeque};
+
+/// Frames kept for the frame time statistics.
+const FRAME_HISTORY: usize = 300;
+/// Repaint causes listed, most frequent first.
+const SHOWN_REPAINT_CAUSES: usize = 10;
+
+/// Opens and closes the panel. It isn't shown anywhere in the UI, since only support needs it.
+pub const TOGGLE_SHORTCUT: egui::KeyboardShortcut = egui::KeyboardShortcut::new(
+    egui::Modifiers::COMMAND.plus(egui::Modifiers::SHIFT),
+    egui::Key::P,
+);
+
+/// Work handed to the UI thread by background threads, measured once per frame.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct QueueDepths {
+    /// Events from watchers and workers handled this frame.
+    pub events: usize,
+    /// Questions from worker threads waiting for an answer.
+    pub prompts: usize,
+}
+
+struct FrameSample {
+    /// Time since the previous frame started, in seconds.
+    interval: f32,
+    /// Time the previous frame spent in the app's update, in seconds.
+    cpu_time: Option<f32>,
+    queues: QueueDepths,
+}
+
+/// Hidden window for diagnosing reports of a sluggish wizard on weak laptops. Shows frame
+/// times, how much work is queued for the UI thread, and what keeps asking for repaints.
+/// Statistics are only collected while the window is open.
+pub struct PerformancePanel {
+    open: bool,
+    frames: VecDeque<FrameSample>,
+    repaint_causes: HashMap<String, u32>,
+}
+
+impl PerformancePanel {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            frames: VecDeque::with_capacity(FRAME_HISTORY),
+            repaint_causes: HashMap::new(),
+        }
+    }
+
+    /// Toggles the window if its shortcut was pressed, then records this frame.
+    pub fn update(&mut self, ctx: &egui::Context, cpu_time: Option<f32>, queues: QueueDepths) {
+        if ctx.input_mut(|i| i.consume_shortcut(&TOGGLE_SHORTCUT)) {
+            self.open = !self.open;
+            self.reset();
+            log::info!(
+                "Performance panel {}",
+                if self.open { "opened" } else { "closed" }
+            );
+        }
+        if !self.open {
+            return;
+        }
+        if self.frames.len() == FRAME_HISTORY {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(FrameSample {
+            interval: ctx.input(|i| i.unstable_dt),
+            cpu_time,
+            queues,
+        });
+        for cause in ctx.repaint_causes() {
+            *self.repaint_causes.entry(cause.to_string()).or_default() += 1;
+        }
+    }
+
+    fn reset(&mut self) {
+        self.frames.clear();
+        self.repaint_causes.clear();
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) {
+        if !self.open {
+            return;
+        }
+        let mut reset = false;
+        egui::Window::new(tr!("perf-title"))
+            .open(&mut self.open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let intervals: Vec<f32> = self.frames.iter().map(|f| f.interval).collect();
+                let cpu_times: Vec<f32> = self.frames.iter().filter_map(|f| f.cpu_time).collect();
+                egui::Grid::new("performance_stats")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label(tr!("perf-frames"));
+                        ui.label(self.frames.len().to_string());
+                        ui.end_row();
+                        ui.label(tr!("perf-frame-interval"));
+                        ui.label(describe_times(&intervals));
+                        ui.end_row();
+                        ui.label(tr!("perf-update-time"));
+                        ui.label(describe_times(&cpu_times));
+                        ui.end_row();
+                        ui.label(tr!("perf-events"));
+                        ui.label(
+                            self.frames
+                                .iter()
+                                .map(|f| f.queues.events)
+                                .max()
+                                .unwrap_or(0)
+                                .to_string(),
+                        );
+                        ui.end_row();
+                        ui.label(tr!("perf-prompts"));
+                        ui.label(
+                            self.frames
+                                .back()
+                                .map_or(0, |f| f.queues.prompts)
+                                .to_string(),
+                        );
+                        ui.end_row();
+                    });
+                ui.separator();
+                ui.label(tr!("perf-repaint-causes"));
+                let mut causes: Vec<(&String, &u32)> = self.repaint_causes.iter().collect();
+                causes.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+                if causes.is_empty() {
+                    ui.label(tr!("perf-no-repaints"));
+                }
+                for (cause, count) in causes.into_iter().take(SHOWN_REPAINT_CAUSES) {
+                    ui.monospace(format!("{count:>6}  {cause}"));
+                }
+                reset = ui.button(tr!("perf-reset")).clicked();
+            });
+        if reset {
+            self.reset();
+        }
+    }
+}
+
+/// Summarizes times in seconds as "average / slowest" in milliseconds.
+fn describe_times(times: &[f32]) -> String {
+    if times.is_empty() {
+        return "-".to_string();
+    }
+    let average = times.iter().sum::<f32>() / times.len() as f32;
+    let slowest = times.iter().copied().fold(0.0, f32::max);
+    tr!(
+        "perf-times",
+        average = format!("{:.1}", average * 1000.0),
+        slowest = format!("{:.1}", slowest * 1000.0)
+    )
+}
@@ -0,0 +1,189 @@
+use crate::i18n::tr;
+use crate::utils::github::GithubRelease;
+use egui::text::{LayoutJob, TextFormat};
+use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
+
+/// Indentation for each level of a nested bullet list.
+const LIST_INDENT: f32 = 12.0;
+
+/// Lays out parsed Markdown one block at a time. Inline text is gathered into a [`LayoutJob`]
+/// so bold, italic, and code spans wrap together, and each paragraph, heading, or list item is
+/// added as its own label.
+struct NotesWriter<'a> {
+    ui: &'a mut egui::Ui,
+    job: LayoutJob,
+    strong: usize,
+    emphasis: usize,
+    strikethrough: usize,
+    link: usize,
+    heading: bool,
+    code_block: bool,
+    /// The next number for each open list, or `None` for bullet lists.
+    lists: Vec<Option<u64>>,
+}
+
+impl<'a> NotesWriter<'a> {
+    fn new(ui: &'a mut egui::Ui) -> Self {
+        Self {
+            ui,
+            job: LayoutJob::default(),
+            strong: 0,
+            emphasis: 0,
+            strikethrough: 0,
+            link: 0,
+            heading: false,
+            code_block: false,
+            lists: vec![],
+        }
+    }
+
+    fn format(&self, code: bool) -> TextFormat {
+        let style = self.ui.style();
+        let visuals = &style.visuals;
+        let mut format =
+            TextFormat::simple(egui::TextStyle::Body.resolve(style), visuals.text_color());
+        if code || self.code_block {
+            format.font_id = egui::TextStyle::Monospace.resolve(style);
+            format.background = visuals.code_bg_color;
+        }
+        if self.strong > 0 || self.heading {
+            format.color = visuals.strong_text_color();
+        }
+        if self.link > 0 {
+            format.color = visuals.hyperlink_color;
+        }
+        format.italics = self.emphasis > 0;
+        if self.strikethrough > 0 {
+            format.strikethrough = egui::Stroke::new(1.0, format.color);
+        }
+        format
+    }
+
+    fn append(&mut self, text: &str, code: bool) {
+        let format = self.format(code);
+        self.job.append(text, 0.0, format);
+    }
+
+    /// Adds the text gathered so far as a label, indented to the current list depth.
+    fn flush(&mut self) {
+        if self.code_block {
+            let trimmed_len = self.job.text.trim_end_matches('\n').len();
+            self.job.text.truncate(trimmed_len);
+            for section in &mut self.job.sections {
+                section.byte_range.end = section.byte_range.end.min(trimmed_len);
+            }
+            self.job
+                .sections
+                .retain(|section| !section.byte_range.is_empty());
+        }
+        if self.job.text.is_empty() {
+            return;
+        }
+        let job = std::mem::take(&mut self.job);
+        let indent = self.lists.len().saturating_sub(1) as f32 * LIST_INDENT;
+        self.ui.horizontal_wrapped(|ui| {
+            ui.add_space(indent);
+            ui.label(job);
+        });
+    }
+
+    fn start(&mut self, tag: Tag) {
+        match tag {
+            Tag::Paragraph | Tag::BlockQuote(_) => self.flush(),
+            Tag::Heading { .. } => {
+                self.flush();
+                self.heading = true;
+            }
+            Tag::CodeBlock(_) => {
+                self.flush();
+                self.code_block = true;
+            }
+            Tag::List(first_number) => {
+                self.flush();
+                self.lists.push(first_number);
+            }
+            Tag::Item => {
+                self.flush();
+                let marker = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}. ", *number - 1)
+                    }
+                    _ => "• ".to_string(),
+                };
+                self.append(&marker, false);
+            }
+            Tag::Strong => self.strong += 1,
+            Tag::Emphasis => self.emphasis += 1,
+            Tag::Strikethrough => self.strikethrough += 1,
+            Tag::Link { .. } => self.link += 1,
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::Paragraph | TagEnd::BlockQuote(_) | TagEnd::Item => self.flush(),
+            TagEnd::Heading(_) => {
+                self.flush();
+                self.heading = false;
+            }
+            TagEnd::CodeBlock => {
+                self.flush();
+                self.code_block = false;
+            }
+            TagEnd::List(_) => {
+                self.flush();
+                self.lists.pop();
+            }
+            TagEnd::Strong => self.strong = self.strong.saturating_sub(1),
+            TagEnd::Emphasis => self.emphasis = self.emphasis.saturating_sub(1),
+            TagEnd::Strikethrough => self.strikethrough = self.strikethrough.saturating_sub(1),
+            TagEnd::Link => self.link = self.link.saturating_sub(1),
+            _ => {}
+        }
+    }
+
+    fn event(&mut self, event: Event) {
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) => self.append(&text, false),
+            Event::Code(code) => self.append(&code, true),
+            Event::SoftBreak => self.append(" ", false),
+            Event::HardBreak => self.append("\n", false),
+            Event::Rule => {
+                self.flush();
+                self.ui.separator();
+            }
+            // HTML in notes is mostly comments left by templates, which GitHub doesn't show.
+            _ => {}
+        }
+    }
+}
+
+/// Shows a release's notes, with the Markdown formatting GitHub would show: headings, lists,
+/// emphasis, code, and rules. Links keep their text but can't be followed from here.
+pub fn show_release_notes(ui: &mut egui::Ui, release: &GithubRelease) {
+    let body = release.body.as_deref().map(str::trim).unwrap_or_default();
+    if body.is_empty() {
+        ui.weak(tr!("notes-none"));
+        return;
+    }
+    let mut writer = NotesWriter::new(ui);
+    for event in Parser::new_ext(body, Options::ENABLE_STRIKETHROUGH) {
+        writer.event(event);
+    }
+    writer.flush();
+}
+
+/// A collapsed "What's new" panel with the notes for the selected release.
+pub fn show_whats_new(ui: &mut egui::Ui, release: &GithubRelease) {
+    egui::CollapsingHeader::new(tr!("notes-whats-new", version = release.display_name()))
+        .id_salt("whats_new")
+        .show(ui, |ui| {
+            egui::ScrollArea::vertical()
+                .max_height(200.0)
+                .show(ui, |ui| show_release_notes(ui, release));
+        });
+}
@@ -0,0 +1,144 @@
+use crate::i18n::tr;
+use crate::utils::github::{GithubRelease, ReleaseChannel, releases_in_channel};
+use crate::utils::github_artifacts::is_development_build;
+use crate::widgets::release_notes::show_whats_new;
+
+/// Search text and keyboard highlight for an open version drop-down.
+#[derive(Clone, Default)]
+struct SearchState {
+    query: String,
+    highlighted: usize,
+}
+
+fn matches_query(release: &GithubRelease, query: &str) -> bool {
+    let query = query.trim().to_lowercase();
+    release.name.to_lowercase().contains(&query) || release.tag_name.to_lowercase().contains(&query)
+}
+
+/// Shows the version drop-down along with the "Include prereleases" and "Include development
+/// builds" checkboxes. The channel is shared by every page, so the choice carries over between
+/// them. If the selected release is hidden by switching channels, the latest release is
+/// selected instead.
+///
+/// Typing in the drop-down filters releases by name or tag. The arrow keys move through the
+/// matches and Enter picks the highlighted one. The selected release's notes are shown in a
+/// "What's new" panel underneath.
+///
+/// Returns true when development builds were turned on or off, since they are only fetched for
+/// the development channel and the page has to fetch its releases again.
+pub fn show_version_picker(
+    ui: &mut egui::Ui,
+    releases: &[GithubRelease],
+    selected: &mut Option<GithubRelease>,
+    channel: &mut ReleaseChannel,
+) -> bool {
+    let state_id = ui.make_persistent_id("version_picker_search");
+    let mut state: SearchState = ui.data_mut(|d| d.get_temp(state_id).unwrap_or_default());
+    let response = egui::ComboBox::from_label(tr!("version-pick"))
+        .selected_text(match selected {
+            Some(version) => version.display_name(),
+            None => tr!("version-select"),
+        })
+        .close_behavior(egui::PopupCloseBehavior::CloseOnClickOutside)
+        .show_ui(ui, |ui| {
+            let matches: Vec<&GithubRelease> = releases_in_channel(releases, *channel)
+                .into_iter()
+                .filter(|r| matches_query(r, &state.query))
+                .collect();
+
+            // Handled before the search box so it doesn't treat the keys as cursor movement.
+            let (down, up, enter) = ui.input_mut(|i| {
+                (
+                    i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+                    i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+                    i.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+                )
+            });
+            if down {
+                state.highlighted = (state.highlighted + 1).min(matches.len().saturating_sub(1));
+            }
+            if up {
+                state.highlighted = state.highlighted.saturating_sub(1);
+            }
+            if enter && let Some(release) = matches.get(state.highlighted) {
+                *selected = Some((*release).clone());
+                ui.close();
+            }
+
+            let search = ui
+                .add(egui::TextEdit::singleline(&mut state.query).hint_text(tr!("version-search")));
+            if !search.has_focus() {
+                search.request_focus();
+            }
+            if search.changed() {
+                state.highlighted = 0;
+            }
+
+            egui::ScrollArea::vertical()
+                .max_height(250.0)
+                .show(ui, |ui| {
+                    if matches.is_empty() {
+                        ui.weak(tr!("version-no-matches"));
+                    }
+                    for (i, release) in matches.iter().enumerate() {
+                        let is_selected = selected.as_ref() == Some(*release);
+                        let mut item = ui.selectable_label(is_selected, release.display_name());
+                        if i == state.highlighted {
+                            item = item.highlight();
+                            if down || up {
+                                item.scroll_to_me(None);
+                            }
+                        }
+                        if item.clicked() {
+                            *selected = Some((*release).clone());
+                            ui.close();
+                        }
+                    }
+                });
+        });
+    // Start with a fresh search each time the drop-down is opened.
+    if response.inner.is_none() {
+        state = SearchState::default();
+    }
+    ui.data_mut(|d| d.insert_temp(state_id, state));
+
+    let previous_channel = *channel;
+    let mut include_prereleases = *channel != ReleaseChannel::Stable;
+    if ui
+        .checkbox(&mut include_prereleases, tr!("version-prereleases"))
+        .on_hover_text(tr!("version-prereleases-hint"))
+        .changed()
+    {
+        *channel = if include_prereleases {
+            ReleaseChannel::Prerelease
+        } else {
+            ReleaseChannel::Stable
+        };
+    }
+    let mut include_development = *channel == ReleaseChannel::Development;
+    if ui
+        .checkbox(&mut include_development, tr!("version-development"))
+        .on_hover_text(tr!("version-development-hint"))
+        .changed()
+    {
+        *channel = if include_development {
+            ReleaseChannel::Development
+        } else {
+            ReleaseChannel::Prerelease
+        };
+    }
+    if *channel != previous_channel {
+        log::info!("Release channel changed to {channel:?}");
+        if selected.as_ref().is_some_and(|r| !channel.includes(r)) {
+            *selected = releases.iter().find(|r| r.latest).cloned();
+        }
+    }
+    if *channel == ReleaseChannel::Development && !releases.iter().any(is_development_build) {
+        ui.weak(tr!("version-no-development-builds"));
+    }
+
+    if let Some(release) = selected {
+        show_whats_new(ui, release);
+    }
+    (*channel == ReleaseChannel::Development) != (previous_channel == ReleaseChannel::Development)
+}